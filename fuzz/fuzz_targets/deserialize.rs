@@ -0,0 +1,45 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scicrypt_he::cryptosystems::curve_el_gamal::{
+    CompressedCurveElGamalCiphertext, CurveElGamalCiphertext, CurveElGamalPK,
+};
+use scicrypt_he::cryptosystems::integer_el_gamal::{IntegerElGamalCiphertext, IntegerElGamalPK};
+use scicrypt_he::cryptosystems::paillier::{PaillierCiphertext, PaillierPK};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::TryInto;
+use std::fmt::Debug;
+
+/// Decodes `data` as a `T`. If that succeeds, the value must survive another round trip.
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(data: &[u8]) {
+    if let Ok(value) = bincode::deserialize::<T>(data) {
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(value, bincode::deserialize::<T>(&bytes).unwrap());
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (selector, data) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    match selector % 6 {
+        0 => round_trip::<PaillierPK>(data),
+        1 => round_trip::<PaillierCiphertext>(data),
+        2 => round_trip::<IntegerElGamalPK>(data),
+        3 => round_trip::<IntegerElGamalCiphertext>(data),
+        4 => round_trip::<CurveElGamalPK>(data),
+        _ => {
+            round_trip::<CurveElGamalCiphertext>(data);
+
+            if let Ok(bytes) = data.try_into() {
+                let compressed = CompressedCurveElGamalCiphertext::from_bytes(bytes);
+                if let Some(ciphertext) = compressed.decompress() {
+                    assert_eq!(ciphertext.compress(), compressed);
+                }
+            }
+        }
+    }
+});
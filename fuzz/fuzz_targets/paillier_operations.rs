@@ -0,0 +1,67 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rug::ops::RemRounding;
+use rug::Integer;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_he::cryptosystems::paillier::{Paillier, PaillierPK, PaillierSK};
+use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, InsecureTestRng};
+use scicrypt_traits::security::BitsOfSecurity;
+use std::convert::TryInto;
+use std::sync::OnceLock;
+
+/// Key generation is far slower than the operations, so all inputs share one key pair.
+fn keys() -> &'static (PaillierPK, PaillierSK) {
+    static KEYS: OnceLock<(PaillierPK, PaillierSK)> = OnceLock::new();
+
+    KEYS.get_or_init(|| {
+        let mut rng = GeneralRng::new(InsecureTestRng::from_seed(0));
+        Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng)
+    })
+}
+
+// Every operation is one byte that selects it, followed by an 8-byte operand
+fuzz_target!(|data: &[u8]| {
+    let (pk, sk) = keys();
+    let n = pk.n.clone().to_rug();
+    let mut rng = GeneralRng::new(InsecureTestRng::from_seed(1));
+
+    let mut expected = Integer::new();
+    let mut ciphertext = pk.encrypt_raw(&UnsignedInteger::from(0u64), &mut rng);
+
+    for operation in data.chunks_exact(9) {
+        let operand = u64::from_le_bytes(operation[1..].try_into().unwrap());
+        let operand_unsigned = UnsignedInteger::from(operand);
+
+        match operation[0] % 5 {
+            0 => {
+                let other = pk.encrypt_raw(&operand_unsigned, &mut rng);
+                ciphertext = pk.add(&ciphertext, &other);
+                expected += operand;
+            }
+            1 => {
+                let other = pk.encrypt_raw(&operand_unsigned, &mut rng);
+                ciphertext = pk.sub(&ciphertext, &other);
+                expected -= operand;
+            }
+            2 => {
+                ciphertext = pk.mul_constant(&ciphertext, &operand_unsigned);
+                expected *= operand;
+            }
+            3 => {
+                ciphertext = pk.add_constant(&ciphertext, &operand_unsigned);
+                expected += operand;
+            }
+            _ => {
+                ciphertext = pk.sub_constant(&ciphertext, &operand_unsigned);
+                expected -= operand;
+            }
+        }
+
+        expected = expected.rem_euc(&n);
+    }
+
+    assert_eq!(expected, sk.decrypt_raw(pk, &ciphertext).to_rug());
+});
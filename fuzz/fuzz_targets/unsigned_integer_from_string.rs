@@ -0,0 +1,34 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rug::Integer;
+use scicrypt_bigint::UnsignedInteger;
+
+// The first byte selects the base, the rest is parsed as digits
+fuzz_target!(|data: &[u8]| {
+    let (base, digits) = match data.split_first() {
+        Some((base, digits)) => (2 + (*base as i32) % 35, digits),
+        None => return,
+    };
+    let string = match std::str::from_utf8(digits) {
+        Ok(string) => string,
+        Err(_) => return,
+    };
+
+    // `from_string_leaky` expects valid digits, so only compare numbers that rug accepts
+    let reference = match Integer::from_str_radix(string, base) {
+        Ok(reference) if reference >= 0 && string.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            reference
+        }
+        _ => return,
+    };
+
+    let size_in_bits = reference.significant_bits().max(1);
+    let parsed = UnsignedInteger::from_string_leaky(string.to_string(), base, size_in_bits);
+
+    assert_eq!(reference, parsed.clone().to_rug());
+    assert_eq!(
+        reference.to_string_radix(base),
+        parsed.to_string_radix_leaky(base)
+    );
+});
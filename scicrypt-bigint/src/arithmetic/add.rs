@@ -7,12 +7,38 @@ use gmp_mpfr_sys::gmp;
 
 use crate::{scratch::Scratch, UnsignedInteger, GMP_NUMB_BITS};
 
+impl UnsignedInteger {
+    /// Sets the declared size of a sum to `size_in_bits`, and stores the `carry` out of its limbs in
+    /// a new limb if that size needs one more limb. Otherwise, the sum fits in the limbs, so the
+    /// addition cannot have carried. Which case applies only depends on the declared sizes.
+    unsafe fn store_carry(&mut self, carry: u64, size_in_bits: u32) {
+        if size_in_bits > self.value.size as u32 * GMP_NUMB_BITS {
+            // The new size is given in bits, so reserve room for one more limb
+            gmp::mpz_realloc2(
+                &mut self.value,
+                (self.value.size as u64 + 1) * GMP_NUMB_BITS as u64,
+            );
+            *self.value.d.as_ptr().offset(self.value.size as isize) = carry;
+            self.value.size += 1;
+        } else {
+            debug_assert_eq!(carry, 0, "the sum does not fit in its declared size");
+        }
+
+        self.size_in_bits = size_in_bits;
+    }
+}
+
 impl AddAssign<&UnsignedInteger> for UnsignedInteger {
     fn add_assign(&mut self, rhs: &Self) {
         debug_assert!(self.size_in_bits >= rhs.size_in_bits);
         debug_assert!(self.value.size >= rhs.value.size);
 
+        // The sum can be one bit larger than the operands. The declared size only depends on their
+        // declared sizes, so that it does not reveal whether the addition carried.
+        let size_in_bits = self.size_in_bits.max(rhs.size_in_bits) + 1;
+
         if rhs.value.size == 0 {
+            self.resize_to(size_in_bits);
             return;
         }
 
@@ -41,14 +67,10 @@ impl AddAssign<&UnsignedInteger> for UnsignedInteger {
                 );
             }
 
-            // Save the carry in a new limb
-            if carry == 1u64 {
-                gmp::mpz_realloc2(&mut self.value, self.value.size as u64 + 1);
-                *self.value.d.as_ptr().offset(self.value.size as isize) = carry;
-                self.value.size += 1;
-                self.size_in_bits += 1;
-            }
+            self.store_carry(carry, size_in_bits);
         }
+
+        self.debug_assert_invariants();
     }
 }
 
@@ -77,14 +99,11 @@ impl AddAssign<u64> for UnsignedInteger {
                 scratch.as_mut(),
             );
 
-            // Save the carry in a new limb
-            if carry == 1u64 {
-                gmp::mpz_realloc2(&mut self.value, self.value.size as u64 + 1);
-                *self.value.d.as_ptr().offset(self.value.size as isize) = carry;
-                self.value.size += 1;
-                self.size_in_bits += 1;
-            }
+            // The right-hand side is declared as a full limb, whatever its value
+            self.store_carry(carry, self.size_in_bits.max(GMP_NUMB_BITS) + 1);
         }
+
+        self.debug_assert_invariants();
     }
 }
 
@@ -128,7 +147,8 @@ mod tests {
             ),
             x
         );
-        assert_eq!(x.size_in_bits, 103);
+        // The sum of two numbers can have one bit more than the largest of them
+        assert_eq!(x.size_in_bits, 104);
     }
 
     #[test]
@@ -145,6 +165,25 @@ mod tests {
         assert_eq!(x.size_in_bits, 65);
     }
 
+    #[test]
+    fn test_addition_overflow_multiple_limbs() {
+        let mut x = UnsignedInteger::from_string_leaky("f".repeat(64), 16, 256);
+        let y = UnsignedInteger::from_string_leaky("f".repeat(64), 16, 256);
+
+        x += &y;
+        assert_eq!(
+            UnsignedInteger::from_string_leaky(format!("1{}e", "f".repeat(63)), 16, 257),
+            x
+        );
+
+        let mut z = UnsignedInteger::from_string_leaky("f".repeat(64), 16, 256);
+        z += 1;
+        assert_eq!(
+            UnsignedInteger::from_string_leaky(format!("1{}", "0".repeat(64)), 16, 257),
+            z
+        );
+    }
+
     #[test]
     fn test_addition_different_sizes() {
         let mut x = UnsignedInteger::from_string_leaky(
@@ -164,7 +203,25 @@ mod tests {
             ),
             x
         );
-        assert_eq!(x.size_in_bits, 103);
+        // The sum of two numbers can have one bit more than the largest of them
+        assert_eq!(x.size_in_bits, 104);
+    }
+
+    #[test]
+    fn test_addition_size_does_not_depend_on_carry() {
+        let one = UnsignedInteger::new(1, 64);
+
+        // Both sums have the same declared size, although only the first one carries
+        let carried = UnsignedInteger::new(u64::MAX, 64) + &one;
+        let not_carried = UnsignedInteger::new(1, 64) + &one;
+        assert_eq!(carried.size_in_bits, 65);
+        assert_eq!(not_carried.size_in_bits, 65);
+        assert_eq!(UnsignedInteger::new(2, 65), not_carried);
+
+        let carried = UnsignedInteger::new(u64::MAX, 64) + u64::MAX;
+        let not_carried = UnsignedInteger::new(1, 64) + 1;
+        assert_eq!(carried.size_in_bits, 65);
+        assert_eq!(not_carried.size_in_bits, 65);
     }
 
     #[test]
@@ -186,6 +243,7 @@ mod tests {
             ),
             x
         );
-        assert_eq!(x.size_in_bits, 103);
+        // The sum of two numbers can have one bit more than the largest of them
+        assert_eq!(x.size_in_bits, 104);
     }
 }
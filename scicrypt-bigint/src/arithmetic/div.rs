@@ -52,6 +52,8 @@ impl UnsignedInteger {
             self.value.size = rhs.value.size;
             self.size_in_bits = rhs.size_in_bits;
 
+            res.debug_assert_invariants();
+            self.debug_assert_invariants();
             (res, self)
         }
     }
@@ -1,4 +1,7 @@
-use std::{iter::Product, ops::Mul};
+use std::{
+    iter::Product,
+    ops::{Mul, MulAssign},
+};
 
 use gmp_mpfr_sys::gmp;
 
@@ -46,12 +49,89 @@ impl Mul for &UnsignedInteger {
 
             result.size_in_bits = self.size_in_bits + rhs.size_in_bits;
             result.value.size = result.size_in_bits.div_ceil(GMP_NUMB_BITS) as i32;
-            result
         }
+
+        result.debug_assert_invariants();
+        result
+    }
+}
+
+impl MulAssign<u64> for UnsignedInteger {
+    /// Multiplies by a public `rhs`. The resulting size grows by the number of bits of `rhs`, so
+    /// the running time only depends on the sizes of the operands.
+    fn mul_assign(&mut self, rhs: u64) {
+        if self.value.size == 0 {
+            return;
+        }
+
+        let size_in_bits = self.size_in_bits + (GMP_NUMB_BITS - rhs.leading_zeros());
+        let limb_count = (size_in_bits.div_ceil(GMP_NUMB_BITS) as i32).max(self.value.size);
+
+        unsafe {
+            let carry = gmp::mpn_mul_1(
+                self.value.d.as_mut(),
+                self.value.d.as_ptr(),
+                self.value.size as i64,
+                rhs,
+            );
+
+            // Save the carry in a new limb if the size requires one, otherwise it must be zero
+            if limb_count > self.value.size {
+                gmp::mpz_realloc2(&mut self.value, limb_count as u64 * GMP_NUMB_BITS as u64);
+                *self.value.d.as_ptr().offset(self.value.size as isize) = carry;
+            } else {
+                debug_assert_eq!(carry, 0);
+            }
+        }
+
+        self.value.size = limb_count;
+        self.size_in_bits = size_in_bits;
+        self.debug_assert_invariants();
+    }
+}
+
+impl Mul<u64> for UnsignedInteger {
+    type Output = UnsignedInteger;
+
+    fn mul(mut self, rhs: u64) -> Self::Output {
+        self *= rhs;
+        self
     }
 }
 
 impl UnsignedInteger {
+    /// Computes `self * rhs` for a public `rhs`, without converting `rhs` to an `UnsignedInteger`.
+    pub fn mul_u64(&self, rhs: u64) -> UnsignedInteger {
+        self.clone() * rhs
+    }
+
+    /// Computes `self * rhs` using GMP's fastest multiplication, which switches from schoolbook multiplication to Karatsuba, Toom-Cook and FFT-based multiplication as the operands grow. This function is not constant-time: the chosen algorithm and its running time depend on the actual number of limbs of both operands, and the intermediate carries depend on their values. Only use it when both operands are public, e.g. when computing with moduli or public exponents.
+    pub fn mul_leaky(&self, rhs: &UnsignedInteger) -> UnsignedInteger {
+        if rhs.value.size > self.value.size {
+            return rhs.mul_leaky(self);
+        }
+
+        debug_assert!(self.value.size != 0);
+        debug_assert!(rhs.value.size != 0);
+
+        let mut result = UnsignedInteger::init(self.value.size + rhs.value.size);
+
+        unsafe {
+            gmp::mpn_mul(
+                result.value.d.as_mut(),
+                self.value.d.as_ptr(),
+                self.value.size as i64,
+                rhs.value.d.as_ptr(),
+                rhs.value.size as i64,
+            );
+        }
+
+        result.size_in_bits = self.size_in_bits + rhs.size_in_bits;
+        result.value.size = result.size_in_bits.div_ceil(GMP_NUMB_BITS) as i32;
+        result.debug_assert_invariants();
+        result
+    }
+
     /// Computes $x^2$, where $x$ is `self`. This is typically faster than performing a multiplication.
     pub fn square(&self) -> UnsignedInteger {
         debug_assert_ne!(self.value.size, 0);
@@ -78,8 +158,69 @@ impl UnsignedInteger {
             );
 
             result.size_in_bits = self.size_in_bits * 2;
-            result.value.size = result.size_in_bits.div_ceil(GMP_NUMB_BITS) as i32; // TODO: Check if this does not cause a memory leak
-            result
+            result.value.size = result.size_in_bits.div_ceil(GMP_NUMB_BITS) as i32;
+            // TODO: Check if this does not cause a memory leak
+        }
+
+        result.debug_assert_invariants();
+        result
+    }
+
+    /// Computes $x^2 \bmod m$, where $x$ is `self` and $m$ is `modulus`. This is typically faster than performing a modular multiplication, which makes it the main building block of exponentiation.
+    pub fn square_mod(&self, modulus: &UnsignedInteger) -> UnsignedInteger {
+        self.square() % modulus
+    }
+
+    /// Computes $x^2$, where $x$ is `self`, using GMP's fastest squaring (`mpn_sqr`). Like `mul_leaky`, this function is not constant-time, so only use it when `self` is public.
+    pub fn square_leaky(&self) -> UnsignedInteger {
+        debug_assert_ne!(self.value.size, 0);
+
+        let mut result = UnsignedInteger::init(self.value.size * 2);
+
+        unsafe {
+            gmp::mpn_sqr(
+                result.value.d.as_mut(),
+                self.value.d.as_ptr(),
+                self.value.size as i64,
+            );
+        }
+
+        result.size_in_bits = self.size_in_bits * 2;
+        result.value.size = result.size_in_bits.div_ceil(GMP_NUMB_BITS) as i32;
+        result.debug_assert_invariants();
+        result
+    }
+
+    /// Computes the product of all `values` using a balanced product tree, which multiplies
+    /// operands of similar sizes and is therefore faster than multiplying them one by one. Like
+    /// the multiplication operator, this is constant-time with regards to the sizes of the values.
+    /// Panics if `values` is empty.
+    pub fn product(values: &[UnsignedInteger]) -> UnsignedInteger {
+        tree_product(values, &|x| x.clone(), &|x, y| x * y)
+    }
+
+    /// Computes the product of all `values` modulo `modulus` using a balanced product tree, which
+    /// reduces after every multiplication to keep the operands small. Panics if `values` is empty.
+    pub fn product_mod(values: &[UnsignedInteger], modulus: &UnsignedInteger) -> UnsignedInteger {
+        tree_product(values, &|x| x.clone() % modulus, &|x, y| (x * y) % modulus)
+    }
+}
+
+/// Multiplies the two halves of `values` recursively, applying `leaf` to single values.
+fn tree_product(
+    values: &[UnsignedInteger],
+    leaf: &dyn Fn(&UnsignedInteger) -> UnsignedInteger,
+    multiply: &dyn Fn(&UnsignedInteger, &UnsignedInteger) -> UnsignedInteger,
+) -> UnsignedInteger {
+    match values {
+        [] => panic!("the product of no values is not defined"),
+        [x] => leaf(x),
+        _ => {
+            let (left, right) = values.split_at(values.len() / 2);
+            multiply(
+                &tree_product(left, leaf, multiply),
+                &tree_product(right, leaf, multiply),
+            )
         }
     }
 }
@@ -95,6 +236,49 @@ impl<'a> Product<&'a UnsignedInteger> for UnsignedInteger {
 mod tests {
     use crate::UnsignedInteger;
 
+    #[test]
+    fn test_mul_u64() {
+        let x = UnsignedInteger::from_string_leaky(
+            "340282366920938463463374607431768211455".to_string(),
+            10,
+            128,
+        );
+        let expected = x.clone().to_rug() * 1_000_003u64;
+
+        let mut y = x.clone();
+        y *= 1_000_003;
+        assert_eq!(UnsignedInteger::from(expected), y);
+        assert_eq!(128 + 20, y.size_in_bits());
+        assert_eq!(y, x.mul_u64(1_000_003));
+
+        let small = UnsignedInteger::new(23, 64) * 14;
+        assert_eq!(UnsignedInteger::from(23u64 * 14), small);
+        assert_eq!(64 + 4, small.size_in_bits());
+        assert_eq!(2, small.value.size);
+    }
+
+    #[test]
+    fn test_product() {
+        let values: Vec<UnsignedInteger> =
+            (1..=25u64).map(|x| UnsignedInteger::new(x, 64)).collect();
+        let expected = (1..=25u64).fold(rug::Integer::from(1), |acc, x| acc * x);
+
+        assert_eq!(
+            UnsignedInteger::from(expected.clone()),
+            UnsignedInteger::product(&values)
+        );
+
+        let modulus = UnsignedInteger::from(1_000_000_007u64);
+        assert_eq!(
+            UnsignedInteger::from(expected % 1_000_000_007u64),
+            UnsignedInteger::product_mod(&values, &modulus)
+        );
+        assert_eq!(
+            UnsignedInteger::from(17u64),
+            UnsignedInteger::product_mod(&[UnsignedInteger::new(1_000_000_024, 64)], &modulus)
+        );
+    }
+
     #[test]
     fn test_square() {
         let x = UnsignedInteger::new(23, 64);
@@ -104,6 +288,21 @@ mod tests {
         assert_eq!(UnsignedInteger::from(23u64 * 23), res);
     }
 
+    #[test]
+    fn test_square_mod() {
+        let x = UnsignedInteger::from_string_leaky("125789402190859323905892".to_string(), 10, 128);
+        let m = UnsignedInteger::from_string_leaky("393530540239137101151".to_string(), 10, 128);
+
+        assert_eq!(&x * &x % &m, x.square_mod(&m));
+    }
+
+    #[test]
+    fn test_square_leaky_matches_square() {
+        let x = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+
+        assert_eq!(x.square(), x.square_leaky());
+    }
+
     #[test]
     fn test_mul_equal_size() {
         let a = UnsignedInteger::new(23, 64);
@@ -143,6 +342,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mul_leaky_matches_mul() {
+        let a = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+        let b = UnsignedInteger::from_string_leaky("125789402190859323905892".to_string(), 10, 128);
+
+        assert_eq!(&a * &b, a.mul_leaky(&b));
+        assert_eq!(&a * &b, b.mul_leaky(&a));
+    }
+
     #[test]
     fn test_mul_larger_b() {
         let a = UnsignedInteger::new(12, 64);
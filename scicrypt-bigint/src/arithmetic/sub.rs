@@ -14,13 +14,31 @@ impl SubAssign<&UnsignedInteger> for UnsignedInteger {
         }
 
         unsafe {
-            gmp::mpn_sub_n(
+            let borrow = gmp::mpn_sub_n(
                 self.value.d.as_mut(),
                 self.value.d.as_ptr(),
                 rhs.value.d.as_ptr(),
                 rhs.value.size as i64,
             );
+
+            let remaining_size = (self.value.size - rhs.value.size) as i64;
+            if remaining_size != 0 {
+                // Propagate the borrow over the remaining (more significant) limbs on the LHS.
+                let scratch_size =
+                    gmp::mpn_sec_sub_1_itch(remaining_size) as usize * GMP_NUMB_BITS as usize;
+                let mut scratch = Scratch::new(scratch_size);
+
+                gmp::mpn_sec_sub_1(
+                    self.value.d.as_ptr().offset(rhs.value.size as isize),
+                    self.value.d.as_ptr().offset(rhs.value.size as isize),
+                    remaining_size,
+                    borrow,
+                    scratch.as_mut(),
+                );
+            }
         }
+
+        self.debug_assert_invariants();
     }
 }
 
@@ -60,6 +78,8 @@ impl SubAssign<u64> for UnsignedInteger {
                 scratch.as_mut(),
             );
         }
+
+        self.debug_assert_invariants();
     }
 }
 
@@ -67,6 +87,19 @@ impl SubAssign<u64> for UnsignedInteger {
 mod tests {
     use crate::UnsignedInteger;
 
+    #[test]
+    fn test_subtract_borrow_multiple_limbs() {
+        let mut x = UnsignedInteger::from_string_leaky(format!("1{}", "0".repeat(32)), 16, 129);
+        let y = UnsignedInteger::new(1, 64);
+
+        x -= &y;
+
+        assert_eq!(
+            UnsignedInteger::from_string_leaky("f".repeat(32), 16, 129),
+            x
+        );
+    }
+
     #[test]
     fn test_subtract() {
         let mut x = UnsignedInteger::from_string_leaky(
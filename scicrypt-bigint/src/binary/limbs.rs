@@ -0,0 +1,43 @@
+use crate::{UnsignedInteger, GMP_NUMB_BITS};
+
+impl UnsignedInteger {
+    /// Computes $x \cdot 2^{64 \cdot \text{count}}$ by moving all limbs up by `count` limbs. The
+    /// running time only depends on the size of `self`.
+    pub fn shl_limbs(&self, count: u32) -> UnsignedInteger {
+        let mut limbs = vec![0; count as usize];
+        limbs.extend_from_slice(self.limbs());
+
+        UnsignedInteger::from_limbs(&limbs, self.size_in_bits + count * GMP_NUMB_BITS)
+    }
+
+    /// Computes $\lfloor x / 2^{64 \cdot \text{count}} \rfloor$ by dropping the `count` least
+    /// significant limbs. The running time only depends on the size of `self`.
+    pub fn shr_limbs(&self, count: u32) -> UnsignedInteger {
+        let size_in_bits = self.size_in_bits.saturating_sub(count * GMP_NUMB_BITS);
+
+        match self.limbs().get(count as usize..) {
+            Some(limbs) if !limbs.is_empty() => UnsignedInteger::from_limbs(limbs, size_in_bits),
+            _ => UnsignedInteger::zero(size_in_bits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UnsignedInteger;
+
+    #[test]
+    fn test_shl_shr_limbs() {
+        let x = UnsignedInteger::from_string_leaky("125789402190859323905892".to_string(), 10, 128);
+
+        let shifted = x.shl_limbs(2);
+        assert_eq!(256, shifted.size_in_bits());
+        assert_eq!(UnsignedInteger::from(x.clone().to_rug() << 128u32), shifted);
+
+        assert_eq!(x, shifted.shr_limbs(2));
+        assert_eq!(64, shifted.shr_limbs(3).size_in_bits());
+        assert_eq!(UnsignedInteger::from(6819u64), shifted.shr_limbs(3));
+        assert!(shifted.shr_limbs(4).is_zero_leaky());
+        assert!(shifted.shr_limbs(10).is_zero_leaky());
+    }
+}
@@ -1 +1,2 @@
+mod limbs;
 mod shr;
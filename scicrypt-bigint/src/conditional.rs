@@ -0,0 +1,132 @@
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::{UnsignedInteger, GMP_NUMB_BITS};
+
+impl UnsignedInteger {
+    /// Returns `a` if `choice` is 0 and `b` if `choice` is 1, limb by limb without branching on `choice`. Both numbers must have the same size in bits.
+    pub fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        debug_assert_eq!(
+            a.size_in_bits, b.size_in_bits,
+            "both operands must have the same size in bits"
+        );
+
+        let limb_count = a.size_in_bits.div_ceil(GMP_NUMB_BITS) as usize;
+        let mut limbs = a.limbs_padded(limb_count);
+        let other_limbs = b.limbs_padded(limb_count);
+
+        // The mask is all ones if choice is 1 and all zeroes if it is 0
+        let mask = 0u64.wrapping_sub(choice.unwrap_u8() as u64);
+        for (limb, other_limb) in limbs.iter_mut().zip(&other_limbs) {
+            *limb ^= mask & (*limb ^ other_limb);
+        }
+
+        UnsignedInteger::from_limbs(&limbs, a.size_in_bits)
+    }
+
+    /// Sets `self` to `other` if `choice` is 1 and leaves it unchanged if `choice` is 0, without branching on `choice`. Both numbers must have the same size in bits.
+    pub fn conditional_assign(&mut self, other: &Self, choice: Choice) {
+        *self = UnsignedInteger::conditional_select(self, other, choice);
+    }
+
+    /// Swaps `a` and `b` if `choice` is 1 and leaves them unchanged if `choice` is 0, without branching on `choice`. Both numbers must have the same size in bits.
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        let new_a = UnsignedInteger::conditional_select(a, b, choice);
+        let new_b = UnsignedInteger::conditional_select(b, a, choice);
+
+        *a = new_a;
+        *b = new_b;
+    }
+}
+
+impl ConstantTimeEq for UnsignedInteger {
+    /// Compares the numbers by their values, padded to the larger of the two declared sizes, so that the running time only depends on those sizes and not on the values or the position where they differ.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let limb_count = (self
+            .size_in_bits
+            .max(other.size_in_bits)
+            .div_ceil(GMP_NUMB_BITS) as usize)
+            .max(self.value.size as usize)
+            .max(other.value.size as usize);
+
+        self.limbs_padded(limb_count)
+            .ct_eq(&other.limbs_padded(limb_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use subtle::{Choice, ConstantTimeEq};
+
+    use crate::UnsignedInteger;
+
+    #[test]
+    fn test_conditional_select() {
+        let a = UnsignedInteger::from_string_leaky(
+            "5378239758327583290580573280735".to_string(),
+            10,
+            103,
+        );
+        let b = UnsignedInteger::from_string_leaky(
+            "4912727741485953100001112999999".to_string(),
+            10,
+            103,
+        );
+
+        assert_eq!(
+            a,
+            UnsignedInteger::conditional_select(&a, &b, Choice::from(0))
+        );
+        assert_eq!(
+            b,
+            UnsignedInteger::conditional_select(&a, &b, Choice::from(1))
+        );
+    }
+
+    #[test]
+    fn test_conditional_swap() {
+        let a = UnsignedInteger::from_string_leaky(
+            "5378239758327583290580573280735".to_string(),
+            10,
+            103,
+        );
+        let b = UnsignedInteger::from_string_leaky(
+            "4912727741485953100001112999999".to_string(),
+            10,
+            103,
+        );
+
+        let mut x = a.clone();
+        let mut y = b.clone();
+        UnsignedInteger::conditional_swap(&mut x, &mut y, Choice::from(0));
+        assert_eq!((&a, &b), (&x, &y));
+
+        UnsignedInteger::conditional_swap(&mut x, &mut y, Choice::from(1));
+        assert_eq!((&b, &a), (&x, &y));
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let a = UnsignedInteger::from_string_leaky(
+            "5378239758327583290580573280735".to_string(),
+            10,
+            103,
+        );
+        let b = UnsignedInteger::from_string_leaky(
+            "4912727741485953100001112999999".to_string(),
+            10,
+            103,
+        );
+        let a_wide = UnsignedInteger::from_string_leaky(
+            "5378239758327583290580573280735".to_string(),
+            10,
+            300,
+        );
+
+        assert!(bool::from(a.ct_eq(&a.clone())));
+        assert!(bool::from(a.ct_eq(&a_wide)));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(bool::from(
+            UnsignedInteger::zero(64).ct_eq(&UnsignedInteger::zero(128))
+        ));
+    }
+}
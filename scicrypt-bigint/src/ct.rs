@@ -0,0 +1,97 @@
+//! Constant-time lookups in tables of precomputed values. A plain lookup `table[index]` only touches
+//! the memory of the selected entry, so an attacker who shares a cache with the computation can
+//! learn the (secret) index. These functions read every entry of the table and select the requested
+//! one with masks, so that the memory access pattern only depends on the size of the table.
+
+use gmp_mpfr_sys::gmp;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+/// Returns entry `index` of `table`, which stores `table.len() / limb_count` entries of
+/// `limb_count` limbs each consecutively. Every limb of the table is read, so that the access pattern
+/// does not depend on `index`.
+pub fn ct_select_from_table(table: &[u64], limb_count: usize, index: usize) -> Vec<u64> {
+    assert!(
+        limb_count > 0,
+        "the entries must consist of at least one limb"
+    );
+    assert_eq!(
+        table.len() % limb_count,
+        0,
+        "the table must consist of whole entries"
+    );
+    let entry_count = table.len() / limb_count;
+    assert!(index < entry_count, "the index lies outside of the table");
+
+    let mut entry = vec![0u64; limb_count];
+
+    unsafe {
+        gmp::mpn_sec_tabselect(
+            entry.as_mut_ptr(),
+            table.as_ptr(),
+            limb_count as i64,
+            entry_count as i64,
+            index as i64,
+        );
+    }
+
+    entry
+}
+
+/// Returns entry `index` of `table`, which stores `table.len() / entry_size` entries of
+/// `entry_size` bytes each consecutively. Every byte of the table is read, so that the access pattern
+/// does not depend on `index`.
+pub fn ct_select_bytes_from_table(table: &[u8], entry_size: usize, index: usize) -> Vec<u8> {
+    assert!(
+        entry_size > 0,
+        "the entries must consist of at least one byte"
+    );
+    assert_eq!(
+        table.len() % entry_size,
+        0,
+        "the table must consist of whole entries"
+    );
+    assert!(
+        index < table.len() / entry_size,
+        "the index lies outside of the table"
+    );
+
+    let mut entry = vec![0u8; entry_size];
+
+    for (i, candidate) in table.chunks_exact(entry_size).enumerate() {
+        let choice = (i as u64).ct_eq(&(index as u64));
+
+        for (byte, candidate_byte) in entry.iter_mut().zip(candidate) {
+            byte.conditional_assign(candidate_byte, choice);
+        }
+    }
+
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ct::{ct_select_bytes_from_table, ct_select_from_table};
+
+    #[test]
+    fn test_ct_select_from_table() {
+        let table: Vec<u64> = (0..12).collect();
+
+        assert_eq!(vec![0, 1, 2], ct_select_from_table(&table, 3, 0));
+        assert_eq!(vec![6, 7, 8], ct_select_from_table(&table, 3, 2));
+        assert_eq!(vec![9, 10, 11], ct_select_from_table(&table, 3, 3));
+    }
+
+    #[test]
+    fn test_ct_select_bytes_from_table() {
+        let table = b"abcdefgh";
+
+        assert_eq!(b"ab".to_vec(), ct_select_bytes_from_table(table, 2, 0));
+        assert_eq!(b"gh".to_vec(), ct_select_bytes_from_table(table, 2, 3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ct_select_out_of_bounds() {
+        ct_select_from_table(&[1, 2, 3, 4], 2, 2);
+    }
+}
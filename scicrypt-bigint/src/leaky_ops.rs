@@ -7,13 +7,13 @@ use crate::UnsignedInteger;
 impl UnsignedInteger {
     /// Checks if `self` equals `other`. This function is not constant-time.
     pub fn eq_leaky(&self, other: &Self) -> bool {
-        unsafe { gmp::mpz_cmp(&self.value, &other.value) == 0 }
+        unsafe { gmp::mpz_cmp(&self.normalized_view(), &other.normalized_view()) == 0 }
     }
 
     /// Compares `self` to `other`, and returns whether it is less than `other`, equal, or greater. This function is not constant-time.
     pub fn partial_cmp_leaky(&self, other: &Self) -> Option<Ordering> {
         unsafe {
-            match gmp::mpz_cmp(&self.value, &other.value) {
+            match gmp::mpz_cmp(&self.normalized_view(), &other.normalized_view()) {
                 0 => Some(Ordering::Equal),
                 1.. => Some(Ordering::Greater),
                 _ => Some(Ordering::Less),
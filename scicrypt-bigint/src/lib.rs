@@ -11,16 +11,26 @@ mod scratch;
 
 mod arithmetic;
 mod binary;
+mod conditional;
+/// Constant-time lookups in tables of precomputed values.
+pub mod ct;
 mod leaky_ops;
 mod modular;
+#[cfg(test)]
+mod properties;
+/// A dudect-style statistical test for data-dependent timing.
+#[cfg(feature = "timing-tests")]
+pub mod timing;
+
+pub use modular::{BarrettContext, FixedBaseTable, LazyProduct};
+pub use subtle::Choice;
 
 use std::{
     cmp::min,
-    ffi::{CStr, CString},
-    fmt::{Debug, Display},
+    ffi::{c_char, CStr, CString},
+    fmt::{Binary, Debug, Display, LowerHex, Octal, UpperHex},
     hash::Hash,
     mem::{ManuallyDrop, MaybeUninit},
-    ptr::null_mut,
 };
 
 use gmp_mpfr_sys::gmp::{self, mpz_fac_ui, mpz_t};
@@ -34,17 +44,35 @@ const GMP_NUMB_BITS: u32 = 64;
 
 impl Display for UnsignedInteger {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        unsafe {
-            if self.is_zero_leaky() {
-                return f.pad_integral(true, "", "0");
-            }
+        f.pad_integral(true, "", &self.to_string_radix_leaky(10))
+    }
+}
 
-            let c_buf = gmp::mpz_get_str(null_mut(), 10, &self.value);
-            let c_str = CStr::from_ptr(c_buf);
-            let str_slice: &str = c_str.to_str().unwrap();
-            let str = str_slice.to_owned();
-            f.pad_integral(true, "", str.trim_start_matches('0'))
-        }
+impl LowerHex for UnsignedInteger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_string_radix_leaky(16))
+    }
+}
+
+impl UpperHex for UnsignedInteger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(
+            true,
+            "0x",
+            &self.to_string_radix_leaky(16).to_ascii_uppercase(),
+        )
+    }
+}
+
+impl Octal for UnsignedInteger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0o", &self.to_string_radix_leaky(8))
+    }
+}
+
+impl Binary for UnsignedInteger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0b", &self.to_string_radix_leaky(2))
     }
 }
 
@@ -100,6 +128,8 @@ pub struct UnsignedInteger {
 }
 
 unsafe impl Send for UnsignedInteger {}
+// Shared references only ever read the underlying limbs
+unsafe impl Sync for UnsignedInteger {}
 
 impl Drop for UnsignedInteger {
     fn drop(&mut self) {
@@ -119,6 +149,10 @@ impl Serialize for UnsignedInteger {
 impl<'de> Deserialize<'de> for UnsignedInteger {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<UnsignedInteger, D::Error> {
         let integer = Integer::deserialize(deserializer)?;
+        if integer < 0 {
+            return Err(serde::de::Error::custom("expected a non-negative integer"));
+        }
+
         Ok(UnsignedInteger::from(integer))
     }
 }
@@ -133,6 +167,53 @@ impl UnsignedInteger {
         self.size_in_bits
     }
 
+    /// The declared size of this number in bits, which is the same as `size_in_bits`. Arithmetic only ever looks at this declared size, which is derived from the sizes of the operands and never from their values, so it is safe to branch on.
+    pub fn declared_size_in_bits(&self) -> u32 {
+        self.size_in_bits
+    }
+
+    /// The number of significant bits of the actual value, which is at most the declared size. This function is not constant-time, because it depends on the value.
+    pub fn significant_bits_leaky(&self) -> u32 {
+        let limbs = self.limbs();
+
+        match limbs.iter().rposition(|&limb| limb != 0) {
+            Some(i) => i as u32 * GMP_NUMB_BITS + GMP_NUMB_BITS - limbs[i].leading_zeros(),
+            None => 0,
+        }
+    }
+
+    /// Changes the declared size of this number to `size_in_bits`, adding or removing limbs as needed. Panics if the value does not fit in `size_in_bits` bits. The running time only depends on the old and new sizes.
+    pub fn resize_to(&mut self, size_in_bits: u32) {
+        let limb_count = size_in_bits.div_ceil(GMP_NUMB_BITS) as usize;
+        let mut limbs = self.limbs_padded(limb_count.max(self.value.size as usize));
+
+        // Combine all bits above the new size without branching on them
+        let mut overflow = limbs[limb_count..].iter().fold(0, |acc, limb| acc | limb);
+        if !size_in_bits.is_multiple_of(GMP_NUMB_BITS) {
+            overflow |= limbs[limb_count - 1] >> (size_in_bits % GMP_NUMB_BITS);
+        }
+        assert_eq!(overflow, 0, "the value does not fit in `size_in_bits` bits");
+
+        limbs.truncate(limb_count);
+        *self = if limbs.is_empty() {
+            UnsignedInteger::zero(size_in_bits)
+        } else {
+            UnsignedInteger::from_limbs(&limbs, size_in_bits)
+        };
+    }
+
+    /// Checks in debug builds that the value fits in the declared size, and that the representation does not use more limbs than the declared size requires.
+    pub(crate) fn debug_assert_invariants(&self) {
+        debug_assert!(
+            self.value.size as u32 <= self.size_in_bits.div_ceil(GMP_NUMB_BITS).max(1),
+            "the representation uses more limbs than the declared size requires"
+        );
+        debug_assert!(
+            self.significant_bits_leaky() <= self.size_in_bits,
+            "the value exceeds its declared size in bits"
+        );
+    }
+
     /// Creates a new `UnsignedInteger` that equals `integer` with the given `size_in_bits`. `size_in_bits` must be a tight estimate; it may only exceed the actual number of bits until the next multiple of 64.
     pub fn new(integer: u64, size_in_bits: u32) -> Self {
         let mut res = UnsignedInteger::zero(size_in_bits);
@@ -157,6 +238,84 @@ impl UnsignedInteger {
         }
     }
 
+    /// Creates the number 1 represented using `size_in_bits` bits, padded with zero-limbs.
+    pub(crate) fn one(size_in_bits: u32) -> Self {
+        let limb_count = size_in_bits.div_ceil(GMP_NUMB_BITS) as i64;
+        let mut number = UnsignedInteger::zero(size_in_bits);
+
+        unsafe {
+            let limbs = gmp::mpz_limbs_write(&mut number.value, limb_count);
+
+            for i in 0..limb_count as isize {
+                limbs.offset(i).write(0);
+            }
+            limbs.write(1);
+
+            number.value.size = limb_count as i32;
+        }
+
+        number
+    }
+
+    /// Creates a number from its little-endian `limbs`, with the given `size_in_bits`.
+    pub(crate) fn from_limbs(limbs: &[u64], size_in_bits: u32) -> Self {
+        let mut number =
+            UnsignedInteger::zero(size_in_bits.max(limbs.len() as u32 * GMP_NUMB_BITS));
+
+        unsafe {
+            let destination = gmp::mpz_limbs_write(&mut number.value, limbs.len() as i64);
+            std::ptr::copy_nonoverlapping(limbs.as_ptr(), destination, limbs.len());
+        }
+
+        number.value.size = limbs.len() as i32;
+        number.size_in_bits = size_in_bits;
+        number
+    }
+
+    /// Creates a number with `size_in_bits` bits from its little-endian `bytes`, for example to convert from the representation of another library. This function is constant-time in the value of the bytes.
+    pub fn from_le_bytes(bytes: &[u8], size_in_bits: u32) -> Self {
+        let limb_count = size_in_bits.div_ceil(GMP_NUMB_BITS) as usize;
+        assert!(
+            bytes.len() <= limb_count * 8,
+            "the bytes do not fit in `size_in_bits` bits"
+        );
+
+        let mut limbs: Vec<u64> = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut limb = [0u8; 8];
+                limb[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(limb)
+            })
+            .collect();
+        limbs.resize(limb_count, 0);
+
+        UnsignedInteger::from_limbs(&limbs, size_in_bits)
+    }
+
+    /// Returns the little-endian bytes of this number, padded with zeroes to a whole number of limbs.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.limbs()
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect()
+    }
+
+    /// The limbs of this number, least significant first.
+    pub(crate) fn limbs(&self) -> &[u64] {
+        match self.value.size {
+            0 => &[],
+            size => unsafe { std::slice::from_raw_parts(self.value.d.as_ptr(), size as usize) },
+        }
+    }
+
+    /// The limbs of this number, least significant first, padded with zero-limbs to `limb_count` limbs.
+    pub(crate) fn limbs_padded(&self, limb_count: usize) -> Vec<u64> {
+        let mut limbs = self.limbs().to_vec();
+        limbs.resize(limb_count, 0);
+        limbs
+    }
+
     /// Creates a BigInteger from a value given as a `string` in a certain `base`. The `size_in_bits` should not be lower than the actual value encoded.
     pub fn from_string_leaky(string: String, base: i32, size_in_bits: u32) -> UnsignedInteger {
         // TODO: debug_assert!() that the size_in_bits is not smaller than the actual value
@@ -178,10 +337,8 @@ impl UnsignedInteger {
         }
     }
 
-    /// Generates a random unsigned number with `bits` bits. `bits` should be a multiple of 8.
+    /// Generates a random unsigned number with `bits` bits.
     pub fn random<R: SecureRng>(bits: u32, rng: &mut GeneralRng<R>) -> Self {
-        debug_assert!((bits % 8) == 0, "`bits` should be a multiple of 8");
-
         unsafe {
             let mut number = UnsignedInteger::zero(bits);
             let limbs =
@@ -193,6 +350,12 @@ impl UnsignedInteger {
                 limbs.offset(i).write(u64::from_be_bytes(bytes));
             }
 
+            // Clear the bits of the last limb that lie above the declared size
+            if !bits.is_multiple_of(GMP_NUMB_BITS) {
+                let last = limbs.offset(bits.div_ceil(GMP_NUMB_BITS) as isize - 1);
+                last.write(last.read() & (u64::MAX >> (GMP_NUMB_BITS - bits % GMP_NUMB_BITS)));
+            }
+
             number.value.size = bits.div_ceil(GMP_NUMB_BITS) as i32;
             number
         }
@@ -200,11 +363,21 @@ impl UnsignedInteger {
 
     /// Generates a random unsigned number below `limit`.
     pub fn random_below<R: SecureRng>(limit: &UnsignedInteger, rng: &mut GeneralRng<R>) -> Self {
+        // `random` fills whole limbs, so we clear the bits above the size of the limit. Otherwise,
+        // almost every draw would be rejected for limits that do not fill their last limb.
+        let bits = limit.size_in_bits.div_ceil(GMP_NUMB_BITS) * GMP_NUMB_BITS;
+
         // Simple rejection sampling, not constant_time
         loop {
-            let random = UnsignedInteger::random(limit.size_in_bits, rng);
+            let mut random = UnsignedInteger::random(bits, rng);
+            for bit_index in limit.size_in_bits..bits {
+                random.clear_bit_leaky(bit_index);
+            }
+            // Clearing bits normalizes the value, but it must keep all of its limbs
+            random.value.size = bits.div_ceil(GMP_NUMB_BITS) as i32;
 
             if random.leak() < limit.leak() {
+                random.size_in_bits = limit.size_in_bits;
                 break random;
             }
         }
@@ -226,12 +399,68 @@ impl UnsignedInteger {
 
     /// Computes self modulo a u64 number. This function is not constant-time.
     pub fn mod_u_leaky(&self, modulus: u64) -> u64 {
-        unsafe { gmp::mpz_fdiv_ui(&self.value, modulus) }
+        unsafe { gmp::mpz_fdiv_ui(&self.normalized_view(), modulus) }
     }
 
     /// Returns true when this number is prime. This function is not constant-time. Internally it uses Baille-PSW.
     pub fn is_probably_prime_leaky(&self) -> bool {
-        unsafe { gmp::mpz_probab_prime_p(&self.value, 25) > 0 }
+        self.is_probably_prime_with_leaky(1)
+    }
+
+    /// Returns true when this number is prime. This function is not constant-time. Internally it uses Baille-PSW, followed by `repetitions` Miller-Rabin rounds with random bases. Each round reduces the probability that a composite number passes by a factor of at least 4.
+    pub fn is_probably_prime_with_leaky(&self, repetitions: u32) -> bool {
+        // GMP performs Baille-PSW followed by reps - 24 Miller-Rabin rounds. It expects a normalized
+        // number, which the result of a shift is not when the most significant limb became zero.
+        unsafe { gmp::mpz_probab_prime_p(&self.normalized_view(), repetitions as i32 + 24) > 0 }
+    }
+
+    /// Returns the digits of this number in the given `base`, which must lie between 2 and 62, without a prefix and without leading zeros. Bases up to 36 use lowercase letters. This function is not constant-time, so only use it on public values or for debugging.
+    pub fn to_string_radix_leaky(&self, base: i32) -> String {
+        assert!(
+            (2..=62).contains(&base),
+            "the base must lie between 2 and 62"
+        );
+
+        // Leading zero limbs are allowed by our representation but not by `mpz_get_str`
+        let view = self.normalized_view();
+        if view.size == 0 {
+            return "0".to_string();
+        }
+
+        unsafe {
+            // One byte for a potential minus sign and one for the null terminator
+            let mut buffer = vec![0u8; gmp::mpz_sizeinbase(&view, base) + 2];
+            gmp::mpz_get_str(buffer.as_mut_ptr() as *mut c_char, base, &view);
+
+            CStr::from_ptr(buffer.as_ptr() as *const c_char)
+                .to_str()
+                .unwrap()
+                .to_owned()
+        }
+    }
+
+    /// Computes the Jacobi symbol $\left(\frac{\text{self}}{n}\right)$, which is 1, 0 or -1. The modulus `n` must be odd. This function is not constant-time, so only use it on public values.
+    pub fn jacobi_leaky(&self, n: &UnsignedInteger) -> i32 {
+        assert_eq!(
+            n.mod_u_leaky(2),
+            1,
+            "the Jacobi symbol requires an odd modulus"
+        );
+
+        unsafe { gmp::mpz_jacobi(&self.normalized_view(), &n.normalized_view()) }
+    }
+
+    /// A view on the same limbs without the leading zero limbs, which most GMP functions require.
+    fn normalized_view(&self) -> mpz_t {
+        let significant_limbs = self
+            .limbs()
+            .iter()
+            .rposition(|&limb| limb != 0)
+            .map_or(0, |index| index + 1);
+
+        let mut view = self.value;
+        view.size = significant_limbs as i32;
+        view
     }
 
     /// Returns true if self == 0. This can be faster than checking equality.
@@ -409,6 +638,82 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_significant_bits_and_resize() {
+        let mut x = UnsignedInteger::new(0b1011, 64);
+        assert_eq!(64, x.declared_size_in_bits());
+        assert_eq!(4, x.significant_bits_leaky());
+
+        x.resize_to(200);
+        assert_eq!(200, x.declared_size_in_bits());
+        assert_eq!(4, x.value.size as u32);
+        assert_eq!(UnsignedInteger::from(0b1011u64), x);
+
+        x.resize_to(4);
+        assert_eq!(4, x.size_in_bits());
+        assert_eq!(1, x.value.size);
+        assert_eq!(UnsignedInteger::from(0b1011u64), x);
+
+        assert_eq!(0, UnsignedInteger::zero(128).significant_bits_leaky());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_resize_too_small() {
+        let mut x = UnsignedInteger::new(0b1011, 64);
+        x.resize_to(3);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_negative() {
+        let negative = bincode::serialize(&rug::Integer::from(-5)).unwrap();
+        assert!(bincode::deserialize::<UnsignedInteger>(&negative).is_err());
+
+        let positive = bincode::serialize(&UnsignedInteger::from(5u64)).unwrap();
+        assert_eq!(
+            UnsignedInteger::from(5u64),
+            bincode::deserialize::<UnsignedInteger>(&positive).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_radix() {
+        let x = UnsignedInteger::new(0xbeef, 64);
+
+        assert_eq!("48879", format!("{x}"));
+        assert_eq!("beef", format!("{x:x}"));
+        assert_eq!("0xBEEF", format!("{x:#X}"));
+        assert_eq!("0o137357", format!("{x:#o}"));
+        assert_eq!("1011111011101111", format!("{x:b}"));
+        assert_eq!("000048879", format!("{x:09}"));
+    }
+
+    #[test]
+    fn test_format_leading_zero_limbs() {
+        let x = &UnsignedInteger::new(5, 64) * &UnsignedInteger::new(3, 64);
+        assert_eq!(2, x.value.size);
+
+        assert_eq!("15", format!("{x}"));
+        assert_eq!("0", UnsignedInteger::zero(128).to_string_radix_leaky(36));
+    }
+
+    #[test]
+    fn test_le_bytes_roundtrip() {
+        let bytes: Vec<u8> = (1..=20).collect();
+        let x = UnsignedInteger::from_le_bytes(&bytes, 160);
+
+        assert_eq!(
+            UnsignedInteger::from_string_leaky(
+                "14131211100f0e0d0c0b0a090807060504030201".to_string(),
+                16,
+                160
+            ),
+            x
+        );
+        assert_eq!(bytes, x.to_le_bytes()[..20]);
+        assert!(x.to_le_bytes()[20..].iter().all(|&byte| byte == 0));
+    }
+
     #[test]
     fn test_hash_eq() {
         let a = UnsignedInteger::from(123u64);
@@ -456,6 +761,33 @@ mod tests {
         assert_eq!(a.value.size, 1024 / GMP_NUMB_BITS as i32);
     }
 
+    #[test]
+    fn test_random_partial_limb() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        for bits in [1, 63, 65, 100] {
+            let a = UnsignedInteger::random(bits, &mut rng);
+
+            assert!(a.significant_bits_leaky() <= bits);
+            assert_eq!(a.value.size, bits.div_ceil(GMP_NUMB_BITS) as i32);
+        }
+
+        assert!(UnsignedInteger::random(0, &mut rng).is_zero_leaky());
+    }
+
+    #[test]
+    fn test_random_below_small_limit() {
+        let mut rng = GeneralRng::new(OsRng);
+        let limit = UnsignedInteger::from(17u64);
+
+        for _ in 0..100 {
+            let a = UnsignedInteger::random_below(&limit, &mut rng);
+
+            assert!(a.leak() < limit.leak());
+            assert_eq!(limit.size_in_bits(), a.size_in_bits());
+        }
+    }
+
     #[test]
     fn test_shift_right_assign() {
         let mut a = UnsignedInteger::new(129, 128);
@@ -510,4 +842,26 @@ mod tests {
 
         assert!(res.is_none());
     }
+
+    #[test]
+    fn test_is_probably_prime_with() {
+        // 561 is a Carmichael number
+        assert!(!UnsignedInteger::from(561u64).is_probably_prime_with_leaky(10));
+        assert!(UnsignedInteger::from(65537u64).is_probably_prime_with_leaky(10));
+    }
+
+    #[test]
+    fn test_jacobi() {
+        let n = UnsignedInteger::from(21u64);
+
+        // 4 is a square, 5 is not a square modulo 3 or 7, 2 is a square modulo 7 but not modulo 3
+        assert_eq!(1, UnsignedInteger::from(4u64).jacobi_leaky(&n));
+        assert_eq!(1, UnsignedInteger::from(5u64).jacobi_leaky(&n));
+        assert_eq!(-1, UnsignedInteger::from(2u64).jacobi_leaky(&n));
+        assert_eq!(0, UnsignedInteger::from(14u64).jacobi_leaky(&n));
+
+        // Leading zero limbs do not change the result
+        let padded = UnsignedInteger::from_limbs(&[2, 0, 0, 0], 4 * GMP_NUMB_BITS);
+        assert_eq!(-1, padded.jacobi_leaky(&n));
+    }
 }
@@ -0,0 +1,150 @@
+use gmp_mpfr_sys::gmp;
+use serde::{Deserialize, Serialize};
+
+use crate::{scratch::Scratch, UnsignedInteger, GMP_NUMB_BITS};
+
+/// Precomputed values for Barrett reduction modulo a fixed modulus. Reducing a product modulo the same modulus many times is faster with a `BarrettContext`, because every reduction only takes two multiplications instead of a division.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BarrettContext {
+    modulus: UnsignedInteger,
+    mu: UnsignedInteger,
+}
+
+impl BarrettContext {
+    /// Precomputes $\mu = \lfloor b^{2k} / m \rfloor$ for the `modulus` $m$ of $k$ limbs, where $b = 2^{64}$. The modulus' size in bits must be tight with its actual size. This function is not constant-time in the modulus.
+    pub fn new(modulus: &UnsignedInteger) -> Self {
+        debug_assert!(modulus.value.size.is_positive());
+        debug_assert_eq!(
+            modulus.size_in_bits.div_ceil(GMP_NUMB_BITS) as i32,
+            modulus.value.size,
+            "the modulus' size in bits must be tight with its actual size"
+        );
+
+        let k = modulus.value.size as u32;
+
+        let mut power = UnsignedInteger::zero(2 * k * GMP_NUMB_BITS + 1);
+        power.set_bit_leaky(2 * k * GMP_NUMB_BITS);
+
+        let mut mu = UnsignedInteger::zero((k + 1) * GMP_NUMB_BITS);
+        unsafe {
+            gmp::mpz_tdiv_q(&mut mu.value, &power.value, &modulus.value);
+        }
+
+        BarrettContext {
+            modulus: modulus.clone(),
+            mu: UnsignedInteger::from_limbs(&mu.limbs_padded(k as usize + 1), mu.size_in_bits),
+        }
+    }
+
+    /// The modulus that this context reduces by.
+    pub fn modulus(&self) -> &UnsignedInteger {
+        &self.modulus
+    }
+
+    /// Computes `x` modulo the modulus. Barrett reduction applies when `x` has at most $2k$ limbs (e.g. the product of two reduced values), and takes time that scales with the size of the modulus. Larger inputs fall back to a regular division.
+    pub fn reduce(&self, x: &UnsignedInteger) -> UnsignedInteger {
+        let k = self.modulus.value.size as usize;
+        if x.value.size as usize > 2 * k {
+            return x.clone() % &self.modulus;
+        }
+
+        let x_limbs = x.limbs_padded(2 * k);
+        let modulus_limbs = self.modulus.limbs_padded(k + 1);
+
+        // q = floor(floor(x / b^(k-1)) * mu / b^(k+1)) estimates the quotient with an error of at most 2
+        let q2 = sec_mul(&x_limbs[k - 1..], self.mu.limbs());
+        let q3 = &q2[k + 1..];
+
+        // r = (x - q * m) mod b^(k+1)
+        let qm = sec_mul(q3, self.modulus.limbs());
+        let mut r = x_limbs[..k + 1].to_vec();
+        let mut difference = vec![0u64; k + 1];
+
+        unsafe {
+            gmp::mpn_sub_n(r.as_mut_ptr(), r.as_ptr(), qm.as_ptr(), (k + 1) as i64);
+
+            // At most two conditional subtractions are necessary to end up below the modulus
+            for _ in 0..2 {
+                let borrow = gmp::mpn_sub_n(
+                    difference.as_mut_ptr(),
+                    r.as_ptr(),
+                    modulus_limbs.as_ptr(),
+                    (k + 1) as i64,
+                );
+                gmp::mpn_cnd_sub_n(
+                    1 - borrow,
+                    r.as_mut_ptr(),
+                    r.as_ptr(),
+                    modulus_limbs.as_ptr(),
+                    (k + 1) as i64,
+                );
+            }
+        }
+
+        UnsignedInteger::from_limbs(&r[..k], self.modulus.size_in_bits)
+    }
+}
+
+fn sec_mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    debug_assert!(a.len() >= b.len());
+
+    let mut result = vec![0u64; a.len() + b.len()];
+
+    unsafe {
+        let scratch_size =
+            gmp::mpn_sec_mul_itch(a.len() as i64, b.len() as i64) as usize * GMP_NUMB_BITS as usize;
+
+        let mut scratch = Scratch::new(scratch_size);
+
+        gmp::mpn_sec_mul(
+            result.as_mut_ptr(),
+            a.as_ptr(),
+            a.len() as i64,
+            b.as_ptr(),
+            b.len() as i64,
+            scratch.as_mut(),
+        );
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BarrettContext, UnsignedInteger};
+
+    #[test]
+    fn test_reduce_small() {
+        let m = UnsignedInteger::from(11u64);
+        let context = BarrettContext::new(&m);
+
+        let x = &UnsignedInteger::new(9, 64) * &UnsignedInteger::new(7, 64);
+
+        assert_eq!(UnsignedInteger::from(8u64), context.reduce(&x));
+    }
+
+    #[test]
+    fn test_reduce_matches_rem() {
+        let a = UnsignedInteger::from_string_leaky("10539499294995885839929294349858893482048503424233434382948939585380202480248428858035020202848894983349030959432221114892829832832820310342164784362849732894729586478637897481742109741907489237586753826748420497102914324234241221888888487774774646263775738582835875726672378181992949120102959881821".to_string(), 10, 1024);
+        let b = UnsignedInteger::from_string_leaky("92848022024833655041372304737256052921065477715975001419347548380734496823522565044177931242947122534563813415992433917108481569319894167972639736788613656007853719476736625612543893748136536594494005487213485785676333621181690463942417781763743640447405597892807333854156631166426238815716390011586838580891".to_string(), 10, 1024);
+        let m = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+        let context = BarrettContext::new(&m);
+
+        let product = &a * &b;
+
+        assert_eq!(product.clone() % &m, context.reduce(&product));
+    }
+
+    #[test]
+    fn test_reduce_limb_boundary() {
+        let m = UnsignedInteger::from(11u64);
+        let context = BarrettContext::new(&m);
+
+        // The largest input of 2k limbs, and the smallest of 2k + 1 limbs
+        let largest = UnsignedInteger::from_string_leaky("f".repeat(32), 16, 128);
+        let smallest = UnsignedInteger::from_string_leaky(format!("1{}", "0".repeat(32)), 16, 129);
+
+        assert_eq!(largest.clone() % &m, context.reduce(&largest));
+        assert_eq!(smallest.clone() % &m, context.reduce(&smallest));
+    }
+}
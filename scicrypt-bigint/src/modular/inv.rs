@@ -67,9 +67,96 @@ impl UnsignedInteger {
                 return None;
             }
 
+            // The inverse may have fewer limbs than the modulus, so clear the limbs above it
+            let size = self.value.size;
+            gmp::mpz_realloc2(
+                &mut self.value,
+                modulus.value.size as u64 * GMP_NUMB_BITS as u64,
+            );
+            for i in size..modulus.value.size {
+                *self.value.d.as_ptr().offset(i as isize) = 0;
+            }
+
             self.value.size = modulus.value.size;
             self.size_in_bits = modulus.size_in_bits;
             Some(self)
         }
     }
+
+    /// Computes the inverses of all `values` modulo `modulus` using Montgomery's trick, which
+    /// replaces all but one of the inversions by three multiplications each. Returns None if any of
+    /// the values has no inverse, including when one of them is zero. `modulus` must be odd.
+    pub fn batch_invert_mod(
+        values: &[UnsignedInteger],
+        modulus: &UnsignedInteger,
+    ) -> Option<Vec<UnsignedInteger>> {
+        // prefixes[i] holds the product of the first i values
+        let mut prefixes = Vec::with_capacity(values.len() + 1);
+        prefixes.push(UnsignedInteger::one(modulus.size_in_bits));
+        for value in values {
+            // Zero has no inverse, and it cannot be multiplied because it has no limbs
+            if value.is_zero_leaky() {
+                return None;
+            }
+
+            let prefix = (prefixes.last().unwrap() * value) % modulus;
+            prefixes.push(prefix);
+        }
+
+        let mut inverse = prefixes.pop().unwrap().invert(modulus)?;
+
+        // Peel off one value at a time, from the last to the first
+        let mut inverses = Vec::with_capacity(values.len());
+        for (value, prefix) in values.iter().zip(prefixes).rev() {
+            inverses.push((&inverse * &prefix) % modulus);
+            inverse = (&inverse * value) % modulus;
+        }
+
+        inverses.reverse();
+        Some(inverses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UnsignedInteger;
+
+    #[test]
+    fn test_batch_invert_mod() {
+        let modulus = UnsignedInteger::from_string_leaky(
+            "340282366920938463463374607431768211297".to_string(),
+            10,
+            128,
+        );
+        let values: Vec<UnsignedInteger> = [2u64, 3, 12345, 98765432123456789]
+            .iter()
+            .map(|x| UnsignedInteger::from(*x))
+            .collect();
+
+        let inverses = UnsignedInteger::batch_invert_mod(&values, &modulus).unwrap();
+
+        assert_eq!(values.len(), inverses.len());
+        for (value, inverse) in values.iter().zip(&inverses) {
+            assert_eq!(UnsignedInteger::from(1u64), (value * inverse) % &modulus);
+        }
+    }
+
+    #[test]
+    fn test_batch_invert_mod_not_invertible() {
+        let modulus = UnsignedInteger::from(15u64);
+        let values = [UnsignedInteger::from(2u64), UnsignedInteger::from(5u64)];
+
+        assert_eq!(None, UnsignedInteger::batch_invert_mod(&values, &modulus));
+        assert_eq!(
+            None,
+            UnsignedInteger::batch_invert_mod(
+                &[UnsignedInteger::from(2u64), UnsignedInteger::zero(64)],
+                &modulus
+            )
+        );
+        assert_eq!(
+            Some(vec![]),
+            UnsignedInteger::batch_invert_mod(&[], &modulus)
+        );
+    }
 }
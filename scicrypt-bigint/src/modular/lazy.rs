@@ -0,0 +1,100 @@
+use crate::{BarrettContext, UnsignedInteger};
+
+/// Accumulates the product of many factors modulo a fixed modulus, reducing only once every `batch_size` multiplications instead of after every one. The unreduced product grows by the size of the modulus with every factor, so larger batches take fewer reductions but longer multiplications. For products of hundreds of factors, batches of 4 to 8 are typically fastest.
+#[derive(Debug)]
+pub struct LazyProduct<'a> {
+    context: &'a BarrettContext,
+    accumulator: Option<UnsignedInteger>,
+    pending: usize,
+    batch_size: usize,
+}
+
+impl<'a> LazyProduct<'a> {
+    /// Starts an empty product modulo the modulus of `context`, which is reduced after every `batch_size` multiplications. `batch_size` must be at least 1.
+    pub fn new(context: &'a BarrettContext, batch_size: usize) -> Self {
+        assert!(batch_size >= 1, "the batch size must be at least 1");
+
+        LazyProduct {
+            context,
+            accumulator: None,
+            pending: 0,
+            batch_size,
+        }
+    }
+
+    /// Multiplies the product by `factor`, which must be reduced modulo the modulus.
+    pub fn mul(&mut self, factor: &UnsignedInteger) {
+        let product = match &self.accumulator {
+            Some(accumulator) => accumulator * factor,
+            None => factor.clone(),
+        };
+
+        self.accumulator = Some(product);
+        self.pending += 1;
+
+        if self.pending >= self.batch_size {
+            self.reduce();
+        }
+    }
+
+    /// Returns the product reduced modulo the modulus, or `None` if no factors were multiplied.
+    pub fn finish(mut self) -> Option<UnsignedInteger> {
+        self.reduce();
+        self.accumulator
+    }
+
+    fn reduce(&mut self) {
+        if let Some(accumulator) = self.accumulator.take() {
+            let modulus = self.context.modulus();
+
+            // Products of two factors fit the Barrett reduction, which is faster than a division
+            self.accumulator = Some(if accumulator.value.size <= 2 * modulus.value.size {
+                self.context.reduce(&accumulator)
+            } else {
+                accumulator % modulus
+            });
+        }
+
+        self.pending = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BarrettContext, LazyProduct, UnsignedInteger};
+
+    #[test]
+    fn test_lazy_product_matches_eager_product() {
+        let m = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+        let context = BarrettContext::new(&m);
+
+        let factors: Vec<_> = (0..13u64)
+            .map(|i| {
+                UnsignedInteger::from_string_leaky(
+                    "92848022024833655041372304737256052921065477715975001419347548380734496823522565044177931242947122534563813415992433917108481569319894167972639736788613656007853719476736625612543893748136536594494005487213485785676333621181690463942417781763743640447405597892807333854156631166426238815716390011586838580891".to_string(),
+                    10,
+                    1024,
+                ) + i
+            })
+            .collect();
+
+        let expected = factors[1..]
+            .iter()
+            .fold(factors[0].clone(), |product, factor| {
+                context.reduce(&(&product * factor))
+            });
+
+        for batch_size in [1, 2, 3, 8, 20] {
+            let mut lazy = LazyProduct::new(&context, batch_size);
+            for factor in &factors {
+                lazy.mul(factor);
+            }
+
+            let result = lazy.finish().unwrap();
+            assert_eq!(result.size_in_bits(), 1024);
+            assert_eq!(expected, result);
+        }
+
+        assert_eq!(None, LazyProduct::new(&context, 4).finish());
+    }
+}
@@ -1,3 +1,10 @@
+mod barrett;
 mod inv;
+mod lazy;
 mod pow;
 mod rem;
+mod table;
+
+pub use barrett::BarrettContext;
+pub use lazy::LazyProduct;
+pub use table::FixedBaseTable;
@@ -61,6 +61,40 @@ impl UnsignedInteger {
             result
         }
     }
+
+    /// Compute `self` to the power `exponent` modulo an odd `modulus`, like `pow_mod`, but using GMP's variable-time exponentiation. This is considerably faster for short exponents, such as the public exponent of RSA. This function is not constant-time: its running time depends on the value of the exponent and possibly of the base.
+    pub fn pow_mod_leaky(
+        &self,
+        exponent: &UnsignedInteger,
+        modulus: &UnsignedInteger,
+    ) -> UnsignedInteger {
+        debug_assert!(!modulus.is_zero_leaky(), "the modulus must not be 0");
+
+        let mut result = UnsignedInteger::init(modulus.value.size);
+
+        unsafe {
+            gmp::mpz_powm(
+                &mut result.value,
+                &self.value,
+                &exponent.value,
+                &modulus.value,
+            );
+
+            // The result may have fewer limbs than the modulus, so clear the limbs above it
+            let size = result.value.size;
+            gmp::mpz_realloc2(
+                &mut result.value,
+                modulus.value.size as u64 * GMP_NUMB_BITS as u64,
+            );
+            for i in size..modulus.value.size {
+                *result.value.d.as_ptr().offset(i as isize) = 0;
+            }
+
+            result.value.size = modulus.value.size;
+            result.size_in_bits = modulus.size_in_bits;
+            result
+        }
+    }
 }
 
 #[cfg(test)]
@@ -103,6 +137,20 @@ mod tests {
         assert_eq!(res, expected);
     }
 
+    #[test]
+    fn test_powmod_leaky_matches_powmod() {
+        let b = UnsignedInteger::from_string_leaky("92848022024833655041372304737256052921065477715975001419347548380734496823522565044177931242947122534563813415992433917108481569319894167972639736788613656007853719476736625612543893748136536594494005487213485785676333621181690463942417781763743640447405597892807333854156631166426238815716390011586838580891".to_string(), 10, 1024);
+        let m = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+
+        for exponent in [0u64, 1, 105, 65537] {
+            let e = UnsignedInteger::from(exponent);
+            let res = b.pow_mod_leaky(&e, &m);
+
+            assert_eq!(res.size_in_bits(), 1024);
+            assert_eq!(res, b.pow_mod(&e, &m));
+        }
+    }
+
     #[test]
     fn test_powmod_mini() {
         let b = UnsignedInteger::from(3u64);
@@ -46,6 +46,8 @@ impl RemAssign<&UnsignedInteger> for UnsignedInteger {
             self.value.size = rhs.value.size;
             self.size_in_bits = rhs.size_in_bits;
         }
+
+        self.debug_assert_invariants();
     }
 }
 
@@ -58,6 +60,49 @@ impl Rem<&UnsignedInteger> for UnsignedInteger {
     }
 }
 
+impl UnsignedInteger {
+    /// Computes self modulo each of the `moduli`, which gives the same result as calling
+    /// `mod_u_leaky` for every modulus. Consecutive moduli whose product fits in 64 bits are
+    /// grouped, and the full number is only divided once per group by the product of the group.
+    /// That remainder is then reduced by every modulus in the group using native arithmetic. For
+    /// the primes below $2^{15}$ that are used in sieving, every group holds at least four moduli, so
+    /// this takes a quarter of the passes over the number. This function is not constant-time.
+    pub fn mod_many_u_leaky(&self, moduli: &[u64]) -> Vec<u64> {
+        assert!(
+            moduli.iter().all(|m| *m != 0),
+            "the moduli must be non-zero"
+        );
+
+        let value = self.normalized_view();
+        let mut remainders = Vec::with_capacity(moduli.len());
+        let mut group_start = 0;
+
+        while group_start < moduli.len() {
+            let mut product = moduli[group_start];
+            let mut group_end = group_start + 1;
+
+            while let Some(next) = moduli
+                .get(group_end)
+                .and_then(|modulus| product.checked_mul(*modulus))
+            {
+                product = next;
+                group_end += 1;
+            }
+
+            let remainder = unsafe { gmp::mpz_fdiv_ui(&value, product) };
+            remainders.extend(
+                moduli[group_start..group_end]
+                    .iter()
+                    .map(|modulus| remainder % modulus),
+            );
+
+            group_start = group_end;
+        }
+
+        remainders
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::UnsignedInteger;
@@ -78,4 +123,23 @@ mod tests {
 
         assert_eq!(UnsignedInteger::from(9u64), a % &m);
     }
+
+    #[test]
+    fn test_mod_many_u() {
+        let value = UnsignedInteger::from_string_leaky(
+            "123456789012345678901234567890123456789012345678901234567890".to_string(),
+            10,
+            256,
+        );
+        let moduli = [3, 5, 7, 11, 13, 1 << 40, u64::MAX, 2, 1];
+
+        let expected: Vec<u64> = moduli.iter().map(|m| value.mod_u_leaky(*m)).collect();
+        assert_eq!(expected, value.mod_many_u_leaky(&moduli));
+        assert_eq!(vec![3], value.mod_many_u_leaky(&[11]));
+        assert!(value.mod_many_u_leaky(&[]).is_empty());
+        assert_eq!(
+            vec![0, 0],
+            UnsignedInteger::zero(128).mod_many_u_leaky(&[3, 5])
+        );
+    }
 }
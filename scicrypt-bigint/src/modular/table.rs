@@ -0,0 +1,154 @@
+use crate::ct::ct_select_from_table;
+use crate::{UnsignedInteger, GMP_NUMB_BITS};
+
+/// Precomputed powers of a fixed base modulo a fixed modulus, which speeds up repeated exponentiations of the same base (such as a generator or a public key). The exponent is split into windows of `window_size` bits, and for every window the table holds all $2^w$ possible powers, so that an exponentiation only requires one modular multiplication per window and no squarings.
+pub struct FixedBaseTable {
+    /// For every window, the limbs of all its powers padded to the size of the modulus and stored consecutively
+    table: Vec<Vec<u64>>,
+    modulus: UnsignedInteger,
+    window_size: u32,
+    exponent_size_in_bits: u32,
+}
+
+impl FixedBaseTable {
+    /// The largest supported window size, for which every window already holds 65536 powers.
+    pub const MAX_WINDOW_SIZE: u32 = 16;
+
+    /// Precomputes the powers of `base` modulo an odd `modulus` for exponents of at most `exponent_size_in_bits` bits. `window_size` must divide 64 and be at most `MAX_WINDOW_SIZE`; larger windows result in fewer multiplications per exponentiation, but the table size grows exponentially with it.
+    pub fn new(
+        base: &UnsignedInteger,
+        modulus: &UnsignedInteger,
+        exponent_size_in_bits: u32,
+        window_size: u32,
+    ) -> Self {
+        assert!(
+            window_size.is_power_of_two() && GMP_NUMB_BITS.is_multiple_of(window_size),
+            "the window size must divide the number of bits in a limb"
+        );
+        assert!(
+            window_size <= Self::MAX_WINDOW_SIZE,
+            "the window size must be at most {}",
+            Self::MAX_WINDOW_SIZE
+        );
+
+        let window_count = exponent_size_in_bits.div_ceil(window_size);
+        let limb_count = modulus.size_in_bits.div_ceil(GMP_NUMB_BITS) as usize;
+        let mut table = Vec::with_capacity(window_count as usize);
+
+        // The base of the first window is the base itself (reduced), the base of every next window is the previous base to the power 2^w
+        let mut window_base = base.pow_mod(&UnsignedInteger::new(1, 1), modulus);
+        for _ in 0..window_count {
+            let mut powers = Vec::with_capacity(1 << window_size);
+            powers.push(UnsignedInteger::one(modulus.size_in_bits));
+            powers.push(window_base.clone());
+
+            for j in 2..(1 << window_size) {
+                let power = (&powers[j - 1] * &window_base) % modulus;
+                powers.push(power);
+            }
+
+            window_base = (&powers[(1 << window_size) - 1] * &window_base) % modulus;
+            table.push(
+                powers
+                    .iter()
+                    .flat_map(|power| power.limbs_padded(limb_count))
+                    .collect(),
+            );
+        }
+
+        FixedBaseTable {
+            table,
+            modulus: modulus.clone(),
+            window_size,
+            exponent_size_in_bits,
+        }
+    }
+
+    /// The largest size in bits of the exponents that this table supports.
+    pub fn exponent_size_in_bits(&self) -> u32 {
+        self.exponent_size_in_bits
+    }
+
+    /// Computes the base to the power `exponent` modulo the modulus using the precomputed table. The computation performs the same number of multiplications for every exponent of the specified size, and every lookup reads all powers of its window, so that the memory access pattern does not depend on the exponent's bits either. Panics if the exponent's size in bits exceeds the size that the table was created for.
+    pub fn pow_with_table(&self, exponent: &UnsignedInteger) -> UnsignedInteger {
+        assert!(
+            exponent.size_in_bits <= self.exponent_size_in_bits,
+            "the exponent is larger than the table supports"
+        );
+
+        let mask = (1u64 << self.window_size) - 1;
+        let windows_per_limb = GMP_NUMB_BITS / self.window_size;
+
+        let digit = |i: usize| -> usize {
+            let limb_index = i / windows_per_limb as usize;
+            if limb_index >= exponent.value.size as usize {
+                return 0;
+            }
+
+            let limb = unsafe { *exponent.value.d.as_ptr().add(limb_index) };
+            let shift = (i % windows_per_limb as usize) as u32 * self.window_size;
+            ((limb >> shift) & mask) as usize
+        };
+
+        let limb_count = self.modulus.size_in_bits.div_ceil(GMP_NUMB_BITS) as usize;
+        let lookup = |i: usize| -> UnsignedInteger {
+            UnsignedInteger::from_limbs(
+                &ct_select_from_table(&self.table[i], limb_count, digit(i)),
+                self.modulus.size_in_bits,
+            )
+        };
+
+        let mut result = lookup(0);
+        for i in 1..self.table.len() {
+            result = (&result * &lookup(i)) % &self.modulus;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FixedBaseTable, UnsignedInteger};
+
+    #[test]
+    fn test_pow_with_table_mini() {
+        let b = UnsignedInteger::from(3u64);
+        let m = UnsignedInteger::from(11u64);
+        let table = FixedBaseTable::new(&b, &m, 64, 4);
+
+        let res = table.pow_with_table(&UnsignedInteger::from(7u64));
+
+        assert_eq!(UnsignedInteger::from(9u64), res);
+    }
+
+    #[test]
+    fn test_pow_with_table_matches_pow_mod() {
+        let b = UnsignedInteger::from_string_leaky("10539499294995885839929294349858893482048503424233434382948939585380202480248428858035020202848894983349030959432221114892829832832820310342164784362849732894729586478637897481742109741907489237586753826748420497102914324234241221888888487774774646263775738582835875726672378181992949120102959881821".to_string(), 10, 1024);
+        let e = UnsignedInteger::from_string_leaky("92848022024833655041372304737256052921065477715975001419347548380734496823522565044177931242947122534563813415992433917108481569319894167972639736788613656007853719476736625612543893748136536594494005487213485785676333621181690463942417781763743640447405597892807333854156631166426238815716390011586838580891".to_string(), 10, 1024);
+        let m = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+
+        let table = FixedBaseTable::new(&b, &m, 1024, 4);
+
+        assert_eq!(b.pow_mod(&e, &m), table.pow_with_table(&e));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 16")]
+    fn test_window_size_too_large() {
+        let b = UnsignedInteger::from(3u64);
+        let m = UnsignedInteger::from(11u64);
+
+        FixedBaseTable::new(&b, &m, 64, 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "larger than the table supports")]
+    fn test_exponent_too_large() {
+        let b = UnsignedInteger::from(3u64);
+        let m = UnsignedInteger::from(11u64);
+        let table = FixedBaseTable::new(&b, &m, 64, 4);
+
+        table.pow_with_table(&UnsignedInteger::new(7, 128));
+    }
+}
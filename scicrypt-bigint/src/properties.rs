@@ -0,0 +1,279 @@
+//! Property tests that cross-check the arithmetic of `UnsignedInteger` against `rug::Integer`.
+//! The operands are drawn with sizes around limb boundaries and with values that trigger long
+//! carry and borrow chains, which the fixed-value tests next to every operation do not cover.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+use rug::integer::Order;
+use rug::Integer;
+
+use crate::UnsignedInteger;
+
+/// The number of random cases that every property is checked for.
+const CASES: u32 = 300;
+
+/// Sizes in bits that lie on or next to a limb boundary.
+const BOUNDARY_SIZES: [u32; 14] = [
+    1, 2, 63, 64, 65, 127, 128, 129, 191, 192, 193, 255, 256, 257,
+];
+
+/// Draws a size in bits, half of the time one that lies on or next to a limb boundary.
+fn size() -> impl Strategy<Value = u32> {
+    prop_oneof![select(BOUNDARY_SIZES.to_vec()), 1..=600u32]
+}
+
+/// Draws a value below $2^\text{bits}$ that is either uniformly random or of a special shape.
+fn value(bits: u32) -> impl Strategy<Value = Integer> {
+    let all_ones = (Integer::from(1) << bits) - 1u32;
+
+    prop_oneof![
+        1 => Just(all_ones.clone()),
+        1 => Just(Integer::from(1) << (bits - 1)),
+        1 => (0..4u32).prop_map(Integer::from),
+        1 => (0..4u32).prop_map(move |offset| all_ones.clone() - offset),
+        2 => vec(any::<u64>(), bits.div_ceil(64) as usize)
+            .prop_map(|limbs| Integer::from_digits(&limbs, Order::Lsf)),
+    ]
+    .prop_map(move |value| value.keep_bits(bits))
+}
+
+/// Draws a value of exactly `bits` bits, like a modulus.
+fn modulus(bits: u32, odd: bool) -> impl Strategy<Value = Integer> {
+    value(bits).prop_map(move |mut value| {
+        value.set_bit(bits - 1, true);
+        if odd {
+            value.set_bit(0, true);
+        }
+
+        value
+    })
+}
+
+/// Draws a size in bits of at least `min_bits` together with a value below that size.
+fn operand_of_at_least(min_bits: u32) -> impl Strategy<Value = (u32, Integer)> {
+    size().prop_flat_map(move |bits| {
+        let bits = bits.max(min_bits);
+        (Just(bits), value(bits))
+    })
+}
+
+/// Draws a size in bits together with a value below that size.
+fn operand() -> impl Strategy<Value = (u32, Integer)> {
+    operand_of_at_least(1)
+}
+
+/// Draws two operands, the one with the largest size first.
+fn ordered_operands() -> impl Strategy<Value = ((u32, Integer), (u32, Integer))> {
+    (operand(), operand()).prop_map(|(a, b)| if a.0 >= b.0 { (a, b) } else { (b, a) })
+}
+
+/// Draws a word that is `u64::MAX` half of the time, which carries into the next limb.
+fn word() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(u64::MAX), any::<u64>()]
+}
+
+/// Draws an odd modulus of at least two bits together with its size, and a value of that size.
+fn odd_modulus_and_value() -> impl Strategy<Value = ((u32, Integer), Integer)> {
+    size().prop_flat_map(|bits| {
+        let bits = bits.max(2);
+        ((Just(bits), modulus(bits, true)), value(bits))
+    })
+}
+
+/// Converts `value` to an `UnsignedInteger` whose representation uses all limbs of `bits` bits.
+fn to_unsigned(value: &Integer, bits: u32) -> UnsignedInteger {
+    UnsignedInteger::from_le_bytes(&value.to_digits::<u8>(Order::Lsf), bits)
+}
+
+fn assert_matches(expected: &Integer, actual: &UnsignedInteger, context: &str) {
+    assert_eq!(
+        *expected,
+        actual.clone().to_rug(),
+        "wrong result for {}",
+        context
+    );
+    assert!(
+        actual.significant_bits_leaky() <= actual.size_in_bits(),
+        "the result exceeds its declared size for {}",
+        context
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_add_matches_rug(((x_bits, x), (y_bits, y)) in ordered_operands()) {
+        let mut actual = to_unsigned(&x, x_bits);
+        actual += &to_unsigned(&y, y_bits);
+
+        assert_matches(&Integer::from(&x + &y), &actual, &format!("{} + {}", x, y));
+    }
+
+    #[test]
+    fn test_add_u64_matches_rug((x_bits, x) in operand_of_at_least(64), y in word()) {
+        let actual = to_unsigned(&x, x_bits) + y;
+
+        assert_matches(&Integer::from(&x + y), &actual, &format!("{} + {}", x, y));
+    }
+
+    #[test]
+    fn test_sub_matches_rug(((x_bits, mut x), (y_bits, mut y)) in ordered_operands()) {
+        if x < y {
+            if x_bits == y_bits {
+                std::mem::swap(&mut x, &mut y);
+            } else {
+                x.set_bit(x_bits - 1, true);
+            }
+        }
+
+        let mut actual = to_unsigned(&x, x_bits);
+        actual -= &to_unsigned(&y, y_bits);
+
+        assert_matches(&Integer::from(&x - &y), &actual, &format!("{} - {}", x, y));
+    }
+
+    #[test]
+    fn test_sub_u64_matches_rug((x_bits, x) in operand_of_at_least(64), y in word()) {
+        let y = Integer::from(y).min(x.clone()).to_u64().unwrap();
+
+        let actual = to_unsigned(&x, x_bits) - y;
+
+        assert_matches(&Integer::from(&x - y), &actual, &format!("{} - {}", x, y));
+    }
+
+    #[test]
+    fn test_mul_matches_rug((x_bits, x) in operand(), (y_bits, y) in operand()) {
+        let (x_unsigned, y_unsigned) = (to_unsigned(&x, x_bits), to_unsigned(&y, y_bits));
+        let expected = Integer::from(&x * &y);
+        let context = format!("{} * {}", x, y);
+
+        assert_matches(&expected, &(&x_unsigned * &y_unsigned), &context);
+        assert_matches(&expected, &x_unsigned.mul_leaky(&y_unsigned), &context);
+        assert_matches(
+            &Integer::from(x.square_ref()),
+            &x_unsigned.square(),
+            &format!("{}^2", x),
+        );
+        assert_matches(
+            &Integer::from(x.square_ref()),
+            &x_unsigned.square_leaky(),
+            &format!("{}^2", x),
+        );
+    }
+
+    #[test]
+    fn test_mul_u64_matches_rug((x_bits, x) in operand(), y in word()) {
+        let actual = to_unsigned(&x, x_bits).mul_u64(y);
+
+        assert_matches(&Integer::from(&x * y), &actual, &format!("{} * {}", x, y));
+    }
+
+    #[test]
+    fn test_rem_and_div_match_rug(
+        ((x_bits, x), (m_bits, m)) in ordered_operands()
+            .prop_flat_map(|(x, (m_bits, _))| (Just(x), (Just(m_bits), modulus(m_bits, false)))),
+    ) {
+        let (x_unsigned, m_unsigned) = (to_unsigned(&x, x_bits), to_unsigned(&m, m_bits));
+        let context = format!("{} / {}", x, m);
+
+        let (quotient, remainder) = x_unsigned.clone().div_rem(&m_unsigned);
+        assert_matches(&Integer::from(&x / &m), &quotient, &context);
+        assert_matches(&Integer::from(&x % &m), &remainder, &context);
+        assert_matches(
+            &Integer::from(&x % &m),
+            &(x_unsigned % &m_unsigned),
+            &context,
+        );
+    }
+
+    #[test]
+    fn test_invert_matches_rug(((m_bits, m), x) in odd_modulus_and_value()) {
+        let x = (x % &m).max(Integer::from(1));
+        let context = format!("{}^-1 mod {}", x, m);
+
+        let expected = x.clone().invert(&m).ok();
+        let m_unsigned = to_unsigned(&m, m_bits);
+
+        for (name, actual) in [
+            ("invert", to_unsigned(&x, m_bits).invert(&m_unsigned)),
+            (
+                "invert_leaky",
+                to_unsigned(&x, m_bits).invert_leaky(&m_unsigned),
+            ),
+        ] {
+            let context = format!("{} using {}", context, name);
+
+            match (&expected, actual) {
+                (Some(expected), Some(actual)) => assert_matches(expected, &actual, &context),
+                (None, None) => {}
+                (expected, actual) => panic!(
+                    "expected {:?} but got {:?} for {}",
+                    expected, actual, context
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_moduli_match_rug(
+        (x_bits, x) in operand(),
+        moduli in vec(1..=u64::MAX, 1..20),
+    ) {
+        let x_unsigned = to_unsigned(&x, x_bits);
+
+        let expected: Vec<u64> = moduli
+            .iter()
+            .map(|m| Integer::from(&x % *m).to_u64().unwrap())
+            .collect();
+        let single: Vec<u64> = moduli.iter().map(|m| x_unsigned.mod_u_leaky(*m)).collect();
+
+        assert_eq!(expected, single, "wrong remainders of {}", x);
+        assert_eq!(
+            expected,
+            x_unsigned.mod_many_u_leaky(&moduli),
+            "wrong remainders of {}",
+            x
+        );
+    }
+
+    #[test]
+    fn test_limb_shifts_match_rug((x_bits, x) in operand(), count in 0..6u32) {
+        let x_unsigned = to_unsigned(&x, x_bits);
+
+        assert_matches(
+            &Integer::from(&x << (64 * count)),
+            &x_unsigned.shl_limbs(count),
+            &format!("{} << {} limbs", x, count),
+        );
+        assert_matches(
+            &Integer::from(&x >> (64 * count)),
+            &x_unsigned.shr_limbs(count),
+            &format!("{} >> {} limbs", x, count),
+        );
+    }
+}
+
+proptest! {
+    // Modular exponentiation is much slower than the other operations, so it gets fewer cases
+    #![proptest_config(ProptestConfig::with_cases(CASES / 3))]
+
+    #[test]
+    fn test_pow_mod_matches_rug(
+        ((m_bits, m), b) in odd_modulus_and_value(),
+        (e_bits, e) in operand(),
+    ) {
+        let b = (b % &m).max(Integer::from(1));
+        let e = e.max(Integer::from(1));
+
+        let actual =
+            to_unsigned(&b, m_bits).pow_mod(&to_unsigned(&e, e_bits), &to_unsigned(&m, m_bits));
+
+        assert_matches(
+            &b.clone().pow_mod(&e, &m).unwrap(),
+            &actual,
+            &format!("{}^{} mod {}", b, e, m),
+        );
+    }
+}
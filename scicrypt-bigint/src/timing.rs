@@ -0,0 +1,201 @@
+//! A statistical test for data-dependent timing in the style of dudect (Reparaz, Balasch and
+//! Verbauwhede, "Dude, is my code constant time?"). An operation is timed on inputs from two
+//! classes, typically one fixed input and uniformly random inputs, in a random interleaving. If the
+//! running time does not depend on the input, both classes have the same timing distribution, which
+//! Welch's t-test checks.
+//!
+//! Timing measurements are noisy, so a single run can give false positives on a busy machine. This
+//! module is only available with the `timing-tests` feature, and the tests that use it are best run
+//! in release mode on an otherwise idle machine.
+
+use std::time::Instant;
+
+/// The absolute t-statistic above which timing is considered to depend on the input class, which
+/// is the threshold used by dudect.
+pub const LEAKAGE_THRESHOLD: f64 = 4.5;
+
+/// The fraction of the slowest measurements that is discarded, because those are mostly caused by
+/// interrupts and context switches rather than by the operation.
+const CROP_FRACTION: f64 = 0.1;
+
+/// The class of an input to a timing test.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InputClass {
+    /// Typically a fixed input, such as a constant or a special value
+    Fixed,
+    /// Typically a uniformly random input
+    Random,
+}
+
+/// The outcome of a timing test.
+#[derive(Copy, Clone, Debug)]
+pub struct TimingReport {
+    /// Welch's t-statistic of the measurements of both classes
+    pub t_statistic: f64,
+    /// The number of measurements that remained after cropping
+    pub measurements: usize,
+}
+
+impl TimingReport {
+    /// Returns true if the timing differs significantly between both input classes.
+    pub fn is_leaky(&self) -> bool {
+        self.t_statistic.abs() > LEAKAGE_THRESHOLD
+    }
+}
+
+/// Times `operation` on every input in `inputs`, which should be generated beforehand with a random
+/// class for every input, and compares the timing of both classes.
+pub fn measure<I: Clone, O, F: FnMut(&I) -> O>(
+    inputs: &[(InputClass, I)],
+    mut operation: F,
+) -> TimingReport {
+    // Inputs that are generated differently end up in different places in memory, which alone can
+    // cause a measurable difference in cache misses. So, we copy all inputs in order first.
+    let inputs: Vec<(InputClass, I)> = inputs.to_vec();
+
+    let mut timings: Vec<(InputClass, f64)> = inputs
+        .iter()
+        .map(|(class, input)| {
+            let start = Instant::now();
+            std::hint::black_box(operation(std::hint::black_box(input)));
+            (*class, start.elapsed().as_nanos() as f64)
+        })
+        .collect();
+
+    timings.sort_by(|a, b| a.1.total_cmp(&b.1));
+    timings.truncate(((1.0 - CROP_FRACTION) * timings.len() as f64) as usize);
+
+    let class_timings = |class| -> Vec<f64> {
+        timings
+            .iter()
+            .filter(|(c, _)| *c == class)
+            .map(|(_, t)| *t)
+            .collect()
+    };
+
+    TimingReport {
+        t_statistic: welch_t_statistic(
+            &class_timings(InputClass::Fixed),
+            &class_timings(InputClass::Random),
+        ),
+        measurements: timings.len(),
+    }
+}
+
+/// Computes Welch's t-statistic for the difference between the means of `a` and `b`.
+fn welch_t_statistic(a: &[f64], b: &[f64]) -> f64 {
+    let mean_variance = |samples: &[f64]| {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, variance / n)
+    };
+
+    let (mean_a, scaled_variance_a) = mean_variance(a);
+    let (mean_b, scaled_variance_b) = mean_variance(b);
+
+    (mean_a - mean_b) / (scaled_variance_a + scaled_variance_b).sqrt()
+}
+
+#[cfg(all(test, feature = "rug"))]
+mod tests {
+    use super::{measure, welch_t_statistic, InputClass};
+    use crate::UnsignedInteger;
+    use rand::rngs::OsRng as RandOsRng;
+    use rand::Rng;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    const SAMPLES: usize = 4000;
+
+    fn classes() -> Vec<InputClass> {
+        let mut rng = rand::thread_rng();
+        (0..SAMPLES)
+            .map(|_| {
+                if rng.gen() {
+                    InputClass::Fixed
+                } else {
+                    InputClass::Random
+                }
+            })
+            .collect()
+    }
+
+    fn modulus() -> UnsignedInteger {
+        let mut modulus = UnsignedInteger::random(512, &mut GeneralRng::new(RandOsRng));
+        modulus.set_bit_leaky(511);
+        modulus.set_bit_leaky(0);
+        modulus
+    }
+
+    #[test]
+    fn test_welch_t_statistic() {
+        assert_eq!(0.0, welch_t_statistic(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]));
+        assert!(welch_t_statistic(&[10.0, 11.0, 12.0], &[1.0, 2.0, 3.0]) > 10.0);
+    }
+
+    #[test]
+    fn test_detects_variable_time_pow_mod() {
+        // GMP's regular modular exponentiation takes time proportional to the exponent's length
+        let modulus = modulus().to_rug();
+        let mut rng = GeneralRng::new(RandOsRng);
+        let inputs: Vec<_> = classes()
+            .into_iter()
+            .map(|class| {
+                let exponent = match class {
+                    InputClass::Fixed => rug::Integer::from(1),
+                    InputClass::Random => UnsignedInteger::random(512, &mut rng).to_rug(),
+                };
+                (class, exponent)
+            })
+            .collect();
+
+        let base = rug::Integer::from(3);
+        let report = measure(&inputs, |exponent| {
+            base.clone().pow_mod(exponent, &modulus).unwrap()
+        });
+
+        assert!(report.is_leaky(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_pow_mod_is_constant_time_in_the_exponent() {
+        let modulus = modulus();
+        let mut rng = GeneralRng::new(RandOsRng);
+        let inputs: Vec<_> = classes()
+            .into_iter()
+            .map(|class| {
+                let exponent = match class {
+                    InputClass::Fixed => UnsignedInteger::from_le_bytes(&[1], 512),
+                    InputClass::Random => UnsignedInteger::random(512, &mut rng),
+                };
+                (class, exponent)
+            })
+            .collect();
+
+        let base = UnsignedInteger::from_le_bytes(&[3], 512);
+        let report = measure(&inputs, |exponent| base.pow_mod(exponent, &modulus));
+
+        assert!(!report.is_leaky(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_pow_mod_is_constant_time_in_the_base() {
+        let modulus = modulus();
+        let exponent = UnsignedInteger::random(512, &mut GeneralRng::new(RandOsRng));
+        let mut rng = GeneralRng::new(RandOsRng);
+        let inputs: Vec<_> = classes()
+            .into_iter()
+            .map(|class| {
+                let base = match class {
+                    InputClass::Fixed => UnsignedInteger::from_le_bytes(&[1], 512),
+                    InputClass::Random => UnsignedInteger::random(512, &mut rng) % &modulus,
+                };
+                (class, base)
+            })
+            .collect();
+
+        let report = measure(&inputs, |base| base.pow_mod(&exponent, &modulus));
+
+        assert!(!report.is_leaky(), "{:?}", report);
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * C bindings for scicrypt, generated by hand from scicrypt-ffi/src/lib.rs.
+ *
+ * Keys and ciphertexts are opaque handles that must be released with the matching _free function.
+ * Every function returns a scicrypt_status_t, and only writes to its output arguments on
+ * SCICRYPT_OK.
+ */
+
+#ifndef SCICRYPT_H
+#define SCICRYPT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum {
+    SCICRYPT_OK = 0,
+    SCICRYPT_NULL_POINTER = 1,
+    SCICRYPT_INVALID_ENCODING = 2,
+    SCICRYPT_INVALID_PARAMETER = 3,
+    SCICRYPT_PLAINTEXT_TOO_LARGE = 4,
+    SCICRYPT_INTERNAL_ERROR = 5,
+} scicrypt_status_t;
+
+/* A byte buffer owned by the library, which must be released with scicrypt_buffer_free. */
+typedef struct {
+    uint8_t *data;
+    size_t len;
+} scicrypt_buffer_t;
+
+typedef struct ScicryptPaillierPK scicrypt_paillier_pk_t;
+typedef struct ScicryptPaillierSK scicrypt_paillier_sk_t;
+typedef struct ScicryptPaillierCiphertext scicrypt_paillier_ciphertext_t;
+
+scicrypt_status_t scicrypt_paillier_generate_keys(uint32_t modulus_bits,
+                                                  scicrypt_paillier_pk_t **public_key_out,
+                                                  scicrypt_paillier_sk_t **secret_key_out);
+
+scicrypt_status_t scicrypt_paillier_encrypt(const scicrypt_paillier_pk_t *public_key,
+                                            uint64_t plaintext,
+                                            scicrypt_paillier_ciphertext_t **ciphertext_out);
+
+scicrypt_status_t scicrypt_paillier_decrypt(const scicrypt_paillier_pk_t *public_key,
+                                            const scicrypt_paillier_sk_t *secret_key,
+                                            const scicrypt_paillier_ciphertext_t *ciphertext,
+                                            uint64_t *plaintext_out);
+
+scicrypt_status_t scicrypt_paillier_add(const scicrypt_paillier_pk_t *public_key,
+                                        const scicrypt_paillier_ciphertext_t *ciphertext_a,
+                                        const scicrypt_paillier_ciphertext_t *ciphertext_b,
+                                        scicrypt_paillier_ciphertext_t **ciphertext_out);
+
+scicrypt_status_t scicrypt_paillier_mul_constant(const scicrypt_paillier_pk_t *public_key,
+                                                 const scicrypt_paillier_ciphertext_t *ciphertext,
+                                                 uint64_t constant,
+                                                 scicrypt_paillier_ciphertext_t **ciphertext_out);
+
+scicrypt_status_t scicrypt_paillier_pk_serialize(const scicrypt_paillier_pk_t *public_key,
+                                                 scicrypt_buffer_t *buffer_out);
+
+scicrypt_status_t scicrypt_paillier_pk_deserialize(const uint8_t *data, size_t len,
+                                                   scicrypt_paillier_pk_t **public_key_out);
+
+scicrypt_status_t scicrypt_paillier_ciphertext_serialize(
+    const scicrypt_paillier_ciphertext_t *ciphertext, scicrypt_buffer_t *buffer_out);
+
+scicrypt_status_t scicrypt_paillier_ciphertext_deserialize(
+    const uint8_t *data, size_t len, scicrypt_paillier_ciphertext_t **ciphertext_out);
+
+void scicrypt_paillier_pk_free(scicrypt_paillier_pk_t *public_key);
+void scicrypt_paillier_sk_free(scicrypt_paillier_sk_t *secret_key);
+void scicrypt_paillier_ciphertext_free(scicrypt_paillier_ciphertext_t *ciphertext);
+void scicrypt_buffer_free(scicrypt_buffer_t *buffer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SCICRYPT_H */
@@ -0,0 +1,618 @@
+#![warn(missing_docs, unused_imports)]
+
+//! _This is a part of **scicrypt**. For more information, head to the
+//! [scicrypt](https://crates.io/crates/scicrypt) crate homepage._
+//!
+//! C ABI bindings for the Paillier cryptosystem, so that it can be called from C, C++, Python
+//! (through `ctypes` or `cffi`) and other languages. The declarations are available as a C header
+//! in `include/scicrypt.h`.
+//!
+//! Keys and ciphertexts are passed around as opaque handles, which are created by this library and
+//! must be released with the corresponding `_free` function. Public keys and ciphertexts can be
+//! converted to and from byte buffers to send them elsewhere. Every function returns a
+//! [`ScicryptStatus`], and only writes to its output arguments if it returns `SCICRYPT_OK`.
+//!
+//! All randomness is drawn from the operating system.
+
+use bincode::Options;
+use rand_core::OsRng;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_he::cryptosystems::paillier::{Paillier, PaillierCiphertext, PaillierPK, PaillierSK};
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use serde::de::DeserializeOwned;
+use std::panic::{catch_unwind, UnwindSafe};
+use std::ptr;
+
+/// The outcome of a call into this library.
+#[repr(C)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ScicryptStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// One of the pointer arguments was null
+    NullPointer = 1,
+    /// A byte buffer did not hold a valid serialized value
+    InvalidEncoding = 2,
+    /// A parameter was outside of the supported range
+    InvalidParameter = 3,
+    /// The decrypted plaintext does not fit in the output type
+    PlaintextTooLarge = 4,
+    /// An unexpected internal error occurred
+    InternalError = 5,
+}
+
+/// A byte buffer that is owned by this library. It must be released with
+/// [`scicrypt_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct ScicryptBuffer {
+    /// Pointer to the first byte
+    pub data: *mut u8,
+    /// Number of bytes
+    pub len: usize,
+}
+
+impl ScicryptBuffer {
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let mut bytes = bytes.into_boxed_slice();
+        let buffer = ScicryptBuffer {
+            data: bytes.as_mut_ptr(),
+            len: bytes.len(),
+        };
+        std::mem::forget(bytes);
+
+        buffer
+    }
+}
+
+/// Opaque handle to a Paillier public key.
+#[derive(Debug)]
+pub struct ScicryptPaillierPK(PaillierPK);
+
+/// Opaque handle to a Paillier secret key.
+#[derive(Debug)]
+pub struct ScicryptPaillierSK(PaillierSK);
+
+/// Opaque handle to a Paillier ciphertext.
+#[derive(Debug)]
+pub struct ScicryptPaillierCiphertext(PaillierCiphertext);
+
+/// Runs `f` and turns a panic into `InternalError`, because unwinding into foreign code is
+/// undefined behavior.
+fn guard<F: FnOnce() -> ScicryptStatus + UnwindSafe>(f: F) -> ScicryptStatus {
+    catch_unwind(f).unwrap_or(ScicryptStatus::InternalError)
+}
+
+/// Moves `value` to the heap and writes the resulting handle to `out`.
+unsafe fn write_handle<T>(out: *mut *mut T, value: T) {
+    *out = Box::into_raw(Box::new(value));
+}
+
+/// Deserializes `bytes` in the same format as `bincode::deserialize`, but never reads past the end
+/// of `bytes`. A corrupted length prefix would otherwise trigger an allocation that aborts the
+/// process, which `guard` cannot catch.
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(bytes.len() as u64)
+        .deserialize(bytes)
+}
+
+/// Releases a handle that was created by this library, where null is ignored.
+unsafe fn free_handle<T>(handle: *mut T) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Generates a Paillier key pair with a modulus of `modulus_bits` bits, and writes handles to the
+/// public and secret key to `public_key_out` and `secret_key_out`.
+///
+/// # Safety
+/// The output pointers must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_generate_keys(
+    modulus_bits: u32,
+    public_key_out: *mut *mut ScicryptPaillierPK,
+    secret_key_out: *mut *mut ScicryptPaillierSK,
+) -> ScicryptStatus {
+    if public_key_out.is_null() || secret_key_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    if modulus_bits < 128 || !modulus_bits.is_multiple_of(2) {
+        return ScicryptStatus::InvalidParameter;
+    }
+
+    guard(|| {
+        let mut rng = GeneralRng::new(OsRng);
+        let paillier = Paillier::setup(&BitsOfSecurity::from_public_key_bit_length(modulus_bits));
+        let (public_key, secret_key) = paillier.generate_keys(&mut rng);
+
+        write_handle(public_key_out, ScicryptPaillierPK(public_key));
+        write_handle(secret_key_out, ScicryptPaillierSK(secret_key));
+        ScicryptStatus::Ok
+    })
+}
+
+/// Encrypts `plaintext` and writes a handle to the ciphertext to `ciphertext_out`.
+///
+/// # Safety
+/// `public_key` must be a valid handle, and `ciphertext_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_encrypt(
+    public_key: *const ScicryptPaillierPK,
+    plaintext: u64,
+    ciphertext_out: *mut *mut ScicryptPaillierCiphertext,
+) -> ScicryptStatus {
+    if public_key.is_null() || ciphertext_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    let public_key = &(*public_key).0;
+
+    guard(|| {
+        let mut rng = GeneralRng::new(OsRng);
+        let ciphertext = public_key.encrypt_raw(&UnsignedInteger::from(plaintext), &mut rng);
+
+        write_handle(ciphertext_out, ScicryptPaillierCiphertext(ciphertext));
+        ScicryptStatus::Ok
+    })
+}
+
+/// Decrypts `ciphertext` and writes the plaintext to `plaintext_out`. Returns
+/// `SCICRYPT_INVALID_ENCODING` if the ciphertext is not valid for `public_key`, and
+/// `SCICRYPT_PLAINTEXT_TOO_LARGE` if the plaintext does not fit in 64 bits.
+///
+/// # Safety
+/// The keys and ciphertext must be valid handles, and `plaintext_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_decrypt(
+    public_key: *const ScicryptPaillierPK,
+    secret_key: *const ScicryptPaillierSK,
+    ciphertext: *const ScicryptPaillierCiphertext,
+    plaintext_out: *mut u64,
+) -> ScicryptStatus {
+    if public_key.is_null()
+        || secret_key.is_null()
+        || ciphertext.is_null()
+        || plaintext_out.is_null()
+    {
+        return ScicryptStatus::NullPointer;
+    }
+    let (public_key, secret_key, ciphertext) =
+        (&(*public_key).0, &(*secret_key).0, &(*ciphertext).0);
+
+    guard(|| {
+        let bytes = match secret_key.decrypt_checked_raw(public_key, ciphertext) {
+            Ok(plaintext) => plaintext.to_le_bytes(),
+            Err(_) => return ScicryptStatus::InvalidEncoding,
+        };
+        if bytes.iter().skip(8).any(|&byte| byte != 0) {
+            return ScicryptStatus::PlaintextTooLarge;
+        }
+
+        let mut plaintext = [0u8; 8];
+        for (byte, &decrypted) in plaintext.iter_mut().zip(&bytes) {
+            *byte = decrypted;
+        }
+
+        *plaintext_out = u64::from_le_bytes(plaintext);
+        ScicryptStatus::Ok
+    })
+}
+
+/// Homomorphically adds two ciphertexts, and writes a handle to the encryption of the sum to
+/// `ciphertext_out`. Returns `SCICRYPT_INVALID_ENCODING` if either ciphertext is not valid for
+/// `public_key`.
+///
+/// # Safety
+/// The key and ciphertexts must be valid handles, and `ciphertext_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_add(
+    public_key: *const ScicryptPaillierPK,
+    ciphertext_a: *const ScicryptPaillierCiphertext,
+    ciphertext_b: *const ScicryptPaillierCiphertext,
+    ciphertext_out: *mut *mut ScicryptPaillierCiphertext,
+) -> ScicryptStatus {
+    if public_key.is_null()
+        || ciphertext_a.is_null()
+        || ciphertext_b.is_null()
+        || ciphertext_out.is_null()
+    {
+        return ScicryptStatus::NullPointer;
+    }
+    let (public_key, ciphertext_a, ciphertext_b) =
+        (&(*public_key).0, &(*ciphertext_a).0, &(*ciphertext_b).0);
+
+    guard(|| {
+        if !ciphertext_a.is_valid_leaky(public_key) || !ciphertext_b.is_valid_leaky(public_key) {
+            return ScicryptStatus::InvalidEncoding;
+        }
+
+        let sum = public_key.add(ciphertext_a, ciphertext_b);
+
+        write_handle(ciphertext_out, ScicryptPaillierCiphertext(sum));
+        ScicryptStatus::Ok
+    })
+}
+
+/// Homomorphically multiplies a ciphertext by the plaintext `constant`, and writes a handle to the
+/// encryption of the product to `ciphertext_out`. Returns `SCICRYPT_INVALID_ENCODING` if the
+/// ciphertext is not valid for `public_key`.
+///
+/// # Safety
+/// The key and ciphertext must be valid handles, and `ciphertext_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_mul_constant(
+    public_key: *const ScicryptPaillierPK,
+    ciphertext: *const ScicryptPaillierCiphertext,
+    constant: u64,
+    ciphertext_out: *mut *mut ScicryptPaillierCiphertext,
+) -> ScicryptStatus {
+    if public_key.is_null() || ciphertext.is_null() || ciphertext_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    let (public_key, ciphertext) = (&(*public_key).0, &(*ciphertext).0);
+
+    guard(|| {
+        if !ciphertext.is_valid_leaky(public_key) {
+            return ScicryptStatus::InvalidEncoding;
+        }
+
+        let product = public_key.mul_constant(ciphertext, &UnsignedInteger::from(constant));
+
+        write_handle(ciphertext_out, ScicryptPaillierCiphertext(product));
+        ScicryptStatus::Ok
+    })
+}
+
+/// Serializes a public key into a buffer that is written to `buffer_out`.
+///
+/// # Safety
+/// `public_key` must be a valid handle, and `buffer_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_pk_serialize(
+    public_key: *const ScicryptPaillierPK,
+    buffer_out: *mut ScicryptBuffer,
+) -> ScicryptStatus {
+    if public_key.is_null() || buffer_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    let public_key = &(*public_key).0;
+
+    guard(|| match bincode::serialize(public_key) {
+        Ok(bytes) => {
+            *buffer_out = ScicryptBuffer::from_vec(bytes);
+            ScicryptStatus::Ok
+        }
+        Err(_) => ScicryptStatus::InternalError,
+    })
+}
+
+/// Deserializes a public key from the `len` bytes at `data`, and writes a handle to it to
+/// `public_key_out`.
+///
+/// # Safety
+/// `data` must be valid for reads of `len` bytes, and `public_key_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_pk_deserialize(
+    data: *const u8,
+    len: usize,
+    public_key_out: *mut *mut ScicryptPaillierPK,
+) -> ScicryptStatus {
+    if data.is_null() || public_key_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    let bytes = std::slice::from_raw_parts(data, len);
+
+    guard(|| match deserialize(bytes) {
+        Ok(public_key) => {
+            write_handle(public_key_out, ScicryptPaillierPK(public_key));
+            ScicryptStatus::Ok
+        }
+        Err(_) => ScicryptStatus::InvalidEncoding,
+    })
+}
+
+/// Serializes a ciphertext into a buffer that is written to `buffer_out`.
+///
+/// # Safety
+/// `ciphertext` must be a valid handle, and `buffer_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_ciphertext_serialize(
+    ciphertext: *const ScicryptPaillierCiphertext,
+    buffer_out: *mut ScicryptBuffer,
+) -> ScicryptStatus {
+    if ciphertext.is_null() || buffer_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    let ciphertext = &(*ciphertext).0;
+
+    guard(|| match bincode::serialize(ciphertext) {
+        Ok(bytes) => {
+            *buffer_out = ScicryptBuffer::from_vec(bytes);
+            ScicryptStatus::Ok
+        }
+        Err(_) => ScicryptStatus::InternalError,
+    })
+}
+
+/// Deserializes a ciphertext from the `len` bytes at `data`, and writes a handle to it to
+/// `ciphertext_out`.
+///
+/// # Safety
+/// `data` must be valid for reads of `len` bytes, and `ciphertext_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_ciphertext_deserialize(
+    data: *const u8,
+    len: usize,
+    ciphertext_out: *mut *mut ScicryptPaillierCiphertext,
+) -> ScicryptStatus {
+    if data.is_null() || ciphertext_out.is_null() {
+        return ScicryptStatus::NullPointer;
+    }
+    let bytes = std::slice::from_raw_parts(data, len);
+
+    guard(|| match deserialize(bytes) {
+        Ok(ciphertext) => {
+            write_handle(ciphertext_out, ScicryptPaillierCiphertext(ciphertext));
+            ScicryptStatus::Ok
+        }
+        Err(_) => ScicryptStatus::InvalidEncoding,
+    })
+}
+
+/// Releases a public key handle. Passing null does nothing.
+///
+/// # Safety
+/// `public_key` must be null or a handle that was not released before.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_pk_free(public_key: *mut ScicryptPaillierPK) {
+    free_handle(public_key)
+}
+
+/// Releases a secret key handle. Passing null does nothing.
+///
+/// # Safety
+/// `secret_key` must be null or a handle that was not released before.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_sk_free(secret_key: *mut ScicryptPaillierSK) {
+    free_handle(secret_key)
+}
+
+/// Releases a ciphertext handle. Passing null does nothing.
+///
+/// # Safety
+/// `ciphertext` must be null or a handle that was not released before.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_paillier_ciphertext_free(
+    ciphertext: *mut ScicryptPaillierCiphertext,
+) {
+    free_handle(ciphertext)
+}
+
+/// Releases a buffer that was returned by this library, and resets it to an empty buffer so that
+/// releasing it twice does nothing.
+///
+/// # Safety
+/// `buffer` must be null or point to a buffer that was returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn scicrypt_buffer_free(buffer: *mut ScicryptBuffer) {
+    if buffer.is_null() || (*buffer).data.is_null() {
+        return;
+    }
+
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+        (*buffer).data,
+        (*buffer).len,
+    )));
+    *buffer = ScicryptBuffer {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_keys() -> (*mut ScicryptPaillierPK, *mut ScicryptPaillierSK) {
+        let mut public_key = ptr::null_mut();
+        let mut secret_key = ptr::null_mut();
+        let status =
+            unsafe { scicrypt_paillier_generate_keys(256, &mut public_key, &mut secret_key) };
+        assert_eq!(ScicryptStatus::Ok, status);
+
+        (public_key, secret_key)
+    }
+
+    unsafe fn encrypt(
+        public_key: *const ScicryptPaillierPK,
+        plaintext: u64,
+    ) -> *mut ScicryptPaillierCiphertext {
+        let mut ciphertext = ptr::null_mut();
+        assert_eq!(
+            ScicryptStatus::Ok,
+            scicrypt_paillier_encrypt(public_key, plaintext, &mut ciphertext)
+        );
+        ciphertext
+    }
+
+    unsafe fn decrypt(
+        public_key: *const ScicryptPaillierPK,
+        secret_key: *const ScicryptPaillierSK,
+        ciphertext: *const ScicryptPaillierCiphertext,
+    ) -> u64 {
+        let mut plaintext = 0;
+        assert_eq!(
+            ScicryptStatus::Ok,
+            scicrypt_paillier_decrypt(public_key, secret_key, ciphertext, &mut plaintext)
+        );
+        plaintext
+    }
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let (public_key, secret_key) = generate_keys();
+
+        unsafe {
+            let ciphertext_a = encrypt(public_key, 20);
+            let ciphertext_b = encrypt(public_key, 22);
+
+            let mut sum = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::Ok,
+                scicrypt_paillier_add(public_key, ciphertext_a, ciphertext_b, &mut sum)
+            );
+            assert_eq!(42, decrypt(public_key, secret_key, sum));
+
+            let mut product = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::Ok,
+                scicrypt_paillier_mul_constant(public_key, sum, 3, &mut product)
+            );
+            assert_eq!(126, decrypt(public_key, secret_key, product));
+
+            for ciphertext in [ciphertext_a, ciphertext_b, sum, product] {
+                scicrypt_paillier_ciphertext_free(ciphertext);
+            }
+            scicrypt_paillier_pk_free(public_key);
+            scicrypt_paillier_sk_free(secret_key);
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let (public_key, secret_key) = generate_keys();
+
+        unsafe {
+            let ciphertext = encrypt(public_key, 7);
+
+            let mut pk_buffer = ScicryptBuffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            let mut ct_buffer = ScicryptBuffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(
+                ScicryptStatus::Ok,
+                scicrypt_paillier_pk_serialize(public_key, &mut pk_buffer)
+            );
+            assert_eq!(
+                ScicryptStatus::Ok,
+                scicrypt_paillier_ciphertext_serialize(ciphertext, &mut ct_buffer)
+            );
+
+            let mut public_key_copy = ptr::null_mut();
+            let mut ciphertext_copy = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::Ok,
+                scicrypt_paillier_pk_deserialize(
+                    pk_buffer.data,
+                    pk_buffer.len,
+                    &mut public_key_copy
+                )
+            );
+            assert_eq!(
+                ScicryptStatus::Ok,
+                scicrypt_paillier_ciphertext_deserialize(
+                    ct_buffer.data,
+                    ct_buffer.len,
+                    &mut ciphertext_copy
+                )
+            );
+            assert_eq!(7, decrypt(public_key_copy, secret_key, ciphertext_copy));
+
+            let mut invalid = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::InvalidEncoding,
+                scicrypt_paillier_ciphertext_deserialize(ct_buffer.data, 3, &mut invalid)
+            );
+
+            // A length prefix far beyond the end of the buffer must not be allocated
+            let oversized = [0xffu8; 16];
+            assert_eq!(
+                ScicryptStatus::InvalidEncoding,
+                scicrypt_paillier_ciphertext_deserialize(
+                    oversized.as_ptr(),
+                    oversized.len(),
+                    &mut invalid
+                )
+            );
+
+            scicrypt_buffer_free(&mut pk_buffer);
+            scicrypt_buffer_free(&mut ct_buffer);
+            assert!(ct_buffer.data.is_null());
+            scicrypt_buffer_free(&mut ct_buffer);
+
+            scicrypt_paillier_ciphertext_free(ciphertext);
+            scicrypt_paillier_ciphertext_free(ciphertext_copy);
+            scicrypt_paillier_pk_free(public_key);
+            scicrypt_paillier_pk_free(public_key_copy);
+            scicrypt_paillier_sk_free(secret_key);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let (public_key, secret_key) = generate_keys();
+
+        unsafe {
+            let mut ciphertext = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::NullPointer,
+                scicrypt_paillier_encrypt(ptr::null(), 1, &mut ciphertext)
+            );
+            assert!(ciphertext.is_null());
+
+            let mut other_public_key = ptr::null_mut();
+            let mut other_secret_key = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::InvalidParameter,
+                scicrypt_paillier_generate_keys(7, &mut other_public_key, &mut other_secret_key)
+            );
+
+            // Multiplying by 2^32 twice overflows 64 bits
+            let ciphertext = encrypt(public_key, 1 << 32);
+            let mut product = ptr::null_mut();
+            scicrypt_paillier_mul_constant(public_key, ciphertext, 1 << 32, &mut product);
+            let mut plaintext = 0;
+            assert_eq!(
+                ScicryptStatus::PlaintextTooLarge,
+                scicrypt_paillier_decrypt(public_key, secret_key, product, &mut plaintext)
+            );
+
+            // A zero ciphertext is not an element of the multiplicative group
+            let zero = Box::into_raw(Box::new(ScicryptPaillierCiphertext(PaillierCiphertext {
+                c: UnsignedInteger::zero(64),
+            })));
+            assert_eq!(
+                ScicryptStatus::InvalidEncoding,
+                scicrypt_paillier_decrypt(public_key, secret_key, zero, &mut plaintext)
+            );
+            let mut sum = ptr::null_mut();
+            assert_eq!(
+                ScicryptStatus::InvalidEncoding,
+                scicrypt_paillier_add(public_key, ciphertext, zero, &mut sum)
+            );
+            assert!(sum.is_null());
+            assert_eq!(
+                ScicryptStatus::InvalidEncoding,
+                scicrypt_paillier_mul_constant(public_key, zero, 2, &mut sum)
+            );
+
+            scicrypt_paillier_ciphertext_free(ciphertext);
+            scicrypt_paillier_ciphertext_free(product);
+            scicrypt_paillier_ciphertext_free(zero);
+            scicrypt_paillier_pk_free(public_key);
+            scicrypt_paillier_sk_free(secret_key);
+        }
+    }
+}
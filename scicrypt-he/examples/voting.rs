@@ -0,0 +1,82 @@
+//! Runs a complete election with three candidates and two out of three trustees, publishes its
+//! transcript in serialized form, and verifies the transcript as an independent observer would.
+//!
+//! Run with `cargo run --example voting`.
+
+use rand_core::OsRng;
+use scicrypt_he::protocols::voting::{Election, ElectionTranscript};
+use scicrypt_he::threshold_cryptosystems::curve_el_gamal::TOfNCurveElGamal;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::threshold_cryptosystems::TOfNCryptosystem;
+
+const CANDIDATES: [&str; 3] = ["Alice", "Bob", "Carol"];
+
+fn main() {
+    let mut rng = GeneralRng::new(OsRng);
+
+    // The trustees generate a joint key, of which any two out of three can decrypt
+    let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+    let (public_key, trustee_keys) = el_gamal.generate_keys(2, 3, &mut rng);
+    let verification_keys: Vec<_> = trustee_keys
+        .iter()
+        .map(|sk| sk.verification_key())
+        .collect();
+
+    let election = Election::new(b"example election", CANDIDATES.len(), public_key);
+
+    // Every voter encrypts their choice and proves that it is a valid vote
+    let choices = [
+        Some(0),
+        Some(1),
+        Some(1),
+        Some(2),
+        None,
+        Some(1),
+        Some(0),
+        Some(1),
+    ];
+    let ballots: Vec<_> = choices
+        .iter()
+        .enumerate()
+        .map(|(voter_id, &choice)| election.cast_ballot(voter_id as u64, choice, &mut rng))
+        .collect();
+    println!("{} ballots were cast", ballots.len());
+
+    // The ballots are verified and added up without decrypting any of them
+    let tally = election.tally(&ballots).expect("all ballots are valid");
+
+    // The first and third trustee decrypt the tally
+    let tally_shares = vec![
+        election.decrypt_tally_share(&tally, &trustee_keys[0]),
+        election.decrypt_tally_share(&tally, &trustee_keys[2]),
+    ];
+    let counts = election
+        .combine_tally(
+            &tally,
+            &tally_shares,
+            &verification_keys,
+            ballots.len() as u64,
+        )
+        .expect("all tally shares are valid");
+
+    for (candidate, count) in CANDIDATES.iter().zip(&counts) {
+        println!("{}: {} votes", candidate, count);
+    }
+
+    // Everything is published, so that observers can check the outcome
+    let transcript = ElectionTranscript {
+        election,
+        ballots,
+        tally_shares,
+        verification_keys,
+        counts,
+    };
+    let published = bincode::serialize(&transcript).expect("the transcript can be serialized");
+    println!("The published transcript takes {} bytes", published.len());
+
+    let observed: ElectionTranscript =
+        bincode::deserialize(&published).expect("the transcript can be deserialized");
+    assert!(observed.verify());
+    println!("The transcript was verified");
+}
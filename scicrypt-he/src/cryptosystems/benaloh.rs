@@ -0,0 +1,389 @@
+//! The Benaloh cryptosystem is additively homomorphic like Paillier, but its plaintext space is a
+//! small prime block size $r$. Its ciphertexts are as large as the modulus $n$ instead of $n^2$,
+//! so they are half the size of Paillier ciphertexts at the same security level. Decryption solves
+//! a discrete logarithm in a subgroup of order $r$, which is only efficient when $r$ is small.
+//!
+//! Here is an example of adding up encrypted votes.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::benaloh::Benaloh;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let benaloh = Benaloh::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = benaloh.generate_keys(&mut rng);
+//!
+//! let mut tally = public_key.encrypt(&0, &mut rng);
+//! for vote in [1, 0, 1, 1, 0] {
+//!     tally = &tally + &public_key.encrypt(&vote, &mut rng);
+//! }
+//!
+//! assert_eq!(3, secret_key.decrypt(&tally));
+//! ```
+
+use crate::cryptosystems::dgk::random_prime;
+use crate::cryptosystems::integer_el_gamal::table_key;
+use crate::identifier::Identifiable;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_numbertheory::gen_benaloh_modulus;
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The number of bits of the plaintext space when using `setup`.
+const DEFAULT_PLAINTEXT_BITS: u32 = 16;
+
+/// The Benaloh cryptosystem.
+#[derive(Copy, Clone)]
+pub struct Benaloh {
+    modulus_size: u32,
+    plaintext_bits: u32,
+    security_level: BitsOfSecurity,
+}
+
+/// Public key for the Benaloh cryptosystem.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BenalohPK {
+    /// Public modulus n for encryption
+    pub n: UnsignedInteger,
+    /// Element whose order is divisible by $r$, which encodes the plaintext
+    pub y: UnsignedInteger,
+    /// The prime block size r, which is the size of the plaintext space
+    pub r: u64,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for BenalohPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n)
+            & self.y.ct_eq(&other.y)
+            & self.r.ct_eq(&other.r)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
+}
+
+/// Decryption key for the Benaloh cryptosystem.
+pub struct BenalohSK {
+    p: UnsignedInteger,
+    /// The exponent $\varphi(n) / r$, which maps ciphertexts into the subgroup of order $r$
+    exponent: UnsignedInteger,
+    /// Baby steps $(y^{\varphi(n) / r})^j \bmod p$ to find plaintexts with the baby-step giant-step
+    /// algorithm
+    baby_steps: HashMap<Vec<u8>, u64>,
+    /// The inverse of $(y^{\varphi(n) / r})^m \bmod p$, where $m$ is the number of baby steps
+    giant_step: UnsignedInteger,
+}
+
+impl ConstantTimeEq for BenalohSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.p.ct_eq(&other.p) & self.exponent.ct_eq(&other.exponent)
+    }
+}
+
+impl Debug for BenalohSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BenalohSK")
+            .field("p", &Redacted::new(&self.p, self.p.size_in_bits()))
+            .field(
+                "exponent",
+                &Redacted::new(&self.exponent, self.exponent.size_in_bits()),
+            )
+            .finish()
+    }
+}
+
+/// Ciphertext of the Benaloh cryptosystem, which is additively homomorphic modulo $r$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct BenalohCiphertext {
+    /// Encrypted message (Ciphertext)
+    pub c: UnsignedInteger,
+}
+
+impl Identifiable for BenalohCiphertext {}
+
+impl ConstantTimeEq for BenalohCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c.ct_eq(&other.c)
+    }
+}
+
+impl Associable<BenalohPK> for BenalohCiphertext {}
+
+impl Benaloh {
+    /// Sets up Benaloh with a plaintext space of a prime $r$ of `plaintext_bits` bits. Decryption
+    /// takes time in the order of $\sqrt{r}$, and the secret key stores as many group elements.
+    pub fn setup_with_plaintext_bits(security_param: &BitsOfSecurity, plaintext_bits: u32) -> Self {
+        assert!(
+            (2..=32).contains(&plaintext_bits),
+            "the plaintext space must have between 2 and 32 bits"
+        );
+
+        Benaloh {
+            modulus_size: security_param.to_public_key_bit_length(),
+            plaintext_bits,
+            security_level: *security_param,
+        }
+    }
+}
+
+impl AsymmetricCryptosystem for Benaloh {
+    type PublicKey = BenalohPK;
+    type SecretKey = BenalohSK;
+
+    fn setup(security_param: &BitsOfSecurity) -> Self {
+        Benaloh::setup_with_plaintext_bits(security_param, DEFAULT_PLAINTEXT_BITS)
+    }
+
+    /// Generates primes $p$ and $q$ such that $r$ divides $p - 1$ exactly once and does not divide
+    /// $q - 1$, and an element $y$ for which $y^{\varphi(n) / r} \neq 1$. For a prime $r$, this
+    /// makes $y^{\varphi(n) / r}$ a generator of the subgroup of order $r$.
+    fn generate_keys<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> (BenalohPK, BenalohSK) {
+        trace_span!(INFO, "generate_keys");
+        // r must be odd, so that it does not divide q - 1 for every prime q
+        let r = loop {
+            let r = random_prime(self.plaintext_bits, rng);
+            if r.is_odd() {
+                break r;
+            }
+        };
+        let r_unsigned = UnsignedInteger::from(r.clone());
+
+        let (n, factorization) = gen_benaloh_modulus(self.modulus_size, &r_unsigned, rng);
+        let p = factorization.p.to_rug();
+        let q = factorization.q.to_rug();
+        let exponent = (p.clone() - 1u32) * (q - 1u32) / &r;
+
+        let (y, base) = loop {
+            let y = UnsignedInteger::random_below(&n, rng).to_rug();
+            let base = rug::Integer::from(y.pow_mod_ref(&exponent, &p).unwrap());
+
+            if base != 1 {
+                break (y, base);
+            }
+        };
+
+        let baby_step_count = r.to_f64().sqrt().ceil() as u64;
+        let mut baby_steps = HashMap::with_capacity(baby_step_count as usize);
+        let mut element = rug::Integer::from(1);
+        for j in 0..baby_step_count {
+            baby_steps.insert(table_key(&UnsignedInteger::from(element.clone())), j);
+            element = element * &base % &p;
+        }
+        let giant_step = element.invert(&p).unwrap();
+
+        (
+            BenalohPK {
+                n,
+                y: UnsignedInteger::from(y),
+                r: r.to_u64().unwrap(),
+                security_level: self.security_level,
+            },
+            BenalohSK {
+                p: UnsignedInteger::from(p),
+                exponent: UnsignedInteger::from(exponent),
+                baby_steps,
+                giant_step: UnsignedInteger::from(giant_step),
+            },
+        )
+    }
+}
+
+impl BenalohSK {
+    /// Maps the ciphertext into the subgroup of order $r$ modulo $p$, in which it is $1$ exactly
+    /// when it encrypts zero.
+    fn project(&self, ciphertext: &BenalohCiphertext) -> UnsignedInteger {
+        (ciphertext.c.clone() % &self.p).pow_mod(&self.exponent, &self.p)
+    }
+}
+
+impl EncryptionKey for BenalohPK {
+    type Input = u64;
+    type Plaintext = u64;
+    type Ciphertext = BenalohCiphertext;
+    type Randomness = UnsignedInteger;
+
+    fn encrypt_without_randomness(&self, plaintext: &u64) -> Self::Ciphertext {
+        assert!(*plaintext < self.r, "the plaintext must be smaller than r");
+
+        BenalohCiphertext {
+            c: self.y.pow_mod(&UnsignedInteger::from(*plaintext), &self.n),
+        }
+    }
+
+    fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: Self::Ciphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
+        // u must be coprime with n but this only fails with negligible probability
+        let u = UnsignedInteger::random_below(&self.n, rng);
+
+        self.randomize_with(ciphertext, &u)
+    }
+
+    fn randomize_with(
+        &self,
+        ciphertext: Self::Ciphertext,
+        randomness: &Self::Randomness,
+    ) -> Self::Ciphertext {
+        let randomizer = randomness.pow_mod(&UnsignedInteger::from(self.r), &self.n);
+
+        BenalohCiphertext {
+            c: (&ciphertext.c * &randomizer) % &self.n,
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: 63 - self.r.leading_zeros(),
+        }
+    }
+}
+
+impl DecryptionKey<BenalohPK> for BenalohSK {
+    /// Decrypts the ciphertext by solving a discrete logarithm in the subgroup of order $r$ with the
+    /// baby-step giant-step algorithm. This is not constant-time, and leaks the plaintext through its
+    /// running time.
+    fn decrypt_raw(&self, public_key: &BenalohPK, ciphertext: &BenalohCiphertext) -> u64 {
+        trace_span!(DEBUG, "decrypt");
+        let baby_step_count = self.baby_steps.len() as u64;
+        let mut current = self.project(ciphertext);
+
+        for i in 0..public_key.r.div_ceil(baby_step_count) {
+            if let Some(j) = self.baby_steps.get(&table_key(&current)) {
+                return i * baby_step_count + j;
+            }
+
+            current = (&current * &self.giant_step) % &self.p;
+        }
+
+        panic!("the ciphertext is not a valid encryption under this key")
+    }
+
+    fn decrypt_identity_raw(
+        &self,
+        _public_key: &BenalohPK,
+        ciphertext: &BenalohCiphertext,
+    ) -> bool {
+        self.project(ciphertext) == UnsignedInteger::from(1u64)
+    }
+}
+
+impl HomomorphicAddition for BenalohPK {
+    fn add(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
+        BenalohCiphertext {
+            c: (&ciphertext_a.c * &ciphertext_b.c) % &self.n,
+        }
+    }
+
+    fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &u64) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
+        BenalohCiphertext {
+            c: ciphertext
+                .c
+                .pow_mod(&UnsignedInteger::from(*input % self.r), &self.n),
+        }
+    }
+
+    /// Subtracts by adding $(r - 1)$ times `ciphertext_b`, which avoids computing an inverse.
+    fn sub(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
+        self.add(
+            ciphertext_a,
+            &self.mul_constant(ciphertext_b, &(self.r - 1)),
+        )
+    }
+
+    fn add_constant(&self, ciphertext: &Self::Ciphertext, constant: &u64) -> Self::Ciphertext {
+        self.add(
+            ciphertext,
+            &self.encrypt_without_randomness(&(constant % self.r)),
+        )
+    }
+
+    fn sub_constant(&self, ciphertext: &Self::Ciphertext, constant: &u64) -> Self::Ciphertext {
+        self.add_constant(ciphertext, &(self.r - constant % self.r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::benaloh::Benaloh;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let benaloh = Benaloh::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = benaloh.generate_keys(&mut rng);
+
+        for plaintext in [0, 1, 1000, pk.r - 1] {
+            let ciphertext = pk.encrypt(&plaintext, &mut rng);
+            assert_eq!(plaintext, sk.decrypt(&ciphertext));
+        }
+
+        assert!(sk.decrypt_identity(&pk.encrypt(&0, &mut rng)));
+        assert!(!sk.decrypt_identity(&pk.encrypt(&5, &mut rng)));
+    }
+
+    #[test]
+    fn test_small_plaintext_space() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let benaloh = Benaloh::setup_with_plaintext_bits(&BitsOfSecurity::ToyParameters, 5);
+        let (pk, sk) = benaloh.generate_keys(&mut rng);
+
+        for plaintext in 0..pk.r {
+            assert_eq!(plaintext, sk.decrypt(&pk.encrypt(&plaintext, &mut rng)));
+        }
+    }
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let benaloh = Benaloh::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = benaloh.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt(&20, &mut rng);
+        let ciphertext_b = pk.encrypt(&7, &mut rng);
+
+        assert_eq!(27, sk.decrypt(&(&ciphertext_a + &ciphertext_b)));
+        assert_eq!(13, sk.decrypt(&(&ciphertext_a - &ciphertext_b)));
+        assert_eq!(pk.r - 13, sk.decrypt(&(&ciphertext_b - &ciphertext_a)));
+        assert_eq!(60, sk.decrypt(&(&ciphertext_a * 3)));
+        assert_eq!(25, sk.decrypt(&(&ciphertext_a + 5)));
+        assert_eq!(15, sk.decrypt(&(&ciphertext_a - 5)));
+    }
+}
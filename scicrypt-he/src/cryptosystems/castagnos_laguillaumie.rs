@@ -0,0 +1,546 @@
+//! The Castagnos-Laguillaumie (CL) cryptosystem is a linearly homomorphic variant of ElGamal in
+//! the class group of an imaginary quadratic order of discriminant $\Delta_q = -q^3 \tilde{p}$.
+//! This group contains a subgroup of order $q$ generated by a form $f$ in which discrete
+//! logarithms are easy, so plaintexts are encoded as $f^m$ and can be decrypted efficiently.
+//! Its plaintext space $\mathbb{Z}_q$ can be chosen freely, e.g. as the order of an elliptic curve,
+//! which is why it is used in threshold ECDSA protocols. Key generation requires no trusted setup,
+//! because the order of the class group is unknown even to the key holder.
+//!
+//! Here is an example of homomorphically computing a linear function.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::castagnos_laguillaumie::CastagnosLaguillaumie;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let cl = CastagnosLaguillaumie::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = cl.generate_keys(&mut rng);
+//!
+//! let x = public_key.encrypt(&UnsignedInteger::from(12), &mut rng);
+//! let y = public_key.encrypt(&UnsignedInteger::from(30), &mut rng);
+//! let result = &(&x * &UnsignedInteger::from(3)) + &y;
+//!
+//! assert_eq!(UnsignedInteger::from(66), secret_key.decrypt(&result));
+//! ```
+//!
+//! The class group arithmetic is not constant-time, so this implementation leaks information about
+//! the secret key and the randomness through timing.
+
+use crate::cryptosystems::class_group::{integer_ct_eq, QuadraticForm};
+use crate::cryptosystems::dgk::random_prime;
+use crate::identifier::Identifiable;
+use rug::Integer;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The CL cryptosystem.
+#[derive(Clone)]
+pub struct CastagnosLaguillaumie {
+    discriminant_bits: u32,
+    message_space: Option<UnsignedInteger>,
+    security_level: BitsOfSecurity,
+}
+
+/// Public key for the CL cryptosystem.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct CastagnosLaguillaumiePK {
+    /// The prime q, which is the size of the plaintext space
+    pub q: UnsignedInteger,
+    /// The discriminant $\Delta_q = q^2 \Delta_K$ of the class group
+    pub discriminant: Integer,
+    /// Generator of the subgroup of order $q$ in which discrete logarithms are easy
+    pub f: QuadraticForm,
+    /// Generator of a subgroup of order $q s$ for an unknown $s$, which encodes the randomness
+    pub g: QuadraticForm,
+    /// The public key $h = g^x$
+    pub h: QuadraticForm,
+    /// The number of random bits used for the randomness, which exceeds a bound on $s$ by 40 bits
+    pub randomness_bits: u32,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for CastagnosLaguillaumiePK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.q.ct_eq(&other.q)
+            & integer_ct_eq(&self.discriminant, &other.discriminant)
+            & self.f.ct_eq(&other.f)
+            & self.g.ct_eq(&other.g)
+            & self.h.ct_eq(&other.h)
+            & self.randomness_bits.ct_eq(&other.randomness_bits)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
+}
+
+/// Decryption key for the CL cryptosystem.
+pub struct CastagnosLaguillaumieSK {
+    x: UnsignedInteger,
+}
+
+impl ConstantTimeEq for CastagnosLaguillaumieSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.x.ct_eq(&other.x)
+    }
+}
+
+impl Debug for CastagnosLaguillaumieSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CastagnosLaguillaumieSK")
+            .field("x", &Redacted::new(&self.x, self.x.size_in_bits()))
+            .finish()
+    }
+}
+
+/// Ciphertext of the CL cryptosystem, which is additively homomorphic modulo $q$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct CastagnosLaguillaumieCiphertext {
+    /// The randomness $g^r$
+    pub c1: QuadraticForm,
+    /// The encoded message $f^m h^r$
+    pub c2: QuadraticForm,
+}
+
+impl Identifiable for CastagnosLaguillaumieCiphertext {}
+
+impl ConstantTimeEq for CastagnosLaguillaumieCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl Associable<CastagnosLaguillaumiePK> for CastagnosLaguillaumieCiphertext {}
+
+/// The number of bits of the fundamental discriminant $\Delta_K$ for a security level, following
+/// the estimates of Biasse, Jacobson and Silvester for computing class groups.
+fn discriminant_bits(security_param: &BitsOfSecurity) -> u32 {
+    match security_param {
+        BitsOfSecurity::ToyParameters => 256,
+        BitsOfSecurity::AES80 => 1024,
+        BitsOfSecurity::AES112 => 1348,
+        BitsOfSecurity::AES128 => 1827,
+        BitsOfSecurity::AES192 => 3598,
+        BitsOfSecurity::AES256 => 5971,
+        BitsOfSecurity::Custom { pk_bits } => *pk_bits,
+    }
+}
+
+impl CastagnosLaguillaumie {
+    /// Sets up CL with the plaintext space $\mathbb{Z}_q$ for the given prime `q`, for example the
+    /// order of the elliptic curve of a threshold ECDSA protocol. The prime must have fewer than
+    /// half as many bits as the discriminant.
+    pub fn setup_with_message_space(security_param: &BitsOfSecurity, q: &UnsignedInteger) -> Self {
+        let discriminant_bits = discriminant_bits(security_param);
+        assert!(
+            2 * q.size_in_bits() + 8 <= discriminant_bits,
+            "q must have fewer than half as many bits as the discriminant"
+        );
+        assert!(
+            q.mod_u_leaky(2) == 1 && q.is_probably_prime_leaky(),
+            "q must be an odd prime"
+        );
+
+        CastagnosLaguillaumie {
+            discriminant_bits,
+            message_space: Some(q.clone()),
+            security_level: *security_param,
+        }
+    }
+
+    fn generate_q<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> Integer {
+        if let Some(q) = &self.message_space {
+            return q.clone().to_rug();
+        }
+
+        let q_bits = match self.discriminant_bits {
+            bits if bits < 1024 => 64,
+            _ => 256,
+        };
+
+        loop {
+            let q = random_prime(q_bits, rng);
+            if q.is_odd() {
+                return q;
+            }
+        }
+    }
+}
+
+impl AsymmetricCryptosystem for CastagnosLaguillaumie {
+    type PublicKey = CastagnosLaguillaumiePK;
+    type SecretKey = CastagnosLaguillaumieSK;
+
+    /// Sets up CL with a random prime $q$ of 256 bits, or 64 bits for toy parameters.
+    fn setup(security_param: &BitsOfSecurity) -> Self {
+        CastagnosLaguillaumie {
+            discriminant_bits: discriminant_bits(security_param),
+            message_space: None,
+            security_level: *security_param,
+        }
+    }
+
+    /// Generates a fundamental discriminant $\Delta_K = -q \tilde{p}$ such that
+    /// $q \tilde{p} \equiv 3 \pmod 4$ and $\left(\frac{q}{\tilde{p}}\right) = -1$. The generator
+    /// $g$ is $f$ times the $q$-th power of the lift of a squared small prime form to
+    /// discriminant $\Delta_q = q^2 \Delta_K$.
+    fn generate_keys<R: SecureRng>(
+        &self,
+        rng: &mut GeneralRng<R>,
+    ) -> (CastagnosLaguillaumiePK, CastagnosLaguillaumieSK) {
+        trace_span!(INFO, "generate_keys");
+        let q = self.generate_q(rng);
+        let p_tilde_bits = self.discriminant_bits - q.significant_bits();
+
+        let fundamental_discriminant = loop {
+            let p_tilde = random_prime(p_tilde_bits, rng);
+            let product = Integer::from(&q * &p_tilde);
+
+            if product.mod_u(4) == 3 && q.jacobi(&p_tilde) == -1 {
+                break -product;
+            }
+        };
+
+        let q_squared = Integer::from(q.square_ref());
+        let discriminant = Integer::from(&fundamental_discriminant * &q_squared);
+
+        let f = QuadraticForm::from_a_b(q_squared.clone(), q.clone(), &discriminant);
+
+        let lifted = lift_small_prime_form(&fundamental_discriminant, &q);
+        let g = lifted.pow_leaky(&q).compose(&f);
+
+        let randomness_bits = (self.discriminant_bits / 2 + 64).div_ceil(64) * 64;
+        let x = UnsignedInteger::random(randomness_bits, rng);
+        let h = g.pow_leaky(&x.clone().to_rug());
+
+        (
+            CastagnosLaguillaumiePK {
+                q: UnsignedInteger::from(q),
+                discriminant,
+                f,
+                g,
+                h,
+                randomness_bits,
+                security_level: self.security_level,
+            },
+            CastagnosLaguillaumieSK { x },
+        )
+    }
+}
+
+/// Finds the form of the smallest prime $\ell$ that splits in the order of discriminant
+/// $\Delta_K$, squares it and lifts it to the order of discriminant $q^2 \Delta_K$.
+fn lift_small_prime_form(fundamental_discriminant: &Integer, q: &Integer) -> QuadraticForm {
+    let mut l = Integer::from(3);
+
+    loop {
+        if l != *q && fundamental_discriminant.kronecker(&l) == 1 {
+            // The discriminant is odd, so b must be odd to satisfy b^2 = discriminant mod 4l
+            let l_u32 = l.to_u32().expect("a splitting prime is found quickly");
+            let b = (1..2 * l_u32)
+                .step_by(2)
+                .find(|b| {
+                    (Integer::from(b * b) - fundamental_discriminant).is_divisible_u(4 * l_u32)
+                })
+                .expect("b exists because the discriminant is a square modulo l");
+
+            let squared =
+                QuadraticForm::from_a_b(l.clone(), Integer::from(b), fundamental_discriminant)
+                    .compose(&QuadraticForm::from_a_b(
+                        l.clone(),
+                        Integer::from(b),
+                        fundamental_discriminant,
+                    ));
+
+            if Integer::from(squared.a.gcd_ref(q)) == 1 {
+                return QuadraticForm {
+                    a: squared.a,
+                    b: squared.b * q,
+                    c: squared.c * Integer::from(q.square_ref()),
+                }
+                .reduce();
+            }
+        }
+
+        l.next_prime_mut();
+    }
+}
+
+impl CastagnosLaguillaumiePK {
+    /// Computes $f^m$ directly: for $m \not\equiv 0 \pmod q$, it is the reduced form
+    /// $(q^2, L(m) q, \cdot)$, where $L(m)$ is the odd integer in $[-q, q]$ congruent to $m^{-1}$
+    /// modulo $q$.
+    fn power_of_f(&self, m: &Integer) -> QuadraticForm {
+        let q = self.q.clone().to_rug();
+        let m = Integer::from(m % &q);
+
+        if m == 0 {
+            return QuadraticForm::identity(&self.discriminant);
+        }
+
+        let inverse = m.invert(&q).expect("q is prime");
+        let l = if inverse.is_odd() {
+            inverse
+        } else {
+            inverse - &q
+        };
+
+        QuadraticForm::from_a_b(Integer::from(q.square_ref()), l * &q, &self.discriminant)
+    }
+}
+
+impl EncryptionKey for CastagnosLaguillaumiePK {
+    type Input = UnsignedInteger;
+    type Plaintext = UnsignedInteger;
+    type Ciphertext = CastagnosLaguillaumieCiphertext;
+    type Randomness = UnsignedInteger;
+
+    fn encrypt_without_randomness(&self, plaintext: &Self::Plaintext) -> Self::Ciphertext {
+        CastagnosLaguillaumieCiphertext {
+            c1: QuadraticForm::identity(&self.discriminant),
+            c2: self.power_of_f(&plaintext.clone().to_rug()),
+        }
+    }
+
+    fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: Self::Ciphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
+        let r = UnsignedInteger::random(self.randomness_bits, rng);
+
+        self.randomize_with(ciphertext, &r)
+    }
+
+    fn randomize_with(
+        &self,
+        ciphertext: Self::Ciphertext,
+        randomness: &Self::Randomness,
+    ) -> Self::Ciphertext {
+        let r = randomness.clone().to_rug();
+
+        CastagnosLaguillaumieCiphertext {
+            c1: ciphertext.c1.compose(&self.g.pow_leaky(&r)),
+            c2: ciphertext.c2.compose(&self.h.pow_leaky(&r)),
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: self.q.significant_bits_leaky() - 1,
+        }
+    }
+}
+
+impl CastagnosLaguillaumieSK {
+    /// Removes the randomness from the ciphertext, which leaves $f^m$.
+    fn unmask(&self, ciphertext: &CastagnosLaguillaumieCiphertext) -> QuadraticForm {
+        let mask = ciphertext.c1.pow_leaky(&self.x.clone().to_rug());
+
+        ciphertext.c2.compose(&mask.inverse())
+    }
+}
+
+impl DecryptionKey<CastagnosLaguillaumiePK> for CastagnosLaguillaumieSK {
+    /// Decrypts the ciphertext by reading $m$ from the reduced form $f^m = (q^2, L(m) q, \cdot)$.
+    /// This is not constant-time.
+    fn decrypt_raw(
+        &self,
+        public_key: &CastagnosLaguillaumiePK,
+        ciphertext: &CastagnosLaguillaumieCiphertext,
+    ) -> UnsignedInteger {
+        trace_span!(DEBUG, "decrypt");
+        let encoded = self.unmask(ciphertext);
+        if encoded.is_identity() {
+            return UnsignedInteger::zero(0);
+        }
+
+        let q = public_key.q.clone().to_rug();
+        assert!(
+            encoded.a == Integer::from(q.square_ref()) && encoded.b.is_divisible(&q),
+            "the ciphertext is not a valid encryption under this key"
+        );
+
+        let l = encoded.b.div_exact(&q);
+        let m = l.invert(&q).expect("L(m) is invertible modulo q");
+
+        UnsignedInteger::from(m)
+    }
+
+    fn decrypt_identity_raw(
+        &self,
+        _public_key: &CastagnosLaguillaumiePK,
+        ciphertext: &CastagnosLaguillaumieCiphertext,
+    ) -> bool {
+        self.unmask(ciphertext).is_identity()
+    }
+}
+
+impl HomomorphicAddition for CastagnosLaguillaumiePK {
+    fn add(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
+        CastagnosLaguillaumieCiphertext {
+            c1: ciphertext_a.c1.compose(&ciphertext_b.c1),
+            c2: ciphertext_a.c2.compose(&ciphertext_b.c2),
+        }
+    }
+
+    fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
+        let exponent = input.clone().to_rug();
+
+        CastagnosLaguillaumieCiphertext {
+            c1: ciphertext.c1.pow_leaky(&exponent),
+            c2: ciphertext.c2.pow_leaky(&exponent),
+        }
+    }
+
+    fn sub(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
+        CastagnosLaguillaumieCiphertext {
+            c1: ciphertext_a.c1.compose(&ciphertext_b.c1.inverse()),
+            c2: ciphertext_a.c2.compose(&ciphertext_b.c2.inverse()),
+        }
+    }
+
+    fn add_constant(
+        &self,
+        ciphertext: &Self::Ciphertext,
+        constant: &Self::Plaintext,
+    ) -> Self::Ciphertext {
+        CastagnosLaguillaumieCiphertext {
+            c1: ciphertext.c1.clone(),
+            c2: ciphertext
+                .c2
+                .compose(&self.power_of_f(&constant.clone().to_rug())),
+        }
+    }
+
+    fn sub_constant(
+        &self,
+        ciphertext: &Self::Ciphertext,
+        constant: &Self::Plaintext,
+    ) -> Self::Ciphertext {
+        CastagnosLaguillaumieCiphertext {
+            c1: ciphertext.c1.clone(),
+            c2: ciphertext
+                .c2
+                .compose(&self.power_of_f(&constant.clone().to_rug()).inverse()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::castagnos_laguillaumie::CastagnosLaguillaumie;
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let cl = CastagnosLaguillaumie::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = cl.generate_keys(&mut rng);
+
+        let largest = UnsignedInteger::from(pk.q.clone().to_rug() - 1u32);
+        for plaintext in [
+            UnsignedInteger::from(1u64),
+            UnsignedInteger::from(15u64),
+            largest,
+        ] {
+            let ciphertext = pk.encrypt(&plaintext, &mut rng);
+            assert_eq!(plaintext, sk.decrypt(&ciphertext));
+        }
+
+        let zero = pk.encrypt(&UnsignedInteger::zero(0), &mut rng);
+        assert!(sk.decrypt_identity(&zero));
+        assert!(sk.decrypt(&zero).is_zero_leaky());
+    }
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let cl = CastagnosLaguillaumie::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = cl.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt(&UnsignedInteger::from(20u64), &mut rng);
+        let ciphertext_b = pk.encrypt(&UnsignedInteger::from(7u64), &mut rng);
+
+        assert_eq!(
+            UnsignedInteger::from(27u64),
+            sk.decrypt(&(&ciphertext_a + &ciphertext_b))
+        );
+        assert_eq!(
+            UnsignedInteger::from(13u64),
+            sk.decrypt(&(&ciphertext_a - &ciphertext_b))
+        );
+        assert_eq!(
+            UnsignedInteger::from(pk.q.clone().to_rug() - 13u32),
+            sk.decrypt(&(&ciphertext_b - &ciphertext_a))
+        );
+        assert_eq!(
+            UnsignedInteger::from(60u64),
+            sk.decrypt(&(&ciphertext_a * &UnsignedInteger::from(3u64)))
+        );
+        assert_eq!(
+            UnsignedInteger::from(25u64),
+            sk.decrypt(&(&ciphertext_a + &UnsignedInteger::from(5u64)))
+        );
+        assert_eq!(
+            UnsignedInteger::from(15u64),
+            sk.decrypt(&(&ciphertext_a - &UnsignedInteger::from(5u64)))
+        );
+    }
+
+    #[test]
+    fn test_chosen_message_space() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        // The order of the secp256k1 curve, as used in threshold ECDSA
+        let q = UnsignedInteger::from_string_leaky(
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141".to_string(),
+            16,
+            256,
+        );
+        let cl = CastagnosLaguillaumie::setup_with_message_space(
+            &BitsOfSecurity::Custom { pk_bits: 640 },
+            &q,
+        );
+        let (pk, sk) = cl.generate_keys(&mut rng);
+        assert_eq!(q, pk.q);
+
+        let plaintext = UnsignedInteger::from(q.clone().to_rug() - 2u32);
+        let ciphertext = pk.encrypt(&plaintext, &mut rng);
+        assert_eq!(plaintext, sk.decrypt(&ciphertext));
+    }
+}
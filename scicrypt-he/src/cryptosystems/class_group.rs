@@ -0,0 +1,266 @@
+//! The class group of an imaginary quadratic order, whose elements are represented by reduced
+//! positive definite binary quadratic forms $a x^2 + b x y + c y^2$ of a negative discriminant
+//! $\Delta = b^2 - 4 a c$. Every class contains exactly one reduced form, so reduced forms can be
+//! compared directly. The group operation is composition of forms, following Algorithm 5.4.7 of
+//! Cohen's "A Course in Computational Algebraic Number Theory".
+//!
+//! The order of a class group is hard to compute for large discriminants, which makes it a group of
+//! unknown order. This is the backend of the Castagnos-Laguillaumie cryptosystem.
+
+use rug::integer::Order;
+use rug::ops::{DivRounding, RemRounding};
+use rug::Integer;
+use serde::{Deserialize, Serialize};
+use subtle::{Choice, ConstantTimeEq};
+
+/// A binary quadratic form $a x^2 + b x y + c y^2$, which represents an element of a class group.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct QuadraticForm {
+    /// The coefficient of $x^2$, which is positive for positive definite forms
+    pub a: Integer,
+    /// The coefficient of $x y$
+    pub b: Integer,
+    /// The coefficient of $y^2$
+    pub c: Integer,
+}
+
+impl ConstantTimeEq for QuadraticForm {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        integer_ct_eq(&self.a, &other.a)
+            & integer_ct_eq(&self.b, &other.b)
+            & integer_ct_eq(&self.c, &other.c)
+    }
+}
+
+impl QuadraticForm {
+    /// Creates the form with coefficients `a` and `b` of the given `discriminant`, which determines
+    /// $c = (b^2 - \Delta) / (4 a)$. The result is reduced. Panics if $b^2 - \Delta$ is not divisible
+    /// by $4 a$.
+    pub fn from_a_b(a: Integer, b: Integer, discriminant: &Integer) -> Self {
+        let numerator = Integer::from(b.square_ref()) - discriminant;
+        let denominator = Integer::from(&a * 4u32);
+        assert!(
+            numerator.is_divisible(&denominator),
+            "b^2 - discriminant must be divisible by 4a"
+        );
+
+        QuadraticForm {
+            a,
+            b,
+            c: numerator.div_exact(&denominator),
+        }
+        .reduce()
+    }
+
+    /// Returns the identity of the class group of the given `discriminant`, which is the principal
+    /// form.
+    pub fn identity(discriminant: &Integer) -> Self {
+        let b = Integer::from(discriminant.is_odd());
+
+        QuadraticForm::from_a_b(Integer::from(1), b, discriminant)
+    }
+
+    /// Returns the discriminant $b^2 - 4 a c$ of this form.
+    pub fn discriminant(&self) -> Integer {
+        Integer::from(self.b.square_ref()) - Integer::from(&self.a * &self.c) * 4u32
+    }
+
+    /// Returns true if this form is reduced, i.e. $|b| \leq a \leq c$, where $b \geq 0$ if either
+    /// inequality is an equality.
+    pub fn is_reduced(&self) -> bool {
+        let b_abs = Integer::from(self.b.abs_ref());
+
+        b_abs <= self.a
+            && self.a <= self.c
+            && (self.b >= 0 || (b_abs != self.a && self.a != self.c))
+    }
+
+    /// Returns true if this form is the identity of its class group.
+    pub fn is_identity(&self) -> bool {
+        self.a == 1
+    }
+
+    /// Moves $b$ into the interval $(-a, a]$ without changing the class of the form.
+    fn normalize(&mut self) {
+        let a_neg = Integer::from(-&self.a);
+        if a_neg < self.b && self.b <= self.a {
+            return;
+        }
+
+        let r = Integer::from(&self.a - &self.b).div_floor(Integer::from(&self.a * 2u32));
+        self.c += Integer::from(&self.a * &r) * &r + Integer::from(&self.b * &r);
+        self.b += Integer::from(&self.a * &r) * 2u32;
+    }
+
+    /// Returns the unique reduced form in the same class as this form.
+    pub fn reduce(mut self) -> Self {
+        self.normalize();
+
+        while self.a > self.c || (self.a == self.c && self.b < 0) {
+            std::mem::swap(&mut self.a, &mut self.c);
+            self.b = -self.b;
+            self.normalize();
+        }
+
+        self
+    }
+
+    /// Returns the inverse of this form in the class group.
+    pub fn inverse(&self) -> Self {
+        QuadraticForm {
+            a: self.a.clone(),
+            b: Integer::from(-&self.b),
+            c: self.c.clone(),
+        }
+        .reduce()
+    }
+
+    /// Composes this form with `other`, which must have the same discriminant, and reduces the
+    /// result.
+    pub fn compose(&self, other: &Self) -> Self {
+        let (f1, f2) = if self.a > other.a {
+            (other, self)
+        } else {
+            (self, other)
+        };
+
+        let s = Integer::from(&f1.b + &f2.b) >> 1u32;
+        let n = Integer::from(&f2.b - &s);
+
+        let (y1, d) = if f2.a.is_divisible(&f1.a) {
+            (Integer::new(), f1.a.clone())
+        } else {
+            let (d, u, _) = f2.a.clone().gcd_cofactors(f1.a.clone(), Integer::new());
+            (u, d)
+        };
+
+        let (x2, y2, d1) = if s.is_divisible(&d) {
+            (Integer::new(), Integer::from(-1), d)
+        } else {
+            let (d1, u, v) = s.clone().gcd_cofactors(d, Integer::new());
+            (u, -v, d1)
+        };
+
+        let v1 = Integer::from(&f1.a / &d1);
+        let v2 = Integer::from(&f2.a / &d1);
+        let r = (Integer::from(&y1 * &y2) * &n - Integer::from(&x2 * &f2.c)).rem_euc(&v1);
+
+        let b3 = Integer::from(&v2 * &r) * 2u32 + &f2.b;
+        let a3 = v1 * v2;
+
+        QuadraticForm::from_a_b(a3, b3, &self.discriminant())
+    }
+
+    /// Raises this form to the non-negative power `exponent` using square-and-multiply. This
+    /// function is not constant-time.
+    pub fn pow_leaky(&self, exponent: &Integer) -> Self {
+        assert!(*exponent >= 0, "the exponent must not be negative");
+
+        let mut result = QuadraticForm::identity(&self.discriminant());
+        for i in (0..exponent.significant_bits()).rev() {
+            result = result.compose(&result);
+
+            if exponent.get_bit(i) {
+                result = result.compose(self);
+            }
+        }
+
+        result
+    }
+}
+
+/// Compares two integers by their signs and their magnitudes, padded to the larger of the two
+/// lengths. The running time depends on these lengths, but not on where the values differ.
+pub(crate) fn integer_ct_eq(a: &Integer, b: &Integer) -> Choice {
+    let mut a_limbs = a.to_digits::<u64>(Order::Lsf);
+    let mut b_limbs = b.to_digits::<u64>(Order::Lsf);
+
+    let limb_count = a_limbs.len().max(b_limbs.len());
+    a_limbs.resize(limb_count, 0);
+    b_limbs.resize(limb_count, 0);
+
+    (a.cmp0() as i8 as u8).ct_eq(&(b.cmp0() as i8 as u8)) & a_limbs.ct_eq(&b_limbs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::class_group::{integer_ct_eq, QuadraticForm};
+    use rug::Integer;
+
+    #[test]
+    fn test_reduce() {
+        // 10x^2 + 19xy + 10y^2 has discriminant -39 and is equivalent to x^2 + xy + 10y^2
+        let form = QuadraticForm {
+            a: Integer::from(10),
+            b: Integer::from(19),
+            c: Integer::from(10),
+        }
+        .reduce();
+
+        assert!(form.is_reduced());
+        assert_eq!(Integer::from(-39), form.discriminant());
+        assert_eq!(QuadraticForm::identity(&Integer::from(-39)), form);
+        assert!(form.is_identity());
+    }
+
+    #[test]
+    fn test_class_group_of_discriminant_minus_47() {
+        // The class group of discriminant -47 is cyclic of order 5
+        let discriminant = Integer::from(-47);
+        let generator = QuadraticForm::from_a_b(Integer::from(2), Integer::from(1), &discriminant);
+
+        let mut element = generator.clone();
+        for _ in 1..5 {
+            assert!(!element.is_identity());
+            assert!(element.is_reduced());
+            element = element.compose(&generator);
+        }
+        assert!(element.is_identity());
+
+        assert_eq!(
+            generator.pow_leaky(&Integer::from(3)),
+            generator.compose(&generator).compose(&generator)
+        );
+        assert!(generator.compose(&generator.inverse()).is_identity());
+        assert_eq!(generator.pow_leaky(&Integer::from(4)), generator.inverse());
+    }
+
+    #[test]
+    fn test_compose_is_associative() {
+        let discriminant = -(Integer::from(1) << 80u32) - 7u32 * 4u32 + 1u32;
+        let forms: Vec<_> = [3u32, 5, 7, 11, 13]
+            .iter()
+            .filter(|&&p| discriminant.kronecker(&Integer::from(p)) == 1)
+            .map(|&p| {
+                let b = (1..2 * p)
+                    .step_by(2)
+                    .find(|b| (Integer::from(b * b) - &discriminant).is_divisible_u(4 * p))
+                    .unwrap();
+                QuadraticForm::from_a_b(Integer::from(p), Integer::from(b), &discriminant)
+            })
+            .collect();
+        assert!(forms.len() >= 2);
+
+        let x = forms[0].pow_leaky(&Integer::from(12345));
+        let y = forms[1].pow_leaky(&Integer::from(67890));
+        let z = forms[0].compose(&forms[1]).pow_leaky(&Integer::from(1000));
+
+        assert_eq!(x.compose(&y).compose(&z), x.compose(&y.compose(&z)));
+        assert_eq!(discriminant, x.compose(&y).discriminant());
+    }
+
+    #[test]
+    fn test_integer_ct_eq() {
+        let cases = [
+            (Integer::from(0), Integer::from(0), true),
+            (Integer::from(-5), Integer::from(-5), true),
+            (Integer::from(5), Integer::from(-5), false),
+            (Integer::from(1) << 200, Integer::from(1) << 200, true),
+            (Integer::from(1) << 200, Integer::from(1), false),
+        ];
+
+        for (a, b, equal) in cases {
+            assert_eq!(equal, bool::from(integer_ct_eq(&a, &b)));
+        }
+    }
+}
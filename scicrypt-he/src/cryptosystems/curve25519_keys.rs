@@ -0,0 +1,214 @@
+//! Imports of existing X25519 and Ed25519 secret keys as curve-based ElGamal key pairs, so that
+//! deployments that already manage Curve25519 keys can start encrypting to them without
+//! distributing new keys. The Ristretto group of `CurveElGamal` is built on the same curve and
+//! basepoint, so a secret scalar of X25519 or Ed25519 is also a valid ElGamal secret key.
+//!
+//! Some caveats apply:
+//! - Only secret keys can be imported. A Ristretto point cannot be recovered from an X25519 or
+//!   Ed25519 public key alone, so the ElGamal public key must be derived and published by the
+//!   owner of the secret key.
+//! - The resulting ElGamal public key has a different encoding than the original public key, even
+//!   though it represents the same multiple of the basepoint.
+//! - Reusing one secret across protocols is only safe as long as none of them reveals values that
+//!   help to attack the others. In particular, an ElGamal decryption oracle reveals $x \cdot P$ for
+//!   points $P$ of the attacker's choice, which is exactly the X25519 shared secret with $P$.
+//!   Deployments that can afford it should derive a separate key instead, for instance a hardened
+//!   child of `ExtendedCurveElGamalSK::from_seed` in the `key_derivation` module.
+//!
+//! The `*_key_pair` functions additionally check that the secret key belongs to the given original
+//! public key, which catches keys that were truncated, swapped or stored in the wrong format.
+//! ```
+//! # use scicrypt_he::cryptosystems::curve25519_keys::from_x25519_key_pair;
+//! # use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! # let secret_key = [0x77, 0x07, 0x6d, 0x0a, 0x73, 0x18, 0xa5, 0x7d, 0x3c, 0x16, 0xc1, 0x72, 0x51, 0xb2, 0x66, 0x45, 0xdf, 0x4c, 0x2f, 0x87, 0xeb, 0xc0, 0x99, 0x2a, 0xb1, 0x77, 0xfb, 0xa5, 0x1d, 0xb9, 0x2c, 0x2a];
+//! # let public_key = [0x85, 0x20, 0xf0, 0x09, 0x89, 0x30, 0xa7, 0x54, 0x74, 0x8b, 0x7d, 0xdc, 0xb4, 0x3e, 0xf7, 0x5a, 0x0d, 0xbf, 0x3a, 0x0d, 0x26, 0x38, 0x1a, 0xf4, 0xeb, 0xa4, 0xa9, 0x8e, 0xaa, 0x9b, 0x4e, 0x6a];
+//! let (el_gamal_pk, el_gamal_sk) = from_x25519_key_pair(&secret_key, &public_key).unwrap();
+//!
+//! let mut rng = GeneralRng::new(OsRng);
+//! let ciphertext = el_gamal_pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+//! assert_eq!(RISTRETTO_BASEPOINT_POINT, el_gamal_sk.decrypt(&ciphertext));
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{
+    CurveElGamalPK, CurveElGamalSK, PrecomputedCurveElGamalPK,
+};
+use curve25519_dalek::constants::{ED25519_BASEPOINT_TABLE, RISTRETTO_BASEPOINT_TABLE};
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha512};
+use subtle::ConstantTimeEq;
+
+/// Error returned when an imported key pair is not consistent.
+#[derive(PartialEq, Eq, Debug)]
+pub enum KeyImportError {
+    /// The secret key does not correspond to the given public key
+    PublicKeyMismatch,
+    /// The secret scalar is zero modulo the group order, which cannot be used as a secret key
+    ZeroKey,
+}
+
+/// Imports an X25519 secret key, which is clamped the same way as in X25519 itself.
+pub fn from_x25519_secret_key(
+    secret_key: &[u8; 32],
+) -> Result<(PrecomputedCurveElGamalPK, CurveElGamalSK), KeyImportError> {
+    key_pair(clamp(*secret_key))
+}
+
+/// Imports an X25519 secret key after checking that it belongs to the X25519 public key
+/// `public_key`, which is the $u$-coordinate of a point on Curve25519.
+pub fn from_x25519_key_pair(
+    secret_key: &[u8; 32],
+    public_key: &[u8; 32],
+) -> Result<(PrecomputedCurveElGamalPK, CurveElGamalSK), KeyImportError> {
+    let scalar = clamp(*secret_key);
+    let expected = (&scalar * &ED25519_BASEPOINT_TABLE).to_montgomery();
+
+    if !bool::from(expected.as_bytes().ct_eq(public_key)) {
+        return Err(KeyImportError::PublicKeyMismatch);
+    }
+
+    key_pair(scalar)
+}
+
+/// Imports an Ed25519 secret key, which is the 32-byte seed that is hashed to obtain the secret
+/// scalar as specified in RFC 8032.
+pub fn from_ed25519_secret_key(
+    secret_key: &[u8; 32],
+) -> Result<(PrecomputedCurveElGamalPK, CurveElGamalSK), KeyImportError> {
+    key_pair(ed25519_scalar(secret_key))
+}
+
+/// Imports an Ed25519 secret key after checking that it belongs to the compressed Ed25519 public
+/// key `public_key`.
+pub fn from_ed25519_key_pair(
+    secret_key: &[u8; 32],
+    public_key: &[u8; 32],
+) -> Result<(PrecomputedCurveElGamalPK, CurveElGamalSK), KeyImportError> {
+    let scalar = ed25519_scalar(secret_key);
+    let expected = (&scalar * &ED25519_BASEPOINT_TABLE).compress();
+
+    if !bool::from(expected.as_bytes().ct_eq(public_key)) {
+        return Err(KeyImportError::PublicKeyMismatch);
+    }
+
+    key_pair(scalar)
+}
+
+/// Clamps the secret key as in X25519: the lowest three bits are cleared so that the scalar is a
+/// multiple of the cofactor, and bit 254 is set.
+fn clamp(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+
+    Scalar::from_bits(bytes)
+}
+
+/// Expands an Ed25519 seed into its secret scalar, which is the clamped first half of its hash.
+fn ed25519_scalar(seed: &[u8; 32]) -> Scalar {
+    let hash = Sha512::digest(seed);
+
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&hash[..32]);
+
+    clamp(bytes)
+}
+
+/// Reduces the clamped `scalar` modulo the group order. Because the basepoint has prime order,
+/// this does not change the public key.
+fn key_pair(scalar: Scalar) -> Result<(PrecomputedCurveElGamalPK, CurveElGamalSK), KeyImportError> {
+    let key = scalar.reduce();
+
+    if bool::from(key.ct_eq(&Scalar::zero())) {
+        return Err(KeyImportError::ZeroKey);
+    }
+
+    let public_key = CurveElGamalPK::new(&key * &RISTRETTO_BASEPOINT_TABLE);
+
+    Ok((public_key.precompute(), CurveElGamalSK { key }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::curve25519_keys::{
+        from_ed25519_key_pair, from_ed25519_secret_key, from_x25519_key_pair,
+        from_x25519_secret_key, KeyImportError,
+    };
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+
+    fn from_hex(hex: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+
+        bytes
+    }
+
+    // Alice's key pair from section 6.1 of RFC 7748
+    const X25519_SECRET_KEY: &str =
+        "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a";
+    const X25519_PUBLIC_KEY: &str =
+        "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a";
+
+    // Test 1 from section 7.1 of RFC 8032
+    const ED25519_SECRET_KEY: &str =
+        "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const ED25519_PUBLIC_KEY: &str =
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+    #[test]
+    fn test_x25519_import() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) =
+            from_x25519_key_pair(&from_hex(X25519_SECRET_KEY), &from_hex(X25519_PUBLIC_KEY))
+                .unwrap();
+
+        assert_eq!(
+            pk,
+            from_x25519_secret_key(&from_hex(X25519_SECRET_KEY))
+                .unwrap()
+                .0
+        );
+
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        assert_eq!(RISTRETTO_BASEPOINT_POINT, sk.decrypt(&ciphertext));
+    }
+
+    #[test]
+    fn test_ed25519_import() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) =
+            from_ed25519_key_pair(&from_hex(ED25519_SECRET_KEY), &from_hex(ED25519_PUBLIC_KEY))
+                .unwrap();
+
+        assert_eq!(
+            pk,
+            from_ed25519_secret_key(&from_hex(ED25519_SECRET_KEY))
+                .unwrap()
+                .0
+        );
+
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        assert_eq!(RISTRETTO_BASEPOINT_POINT, sk.decrypt(&ciphertext));
+    }
+
+    #[test]
+    fn test_public_key_mismatch() {
+        assert_eq!(
+            Err(KeyImportError::PublicKeyMismatch),
+            from_x25519_key_pair(&from_hex(X25519_SECRET_KEY), &from_hex(ED25519_PUBLIC_KEY))
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err(KeyImportError::PublicKeyMismatch),
+            from_ed25519_key_pair(&from_hex(X25519_SECRET_KEY), &from_hex(ED25519_PUBLIC_KEY))
+                .map(|_| ())
+        );
+    }
+}
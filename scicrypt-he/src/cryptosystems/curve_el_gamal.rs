@@ -1,21 +1,32 @@
-use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
-use curve25519_dalek::ristretto::{RistrettoBasepointTable, RistrettoPoint};
+use crate::identifier::{hash_serialized, Identifiable};
+use curve25519_dalek::constants::{RISTRETTO_BASEPOINT_POINT, RISTRETTO_BASEPOINT_TABLE};
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoBasepointTable, RistrettoPoint};
 use curve25519_dalek::scalar::Scalar;
-use curve25519_dalek::traits::Identity;
+use curve25519_dalek::traits::{Identity, MultiscalarMul, VartimeMultiscalarMul};
 use scicrypt_traits::cryptosystems::{
     Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
 };
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
 use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::kem::{Kem, SharedSecret};
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
-use serde::{Deserialize, Serialize};
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha512};
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use subtle::{Choice, ConstantTimeEq};
 
 /// ElGamal over the Ristretto-encoded Curve25519 elliptic curve. The curve is provided by the
 /// `curve25519-dalek` crate. ElGamal is a partially homomorphic cryptosystem.
 #[derive(Copy, Clone)]
-pub struct CurveElGamal;
+pub struct CurveElGamal {
+    security_level: BitsOfSecurity,
+}
 
 /// ElGamal ciphertext containing curve points. The addition operator on the ciphertext is
 /// reflected as the curve operation on the associated plaintext.
@@ -27,6 +38,165 @@ pub struct CurveElGamalCiphertext {
     pub c2: RistrettoPoint,
 }
 
+impl Identifiable for CurveElGamalCiphertext {}
+
+impl ConstantTimeEq for CurveElGamalCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl Hash for CurveElGamalCiphertext {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_serialized(self, state);
+    }
+}
+
+impl CurveElGamalCiphertext {
+    /// Computes the multi-scalar multiplication $\sum_i s_i \cdot [m_i]$ of `ciphertexts` with `scalars` in bulk over the c1 and c2 points. This is much faster than multiplying each ciphertext separately. This function is constant-time in the scalars.
+    pub fn msm(ciphertexts: &[CurveElGamalCiphertext], scalars: &[Scalar]) -> Self {
+        assert_eq!(ciphertexts.len(), scalars.len());
+
+        CurveElGamalCiphertext {
+            c1: RistrettoPoint::multiscalar_mul(scalars, ciphertexts.iter().map(|c| c.c1)),
+            c2: RistrettoPoint::multiscalar_mul(scalars, ciphertexts.iter().map(|c| c.c2)),
+        }
+    }
+
+    /// Computes the multi-scalar multiplication $\sum_i s_i \cdot [m_i]$ of `ciphertexts` with `scalars` in bulk using Pippenger's algorithm. This is faster than `msm` for many ciphertexts, but it is not constant-time, so the scalars must be public.
+    pub fn msm_leaky(ciphertexts: &[CurveElGamalCiphertext], scalars: &[Scalar]) -> Self {
+        assert_eq!(ciphertexts.len(), scalars.len());
+
+        CurveElGamalCiphertext {
+            c1: RistrettoPoint::vartime_multiscalar_mul(scalars, ciphertexts.iter().map(|c| c.c1)),
+            c2: RistrettoPoint::vartime_multiscalar_mul(scalars, ciphertexts.iter().map(|c| c.c2)),
+        }
+    }
+
+    /// Encodes the ciphertext as 64 bytes: the compressed encodings of c1 and c2.
+    pub fn to_compressed_bytes(&self) -> [u8; 64] {
+        self.compress().to_bytes()
+    }
+
+    /// Decodes a ciphertext from the 64 bytes produced by `to_compressed_bytes`. Returns `None` if
+    /// either half is not the canonical encoding of a Ristretto point.
+    pub fn from_compressed_bytes(bytes: &[u8; 64]) -> Option<Self> {
+        CompressedCurveElGamalCiphertext::from_bytes(bytes).decompress()
+    }
+
+    /// Compresses both points of the ciphertext. The compressed ciphertext takes up 64 bytes instead
+    /// of 320, which is useful to store large numbers of ciphertexts, but it must be decompressed
+    /// before any homomorphic operation.
+    pub fn compress(&self) -> CompressedCurveElGamalCiphertext {
+        CompressedCurveElGamalCiphertext {
+            c1: self.c1.compress(),
+            c2: self.c2.compress(),
+        }
+    }
+}
+
+/// ElGamal ciphertext containing compressed curve points, for compact storage and transmission.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub struct CompressedCurveElGamalCiphertext {
+    /// First part of ciphertext
+    pub c1: CompressedRistretto,
+    /// Second part of ciphertext
+    pub c2: CompressedRistretto,
+}
+
+impl Identifiable for CompressedCurveElGamalCiphertext {}
+
+impl ConstantTimeEq for CompressedCurveElGamalCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl CompressedCurveElGamalCiphertext {
+    /// Decompresses both points of the ciphertext. Returns `None` if either of them is not the
+    /// canonical encoding of a Ristretto point.
+    pub fn decompress(&self) -> Option<CurveElGamalCiphertext> {
+        Some(CurveElGamalCiphertext {
+            c1: self.c1.decompress()?,
+            c2: self.c2.decompress()?,
+        })
+    }
+
+    /// Returns the 64-byte encoding of this ciphertext.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(self.c1.as_bytes());
+        bytes[32..].copy_from_slice(self.c2.as_bytes());
+        bytes
+    }
+
+    /// Reads a compressed ciphertext from its 64-byte encoding without validating it, which happens
+    /// upon decompression.
+    pub fn from_bytes(bytes: &[u8; 64]) -> Self {
+        CompressedCurveElGamalCiphertext {
+            c1: CompressedRistretto::from_slice(&bytes[..32]),
+            c2: CompressedRistretto::from_slice(&bytes[32..]),
+        }
+    }
+}
+
+impl Sum for CurveElGamalCiphertext {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(
+            CurveElGamalCiphertext {
+                c1: RistrettoPoint::identity(),
+                c2: RistrettoPoint::identity(),
+            },
+            |mut a, b| {
+                a.c1 += b.c1;
+                a.c2 += b.c2;
+                a
+            },
+        )
+    }
+}
+
+impl<'a> Sum<&'a CurveElGamalCiphertext> for CurveElGamalCiphertext {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+/// ElGamal ciphertext that encrypts the same plaintext to several recipients. All recipients share
+/// the first part of the ciphertext, because it is computed from the same randomness, which is as
+/// secure as separate encryptions as long as the recipients' public keys are distinct.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CurveElGamalMultiRecipientCiphertext {
+    /// First part of ciphertext, shared by all recipients
+    pub c1: RistrettoPoint,
+    /// Second part of ciphertext for every recipient, in the order of their public keys
+    pub c2s: Vec<RistrettoPoint>,
+}
+
+impl Identifiable for CurveElGamalMultiRecipientCiphertext {}
+
+impl ConstantTimeEq for CurveElGamalMultiRecipientCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2s[..].ct_eq(&other.c2s[..])
+    }
+}
+
+impl Hash for CurveElGamalMultiRecipientCiphertext {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_serialized(self, state);
+    }
+}
+
+impl CurveElGamalMultiRecipientCiphertext {
+    /// Returns the ciphertext of the recipient at `index`, which they can decrypt as usual.
+    pub fn ciphertext_for(&self, index: usize) -> CurveElGamalCiphertext {
+        CurveElGamalCiphertext {
+            c1: self.c1,
+            c2: self.c2s[index],
+        }
+    }
+}
+
 impl Associable<CurveElGamalPK> for CurveElGamalCiphertext {}
 impl Associable<PrecomputedCurveElGamalPK> for CurveElGamalCiphertext {}
 
@@ -35,27 +205,126 @@ impl Associable<PrecomputedCurveElGamalPK> for CurveElGamalCiphertext {}
 pub struct CurveElGamalPK {
     /// Public key as a RistrettoPoint
     pub point: RistrettoPoint,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for CurveElGamalPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.point.ct_eq(&other.point)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
 }
 
 /// Decryption key for curve-based ElGamal
 pub struct CurveElGamalSK {
-    key: Scalar,
+    pub(crate) key: Scalar,
+}
+
+impl ConstantTimeEq for CurveElGamalSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.key.ct_eq(&other.key)
+    }
+}
+
+impl Debug for CurveElGamalSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CurveElGamalSK")
+            .field("key", &Redacted::new(&self.key, 253))
+            .finish()
+    }
 }
 
 impl CurveElGamalPK {
+    /// Creates a public key for `point` that was not generated through `setup`, for example one
+    /// imported from another format. It provides the AES128 security of the Ristretto group.
+    pub fn new(point: RistrettoPoint) -> Self {
+        CurveElGamalPK {
+            point,
+            security_level: BitsOfSecurity::AES128,
+        }
+    }
+
+    /// Encrypts the plaintext using a fresh random scalar, which is returned alongside the ciphertext so that it can later be used to prove correct encryption.
+    pub fn encrypt_returning_randomness<R: SecureRng>(
+        &self,
+        plaintext: &RistrettoPoint,
+        rng: &mut GeneralRng<R>,
+    ) -> (CurveElGamalCiphertext, Scalar) {
+        let randomness = Scalar::random(rng.rng());
+
+        (
+            self.encrypt_with_randomness(plaintext, &randomness),
+            randomness,
+        )
+    }
+
+    /// Encrypts `plaintext` to every key in `public_keys` using the same randomness. This takes one
+    /// scalar multiplication per recipient and one for the shared part, instead of two per recipient
+    /// for separate encryptions.
+    pub fn encrypt_multi<R: SecureRng>(
+        plaintext: &RistrettoPoint,
+        public_keys: &[CurveElGamalPK],
+        rng: &mut GeneralRng<R>,
+    ) -> CurveElGamalMultiRecipientCiphertext {
+        let randomness = Scalar::random(rng.rng());
+
+        CurveElGamalMultiRecipientCiphertext {
+            c1: &randomness * &RISTRETTO_BASEPOINT_TABLE,
+            c2s: public_keys
+                .iter()
+                .map(|public_key| plaintext + randomness * public_key.point)
+                .collect(),
+        }
+    }
+
     /// Precompute values for the encryption key to speed-up future encryptions
     pub fn precompute(self) -> PrecomputedCurveElGamalPK {
         PrecomputedCurveElGamalPK {
             point: RistrettoBasepointTable::create(&self.point),
+            security_level: self.security_level,
         }
     }
 }
 
 impl PrecomputedCurveElGamalPK {
+    /// Encrypts the plaintext using a fresh random scalar, which is returned alongside the ciphertext so that it can later be used to prove correct encryption.
+    pub fn encrypt_returning_randomness<R: SecureRng>(
+        &self,
+        plaintext: &RistrettoPoint,
+        rng: &mut GeneralRng<R>,
+    ) -> (CurveElGamalCiphertext, Scalar) {
+        let randomness = Scalar::random(rng.rng());
+
+        (
+            self.encrypt_with_randomness(plaintext, &randomness),
+            randomness,
+        )
+    }
+
+    /// Encrypts `plaintext` to every key in `public_keys`, like `CurveElGamalPK::encrypt_multi`, but
+    /// using the precomputed tables of the keys.
+    pub fn encrypt_multi<R: SecureRng>(
+        plaintext: &RistrettoPoint,
+        public_keys: &[PrecomputedCurveElGamalPK],
+        rng: &mut GeneralRng<R>,
+    ) -> CurveElGamalMultiRecipientCiphertext {
+        let randomness = Scalar::random(rng.rng());
+
+        CurveElGamalMultiRecipientCiphertext {
+            c1: &randomness * &RISTRETTO_BASEPOINT_TABLE,
+            c2s: public_keys
+                .iter()
+                .map(|public_key| plaintext + &randomness * &public_key.point)
+                .collect(),
+        }
+    }
+
     /// Compresses the encryption key down to a `CurveElGamalPK` which is slower but more compact. This is useful for serialization.
     pub fn compress(self) -> CurveElGamalPK {
         CurveElGamalPK {
             point: self.point.basepoint(),
+            security_level: self.security_level,
         }
     }
 }
@@ -66,6 +335,87 @@ impl CurveElGamalSK {
     }
 }
 
+/// The largest number of baby steps that a `CurveDiscreteLogTable` stores, which limits the table
+/// to roughly 1 GB of memory.
+pub const MAX_BABY_STEPS: u64 = 1 << 24;
+
+/// Precomputed table to solve small discrete logarithms $m$ of points $m \cdot G$ with respect to
+/// the Ristretto base point $G$, using the baby-step giant-step algorithm. It solves for $m$ below
+/// `baby_steps * giant_steps` using `baby_steps` stored points and at most `giant_steps` point
+/// additions.
+#[derive(Clone)]
+pub struct CurveDiscreteLogTable {
+    baby_steps: HashMap<CompressedRistretto, u64>,
+    baby_step_count: u64,
+    giant_step_count: u64,
+    giant_step: RistrettoPoint,
+}
+
+/// Error returned when a `CurveDiscreteLogTable` would need no baby steps or more than
+/// `MAX_BABY_STEPS`, or when its bound does not fit in a `u64`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct TableTooLarge;
+
+impl CurveDiscreteLogTable {
+    /// Precomputes a table that solves discrete logarithms below `baby_steps * giant_steps`.
+    /// Choosing both close to the square root of the bound minimizes the total work.
+    pub fn new(baby_steps: u64, giant_steps: u64) -> Result<Self, TableTooLarge> {
+        if baby_steps == 0
+            || baby_steps > MAX_BABY_STEPS
+            || baby_steps.checked_mul(giant_steps).is_none()
+        {
+            return Err(TableTooLarge);
+        }
+
+        let mut table = HashMap::with_capacity(baby_steps as usize);
+        let mut step = RistrettoPoint::identity();
+
+        for j in 0..baby_steps {
+            table.insert(step.compress(), j);
+            step += RISTRETTO_BASEPOINT_POINT;
+        }
+
+        Ok(CurveDiscreteLogTable {
+            baby_steps: table,
+            baby_step_count: baby_steps,
+            giant_step_count: giant_steps,
+            // After the loop, `step` equals baby_steps * G
+            giant_step: step,
+        })
+    }
+
+    /// Precomputes a table that solves discrete logarithms up to and including `max`, using about
+    /// $\sqrt{\text{max}}$ baby steps and as many giant steps.
+    pub fn with_max(max: u64) -> Result<Self, TableTooLarge> {
+        let baby_steps = max.isqrt() + 1;
+
+        Self::new(baby_steps, max / baby_steps + 1)
+    }
+
+    /// The exclusive upper bound on the discrete logarithms that this table can find.
+    pub fn bound(&self) -> u64 {
+        // The constructor checked that this product does not overflow
+        self.baby_step_count * self.giant_step_count
+    }
+
+    /// Returns $m$ below the bound of this table such that `point` equals $m \cdot G$, or `None` if
+    /// there is no such $m$. This function is not constant-time, and leaks $m$ through its running
+    /// time.
+    pub fn discrete_log_leaky(&self, point: &RistrettoPoint) -> Option<u64> {
+        let mut candidate = *point;
+
+        for i in 0..self.giant_step_count {
+            if let Some(j) = self.baby_steps.get(&candidate.compress()) {
+                return Some(i * self.baby_step_count + j);
+            }
+
+            candidate -= self.giant_step;
+        }
+
+        None
+    }
+}
+
 impl AsymmetricCryptosystem for CurveElGamal {
     type PublicKey = PrecomputedCurveElGamalPK;
     type SecretKey = CurveElGamalSK;
@@ -80,23 +430,87 @@ impl AsymmetricCryptosystem for CurveElGamal {
             ),
         }
 
-        CurveElGamal {}
+        CurveElGamal {
+            security_level: *security_param,
+        }
     }
 
     fn generate_keys<R: SecureRng>(
         &self,
         rng: &mut GeneralRng<R>,
     ) -> (PrecomputedCurveElGamalPK, CurveElGamalSK) {
+        trace_span!(INFO, "generate_keys");
         let secret_key = Scalar::random(rng.rng());
         let public_key = &secret_key * &RISTRETTO_BASEPOINT_TABLE;
 
         (
-            CurveElGamalPK { point: public_key }.precompute(),
+            CurveElGamalPK {
+                point: public_key,
+                security_level: self.security_level,
+            }
+            .precompute(),
             CurveElGamalSK { key: secret_key },
         )
     }
 }
 
+const KEM_DOMAIN: &[u8] = b"scicrypt-he curve el gamal kem";
+
+/// Elliptic-curve Diffie-Hellman between an ephemeral key and the ElGamal public key. The shared
+/// secret is derived by hashing the Diffie-Hellman point together with both public points.
+impl Kem for CurveElGamal {
+    type Encapsulation = RistrettoPoint;
+
+    fn encapsulate<R: SecureRng>(
+        public_key: &PrecomputedCurveElGamalPK,
+        rng: &mut GeneralRng<R>,
+    ) -> (SharedSecret, RistrettoPoint) {
+        let ephemeral_key = Scalar::random(rng.rng());
+        let encapsulation = &ephemeral_key * &RISTRETTO_BASEPOINT_TABLE;
+        let shared_point = &ephemeral_key * &public_key.point;
+
+        (
+            derive_shared_secret(&shared_point, &encapsulation, public_key),
+            encapsulation,
+        )
+    }
+
+    /// Returns `None` if the encapsulation is the identity, which would force a known shared point.
+    /// The Ristretto group has prime order, so every other point is a valid encapsulation.
+    fn decapsulate(
+        secret_key: &CurveElGamalSK,
+        public_key: &PrecomputedCurveElGamalPK,
+        encapsulation: &RistrettoPoint,
+    ) -> Option<SharedSecret> {
+        if *encapsulation == RistrettoPoint::identity() {
+            return None;
+        }
+
+        Some(derive_shared_secret(
+            &(secret_key.key * encapsulation),
+            encapsulation,
+            public_key,
+        ))
+    }
+}
+
+fn derive_shared_secret(
+    shared_point: &RistrettoPoint,
+    encapsulation: &RistrettoPoint,
+    public_key: &PrecomputedCurveElGamalPK,
+) -> SharedSecret {
+    let digest = Sha512::new()
+        .chain_update(KEM_DOMAIN)
+        .chain_update(shared_point.compress().as_bytes())
+        .chain_update(encapsulation.compress().as_bytes())
+        .chain_update(public_key.point.basepoint().compress().as_bytes())
+        .finalize();
+
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&digest[..32]);
+    SharedSecret(secret)
+}
+
 impl EncryptionKey for CurveElGamalPK {
     type Input = Scalar;
     type Plaintext = RistrettoPoint;
@@ -115,6 +529,7 @@ impl EncryptionKey for CurveElGamalPK {
         ciphertext: Self::Ciphertext,
         rng: &mut GeneralRng<R>,
     ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
         let randomness = Scalar::random(rng.rng());
 
         self.randomize_with(ciphertext, &randomness)
@@ -130,12 +545,25 @@ impl EncryptionKey for CurveElGamalPK {
             c2: ciphertext.c2 + randomness * self.point,
         }
     }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            // The group order is slightly larger than 2^252
+            capacity_bits: 252,
+        }
+    }
 }
 
 /// Public key with several precomputations to speed-up encryption
 #[derive(Clone)]
 pub struct PrecomputedCurveElGamalPK {
     pub(crate) point: RistrettoBasepointTable,
+    pub(crate) security_level: BitsOfSecurity,
 }
 
 impl Debug for PrecomputedCurveElGamalPK {
@@ -147,6 +575,32 @@ impl Debug for PrecomputedCurveElGamalPK {
 impl PartialEq for PrecomputedCurveElGamalPK {
     fn eq(&self, other: &Self) -> bool {
         self.point.basepoint() == other.point.basepoint()
+            && self.security_level == other.security_level
+    }
+}
+
+/// The precomputed table is not serialized, only the compressed public key, from which the table is
+/// recomputed upon deserialization.
+impl Serialize for PrecomputedCurveElGamalPK {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CurveElGamalPK {
+            point: self.point.basepoint(),
+            security_level: self.security_level,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PrecomputedCurveElGamalPK {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(CurveElGamalPK::deserialize(deserializer)?.precompute())
+    }
+}
+
+impl ConstantTimeEq for PrecomputedCurveElGamalPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.point.basepoint().ct_eq(&other.point.basepoint())
+            & Choice::from(u8::from(self.security_level == other.security_level))
     }
 }
 
@@ -168,6 +622,7 @@ impl EncryptionKey for PrecomputedCurveElGamalPK {
         ciphertext: Self::Ciphertext,
         rng: &mut GeneralRng<R>,
     ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
         let randomness = Scalar::random(rng.rng());
 
         self.randomize_with(ciphertext, &randomness)
@@ -183,6 +638,18 @@ impl EncryptionKey for PrecomputedCurveElGamalPK {
             c2: ciphertext.c2 + randomness * &self.point,
         }
     }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            // The group order is slightly larger than 2^252
+            capacity_bits: 252,
+        }
+    }
 }
 
 impl DecryptionKey<CurveElGamalPK> for CurveElGamalSK {
@@ -191,6 +658,7 @@ impl DecryptionKey<CurveElGamalPK> for CurveElGamalSK {
         _public_key: &CurveElGamalPK,
         ciphertext: &CurveElGamalCiphertext,
     ) -> RistrettoPoint {
+        trace_span!(DEBUG, "decrypt");
         self.decrypt_directly(ciphertext)
     }
 
@@ -209,6 +677,7 @@ impl DecryptionKey<PrecomputedCurveElGamalPK> for CurveElGamalSK {
         _public_key: &PrecomputedCurveElGamalPK,
         ciphertext: &CurveElGamalCiphertext,
     ) -> RistrettoPoint {
+        trace_span!(DEBUG, "decrypt");
         self.decrypt_directly(ciphertext)
     }
 
@@ -227,13 +696,22 @@ impl HomomorphicAddition for CurveElGamalPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
         CurveElGamalCiphertext {
             c1: ciphertext_a.c1 + ciphertext_b.c1,
             c2: ciphertext_a.c2 + ciphertext_b.c2,
         }
     }
 
+    fn add_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        ciphertext_a.c1 += ciphertext_b.c1;
+        ciphertext_a.c2 += ciphertext_b.c2;
+    }
+
+    /// Scalars are taken modulo the group order, so multiplying by a negated scalar such as
+    /// `-Scalar::from(3u64)` negates the scaled plaintext point.
     fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
         CurveElGamalCiphertext {
             c1: ciphertext.c1 * input,
             c2: ciphertext.c2 * input,
@@ -245,12 +723,30 @@ impl HomomorphicAddition for CurveElGamalPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
         CurveElGamalCiphertext {
             c1: ciphertext_a.c1 - ciphertext_b.c1,
             c2: ciphertext_a.c2 - ciphertext_b.c2,
         }
     }
 
+    fn sub_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        ciphertext_a.c1 -= ciphertext_b.c1;
+        ciphertext_a.c2 -= ciphertext_b.c2;
+    }
+
+    fn linear_combination(
+        &self,
+        ciphertexts: &[Self::Ciphertext],
+        inputs: &[Self::Input],
+    ) -> Option<Self::Ciphertext> {
+        if ciphertexts.is_empty() {
+            return None;
+        }
+
+        Some(CurveElGamalCiphertext::msm(ciphertexts, inputs))
+    }
+
     fn add_constant(
         &self,
         ciphertext: &Self::Ciphertext,
@@ -280,13 +776,20 @@ impl HomomorphicAddition for PrecomputedCurveElGamalPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
         CurveElGamalCiphertext {
             c1: ciphertext_a.c1 + ciphertext_b.c1,
             c2: ciphertext_a.c2 + ciphertext_b.c2,
         }
     }
 
+    fn add_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        ciphertext_a.c1 += ciphertext_b.c1;
+        ciphertext_a.c2 += ciphertext_b.c2;
+    }
+
     fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
         CurveElGamalCiphertext {
             c1: ciphertext.c1 * input,
             c2: ciphertext.c2 * input,
@@ -298,12 +801,30 @@ impl HomomorphicAddition for PrecomputedCurveElGamalPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
         CurveElGamalCiphertext {
             c1: ciphertext_a.c1 - ciphertext_b.c1,
             c2: ciphertext_a.c2 - ciphertext_b.c2,
         }
     }
 
+    fn sub_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        ciphertext_a.c1 -= ciphertext_b.c1;
+        ciphertext_a.c2 -= ciphertext_b.c2;
+    }
+
+    fn linear_combination(
+        &self,
+        ciphertexts: &[Self::Ciphertext],
+        inputs: &[Self::Input],
+    ) -> Option<Self::Ciphertext> {
+        if ciphertexts.is_empty() {
+            return None;
+        }
+
+        Some(CurveElGamalCiphertext::msm(ciphertexts, inputs))
+    }
+
     fn add_constant(
         &self,
         ciphertext: &Self::Ciphertext,
@@ -329,16 +850,185 @@ impl HomomorphicAddition for PrecomputedCurveElGamalPK {
 
 #[cfg(test)]
 mod tests {
-    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::cryptosystems::curve_el_gamal::{
+        CompressedCurveElGamalCiphertext, CurveDiscreteLogTable, CurveElGamal,
+        CurveElGamalCiphertext, CurveElGamalPK, PrecomputedCurveElGamalPK, TableTooLarge,
+        MAX_BABY_STEPS,
+    };
     use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
     use curve25519_dalek::ristretto::RistrettoPoint;
     use curve25519_dalek::scalar::Scalar;
     use curve25519_dalek::traits::Identity;
     use rand_core::OsRng;
     use scicrypt_traits::cryptosystems::{
-        Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+        Associable, AssociatedCiphertext, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
     };
-    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::kem::Kem;
+    use scicrypt_traits::randomness::{GeneralRng, InsecureTestRng};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_encrypt_multi() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let keys: Vec<_> = (0..3).map(|_| el_gamal.generate_keys(&mut rng)).collect();
+        let public_keys: Vec<_> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+        let plaintext = RISTRETTO_BASEPOINT_POINT * Scalar::from(7u64);
+
+        let ciphertext =
+            PrecomputedCurveElGamalPK::encrypt_multi(&plaintext, &public_keys, &mut rng);
+        assert_eq!(3, ciphertext.c2s.len());
+
+        for (i, (pk, sk)) in keys.iter().enumerate() {
+            let own = ciphertext.ciphertext_for(i).associate(pk);
+            assert_eq!(plaintext, sk.decrypt(&own));
+        }
+
+        let compressed: Vec<_> = public_keys.into_iter().map(|pk| pk.compress()).collect();
+        let ciphertext = CurveElGamalPK::encrypt_multi(&plaintext, &compressed, &mut rng);
+
+        for (i, (pk, (_, sk))) in compressed.iter().zip(&keys).enumerate() {
+            let own = ciphertext.ciphertext_for(i).associate(pk);
+            assert_eq!(plaintext, sk.decrypt(&own));
+        }
+    }
+
+    #[test]
+    fn test_reproducible_with_seed() {
+        let el_gamal = CurveElGamal::setup(&Default::default());
+
+        let mut rng = GeneralRng::new(InsecureTestRng::from_seed(7));
+        let (pk_a, _) = el_gamal.generate_keys(&mut rng);
+        let ciphertext_a = pk_a.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        let mut rng = GeneralRng::new(InsecureTestRng::from_seed(7));
+        let (pk_b, _) = el_gamal.generate_keys(&mut rng);
+        let ciphertext_b = pk_b.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        assert_eq!(pk_a, pk_b);
+        assert_eq!(ciphertext_a.ciphertext, ciphertext_b.ciphertext);
+    }
+
+    #[test]
+    fn test_encrypt_with_returned_randomness() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let (ciphertext, randomness) =
+            pk.encrypt_returning_randomness(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        assert_eq!(
+            ciphertext,
+            pk.encrypt_with_randomness(&RISTRETTO_BASEPOINT_POINT, &randomness)
+        );
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT,
+            sk.decrypt(&ciphertext.associate(&pk))
+        );
+    }
+
+    #[test]
+    fn test_owned_ciphertext_across_threads() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let pk = Arc::new(pk);
+
+        let ciphertext = pk
+            .encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng)
+            .associate_owned(pk.clone());
+        let ciphertext_clone = ciphertext.clone();
+
+        let ciphertext_twice = thread::spawn(move || &ciphertext + &ciphertext_clone)
+            .join()
+            .unwrap();
+
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT + RISTRETTO_BASEPOINT_POINT,
+            sk.decrypt(&ciphertext_twice.to_associated())
+        );
+    }
+
+    #[test]
+    fn test_homomorphic_add_assign() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        let mut ciphertext_thrice = ciphertext.clone();
+        ciphertext_thrice += &ciphertext;
+        let ciphertext_thrice = ciphertext_thrice + &ciphertext;
+
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT * Scalar::from(3u64),
+            sk.decrypt(&ciphertext_thrice)
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertexts: Vec<_> = (0..4)
+            .map(|_| pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng))
+            .collect();
+        let expected = RISTRETTO_BASEPOINT_POINT * Scalar::from(4u64);
+
+        let sum: AssociatedCiphertext<_, _> = ciphertexts.iter().sum();
+        assert_eq!(expected, sk.decrypt(&sum));
+
+        let raw_sum: CurveElGamalCiphertext = ciphertexts.iter().map(|c| &c.ciphertext).sum();
+        assert_eq!(expected, sk.decrypt(&raw_sum.associate(&pk)));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_par_sum() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertexts: Vec<_> = (0..100)
+            .map(|_| pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng))
+            .collect();
+        let sum = scicrypt_traits::homomorphic::par_sum(&pk, &ciphertexts).unwrap();
+
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT * Scalar::from(100u64),
+            sk.decrypt(&sum.associate(&pk))
+        );
+    }
+
+    #[test]
+    fn test_msm() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertexts: Vec<_> = (0..50)
+            .map(|_| pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng))
+            .collect();
+        let scalars: Vec<_> = (0..50u64).map(Scalar::from).collect();
+        let expected = RISTRETTO_BASEPOINT_POINT * Scalar::from(1225u64);
+
+        let msm = CurveElGamalCiphertext::msm(&ciphertexts, &scalars);
+        assert_eq!(expected, sk.decrypt(&msm.associate(&pk)));
+
+        let msm_leaky = CurveElGamalCiphertext::msm_leaky(&ciphertexts, &scalars);
+        assert_eq!(expected, sk.decrypt(&msm_leaky.associate(&pk)));
+    }
 
     #[test]
     fn test_encrypt_decrypt_generator() {
@@ -389,7 +1079,7 @@ mod tests {
         let ciphertext_twice = &ciphertext_a + &ciphertext_b;
 
         assert_eq!(
-            &Scalar::from(2u64) * &RISTRETTO_BASEPOINT_POINT,
+            Scalar::from(2u64) * RISTRETTO_BASEPOINT_POINT,
             sk.decrypt(&ciphertext_twice)
         );
     }
@@ -401,18 +1091,12 @@ mod tests {
         let el_gamal = CurveElGamal::setup(&Default::default());
         let (pk, sk) = el_gamal.generate_keys(&mut rng);
 
-        let ciphertext_a = pk.encrypt(
-            &(&Scalar::from(5u64) * &RISTRETTO_BASEPOINT_POINT),
-            &mut rng,
-        );
-        let ciphertext_b = pk.encrypt(
-            &(&Scalar::from(3u64) * &RISTRETTO_BASEPOINT_POINT),
-            &mut rng,
-        );
+        let ciphertext_a = pk.encrypt(&(Scalar::from(5u64) * RISTRETTO_BASEPOINT_POINT), &mut rng);
+        let ciphertext_b = pk.encrypt(&(Scalar::from(3u64) * RISTRETTO_BASEPOINT_POINT), &mut rng);
         let ciphertext_res = &ciphertext_a - &ciphertext_b;
 
         assert_eq!(
-            &Scalar::from(2u64) * &RISTRETTO_BASEPOINT_POINT,
+            Scalar::from(2u64) * RISTRETTO_BASEPOINT_POINT,
             sk.decrypt(&ciphertext_res)
         );
     }
@@ -425,10 +1109,10 @@ mod tests {
         let (pk, sk) = el_gamal.generate_keys(&mut rng);
 
         let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
-        let ciphertext_twice = &ciphertext + &RISTRETTO_BASEPOINT_POINT;
+        let ciphertext_twice = &ciphertext + RISTRETTO_BASEPOINT_POINT;
 
         assert_eq!(
-            &Scalar::from(2u64) * &RISTRETTO_BASEPOINT_POINT,
+            Scalar::from(2u64) * RISTRETTO_BASEPOINT_POINT,
             sk.decrypt(&ciphertext_twice)
         );
     }
@@ -440,14 +1124,11 @@ mod tests {
         let el_gamal = CurveElGamal::setup(&Default::default());
         let (pk, sk) = el_gamal.generate_keys(&mut rng);
 
-        let ciphertext = pk.encrypt(
-            &(&Scalar::from(5u64) * &RISTRETTO_BASEPOINT_POINT),
-            &mut rng,
-        );
-        let ciphertext_res = &ciphertext - &(&Scalar::from(3u64) * &RISTRETTO_BASEPOINT_POINT);
+        let ciphertext = pk.encrypt(&(Scalar::from(5u64) * RISTRETTO_BASEPOINT_POINT), &mut rng);
+        let ciphertext_res = &ciphertext - (Scalar::from(3u64) * RISTRETTO_BASEPOINT_POINT);
 
         assert_eq!(
-            &Scalar::from(2u64) * &RISTRETTO_BASEPOINT_POINT,
+            Scalar::from(2u64) * RISTRETTO_BASEPOINT_POINT,
             sk.decrypt(&ciphertext_res)
         );
     }
@@ -460,14 +1141,30 @@ mod tests {
         let (pk, sk) = el_gamal.generate_keys(&mut rng);
 
         let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
-        let ciphertext_thrice = &ciphertext * &Scalar::from(3u64);
+        let ciphertext_thrice = &ciphertext * Scalar::from(3u64);
 
         assert_eq!(
-            &Scalar::from(3u64) * &RISTRETTO_BASEPOINT_POINT,
+            Scalar::from(3u64) * RISTRETTO_BASEPOINT_POINT,
             sk.decrypt(&ciphertext_thrice)
         );
     }
 
+    #[test]
+    fn test_homomorphic_negative_scalar_mul() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        let ciphertext_negated = &ciphertext * -Scalar::from(3u64);
+
+        assert_eq!(
+            -(Scalar::from(3u64) * RISTRETTO_BASEPOINT_POINT),
+            sk.decrypt(&ciphertext_negated)
+        );
+    }
+
     #[test]
     fn test_randomize() {
         let mut rng = GeneralRng::new(OsRng);
@@ -476,16 +1173,14 @@ mod tests {
         let (pk, sk) = el_gamal.generate_keys(&mut rng);
         let pk = pk.compress();
 
-        let ciphertext = pk.encrypt_raw(
-            &(&Scalar::from(42u64) * &RISTRETTO_BASEPOINT_POINT),
-            &mut rng,
-        );
+        let ciphertext =
+            pk.encrypt_raw(&(Scalar::from(42u64) * RISTRETTO_BASEPOINT_POINT), &mut rng);
         let randomized_ciphertext = pk.randomize(ciphertext.clone(), &mut rng);
 
         assert_ne!(ciphertext, randomized_ciphertext);
 
         assert_eq!(
-            &(&Scalar::from(42u64) * &RISTRETTO_BASEPOINT_POINT),
+            &(Scalar::from(42u64) * RISTRETTO_BASEPOINT_POINT),
             &sk.decrypt(&randomized_ciphertext.associate(&pk))
         );
     }
@@ -497,17 +1192,129 @@ mod tests {
         let el_gamal = CurveElGamal::setup(&Default::default());
         let (pk, sk) = el_gamal.generate_keys(&mut rng);
 
-        let ciphertext = pk.encrypt_raw(
-            &(&Scalar::from(42u64) * &RISTRETTO_BASEPOINT_POINT),
-            &mut rng,
-        );
+        let ciphertext =
+            pk.encrypt_raw(&(Scalar::from(42u64) * RISTRETTO_BASEPOINT_POINT), &mut rng);
         let randomized_ciphertext = pk.randomize(ciphertext.clone(), &mut rng);
 
         assert_ne!(ciphertext, randomized_ciphertext);
 
         assert_eq!(
-            &(&Scalar::from(42u64) * &RISTRETTO_BASEPOINT_POINT),
+            &(Scalar::from(42u64) * RISTRETTO_BASEPOINT_POINT),
             &sk.decrypt(&randomized_ciphertext.associate(&pk))
         );
     }
+
+    #[test]
+    fn test_kem_encapsulate_decapsulate() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let (other_pk, other_sk) = el_gamal.generate_keys(&mut rng);
+
+        let (shared_secret, encapsulation) = CurveElGamal::encapsulate(&pk, &mut rng);
+
+        assert_eq!(
+            Some(shared_secret.clone()),
+            CurveElGamal::decapsulate(&sk, &pk, &encapsulation)
+        );
+        assert_ne!(
+            Some(shared_secret),
+            CurveElGamal::decapsulate(&other_sk, &other_pk, &encapsulation)
+        );
+        assert_eq!(
+            None,
+            CurveElGamal::decapsulate(&sk, &pk, &RistrettoPoint::identity())
+        );
+    }
+
+    #[test]
+    fn test_compressed_bytes_roundtrip() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        let bytes = ciphertext.ciphertext.to_compressed_bytes();
+        let decoded = CurveElGamalCiphertext::from_compressed_bytes(&bytes).unwrap();
+
+        assert_eq!(ciphertext.ciphertext, decoded);
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT,
+            sk.decrypt(&decoded.associate(&pk))
+        );
+        assert_eq!(
+            Some(ciphertext.ciphertext.clone()),
+            ciphertext.ciphertext.compress().decompress()
+        );
+    }
+
+    #[test]
+    fn test_compressed_bytes_rejects_invalid_points() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        let mut bytes = ciphertext.ciphertext.to_compressed_bytes();
+
+        // A non-canonical field element is not a valid encoding
+        bytes[32..].copy_from_slice(&[0xff; 32]);
+        assert_eq!(None, CurveElGamalCiphertext::from_compressed_bytes(&bytes));
+        assert_eq!(
+            None,
+            CompressedCurveElGamalCiphertext::from_bytes(&bytes).decompress()
+        );
+    }
+
+    #[test]
+    fn test_serialize_precomputed_public_key() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let deserialized: PrecomputedCurveElGamalPK =
+            bincode::deserialize(&bincode::serialize(&pk).unwrap()).unwrap();
+        assert_eq!(pk, deserialized);
+
+        let ciphertext = deserialized.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        assert_eq!(RISTRETTO_BASEPOINT_POINT, sk.decrypt(&ciphertext));
+    }
+
+    #[test]
+    fn test_discrete_log_table() {
+        let table = CurveDiscreteLogTable::with_max(1000).unwrap();
+        assert!(table.bound() > 1000);
+
+        for m in [0u64, 1, 31, 32, 999, 1000] {
+            let point = RISTRETTO_BASEPOINT_POINT * Scalar::from(m);
+            assert_eq!(Some(m), table.discrete_log_leaky(&point));
+        }
+
+        let point = RISTRETTO_BASEPOINT_POINT * Scalar::from(table.bound());
+        assert_eq!(None, table.discrete_log_leaky(&point));
+    }
+
+    #[test]
+    fn test_discrete_log_table_too_large() {
+        assert_eq!(
+            Err(TableTooLarge),
+            CurveDiscreteLogTable::new(MAX_BABY_STEPS + 1, 1).map(|table| table.bound())
+        );
+        assert_eq!(
+            Err(TableTooLarge),
+            CurveDiscreteLogTable::new(0, 1).map(|table| table.bound())
+        );
+        assert_eq!(
+            Err(TableTooLarge),
+            CurveDiscreteLogTable::new(2, u64::MAX).map(|table| table.bound())
+        );
+        assert_eq!(
+            Err(TableTooLarge),
+            CurveDiscreteLogTable::with_max(1 << 60).map(|table| table.bound())
+        );
+    }
 }
@@ -0,0 +1,157 @@
+//! Prime-order elliptic curve groups that `GenericCurveElGamal` can be instantiated with. Ristretto
+//! is always available, while secp256k1 and NIST P-256 are enabled by the `secp256k1` and `p256`
+//! features respectively. The latter two allow interoperability with key material from blockchains
+//! and TLS, which is standardized on these curves.
+
+use curve25519_dalek::constants::{RISTRETTO_BASEPOINT_POINT, RISTRETTO_BASEPOINT_TABLE};
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::traits::Identity;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+use subtle::ConstantTimeEq;
+
+/// The operations that ElGamal needs from a prime-order elliptic curve group.
+pub trait CurveGroup: Copy + Debug + PartialEq + Eq {
+    /// Scalars modulo the order of the group
+    type Scalar: Copy + Debug + PartialEq + ConstantTimeEq;
+    /// Points in the group, as they are stored in keys and ciphertexts
+    type Point: Copy + Debug + PartialEq + Eq + ConstantTimeEq + Serialize + DeserializeOwned;
+
+    /// The number of bits of the group order
+    const SCALAR_BITS: u32;
+
+    /// The standard generator of the group.
+    fn generator() -> Self::Point;
+    /// The identity element of the group.
+    fn identity() -> Self::Point;
+    /// Samples a uniformly random scalar.
+    fn random_scalar<R: SecureRng>(rng: &mut GeneralRng<R>) -> Self::Scalar;
+    /// Converts an integer to a scalar.
+    fn scalar_from_u64(value: u64) -> Self::Scalar;
+    /// Encodes a scalar as bytes.
+    fn scalar_to_bytes(scalar: &Self::Scalar) -> Vec<u8>;
+
+    /// Computes $a + b$.
+    fn add(a: &Self::Point, b: &Self::Point) -> Self::Point;
+    /// Computes $a - b$.
+    fn sub(a: &Self::Point, b: &Self::Point) -> Self::Point;
+    /// Computes $s \cdot P$ in constant time.
+    fn mul(point: &Self::Point, scalar: &Self::Scalar) -> Self::Point;
+    /// Computes $s \cdot G$ for the generator $G$ in constant time. Backends can override this to use
+    /// a precomputed table.
+    fn mul_generator(scalar: &Self::Scalar) -> Self::Point {
+        Self::mul(&Self::generator(), scalar)
+    }
+}
+
+/// The Ristretto group over Curve25519, as provided by `curve25519-dalek`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Ristretto;
+
+impl CurveGroup for Ristretto {
+    type Scalar = curve25519_dalek::scalar::Scalar;
+    type Point = RistrettoPoint;
+
+    const SCALAR_BITS: u32 = 253;
+
+    fn generator() -> RistrettoPoint {
+        RISTRETTO_BASEPOINT_POINT
+    }
+
+    fn identity() -> RistrettoPoint {
+        RistrettoPoint::identity()
+    }
+
+    fn random_scalar<R: SecureRng>(rng: &mut GeneralRng<R>) -> Self::Scalar {
+        Self::Scalar::random(rng.rng())
+    }
+
+    fn scalar_from_u64(value: u64) -> Self::Scalar {
+        Self::Scalar::from(value)
+    }
+
+    fn scalar_to_bytes(scalar: &Self::Scalar) -> Vec<u8> {
+        scalar.to_bytes().to_vec()
+    }
+
+    fn add(a: &RistrettoPoint, b: &RistrettoPoint) -> RistrettoPoint {
+        a + b
+    }
+
+    fn sub(a: &RistrettoPoint, b: &RistrettoPoint) -> RistrettoPoint {
+        a - b
+    }
+
+    fn mul(point: &RistrettoPoint, scalar: &Self::Scalar) -> RistrettoPoint {
+        point * scalar
+    }
+
+    fn mul_generator(scalar: &Self::Scalar) -> RistrettoPoint {
+        scalar * &RISTRETTO_BASEPOINT_TABLE
+    }
+}
+
+/// Implements `CurveGroup` for a short Weierstrass curve from the RustCrypto `elliptic-curves`
+/// crates. Points are stored in affine form, because only that form can be serialized; the
+/// arithmetic happens in projective form.
+#[cfg(any(feature = "secp256k1", feature = "p256"))]
+macro_rules! impl_weierstrass_curve_group {
+    ($group:ident, $curve:ident, $bits:expr) => {
+        impl CurveGroup for $group {
+            type Scalar = $curve::Scalar;
+            type Point = $curve::AffinePoint;
+
+            const SCALAR_BITS: u32 = $bits;
+
+            fn generator() -> $curve::AffinePoint {
+                $curve::AffinePoint::GENERATOR
+            }
+
+            fn identity() -> $curve::AffinePoint {
+                $curve::AffinePoint::IDENTITY
+            }
+
+            fn random_scalar<R: SecureRng>(rng: &mut GeneralRng<R>) -> $curve::Scalar {
+                <$curve::Scalar as $curve::elliptic_curve::Field>::random(rng.rng())
+            }
+
+            fn scalar_from_u64(value: u64) -> $curve::Scalar {
+                $curve::Scalar::from(value)
+            }
+
+            fn scalar_to_bytes(scalar: &$curve::Scalar) -> Vec<u8> {
+                scalar.to_bytes().to_vec()
+            }
+
+            fn add(a: &$curve::AffinePoint, b: &$curve::AffinePoint) -> $curve::AffinePoint {
+                ($curve::ProjectivePoint::from(*a) + b).to_affine()
+            }
+
+            fn sub(a: &$curve::AffinePoint, b: &$curve::AffinePoint) -> $curve::AffinePoint {
+                ($curve::ProjectivePoint::from(*a) - b).to_affine()
+            }
+
+            fn mul(point: &$curve::AffinePoint, scalar: &$curve::Scalar) -> $curve::AffinePoint {
+                ($curve::ProjectivePoint::from(*point) * scalar).to_affine()
+            }
+        }
+    };
+}
+
+/// The secp256k1 curve used by Bitcoin and Ethereum, as provided by the `k256` crate.
+#[cfg(feature = "secp256k1")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Secp256k1;
+
+#[cfg(feature = "secp256k1")]
+impl_weierstrass_curve_group!(Secp256k1, k256, 256);
+
+/// The NIST P-256 curve (also known as secp256r1) used in TLS, as provided by the `p256` crate.
+#[cfg(feature = "p256")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NistP256;
+
+#[cfg(feature = "p256")]
+impl_weierstrass_curve_group!(NistP256, p256, 256);
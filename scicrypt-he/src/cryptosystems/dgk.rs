@@ -0,0 +1,478 @@
+//! The cryptosystem of Damgård, Geisler and Krøigaard (DGK) is additively homomorphic like
+//! Paillier, but its plaintext space is a small prime $u$. This makes ciphertexts cheaper to work
+//! with, and especially makes checking whether a ciphertext encrypts zero much faster than a full
+//! decryption. Both properties are used heavily in secure comparison protocols.
+//!
+//! Here is an example of checking whether two encrypted values are equal.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::dgk::Dgk;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, EncryptionKey};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let dgk = Dgk::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = dgk.generate_keys(&mut rng);
+//!
+//! let ciphertext_a = public_key.encrypt(&21, &mut rng);
+//! let ciphertext_b = public_key.encrypt(&21, &mut rng);
+//! let difference = &ciphertext_a - &ciphertext_b;
+//!
+//! assert!(secret_key.is_zero_decrypt(&difference.ciphertext));
+//! ```
+
+use crate::cryptosystems::integer_el_gamal::table_key;
+use crate::identifier::Identifiable;
+use rug::Integer;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The number of bits of the plaintext space when using `setup`.
+const DEFAULT_PLAINTEXT_BITS: u32 = 16;
+
+/// The DGK cryptosystem.
+#[derive(Copy, Clone)]
+pub struct Dgk {
+    modulus_size: u32,
+    subgroup_size: u32,
+    plaintext_bits: u32,
+    miller_rabin_repetitions: u32,
+    security_level: BitsOfSecurity,
+}
+
+/// Public key for the DGK cryptosystem.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct DgkPK {
+    /// Public modulus n for encryption
+    pub n: UnsignedInteger,
+    /// Generator of order $u v_p v_q$, which encodes the plaintext
+    pub g: UnsignedInteger,
+    /// Generator of order $v_p v_q$, which encodes the randomness
+    pub h: UnsignedInteger,
+    /// The prime size u of the plaintext space
+    pub u: u64,
+    /// The number of random bits used for the randomness, which is 2.5 times the size of $v_p$
+    pub randomness_bits: u32,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for DgkPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n)
+            & self.g.ct_eq(&other.g)
+            & self.h.ct_eq(&other.h)
+            & self.u.ct_eq(&other.u)
+            & self.randomness_bits.ct_eq(&other.randomness_bits)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
+}
+
+/// Decryption key for the DGK cryptosystem.
+pub struct DgkSK {
+    p: UnsignedInteger,
+    v_p: UnsignedInteger,
+    /// Baby steps $(g^{v_p})^j \bmod p$ to find plaintexts with the baby-step giant-step algorithm
+    baby_steps: HashMap<Vec<u8>, u64>,
+    /// The inverse of $(g^{v_p})^m \bmod p$, where $m$ is the number of baby steps
+    giant_step: UnsignedInteger,
+}
+
+impl ConstantTimeEq for DgkSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.p.ct_eq(&other.p) & self.v_p.ct_eq(&other.v_p)
+    }
+}
+
+impl Debug for DgkSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DgkSK")
+            .field("p", &Redacted::new(&self.p, self.p.size_in_bits()))
+            .field("v_p", &Redacted::new(&self.v_p, self.v_p.size_in_bits()))
+            .finish()
+    }
+}
+
+/// Ciphertext of the DGK cryptosystem, which is additively homomorphic modulo $u$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct DgkCiphertext {
+    /// Encrypted message (Ciphertext)
+    pub c: UnsignedInteger,
+}
+
+impl Identifiable for DgkCiphertext {}
+
+impl ConstantTimeEq for DgkCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c.ct_eq(&other.c)
+    }
+}
+
+impl Associable<DgkPK> for DgkCiphertext {}
+
+impl Dgk {
+    /// Sets up DGK with a plaintext space of a prime $u$ of `plaintext_bits` bits. To compare
+    /// $\ell$-bit numbers, comparison protocols typically require $u$ to have at least $\ell + 2$
+    /// bits.
+    pub fn setup_with_plaintext_bits(security_param: &BitsOfSecurity, plaintext_bits: u32) -> Self {
+        assert!(
+            (2..=32).contains(&plaintext_bits),
+            "the plaintext space must have between 2 and 32 bits"
+        );
+
+        // The subgroups have twice the bits of security, like in the original paper (160 bits for
+        // a 1024-bit modulus). Toy parameters use small subgroups that still fit in the modulus.
+        let subgroup_size = match security_param.to_symmetric_bit_length() {
+            0 => 40,
+            bits => 2 * bits,
+        };
+
+        Dgk {
+            modulus_size: security_param.to_public_key_bit_length(),
+            subgroup_size,
+            plaintext_bits,
+            miller_rabin_repetitions: security_param.to_miller_rabin_repetitions(),
+            security_level: *security_param,
+        }
+    }
+
+    /// Generates a prime $p = 2 u v r + 1$ of half the modulus size for a random $r$.
+    fn random_prime_with_factors<R: SecureRng>(
+        &self,
+        u: &Integer,
+        v: &Integer,
+        rng: &mut GeneralRng<R>,
+    ) -> Integer {
+        let factor = Integer::from(u * v) << 1u32;
+        let prime_size = self.modulus_size / 2;
+        let cofactor_size = prime_size - factor.significant_bits();
+
+        loop {
+            let candidate = &factor * random_bits(cofactor_size, rng) + 1u32;
+
+            if candidate.significant_bits() == prime_size
+                && UnsignedInteger::from(candidate.clone())
+                    .is_probably_prime_with_leaky(self.miller_rabin_repetitions)
+            {
+                return candidate;
+            }
+        }
+    }
+}
+
+impl AsymmetricCryptosystem for Dgk {
+    type PublicKey = DgkPK;
+    type SecretKey = DgkSK;
+
+    fn setup(security_param: &BitsOfSecurity) -> Self {
+        Dgk::setup_with_plaintext_bits(security_param, DEFAULT_PLAINTEXT_BITS)
+    }
+
+    /// Generates primes $p$ and $q$ such that $u v_p$ divides $p - 1$ and $u v_q$ divides $q - 1$,
+    /// for a small prime $u$ and primes $v_p$ and $v_q$ of the size of the subgroups. The
+    /// generators are combined from elements of the right order modulo $p$ and modulo $q$.
+    fn generate_keys<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> (DgkPK, DgkSK) {
+        trace_span!(INFO, "generate_keys");
+        let u = random_prime(self.plaintext_bits, rng);
+        let v_p = random_prime(self.subgroup_size, rng);
+        let v_q = random_prime(self.subgroup_size, rng);
+
+        let p = self.random_prime_with_factors(&u, &v_p, rng);
+        let q = self.random_prime_with_factors(&u, &v_q, rng);
+        let n = Integer::from(&p * &q);
+
+        let uv_p = Integer::from(&u * &v_p);
+        let uv_q = Integer::from(&u * &v_q);
+        let g = crt(
+            &element_of_order(&p, &[&u, &v_p], &uv_p, rng),
+            &element_of_order(&q, &[&u, &v_q], &uv_q, rng),
+            &p,
+            &q,
+        );
+        let h = crt(
+            &element_of_order(&p, &[&v_p], &v_p, rng),
+            &element_of_order(&q, &[&v_q], &v_q, rng),
+            &p,
+            &q,
+        );
+
+        // g^v_p generates the subgroup of order u modulo p, in which we solve discrete logarithms
+        let base = Integer::from(g.pow_mod_ref(&v_p, &p).unwrap());
+        let baby_step_count = u.to_f64().sqrt().ceil() as u64;
+
+        let mut baby_steps = HashMap::with_capacity(baby_step_count as usize);
+        let mut element = Integer::from(1);
+        for j in 0..baby_step_count {
+            baby_steps.insert(table_key(&UnsignedInteger::from(element.clone())), j);
+            element = element * &base % &p;
+        }
+        let giant_step = element.invert(&p).unwrap();
+
+        (
+            DgkPK {
+                n: UnsignedInteger::from(n),
+                g: UnsignedInteger::from(g),
+                h: UnsignedInteger::from(h),
+                u: u.to_u64().unwrap(),
+                randomness_bits: (5 * self.subgroup_size / 2).div_ceil(64) * 64,
+                security_level: self.security_level,
+            },
+            DgkSK {
+                p: UnsignedInteger::from(p),
+                v_p: UnsignedInteger::from(v_p),
+                baby_steps,
+                giant_step: UnsignedInteger::from(giant_step),
+            },
+        )
+    }
+}
+
+/// Samples a random number of exactly `bits` bits.
+fn random_bits<R: SecureRng>(bits: u32, rng: &mut GeneralRng<R>) -> Integer {
+    let mut number = UnsignedInteger::random(bits.div_ceil(64) * 64, rng).to_rug();
+    number.keep_bits_mut(bits);
+    number.set_bit(bits - 1, true);
+    number
+}
+
+/// Samples a random prime of exactly `bits` bits.
+pub(crate) fn random_prime<R: SecureRng>(bits: u32, rng: &mut GeneralRng<R>) -> Integer {
+    loop {
+        let prime = random_bits(bits, rng).next_prime();
+
+        if prime.significant_bits() == bits {
+            return prime;
+        }
+    }
+}
+
+/// Samples an element of order `order` modulo the prime `p`, given the prime factors of the order.
+fn element_of_order<R: SecureRng>(
+    p: &Integer,
+    prime_factors: &[&Integer],
+    order: &Integer,
+    rng: &mut GeneralRng<R>,
+) -> Integer {
+    let exponent = Integer::from(p - 1u32) / order;
+
+    loop {
+        let x = random_bits(p.significant_bits() + 64, rng) % p;
+        let element = Integer::from(x.pow_mod_ref(&exponent, p).unwrap());
+
+        // The order divides `order`, so it is exactly `order` if no smaller power is 1
+        let has_full_order = prime_factors.iter().all(|factor| {
+            let cofactor = Integer::from(order / *factor);
+            Integer::from(element.pow_mod_ref(&cofactor, p).unwrap()) != 1
+        });
+
+        if has_full_order {
+            return element;
+        }
+    }
+}
+
+/// Combines `x_p` modulo `p` and `x_q` modulo `q` into the unique number modulo $p q$ using the
+/// Chinese remainder theorem.
+fn crt(x_p: &Integer, x_q: &Integer, p: &Integer, q: &Integer) -> Integer {
+    let p_inverse = Integer::from(p.invert_ref(q).unwrap());
+    let difference = Integer::from(x_q - x_p) * p_inverse % q;
+
+    let result = (difference + q) % q * p + x_p;
+    result % Integer::from(p * q)
+}
+
+impl DgkSK {
+    /// Returns true if `ciphertext` encrypts zero. This only requires a single exponentiation with
+    /// the small exponent $v_p$ modulo $p$, which is much faster than a full decryption.
+    pub fn is_zero_decrypt(&self, ciphertext: &DgkCiphertext) -> bool {
+        let residue = (ciphertext.c.clone() % &self.p).pow_mod(&self.v_p, &self.p);
+
+        residue == UnsignedInteger::from(1u64)
+    }
+}
+
+impl EncryptionKey for DgkPK {
+    type Input = u64;
+    type Plaintext = u64;
+    type Ciphertext = DgkCiphertext;
+    type Randomness = UnsignedInteger;
+
+    fn encrypt_without_randomness(&self, plaintext: &u64) -> Self::Ciphertext {
+        assert!(*plaintext < self.u, "the plaintext must be smaller than u");
+
+        DgkCiphertext {
+            c: self.g.pow_mod(&UnsignedInteger::from(*plaintext), &self.n),
+        }
+    }
+
+    fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: Self::Ciphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
+        let r = UnsignedInteger::random(self.randomness_bits, rng);
+
+        self.randomize_with(ciphertext, &r)
+    }
+
+    fn randomize_with(
+        &self,
+        ciphertext: Self::Ciphertext,
+        randomness: &Self::Randomness,
+    ) -> Self::Ciphertext {
+        let randomizer = self.h.pow_mod(randomness, &self.n);
+
+        DgkCiphertext {
+            c: (&ciphertext.c * &randomizer) % &self.n,
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: 63 - self.u.leading_zeros(),
+        }
+    }
+}
+
+impl DecryptionKey<DgkPK> for DgkSK {
+    /// Decrypts the ciphertext by solving a discrete logarithm in the subgroup of order $u$ with the
+    /// baby-step giant-step algorithm. This is not constant-time, and leaks the plaintext through its
+    /// running time. Use `is_zero_decrypt` when only equality to zero matters.
+    fn decrypt_raw(&self, public_key: &DgkPK, ciphertext: &DgkCiphertext) -> u64 {
+        trace_span!(DEBUG, "decrypt");
+        let baby_step_count = self.baby_steps.len() as u64;
+        let mut current = (ciphertext.c.clone() % &self.p).pow_mod(&self.v_p, &self.p);
+
+        for i in 0..public_key.u.div_ceil(baby_step_count) {
+            if let Some(j) = self.baby_steps.get(&table_key(&current)) {
+                return i * baby_step_count + j;
+            }
+
+            current = (&current * &self.giant_step) % &self.p;
+        }
+
+        panic!("the ciphertext is not a valid encryption under this key")
+    }
+
+    fn decrypt_identity_raw(&self, _public_key: &DgkPK, ciphertext: &DgkCiphertext) -> bool {
+        self.is_zero_decrypt(ciphertext)
+    }
+}
+
+impl HomomorphicAddition for DgkPK {
+    fn add(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
+        DgkCiphertext {
+            c: (&ciphertext_a.c * &ciphertext_b.c) % &self.n,
+        }
+    }
+
+    fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &u64) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
+        DgkCiphertext {
+            c: ciphertext
+                .c
+                .pow_mod(&UnsignedInteger::from(*input % self.u), &self.n),
+        }
+    }
+
+    /// Subtracts by adding $(u - 1)$ times `ciphertext_b`, which avoids computing an inverse.
+    fn sub(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
+        self.add(
+            ciphertext_a,
+            &self.mul_constant(ciphertext_b, &(self.u - 1)),
+        )
+    }
+
+    fn add_constant(&self, ciphertext: &Self::Ciphertext, constant: &u64) -> Self::Ciphertext {
+        self.add(
+            ciphertext,
+            &self.encrypt_without_randomness(&(constant % self.u)),
+        )
+    }
+
+    fn sub_constant(&self, ciphertext: &Self::Ciphertext, constant: &u64) -> Self::Ciphertext {
+        self.add_constant(ciphertext, &(self.u - constant % self.u))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::dgk::Dgk;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let dgk = Dgk::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = dgk.generate_keys(&mut rng);
+
+        for plaintext in [0, 1, 1000, pk.u - 1] {
+            let ciphertext = pk.encrypt(&plaintext, &mut rng);
+            assert_eq!(plaintext, sk.decrypt(&ciphertext));
+        }
+    }
+
+    #[test]
+    fn test_is_zero_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let dgk = Dgk::setup_with_plaintext_bits(&BitsOfSecurity::ToyParameters, 8);
+        let (pk, sk) = dgk.generate_keys(&mut rng);
+
+        assert!(sk.is_zero_decrypt(&pk.encrypt_raw(&0, &mut rng)));
+        assert!(sk.decrypt_identity(&pk.encrypt(&0, &mut rng)));
+        for plaintext in 1..pk.u {
+            assert!(!sk.is_zero_decrypt(&pk.encrypt_raw(&plaintext, &mut rng)));
+        }
+    }
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let dgk = Dgk::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = dgk.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt(&20, &mut rng);
+        let ciphertext_b = pk.encrypt(&7, &mut rng);
+
+        assert_eq!(27, sk.decrypt(&(&ciphertext_a + &ciphertext_b)));
+        assert_eq!(13, sk.decrypt(&(&ciphertext_a - &ciphertext_b)));
+        assert_eq!(pk.u - 13, sk.decrypt(&(&ciphertext_b - &ciphertext_a)));
+        assert_eq!(60, sk.decrypt(&(&ciphertext_a * 3)));
+        assert_eq!(25, sk.decrypt(&(&ciphertext_a + 5)));
+        assert_eq!(15, sk.decrypt(&(&ciphertext_a - 5)));
+    }
+}
@@ -0,0 +1,244 @@
+//! Encodings of `u64` and `i64` (and therefore `FixedPoint`) values into the plaintext spaces of the
+//! integer-based cryptosystems. Negative numbers are represented by their residues modulo the
+//! plaintext modulus, so that homomorphic operations on encodings match the operations on the
+//! numbers, as long as the results stay within half the modulus.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::encoding::FixedPoint;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = paillier.generate_keys(&mut rng);
+//!
+//! let price = FixedPoint::<2>::from_f64(19.99).unwrap();
+//! let discount = FixedPoint::<2>::from_f64(-2.5).unwrap();
+//! let ciphertext_a = public_key.encrypt_encodable(&price, &mut rng).unwrap();
+//! let ciphertext_b = public_key.encrypt_encodable(&discount, &mut rng).unwrap();
+//!
+//! let total: FixedPoint<2> = secret_key
+//!     .decrypt_decodable(&(&ciphertext_a + &ciphertext_b))
+//!     .unwrap();
+//! assert_eq!(total.to_f64(), 17.49);
+//! ```
+
+use crate::cryptosystems::dgk::DgkPK;
+use crate::cryptosystems::integer_el_gamal::IntegerElGamalPK;
+use crate::cryptosystems::paillier::PaillierPK;
+use crate::cryptosystems::rsa::RsaPK;
+use rug::Integer;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::encoding::{EncodingError, PlaintextEncodable};
+
+/// Implements the encodings of `u64` and `i64` for a public key whose plaintexts are the integers
+/// modulo its field `$modulus`.
+macro_rules! impl_integer_encodings {
+    ($public_key:ty, $modulus:ident) => {
+        impl PlaintextEncodable<$public_key> for u64 {
+            fn encode(&self, public_key: &$public_key) -> Result<UnsignedInteger, EncodingError> {
+                encode_unsigned(*self, &public_key.$modulus)
+            }
+
+            fn decode(
+                plaintext: &UnsignedInteger,
+                _public_key: &$public_key,
+            ) -> Result<Self, EncodingError> {
+                plaintext.clone().to_rug().to_u64().ok_or(EncodingError)
+            }
+        }
+
+        impl PlaintextEncodable<$public_key> for i64 {
+            fn encode(&self, public_key: &$public_key) -> Result<UnsignedInteger, EncodingError> {
+                encode_signed(*self, &public_key.$modulus)
+            }
+
+            fn decode(
+                plaintext: &UnsignedInteger,
+                public_key: &$public_key,
+            ) -> Result<Self, EncodingError> {
+                decode_signed(plaintext, &public_key.$modulus)
+            }
+        }
+    };
+}
+
+impl_integer_encodings!(PaillierPK, n);
+impl_integer_encodings!(RsaPK, n);
+impl_integer_encodings!(IntegerElGamalPK, modulus);
+
+fn encode_unsigned(
+    value: u64,
+    modulus: &UnsignedInteger,
+) -> Result<UnsignedInteger, EncodingError> {
+    if modulus.clone().to_rug() <= value {
+        return Err(EncodingError);
+    }
+
+    Ok(UnsignedInteger::from(value))
+}
+
+fn encode_signed(value: i64, modulus: &UnsignedInteger) -> Result<UnsignedInteger, EncodingError> {
+    let modulus = modulus.clone().to_rug();
+    let half = (modulus.clone() - 1u32) / 2u32;
+
+    let magnitude = Integer::from(value.unsigned_abs());
+    if magnitude > half {
+        return Err(EncodingError);
+    }
+
+    if value >= 0 {
+        Ok(UnsignedInteger::from(value as u64))
+    } else {
+        Ok(UnsignedInteger::from(modulus - magnitude))
+    }
+}
+
+fn decode_signed(
+    plaintext: &UnsignedInteger,
+    modulus: &UnsignedInteger,
+) -> Result<i64, EncodingError> {
+    let modulus = modulus.clone().to_rug();
+    let half = (modulus.clone() - 1u32) / 2u32;
+    let plaintext = plaintext.clone().to_rug();
+
+    let value = if plaintext > half {
+        plaintext - modulus
+    } else {
+        plaintext
+    };
+
+    value.to_i64().ok_or(EncodingError)
+}
+
+/// DGK's plaintexts are the integers modulo the small prime $u$.
+impl PlaintextEncodable<DgkPK> for u64 {
+    fn encode(&self, public_key: &DgkPK) -> Result<u64, EncodingError> {
+        if *self >= public_key.u {
+            return Err(EncodingError);
+        }
+
+        Ok(*self)
+    }
+
+    fn decode(plaintext: &u64, _public_key: &DgkPK) -> Result<Self, EncodingError> {
+        Ok(*plaintext)
+    }
+}
+
+impl PlaintextEncodable<DgkPK> for i64 {
+    fn encode(&self, public_key: &DgkPK) -> Result<u64, EncodingError> {
+        let magnitude = self.unsigned_abs();
+        if magnitude > (public_key.u - 1) / 2 {
+            return Err(EncodingError);
+        }
+
+        if *self >= 0 {
+            Ok(magnitude)
+        } else {
+            Ok(public_key.u - magnitude)
+        }
+    }
+
+    fn decode(plaintext: &u64, public_key: &DgkPK) -> Result<Self, EncodingError> {
+        if *plaintext > (public_key.u - 1) / 2 {
+            Ok(-((public_key.u - plaintext) as i64))
+        } else {
+            Ok(*plaintext as i64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::dgk::Dgk;
+    use crate::cryptosystems::integer_el_gamal::IntegerElGamal;
+    use crate::cryptosystems::paillier::Paillier;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::encoding::{
+        EncodingError, FixedPoint, PlaintextEncodable, PlaintextOperation,
+    };
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_paillier_signed_addition() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt_encodable(&-50i64, &mut rng).unwrap();
+        let ciphertext_b = pk.encrypt_encodable(&8i64, &mut rng).unwrap();
+
+        assert_eq!(
+            Ok(-42i64),
+            sk.decrypt_decodable(&(&ciphertext_a + &ciphertext_b))
+        );
+        assert_eq!(
+            Ok(u64::MAX),
+            sk.decrypt_decodable(&pk.encrypt_encodable(&u64::MAX, &mut rng).unwrap())
+        );
+        // A negative number does not decode as an unsigned one
+        assert_eq!(
+            Err(EncodingError),
+            sk.decrypt_decodable::<u64>(&ciphertext_a)
+        );
+    }
+
+    #[test]
+    fn test_integer_el_gamal_signed_product() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = IntegerElGamal::setup(&Default::default()).generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt_encodable(&-6i64, &mut rng).unwrap();
+        let ciphertext_b = pk.encrypt_encodable(&FixedPoint::<1>::from_f64(0.7).unwrap(), &mut rng);
+
+        let product: FixedPoint<1> = sk
+            .decrypt_decodable(&(&ciphertext_a * &ciphertext_b.unwrap()))
+            .unwrap();
+        assert_eq!(-4.2, product.to_f64());
+    }
+
+    #[test]
+    fn test_dgk_range() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Dgk::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_encodable(&-3i64, &mut rng).unwrap();
+        assert_eq!(Ok(-3i64), sk.decrypt_decodable(&ciphertext));
+
+        assert_eq!(Err(EncodingError), pk.u.encode(&pk));
+        assert_eq!(Err(EncodingError), (pk.u as i64 / 2 + 1).encode(&pk));
+    }
+
+    #[test]
+    fn test_dgk_max_safe_sum() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Dgk::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let space = pk.plaintext_space();
+        assert_eq!(PlaintextOperation::Addition, space.operation);
+
+        let value_bits = space.capacity_bits - 2;
+        let value = (1u64 << value_bits) - 1;
+        let count = space.max_safe_operands(value_bits);
+        assert_eq!(4, count);
+
+        let sum = (0..count)
+            .map(|_| pk.encrypt_encodable(&value, &mut rng).unwrap())
+            .reduce(|a, b| &a + &b)
+            .unwrap();
+        assert_eq!(Ok(count * value), sk.decrypt_decodable(&sum));
+    }
+
+    #[test]
+    fn test_integer_el_gamal_plaintext_space() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = IntegerElGamal::setup(&Default::default()).generate_keys(&mut rng);
+
+        let space = pk.plaintext_space();
+        assert_eq!(PlaintextOperation::Multiplication, space.operation);
+        assert_eq!(pk.modulus.significant_bits_leaky() - 1, space.capacity_bits);
+    }
+}
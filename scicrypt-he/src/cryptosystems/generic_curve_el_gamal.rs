@@ -0,0 +1,325 @@
+//! ElGamal over any prime-order elliptic curve group that implements `CurveGroup`. Use this to
+//! encrypt under keys on standardized curves such as secp256k1 or NIST P-256. For the Ristretto
+//! group, `CurveElGamal` is faster because it uses precomputed tables and multi-scalar
+//! multiplication.
+//!
+//! Here is an example of encrypting a point on the Ristretto group through the generic interface.
+//! Enabling the `secp256k1` or `p256` feature allows `Secp256k1` or `NistP256` to be used instead.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_group::{CurveGroup, Ristretto};
+//! # use scicrypt_he::cryptosystems::generic_curve_el_gamal::GenericCurveElGamal;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = GenericCurveElGamal::<Ristretto>::setup(&Default::default());
+//! let (public_key, secret_key) = el_gamal.generate_keys(&mut rng);
+//!
+//! let plaintext = Ristretto::mul_generator(&Ristretto::scalar_from_u64(5));
+//! let ciphertext = public_key.encrypt(&plaintext, &mut rng);
+//!
+//! assert_eq!(plaintext, secret_key.decrypt(&ciphertext));
+//! ```
+
+use crate::cryptosystems::curve_group::CurveGroup;
+#[cfg(feature = "p256")]
+use crate::cryptosystems::curve_group::NistP256;
+#[cfg(feature = "secp256k1")]
+use crate::cryptosystems::curve_group::Secp256k1;
+use crate::identifier::{hash_serialized, Identifiable};
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::security::{BitsOfSecurity, Redacted};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use subtle::{Choice, ConstantTimeEq};
+
+/// ElGamal over the elliptic curve group `G`. ElGamal is a partially homomorphic cryptosystem.
+#[derive(Copy, Clone)]
+pub struct GenericCurveElGamal<G: CurveGroup> {
+    group: PhantomData<G>,
+    security_level: BitsOfSecurity,
+}
+
+/// ElGamal over the secp256k1 curve.
+#[cfg(feature = "secp256k1")]
+pub type Secp256k1ElGamal = GenericCurveElGamal<Secp256k1>;
+
+/// ElGamal over the NIST P-256 curve.
+#[cfg(feature = "p256")]
+pub type P256ElGamal = GenericCurveElGamal<NistP256>;
+
+/// ElGamal ciphertext containing points of the group `G`. The addition operator on the ciphertext
+/// is reflected as the group operation on the associated plaintext.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct GenericCurveElGamalCiphertext<G: CurveGroup> {
+    /// First part of ciphertext
+    pub c1: G::Point,
+    /// Second part of ciphertext
+    pub c2: G::Point,
+}
+
+impl<G: CurveGroup> Associable<GenericCurveElGamalPK<G>> for GenericCurveElGamalCiphertext<G> {}
+
+impl<G: CurveGroup> Identifiable for GenericCurveElGamalCiphertext<G> {}
+
+impl<G: CurveGroup> ConstantTimeEq for GenericCurveElGamalCiphertext<G> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl<G: CurveGroup> Hash for GenericCurveElGamalCiphertext<G> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_serialized(self, state);
+    }
+}
+
+/// Encryption key for ElGamal over the group `G`
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct GenericCurveElGamalPK<G: CurveGroup> {
+    /// Public key as a point of the group
+    pub point: G::Point,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl<G: CurveGroup> ConstantTimeEq for GenericCurveElGamalPK<G> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.point.ct_eq(&other.point)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
+}
+
+/// Decryption key for ElGamal over the group `G`
+pub struct GenericCurveElGamalSK<G: CurveGroup> {
+    pub(crate) key: G::Scalar,
+}
+
+impl<G: CurveGroup> ConstantTimeEq for GenericCurveElGamalSK<G> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.key.ct_eq(&other.key)
+    }
+}
+
+impl<G: CurveGroup> Debug for GenericCurveElGamalSK<G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GenericCurveElGamalSK")
+            .field(
+                "key",
+                &Redacted::new(&G::scalar_to_bytes(&self.key)[..], G::SCALAR_BITS),
+            )
+            .finish()
+    }
+}
+
+impl<G: CurveGroup> AsymmetricCryptosystem for GenericCurveElGamal<G> {
+    type PublicKey = GenericCurveElGamalPK<G>;
+    type SecretKey = GenericCurveElGamalSK<G>;
+
+    fn setup(security_param: &BitsOfSecurity) -> Self {
+        match security_param {
+            BitsOfSecurity::AES128
+            | BitsOfSecurity::ToyParameters
+            | BitsOfSecurity::Custom { pk_bits: 128 } => (),
+            _ => panic!("Currently only curves with security level AES128 are supported."),
+        }
+
+        GenericCurveElGamal {
+            group: PhantomData,
+            security_level: *security_param,
+        }
+    }
+
+    fn generate_keys<R: SecureRng>(
+        &self,
+        rng: &mut GeneralRng<R>,
+    ) -> (GenericCurveElGamalPK<G>, GenericCurveElGamalSK<G>) {
+        trace_span!(INFO, "generate_keys");
+        let secret_key = G::random_scalar(rng);
+
+        (
+            GenericCurveElGamalPK {
+                point: G::mul_generator(&secret_key),
+                security_level: self.security_level,
+            },
+            GenericCurveElGamalSK { key: secret_key },
+        )
+    }
+}
+
+impl<G: CurveGroup> EncryptionKey for GenericCurveElGamalPK<G> {
+    type Input = G::Scalar;
+    type Plaintext = G::Point;
+    type Ciphertext = GenericCurveElGamalCiphertext<G>;
+    type Randomness = G::Scalar;
+
+    fn encrypt_without_randomness(&self, plaintext: &G::Point) -> Self::Ciphertext {
+        GenericCurveElGamalCiphertext {
+            c1: G::identity(),
+            c2: *plaintext,
+        }
+    }
+
+    fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: Self::Ciphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
+        let randomness = G::random_scalar(rng);
+
+        self.randomize_with(ciphertext, &randomness)
+    }
+
+    fn randomize_with(
+        &self,
+        ciphertext: Self::Ciphertext,
+        randomness: &G::Scalar,
+    ) -> Self::Ciphertext {
+        GenericCurveElGamalCiphertext {
+            c1: G::add(&ciphertext.c1, &G::mul_generator(randomness)),
+            c2: G::add(&ciphertext.c2, &G::mul(&self.point, randomness)),
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: G::SCALAR_BITS - 1,
+        }
+    }
+}
+
+impl<G: CurveGroup> DecryptionKey<GenericCurveElGamalPK<G>> for GenericCurveElGamalSK<G> {
+    fn decrypt_raw(
+        &self,
+        _public_key: &GenericCurveElGamalPK<G>,
+        ciphertext: &GenericCurveElGamalCiphertext<G>,
+    ) -> G::Point {
+        trace_span!(DEBUG, "decrypt");
+        G::sub(&ciphertext.c2, &G::mul(&ciphertext.c1, &self.key))
+    }
+
+    fn decrypt_identity_raw(
+        &self,
+        _public_key: &GenericCurveElGamalPK<G>,
+        ciphertext: &GenericCurveElGamalCiphertext<G>,
+    ) -> bool {
+        ciphertext.c2 == G::mul(&ciphertext.c1, &self.key)
+    }
+}
+
+impl<G: CurveGroup> HomomorphicAddition for GenericCurveElGamalPK<G> {
+    fn add(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
+        GenericCurveElGamalCiphertext {
+            c1: G::add(&ciphertext_a.c1, &ciphertext_b.c1),
+            c2: G::add(&ciphertext_a.c2, &ciphertext_b.c2),
+        }
+    }
+
+    fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &G::Scalar) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
+        GenericCurveElGamalCiphertext {
+            c1: G::mul(&ciphertext.c1, input),
+            c2: G::mul(&ciphertext.c2, input),
+        }
+    }
+
+    fn sub(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
+        GenericCurveElGamalCiphertext {
+            c1: G::sub(&ciphertext_a.c1, &ciphertext_b.c1),
+            c2: G::sub(&ciphertext_a.c2, &ciphertext_b.c2),
+        }
+    }
+
+    fn add_constant(&self, ciphertext: &Self::Ciphertext, constant: &G::Point) -> Self::Ciphertext {
+        GenericCurveElGamalCiphertext {
+            c1: ciphertext.c1,
+            c2: G::add(&ciphertext.c2, constant),
+        }
+    }
+
+    fn sub_constant(&self, ciphertext: &Self::Ciphertext, constant: &G::Point) -> Self::Ciphertext {
+        GenericCurveElGamalCiphertext {
+            c1: ciphertext.c1,
+            c2: G::sub(&ciphertext.c2, constant),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::curve_group::{CurveGroup, Ristretto};
+    use crate::cryptosystems::generic_curve_el_gamal::GenericCurveElGamal;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::homomorphic::HomomorphicAddition;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    fn test_encrypt_add_decrypt<G: CurveGroup>() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = GenericCurveElGamal::<G>::setup(&BitsOfSecurity::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let plaintext_a = G::mul_generator(&G::scalar_from_u64(19));
+        let plaintext_b = G::mul_generator(&G::scalar_from_u64(23));
+        let ciphertext_a = pk.encrypt(&plaintext_a, &mut rng);
+        let ciphertext_b = pk.encrypt(&plaintext_b, &mut rng);
+
+        assert_eq!(plaintext_a, sk.decrypt(&ciphertext_a));
+        assert_eq!(
+            G::mul_generator(&G::scalar_from_u64(42)),
+            sk.decrypt(&(&ciphertext_a + &ciphertext_b))
+        );
+        assert_eq!(
+            G::mul_generator(&G::scalar_from_u64(57)),
+            sk.decrypt_raw(
+                &pk,
+                &pk.mul_constant(&ciphertext_a.ciphertext, &G::scalar_from_u64(3))
+            )
+        );
+        assert!(sk.decrypt_identity(&pk.encrypt(&G::identity(), &mut rng)));
+    }
+
+    #[test]
+    fn test_ristretto() {
+        test_encrypt_add_decrypt::<Ristretto>();
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_secp256k1() {
+        test_encrypt_add_decrypt::<crate::cryptosystems::curve_group::Secp256k1>();
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_p256() {
+        test_encrypt_add_decrypt::<crate::cryptosystems::curve_group::NistP256>();
+    }
+}
@@ -0,0 +1,360 @@
+//! The Goldwasser-Micali cryptosystem encrypts single bits as quadratic residues (a 0) or
+//! non-residues (a 1) modulo a Blum integer $n$. Multiplying two ciphertexts computes the XOR of
+//! their bits, which is exposed through `HomomorphicAddition`. Although its ciphertexts are large
+//! compared to the single bit they encrypt, it is a common building block of secure comparison
+//! protocols.
+//!
+//! Here is an example of encrypting two numbers bit by bit and computing their XOR.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::goldwasser_micali::GoldwasserMicali;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::homomorphic::HomomorphicAddition;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let goldwasser_micali = GoldwasserMicali::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = goldwasser_micali.generate_keys(&mut rng);
+//!
+//! let bits_a = public_key.encrypt_bits(0b1100, 4, &mut rng);
+//! let bits_b = public_key.encrypt_bits(0b1010, 4, &mut rng);
+//! let xor: Vec<_> = bits_a
+//!     .iter()
+//!     .zip(&bits_b)
+//!     .map(|(a, b)| public_key.add(a, b))
+//!     .collect();
+//!
+//! assert_eq!(0b0110, secret_key.decrypt_bits(&xor));
+//! ```
+
+use crate::identifier::Identifiable;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_numbertheory::gen_blum_modulus;
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The Goldwasser-Micali cryptosystem.
+#[derive(Copy, Clone)]
+pub struct GoldwasserMicali {
+    modulus_size: u32,
+    security_level: BitsOfSecurity,
+}
+
+/// Public key for the Goldwasser-Micali cryptosystem. Because $n$ is a Blum integer, $n - 1$ is a
+/// quadratic non-residue with Jacobi symbol 1, so it is used to encrypt ones.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct GoldwasserMicaliPK {
+    /// Public Blum modulus n for encryption
+    pub n: UnsignedInteger,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for GoldwasserMicaliPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n) & Choice::from(u8::from(self.security_level == other.security_level))
+    }
+}
+
+/// Decryption key for the Goldwasser-Micali cryptosystem.
+pub struct GoldwasserMicaliSK {
+    p: UnsignedInteger,
+    /// The exponent $(p - 1) / 2$ of Euler's criterion
+    p_half: UnsignedInteger,
+}
+
+impl ConstantTimeEq for GoldwasserMicaliSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.p.ct_eq(&other.p)
+    }
+}
+
+impl Debug for GoldwasserMicaliSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GoldwasserMicaliSK")
+            .field("p", &Redacted::new(&self.p, self.p.size_in_bits()))
+            .finish()
+    }
+}
+
+/// Ciphertext of the Goldwasser-Micali cryptosystem, which is homomorphic with respect to XOR.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct GoldwasserMicaliCiphertext {
+    /// Encrypted bit (Ciphertext)
+    pub c: UnsignedInteger,
+}
+
+impl Identifiable for GoldwasserMicaliCiphertext {}
+
+impl ConstantTimeEq for GoldwasserMicaliCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c.ct_eq(&other.c)
+    }
+}
+
+impl Associable<GoldwasserMicaliPK> for GoldwasserMicaliCiphertext {
+    fn is_valid_leaky(&self, public_key: &GoldwasserMicaliPK) -> bool {
+        public_key.is_valid_ciphertext_leaky(self)
+    }
+}
+
+impl AsymmetricCryptosystem for GoldwasserMicali {
+    type PublicKey = GoldwasserMicaliPK;
+    type SecretKey = GoldwasserMicaliSK;
+
+    fn setup(security_param: &BitsOfSecurity) -> Self {
+        GoldwasserMicali {
+            modulus_size: security_param.to_public_key_bit_length(),
+            security_level: *security_param,
+        }
+    }
+
+    fn generate_keys<R: SecureRng>(
+        &self,
+        rng: &mut GeneralRng<R>,
+    ) -> (GoldwasserMicaliPK, GoldwasserMicaliSK) {
+        trace_span!(INFO, "generate_keys", modulus_bits = self.modulus_size);
+        let (n, factorization) = gen_blum_modulus(self.modulus_size, rng);
+
+        let p = factorization.p;
+        let p_half = &(p.clone() - 1) >> 1;
+
+        let public_key = GoldwasserMicaliPK {
+            n,
+            security_level: self.security_level,
+        };
+
+        (public_key, GoldwasserMicaliSK { p, p_half })
+    }
+}
+
+impl GoldwasserMicaliPK {
+    /// Encrypts the `bit_count` least significant bits of `value` separately, least significant bit
+    /// first.
+    pub fn encrypt_bits<R: SecureRng>(
+        &self,
+        value: u64,
+        bit_count: u32,
+        rng: &mut GeneralRng<R>,
+    ) -> Vec<GoldwasserMicaliCiphertext> {
+        assert!(bit_count <= 64, "a u64 has at most 64 bits");
+
+        (0..bit_count)
+            .map(|i| self.encrypt_raw(&((value >> i) & 1 == 1), rng))
+            .collect()
+    }
+
+    /// Returns true if the Jacobi symbol of the ciphertext is 1, which holds for every honestly
+    /// generated ciphertext. Ciphertexts from untrusted parties should be checked before they are
+    /// combined with other ciphertexts. This function is not constant-time.
+    pub fn is_valid_ciphertext_leaky(&self, ciphertext: &GoldwasserMicaliCiphertext) -> bool {
+        ciphertext.c.jacobi_leaky(&self.n) == 1
+    }
+}
+
+impl GoldwasserMicaliSK {
+    /// Decrypts a sequence of encrypted bits, least significant bit first, into a number.
+    pub fn decrypt_bits(&self, ciphertexts: &[GoldwasserMicaliCiphertext]) -> u64 {
+        assert!(ciphertexts.len() <= 64, "a u64 has at most 64 bits");
+
+        ciphertexts
+            .iter()
+            .enumerate()
+            .map(|(i, ciphertext)| (self.decrypt_bit(ciphertext) as u64) << i)
+            .sum()
+    }
+
+    /// Decrypts a bit using Euler's criterion modulo $p$: the ciphertext encrypts a 0 exactly when
+    /// $c^{(p - 1) / 2} \equiv 1 \pmod p$.
+    fn decrypt_bit(&self, ciphertext: &GoldwasserMicaliCiphertext) -> bool {
+        let residue = (ciphertext.c.clone() % &self.p).pow_mod(&self.p_half, &self.p);
+
+        residue != UnsignedInteger::from(1u64)
+    }
+}
+
+impl EncryptionKey for GoldwasserMicaliPK {
+    type Input = bool;
+    type Plaintext = bool;
+    type Ciphertext = GoldwasserMicaliCiphertext;
+    type Randomness = UnsignedInteger;
+
+    fn encrypt_without_randomness(&self, plaintext: &bool) -> Self::Ciphertext {
+        let one = UnsignedInteger::new(1, self.n.size_in_bits());
+        let non_residue = self.n.clone() - 1;
+
+        GoldwasserMicaliCiphertext {
+            c: UnsignedInteger::conditional_select(
+                &one,
+                &non_residue,
+                Choice::from(*plaintext as u8),
+            ),
+        }
+    }
+
+    fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: Self::Ciphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
+        // r must be coprime with n but this only fails with probability 2^(1 - n_in_bits / 2)
+        let r = UnsignedInteger::random_below(&self.n, rng);
+
+        self.randomize_with(ciphertext, &r)
+    }
+
+    fn randomize_with(
+        &self,
+        ciphertext: Self::Ciphertext,
+        randomness: &Self::Randomness,
+    ) -> Self::Ciphertext {
+        let randomizer = randomness.square() % &self.n;
+
+        GoldwasserMicaliCiphertext {
+            c: (&ciphertext.c * &randomizer) % &self.n,
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: 1,
+        }
+    }
+}
+
+impl DecryptionKey<GoldwasserMicaliPK> for GoldwasserMicaliSK {
+    fn decrypt_raw(
+        &self,
+        _public_key: &GoldwasserMicaliPK,
+        ciphertext: &GoldwasserMicaliCiphertext,
+    ) -> bool {
+        trace_span!(DEBUG, "decrypt");
+        self.decrypt_bit(ciphertext)
+    }
+
+    fn decrypt_identity_raw(
+        &self,
+        _public_key: &GoldwasserMicaliPK,
+        ciphertext: &GoldwasserMicaliCiphertext,
+    ) -> bool {
+        !self.decrypt_bit(ciphertext)
+    }
+}
+
+/// Addition of bits modulo 2 is XOR, so both `add` and `sub` compute the XOR of two encrypted bits.
+impl HomomorphicAddition for GoldwasserMicaliPK {
+    fn add(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
+        GoldwasserMicaliCiphertext {
+            c: (&ciphertext_a.c * &ciphertext_b.c) % &self.n,
+        }
+    }
+
+    fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &bool) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
+        GoldwasserMicaliCiphertext {
+            c: ciphertext
+                .c
+                .pow_mod(&UnsignedInteger::from(*input as u64), &self.n),
+        }
+    }
+
+    fn sub(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
+        self.add(ciphertext_a, ciphertext_b)
+    }
+
+    fn add_constant(&self, ciphertext: &Self::Ciphertext, constant: &bool) -> Self::Ciphertext {
+        self.add(ciphertext, &self.encrypt_without_randomness(constant))
+    }
+
+    fn sub_constant(&self, ciphertext: &Self::Ciphertext, constant: &bool) -> Self::Ciphertext {
+        self.add_constant(ciphertext, constant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::goldwasser_micali::{GoldwasserMicali, GoldwasserMicaliCiphertext};
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_encrypt_decrypt_bits() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let goldwasser_micali = GoldwasserMicali::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = goldwasser_micali.generate_keys(&mut rng);
+
+        assert!(sk.decrypt(&pk.encrypt(&true, &mut rng)));
+        assert!(!sk.decrypt(&pk.encrypt(&false, &mut rng)));
+        assert!(sk.decrypt_identity(&pk.encrypt(&false, &mut rng)));
+
+        let ciphertexts = pk.encrypt_bits(0xdead_beef, 32, &mut rng);
+        assert!(ciphertexts.iter().all(|c| pk.is_valid_ciphertext_leaky(c)));
+        assert_eq!(0xdead_beef, sk.decrypt_bits(&ciphertexts));
+    }
+
+    #[test]
+    fn test_homomorphic_xor() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let goldwasser_micali = GoldwasserMicali::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = goldwasser_micali.generate_keys(&mut rng);
+
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            let ciphertext_a = pk.encrypt(&a, &mut rng);
+            let ciphertext_b = pk.encrypt(&b, &mut rng);
+
+            assert_eq!(a ^ b, sk.decrypt(&(&ciphertext_a + &ciphertext_b)));
+            assert_eq!(a ^ b, sk.decrypt(&(&ciphertext_a - &ciphertext_b)));
+            assert_eq!(a ^ b, sk.decrypt(&(&ciphertext_a + b)));
+            assert_eq!(a & b, sk.decrypt(&(&ciphertext_a * b)));
+        }
+    }
+
+    #[test]
+    fn test_invalid_ciphertext() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let goldwasser_micali = GoldwasserMicali::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, _) = goldwasser_micali.generate_keys(&mut rng);
+
+        // Find a number with Jacobi symbol -1, which is not a valid ciphertext
+        let invalid = (2u64..)
+            .map(|c| GoldwasserMicaliCiphertext {
+                c: UnsignedInteger::from(c),
+            })
+            .find(|c| c.c.jacobi_leaky(&pk.n) == -1)
+            .unwrap();
+
+        assert!(!pk.is_valid_ciphertext_leaky(&invalid));
+    }
+}
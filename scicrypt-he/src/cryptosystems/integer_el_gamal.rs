@@ -14,17 +14,30 @@
 //! ```
 
 use crate::constants::{SAFE_PRIME_1024, SAFE_PRIME_2048, SAFE_PRIME_3072};
-use scicrypt_bigint::UnsignedInteger;
+use crate::exponentiation::{ExpConfig, FixedBase};
+use crate::identifier::Identifiable;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use scicrypt_bigint::{BarrettContext, LazyProduct, UnsignedInteger};
+use scicrypt_numbertheory::gen_safe_prime;
 use scicrypt_traits::cryptosystems::{
-    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+    Associable, AssociatedCiphertext, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
 };
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
 use scicrypt_traits::homomorphic::HomomorphicMultiplication;
+use scicrypt_traits::kem::{Kem, SharedSecret};
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
-use serde::{Deserialize, Serialize};
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
 
-/// Multiplicatively homomorphic ElGamal over a safe prime group where the generator is 4.
+/// Multiplicatively homomorphic ElGamal over the quadratic residues modulo a safe prime. The
+/// generator is 4 by default, and can be changed with `with_generator`.
 ///
 /// As an example we compute the product between 4 and 6 using ElGamal's homomorphic property.
 /// ```
@@ -47,19 +60,75 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone)]
 pub struct IntegerElGamal {
     modulus: UnsignedInteger,
+    generator: UnsignedInteger,
+    pub(crate) security_level: BitsOfSecurity,
 }
 
 /// Public key containing the ElGamal encryption key and the modulus of the group.
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Clone)]
 pub struct IntegerElGamalPK {
     /// Generator for encrypting
     pub h: UnsignedInteger,
+    /// Generator $g$ of the subgroup of quadratic residues, such that $h = g^x$ for the secret key $x$
+    pub generator: UnsignedInteger,
     /// Modulus of public key
     pub modulus: UnsignedInteger,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+    /// Precomputed context for reducing products modulo the modulus, which is derived from the
+    /// modulus and therefore never serialized
+    #[serde(skip)]
+    pub(crate) barrett: BarrettContext,
+}
+
+/// The serialized fields of an `IntegerElGamalPK`, from which its Barrett context is rebuilt.
+#[derive(Deserialize)]
+#[serde(rename = "IntegerElGamalPK")]
+struct IntegerElGamalPKFields {
+    h: UnsignedInteger,
+    generator: UnsignedInteger,
+    modulus: UnsignedInteger,
+    security_level: BitsOfSecurity,
+}
+
+impl<'de> Deserialize<'de> for IntegerElGamalPK {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = IntegerElGamalPKFields::deserialize(deserializer)?;
+        if fields.modulus.is_zero_leaky() {
+            return Err(serde::de::Error::custom("expected a non-zero modulus"));
+        }
+
+        Ok(IntegerElGamalPK::new(
+            fields.h,
+            fields.generator,
+            fields.modulus,
+            fields.security_level,
+        ))
+    }
+}
+
+impl PartialEq for IntegerElGamalPK {
+    fn eq(&self, other: &Self) -> bool {
+        self.h == other.h
+            && self.generator == other.generator
+            && self.modulus == other.modulus
+            && self.security_level == other.security_level
+    }
+}
+
+impl Eq for IntegerElGamalPK {}
+
+impl ConstantTimeEq for IntegerElGamalPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.h.ct_eq(&other.h)
+            & self.generator.ct_eq(&other.generator)
+            & self.modulus.ct_eq(&other.modulus)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
 }
 
 /// ElGamal ciphertext of integers.
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
 pub struct IntegerElGamalCiphertext {
     /// First part of ciphertext
     pub c1: UnsignedInteger,
@@ -67,13 +136,76 @@ pub struct IntegerElGamalCiphertext {
     pub c2: UnsignedInteger,
 }
 
-impl Associable<IntegerElGamalPK> for IntegerElGamalCiphertext {}
+impl Identifiable for IntegerElGamalCiphertext {}
+
+impl ConstantTimeEq for IntegerElGamalCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+/// ElGamal ciphertext that encrypts the same plaintext to several recipients in the same group. All
+/// recipients share the first part of the ciphertext, because it is computed from the same
+/// randomness, which is as secure as separate encryptions as long as the recipients' public keys are
+/// distinct.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct IntegerElGamalMultiRecipientCiphertext {
+    /// First part of ciphertext, shared by all recipients
+    pub c1: UnsignedInteger,
+    /// Second part of ciphertext for every recipient, in the order of their public keys
+    pub c2s: Vec<UnsignedInteger>,
+}
+
+impl Identifiable for IntegerElGamalMultiRecipientCiphertext {}
+
+impl ConstantTimeEq for IntegerElGamalMultiRecipientCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2s[..].ct_eq(&other.c2s[..])
+    }
+}
+
+impl IntegerElGamalMultiRecipientCiphertext {
+    /// Returns the ciphertext of the recipient at `index`, which they can decrypt as usual.
+    pub fn ciphertext_for(&self, index: usize) -> IntegerElGamalCiphertext {
+        IntegerElGamalCiphertext {
+            c1: self.c1.clone(),
+            c2: self.c2s[index].clone(),
+        }
+    }
+}
+
+impl Associable<IntegerElGamalPK> for IntegerElGamalCiphertext {
+    /// Returns true if $c_1$ lies in the subgroup of quadratic residues that is generated by the
+    /// generator, and $c_2$ is a nonzero element below the modulus. Raising $c_1$ outside of this subgroup to the
+    /// secret key during decryption would leak the secret key modulo 2. The message in $c_2$ does not
+    /// have to be a quadratic residue, so only its range is checked.
+    fn is_valid_leaky(&self, public_key: &IntegerElGamalPK) -> bool {
+        let in_group =
+            |c: &UnsignedInteger| !c.is_zero_leaky() && c.leak() < public_key.modulus.leak();
+
+        in_group(&self.c1) && in_group(&self.c2) && self.c1.jacobi_leaky(&public_key.modulus) == 1
+    }
+}
 
 /// Decryption key for Integer-based ElGamal
 pub struct IntegerElGamalSK {
     pub(crate) key: UnsignedInteger,
 }
 
+impl ConstantTimeEq for IntegerElGamalSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.key.ct_eq(&other.key)
+    }
+}
+
+impl Debug for IntegerElGamalSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntegerElGamalSK")
+            .field("key", &Redacted::new(&self.key, self.key.size_in_bits()))
+            .finish()
+    }
+}
+
 impl AsymmetricCryptosystem for IntegerElGamal {
     type PublicKey = IntegerElGamalPK;
     type SecretKey = IntegerElGamalSK;
@@ -92,6 +224,8 @@ impl AsymmetricCryptosystem for IntegerElGamal {
                 16,
                 public_key_len,
             ),
+            generator: UnsignedInteger::from(4u64),
+            security_level: *security_param,
         }
     }
 
@@ -110,20 +244,588 @@ impl AsymmetricCryptosystem for IntegerElGamal {
         &self,
         rng: &mut GeneralRng<R>,
     ) -> (IntegerElGamalPK, IntegerElGamalSK) {
+        trace_span!(
+            INFO,
+            "generate_keys",
+            modulus_bits = self.modulus.size_in_bits()
+        );
         let q = &self.modulus >> 1;
         let secret_key = UnsignedInteger::random_below(&q, rng);
-        let public_key = UnsignedInteger::from(4u64).pow_mod(&secret_key, &self.modulus);
+        let public_key = self.generator.pow_mod(&secret_key, &self.modulus);
 
         (
-            IntegerElGamalPK {
-                h: public_key,
-                modulus: self.modulus.clone(),
-            },
+            IntegerElGamalPK::new(
+                public_key,
+                self.generator.clone(),
+                self.modulus.clone(),
+                self.security_level,
+            ),
             IntegerElGamalSK { key: secret_key },
         )
     }
 }
 
+/// The public parameters of the group in which ElGamal operates, which can be exported and shared
+/// with other parties so that they can audit them or generate keys in the same group.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct GroupParameters {
+    /// Safe prime modulus $p = 2q + 1$
+    pub modulus: UnsignedInteger,
+    /// Generator of the subgroup of order $q$
+    pub generator: UnsignedInteger,
+}
+
+impl GroupParameters {
+    /// Returns true if the modulus is a safe prime $p = 2q + 1$ and the generator generates the
+    /// subgroup of order $q$. This function is not constant-time, which is fine because the
+    /// parameters are public.
+    pub fn verify_leaky(&self) -> bool {
+        let repetitions = BitsOfSecurity::from_public_key_bit_length(self.modulus.size_in_bits())
+            .to_miller_rabin_repetitions();
+        let q = &self.modulus >> 1;
+
+        self.modulus.is_probably_prime_with_leaky(repetitions)
+            && q.is_probably_prime_with_leaky(repetitions)
+            && generates_subgroup_leaky(&self.generator, &self.modulus)
+    }
+}
+
+/// Returns true if `generator` generates the subgroup of order $q$ modulo the safe prime
+/// $p = 2q + 1$. Because $q$ is prime, this holds for every element of the subgroup except 1.
+fn generates_subgroup_leaky(generator: &UnsignedInteger, modulus: &UnsignedInteger) -> bool {
+    let q = modulus >> 1;
+
+    generator.clone() % modulus == *generator
+        && *generator != UnsignedInteger::from(1u64)
+        && generator.pow_mod(&q, modulus) == UnsignedInteger::from(1u64)
+}
+
+const SETUP_DOMAIN: &[u8] = b"scicrypt-he integer el gamal setup";
+
+/// The number of ciphertext components that `IntegerElGamalPK::product` multiplies before reducing.
+const LAZY_REDUCTION_BATCH_SIZE: usize = 4;
+
+/// Commitment to the seed of a setup, which the party running the setup publishes in the first
+/// step of `IntegerElGamal::commit_setup`. It binds that party to the seed before the other parties
+/// choose their contribution.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct SetupCommitment {
+    /// The level of security that the safe prime is generated for
+    pub security_level: BitsOfSecurity,
+    /// Hash commitment to the seed
+    pub seed_commitment: [u8; 32],
+}
+
+/// Seed of a setup, which the party running the setup keeps secret until every other party has
+/// chosen their contribution, and then reveals to them.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct SetupSeed {
+    /// Random seed that was committed to
+    pub seed: [u8; 32],
+}
+
+fn commit_to_seed(seed: &[u8; 32]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(SETUP_DOMAIN)
+        .chain_update(seed)
+        .finalize()
+        .into()
+}
+
+fn safe_prime_from_seed(
+    bit_length: u32,
+    seed: &[u8; 32],
+    contribution: &[u8; 32],
+) -> UnsignedInteger {
+    let derived_seed: [u8; 32] = Sha256::new()
+        .chain_update(SETUP_DOMAIN)
+        .chain_update(seed)
+        .chain_update(contribution)
+        .finalize()
+        .into();
+    let mut rng = GeneralRng::new(ChaCha20Rng::from_seed(derived_seed));
+
+    gen_safe_prime(bit_length, &mut rng)
+}
+
+impl IntegerElGamal {
+    /// First step of a setup that generates a fresh safe prime modulus of the length that
+    /// corresponds to `security_param`, instead of using one of the fixed moduli. Returns a random
+    /// seed, which must be kept secret for now, and a commitment to it, which is published. The other
+    /// parties then choose a random contribution together and reveal it, after which the seed is
+    /// revealed for `reveal_setup`. Because the seed is fixed before the contribution is known, the
+    /// party running the setup cannot try many seeds until the prime has a hidden structure.
+    pub fn commit_setup<R: SecureRng>(
+        security_param: &BitsOfSecurity,
+        rng: &mut GeneralRng<R>,
+    ) -> (SetupSeed, SetupCommitment) {
+        let mut seed = [0u8; 32];
+        rng.rng().fill_bytes(&mut seed);
+
+        let commitment = SetupCommitment {
+            security_level: *security_param,
+            seed_commitment: commit_to_seed(&seed),
+        };
+
+        (SetupSeed { seed }, commitment)
+    }
+
+    /// Second step of the setup started by `commit_setup`, which every party runs after the seed is
+    /// revealed. Returns `None` if `seed` does not match `commitment`. Otherwise, it derives the safe
+    /// prime from the seed and the `contribution` of the other parties, so all parties end up with
+    /// the same modulus. This generates a safe prime, so it takes as long as a regular setup.
+    pub fn reveal_setup(
+        commitment: &SetupCommitment,
+        seed: &SetupSeed,
+        contribution: &[u8; 32],
+    ) -> Option<Self> {
+        if commit_to_seed(&seed.seed) != commitment.seed_commitment {
+            return None;
+        }
+
+        let bit_length = commitment.security_level.to_public_key_bit_length();
+
+        Some(IntegerElGamal {
+            modulus: safe_prime_from_seed(bit_length, &seed.seed, contribution),
+            generator: UnsignedInteger::from(4u64),
+            security_level: commitment.security_level,
+        })
+    }
+
+    /// Sets up ElGamal in the group described by `parameters`, for example after receiving them from
+    /// another party. Returns `None` if the parameters do not describe a valid group. The parameters
+    /// do not record a security level, so it follows from the size of the modulus.
+    pub fn from_group_parameters(parameters: &GroupParameters) -> Option<Self> {
+        if !parameters.verify_leaky() {
+            return None;
+        }
+
+        Some(IntegerElGamal {
+            modulus: parameters.modulus.clone(),
+            generator: parameters.generator.clone(),
+            security_level: BitsOfSecurity::from_public_key_bit_length(
+                parameters.modulus.size_in_bits(),
+            ),
+        })
+    }
+
+    /// Replaces the generator by `generator`, for example to interoperate with an existing
+    /// deployment that uses a specific generator. Returns `None` if `generator` does not generate
+    /// the subgroup of quadratic residues, which would leak the secret key through elements of small
+    /// order.
+    pub fn with_generator(self, generator: UnsignedInteger) -> Option<Self> {
+        if !generates_subgroup_leaky(&generator, &self.modulus) {
+            return None;
+        }
+
+        Some(IntegerElGamal {
+            modulus: self.modulus,
+            generator,
+            security_level: self.security_level,
+        })
+    }
+
+    /// Returns the public parameters of the group.
+    pub fn group_parameters(&self) -> GroupParameters {
+        GroupParameters {
+            modulus: self.modulus.clone(),
+            generator: self.generator.clone(),
+        }
+    }
+
+    /// Generates a fresh ElGamal keypair like `generate_keys`, and prepares the public key for
+    /// encryptions that are tuned by `config`.
+    pub fn generate_keys_with_config<R: SecureRng>(
+        &self,
+        config: &ExpConfig,
+        rng: &mut GeneralRng<R>,
+    ) -> (PrecomputedIntegerElGamalPK, IntegerElGamalSK) {
+        let (public_key, secret_key) = self.generate_keys(rng);
+
+        (public_key.precompute(config), secret_key)
+    }
+}
+
+impl IntegerElGamalPK {
+    /// Creates a public key $h = g^x$ for the `generator` $g$ modulo `modulus` that was generated for
+    /// `security_level`, and precomputes the context for reducing modulo `modulus`.
+    pub fn new(
+        h: UnsignedInteger,
+        generator: UnsignedInteger,
+        modulus: UnsignedInteger,
+        security_level: BitsOfSecurity,
+    ) -> Self {
+        IntegerElGamalPK {
+            h,
+            generator,
+            barrett: BarrettContext::new(&modulus),
+            modulus,
+            security_level,
+        }
+    }
+
+    /// Returns true if the generator generates the subgroup of quadratic residues, and $h$ is an
+    /// element of this subgroup. Check this for public keys from untrusted parties, because
+    /// ciphertexts under an invalid key can leak the plaintext. This does not check that the modulus
+    /// is a safe prime, which `GroupParameters::verify_leaky` does.
+    pub fn verify_leaky(&self) -> bool {
+        generates_subgroup_leaky(&self.generator, &self.modulus)
+            && self.barrett.modulus() == &self.modulus
+            && !self.h.is_zero_leaky()
+            && self.h.leak() < self.modulus.leak()
+            && self.h.jacobi_leaky(&self.modulus) == 1
+    }
+
+    /// Encrypts the plaintext using a fresh random exponent, which is returned alongside the ciphertext so that it can later be used to prove correct encryption.
+    pub fn encrypt_returning_randomness<R: SecureRng>(
+        &self,
+        plaintext: &UnsignedInteger,
+        rng: &mut GeneralRng<R>,
+    ) -> (IntegerElGamalCiphertext, UnsignedInteger) {
+        let q = &self.modulus >> 1;
+        let randomness = UnsignedInteger::random_below(&q, rng);
+
+        (
+            self.encrypt_with_randomness(plaintext, &randomness),
+            randomness,
+        )
+    }
+
+    /// Multiplies all `ciphertexts`, so that the result decrypts to the product of their plaintexts.
+    /// The components are only reduced once every few multiplications, which is considerably
+    /// faster than repeated `mul` for large aggregations. Returns an encryption of 1 without
+    /// randomness if there are no ciphertexts.
+    pub fn product(&self, ciphertexts: &[IntegerElGamalCiphertext]) -> IntegerElGamalCiphertext {
+        trace_span!(DEBUG, "product", count = ciphertexts.len());
+        let mut c1 = LazyProduct::new(&self.barrett, LAZY_REDUCTION_BATCH_SIZE);
+        let mut c2 = LazyProduct::new(&self.barrett, LAZY_REDUCTION_BATCH_SIZE);
+
+        for ciphertext in ciphertexts {
+            c1.mul(&ciphertext.c1);
+            c2.mul(&ciphertext.c2);
+        }
+
+        match (c1.finish(), c2.finish()) {
+            (Some(c1), Some(c2)) => IntegerElGamalCiphertext { c1, c2 },
+            _ => self.encrypt_without_randomness(&UnsignedInteger::from(1u64)),
+        }
+    }
+
+    /// Encrypts `plaintext` to every key in `public_keys` using the same randomness. This takes one
+    /// modular exponentiation per recipient and one for the shared part, instead of two per
+    /// recipient for separate encryptions. Panics if the keys do not share the same group.
+    pub fn encrypt_multi<R: SecureRng>(
+        plaintext: &UnsignedInteger,
+        public_keys: &[IntegerElGamalPK],
+        rng: &mut GeneralRng<R>,
+    ) -> IntegerElGamalMultiRecipientCiphertext {
+        let group = public_keys
+            .first()
+            .expect("there must be at least one recipient");
+        assert!(
+            public_keys
+                .iter()
+                .all(|pk| pk.modulus == group.modulus && pk.generator == group.generator),
+            "all recipients must use the same group"
+        );
+
+        let q = &group.modulus >> 1;
+        let randomness = UnsignedInteger::random_below(&q, rng);
+        let message = plaintext.clone() % &group.modulus;
+
+        IntegerElGamalMultiRecipientCiphertext {
+            c1: group.generator.pow_mod(&randomness, &group.modulus),
+            c2s: public_keys
+                .iter()
+                .map(|pk| {
+                    pk.barrett
+                        .reduce(&(&message * &pk.h.pow_mod(&randomness, &pk.modulus)))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Public key that is prepared for many encryptions, using tables for the generator and $h$ if the
+/// `ExpConfig` asks for them. Its ciphertexts are ordinary `IntegerElGamalCiphertext`s, which are
+/// decrypted with the original public key.
+#[derive(Debug)]
+pub struct PrecomputedIntegerElGamalPK {
+    public_key: IntegerElGamalPK,
+    generator: FixedBase,
+    h: FixedBase,
+}
+
+impl IntegerElGamalPK {
+    /// Precomputes the exponentiations of the generator and $h$ as configured by `config`, to
+    /// speed up future encryptions.
+    pub fn precompute(self, config: &ExpConfig) -> PrecomputedIntegerElGamalPK {
+        let exponent_size_in_bits = self.modulus.size_in_bits();
+
+        PrecomputedIntegerElGamalPK {
+            generator: FixedBase::new(
+                &self.generator,
+                &self.modulus,
+                exponent_size_in_bits,
+                config,
+            ),
+            h: FixedBase::new(&self.h, &self.modulus, exponent_size_in_bits, config),
+            public_key: self,
+        }
+    }
+}
+
+impl PrecomputedIntegerElGamalPK {
+    /// Returns the public key without the precomputations.
+    pub fn public_key(&self) -> &IntegerElGamalPK {
+        &self.public_key
+    }
+
+    /// Encrypts `plaintext` using fresh randomness.
+    pub fn encrypt<R: SecureRng>(
+        &self,
+        plaintext: &UnsignedInteger,
+        rng: &mut GeneralRng<R>,
+    ) -> IntegerElGamalCiphertext {
+        self.randomize(self.public_key.encrypt_without_randomness(plaintext), rng)
+    }
+
+    /// Randomizes `ciphertext`, so that it cannot be linked to the original ciphertext.
+    pub fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: IntegerElGamalCiphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> IntegerElGamalCiphertext {
+        let q = &self.public_key.modulus >> 1;
+        let randomness = UnsignedInteger::random_below(&q, rng);
+
+        self.randomize_with(ciphertext, &randomness)
+    }
+
+    /// Randomizes `ciphertext` using the given `randomness`, like `randomize_with` of the public key.
+    pub fn randomize_with(
+        &self,
+        ciphertext: IntegerElGamalCiphertext,
+        randomness: &UnsignedInteger,
+    ) -> IntegerElGamalCiphertext {
+        let barrett = &self.public_key.barrett;
+
+        IntegerElGamalCiphertext {
+            c1: barrett.reduce(&(&ciphertext.c1 * &self.generator.pow(randomness))),
+            c2: barrett.reduce(&(&ciphertext.c2 * &self.h.pow(randomness))),
+        }
+    }
+}
+
+/// Precomputed table to solve small discrete logarithms with respect to the generator, using the
+/// baby-step giant-step algorithm. It solves for exponents below `baby_steps * giant_steps` using
+/// `baby_steps` stored group elements and at most `giant_steps` group multiplications.
+pub struct DiscreteLogTable {
+    baby_steps: HashMap<Vec<u8>, u64>,
+    baby_step_count: u64,
+    giant_step_count: u64,
+    giant_step: UnsignedInteger,
+}
+
+impl DiscreteLogTable {
+    /// Precomputes a table for the group of `public_key` that solves discrete logarithms below
+    /// `baby_steps * giant_steps`. Choosing both close to the square root of the bound minimizes the
+    /// total work.
+    pub fn new(public_key: &IntegerElGamalPK, baby_steps: u64, giant_steps: u64) -> Self {
+        assert!(baby_steps > 0, "the table needs at least one baby step");
+
+        let generator = &public_key.generator;
+        let mut table = HashMap::with_capacity(baby_steps as usize);
+        let mut element = UnsignedInteger::from(1u64);
+
+        for j in 0..baby_steps {
+            table.entry(table_key(&element)).or_insert(j);
+            element = public_key.barrett.reduce(&(&element * generator));
+        }
+
+        DiscreteLogTable {
+            baby_steps: table,
+            baby_step_count: baby_steps,
+            giant_step_count: giant_steps,
+            // After the loop, `element` equals g^baby_steps
+            giant_step: element.invert(&public_key.modulus).unwrap(),
+        }
+    }
+
+    /// Returns the exponent $m$ such that $g^m$ equals `element`, if it lies within the bound of
+    /// this table. This function is not constant-time, and leaks the exponent through its running
+    /// time.
+    pub fn discrete_log_leaky(
+        &self,
+        public_key: &IntegerElGamalPK,
+        element: &UnsignedInteger,
+    ) -> Option<u64> {
+        let mut current = element.clone();
+
+        for i in 0..self.giant_step_count {
+            if let Some(j) = self.baby_steps.get(&table_key(&current)) {
+                return Some(i * self.baby_step_count + j);
+            }
+
+            current = public_key.barrett.reduce(&(&current * &self.giant_step));
+        }
+
+        None
+    }
+}
+
+/// Key under which an element is stored in the table, independent of the number of limbs.
+pub(crate) fn table_key(element: &UnsignedInteger) -> Vec<u8> {
+    let mut bytes = element.to_le_bytes();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    bytes
+}
+
+impl IntegerElGamalPK {
+    /// Encrypts `plaintext` in the exponent as $g^m$, which makes the scheme additively homomorphic:
+    /// multiplying two such ciphertexts results in an encryption of the sum of their plaintexts. Use
+    /// `IntegerElGamalSK::decrypt_exponential_leaky` to decrypt.
+    pub fn encrypt_exponential<R: SecureRng>(
+        &self,
+        plaintext: u64,
+        rng: &mut GeneralRng<R>,
+    ) -> AssociatedCiphertext<'_, IntegerElGamalCiphertext, IntegerElGamalPK> {
+        let encoded = self
+            .generator
+            .pow_mod(&UnsignedInteger::new(plaintext, 64), &self.modulus);
+
+        self.encrypt(&encoded, rng)
+    }
+}
+
+impl IntegerElGamalSK {
+    /// Decrypts a ciphertext that was encrypted in the exponent, using `table` to find the discrete
+    /// logarithm. Returns `None` if the plaintext exceeds the bound of the table. This function is
+    /// not constant-time, and leaks the plaintext through its running time.
+    pub fn decrypt_exponential_leaky(
+        &self,
+        ciphertext: &AssociatedCiphertext<'_, IntegerElGamalCiphertext, IntegerElGamalPK>,
+        table: &DiscreteLogTable,
+    ) -> Option<u64> {
+        table.discrete_log_leaky(ciphertext.public_key, &self.decrypt(ciphertext))
+    }
+
+    /// Decrypts a ciphertext like `decrypt`, but hardened against side-channel and fault attacks on
+    /// the secret key. The secret exponent $x$ is replaced by $x + kq$ for a fresh random 64-bit $k$
+    /// and the group order $q$, and the base $c_1$ is multiplied by a random group element $g^r$ whose
+    /// contribution $h^r$ is removed afterwards. Consequently, no two decryptions exponentiate the
+    /// same base with the same exponent. This requires $c_1$ to lie in the group generated by $g$,
+    /// which holds for all honestly generated ciphertexts.
+    pub fn decrypt_blinded<R: SecureRng>(
+        &self,
+        rich_ciphertext: &AssociatedCiphertext<'_, IntegerElGamalCiphertext, IntegerElGamalPK>,
+        rng: &mut GeneralRng<R>,
+    ) -> UnsignedInteger {
+        let public_key = rich_ciphertext.public_key;
+        let ciphertext = &rich_ciphertext.ciphertext;
+        let q = &public_key.modulus >> 1;
+
+        let blinded_key = &q * &UnsignedInteger::random(64, rng) + &self.key;
+
+        let r = UnsignedInteger::random_below(&q, rng);
+        let base_blind = public_key.generator.pow_mod(&r, &public_key.modulus);
+        let blinded_base = public_key.barrett.reduce(&(&ciphertext.c1 * &base_blind));
+
+        // (c_1 g^r)^(x + kq) = c_1^x h^r, so multiplying c_2 by h^r leaves c_2 / c_1^x
+        let unblinder = public_key.h.pow_mod(&r, &public_key.modulus);
+        let shared_secret = blinded_base
+            .pow_mod(&blinded_key, &public_key.modulus)
+            .invert(&public_key.modulus)
+            .unwrap();
+
+        public_key
+            .barrett
+            .reduce(&(&public_key.barrett.reduce(&(&ciphertext.c2 * &unblinder)) * &shared_secret))
+    }
+}
+
+const KEM_DOMAIN: &[u8] = b"scicrypt-he integer el gamal kem";
+
+/// Diffie-Hellman between an ephemeral key and the ElGamal public key in the safe prime group. The
+/// shared secret is derived by hashing the Diffie-Hellman value together with both public values.
+impl Kem for IntegerElGamal {
+    type Encapsulation = UnsignedInteger;
+
+    fn encapsulate<R: SecureRng>(
+        public_key: &IntegerElGamalPK,
+        rng: &mut GeneralRng<R>,
+    ) -> (SharedSecret, UnsignedInteger) {
+        let q = &public_key.modulus >> 1;
+        let ephemeral_key = UnsignedInteger::random_below(&q, rng);
+
+        let encapsulation = public_key
+            .generator
+            .pow_mod(&ephemeral_key, &public_key.modulus);
+        let shared_value = public_key.h.pow_mod(&ephemeral_key, &public_key.modulus);
+
+        (
+            derive_shared_secret(&shared_value, &encapsulation, public_key),
+            encapsulation,
+        )
+    }
+
+    /// Returns `None` unless the encapsulation lies in $[2, p - 1)$ and is a quadratic residue.
+    /// Otherwise, 0, 1 and $p - 1$ would force a known shared value, and elements outside the
+    /// subgroup of quadratic residues would leak the secret key modulo 2.
+    fn decapsulate(
+        secret_key: &IntegerElGamalSK,
+        public_key: &IntegerElGamalPK,
+        encapsulation: &UnsignedInteger,
+    ) -> Option<SharedSecret> {
+        let minus_one = public_key.modulus.clone() - 1;
+        if encapsulation.leak() < UnsignedInteger::from(2u64).leak()
+            || encapsulation.leak() >= minus_one.leak()
+            || encapsulation.jacobi_leaky(&public_key.modulus) != 1
+        {
+            return None;
+        }
+
+        let shared_value = (encapsulation.clone() % &public_key.modulus)
+            .pow_mod(&secret_key.key, &public_key.modulus);
+
+        Some(derive_shared_secret(
+            &shared_value,
+            encapsulation,
+            public_key,
+        ))
+    }
+}
+
+fn derive_shared_secret(
+    shared_value: &UnsignedInteger,
+    encapsulation: &UnsignedInteger,
+    public_key: &IntegerElGamalPK,
+) -> SharedSecret {
+    let digest = Sha512::new()
+        .chain_update(KEM_DOMAIN)
+        .chain_update(to_length_prefixed_bytes(shared_value))
+        .chain_update(to_length_prefixed_bytes(encapsulation))
+        .chain_update(to_length_prefixed_bytes(&public_key.modulus))
+        .chain_update(to_length_prefixed_bytes(&public_key.generator))
+        .chain_update(to_length_prefixed_bytes(&public_key.h))
+        .finalize();
+
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&digest[..32]);
+    SharedSecret(secret)
+}
+
+/// Encodes `integer` unambiguously for hashing, by prefixing its hexadecimal digits with their length.
+pub(crate) fn to_length_prefixed_bytes(integer: &UnsignedInteger) -> Vec<u8> {
+    let digits = integer.to_string_radix_leaky(16);
+
+    let mut bytes = (digits.len() as u64).to_be_bytes().to_vec();
+    bytes.extend(digits.as_bytes());
+    bytes
+}
+
 impl EncryptionKey for IntegerElGamalPK {
     type Input = UnsignedInteger;
     type Plaintext = UnsignedInteger;
@@ -142,6 +844,7 @@ impl EncryptionKey for IntegerElGamalPK {
         ciphertext: Self::Ciphertext,
         rng: &mut GeneralRng<R>,
     ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
         let q = &self.modulus >> 1;
         let y = UnsignedInteger::random_below(&q, rng);
 
@@ -154,8 +857,23 @@ impl EncryptionKey for IntegerElGamalPK {
         randomness: &Self::Randomness,
     ) -> Self::Ciphertext {
         IntegerElGamalCiphertext {
-            c1: &ciphertext.c1 * &UnsignedInteger::from(4u64).pow_mod(randomness, &self.modulus),
-            c2: (&ciphertext.c2 * &self.h.pow_mod(randomness, &self.modulus)) % &self.modulus,
+            c1: self
+                .barrett
+                .reduce(&(&ciphertext.c1 * &self.generator.pow_mod(randomness, &self.modulus))),
+            c2: self
+                .barrett
+                .reduce(&(&ciphertext.c2 * &self.h.pow_mod(randomness, &self.modulus))),
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Multiplication,
+            capacity_bits: self.modulus.significant_bits_leaky() - 1,
         }
     }
 }
@@ -181,6 +899,7 @@ impl DecryptionKey<IntegerElGamalPK> for IntegerElGamalSK {
         public_key: &IntegerElGamalPK,
         ciphertext: &IntegerElGamalCiphertext,
     ) -> UnsignedInteger {
+        trace_span!(DEBUG, "decrypt");
         (&ciphertext.c2
             * &ciphertext
                 .c1
@@ -205,29 +924,63 @@ impl HomomorphicMultiplication for IntegerElGamalPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul");
         IntegerElGamalCiphertext {
-            c1: (&ciphertext_a.c1 * &ciphertext_b.c1) % &self.modulus,
-            c2: (&ciphertext_a.c2 * &ciphertext_b.c2) % &self.modulus,
+            c1: self.barrett.reduce(&(&ciphertext_a.c1 * &ciphertext_b.c1)),
+            c2: self.barrett.reduce(&(&ciphertext_a.c2 * &ciphertext_b.c2)),
         }
     }
 
     fn pow(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "pow");
         IntegerElGamalCiphertext {
             c1: ciphertext.c1.pow_mod(input, &self.modulus),
             c2: ciphertext.c2.pow_mod(input, &self.modulus),
         }
     }
+
+    fn pow_leaky(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "pow_leaky");
+        IntegerElGamalCiphertext {
+            c1: ciphertext.c1.pow_mod_leaky(input, &self.modulus),
+            c2: ciphertext.c2.pow_mod_leaky(input, &self.modulus),
+        }
+    }
+
+    /// Inverts both components with a single modular inversion, because the components of a
+    /// ciphertext are public. The components of a valid ciphertext are always invertible modulo the
+    /// prime modulus, so this only returns `None` for invalid ciphertexts, such as one with $c_1 = 0$.
+    fn invert(&self, ciphertext: &Self::Ciphertext) -> Option<Self::Ciphertext> {
+        trace_span!(TRACE, "invert");
+        let mut inverses = UnsignedInteger::batch_invert_mod(
+            &[ciphertext.c1.clone(), ciphertext.c2.clone()],
+            &self.modulus,
+        )?
+        .into_iter();
+
+        Some(IntegerElGamalCiphertext {
+            c1: inverses.next().unwrap(),
+            c2: inverses.next().unwrap(),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::cryptosystems::integer_el_gamal::IntegerElGamal;
+    use crate::cryptosystems::integer_el_gamal::{
+        DiscreteLogTable, GroupParameters, IntegerElGamal, IntegerElGamalCiphertext,
+        IntegerElGamalPK,
+    };
+    use crate::exponentiation::ExpConfig;
     use rand_core::OsRng;
     use scicrypt_bigint::UnsignedInteger;
     use scicrypt_traits::cryptosystems::{
-        Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+        Associable, AssociatedCiphertext, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
     };
+    use scicrypt_traits::homomorphic::HomomorphicMultiplication;
+    use scicrypt_traits::kem::Kem;
     use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
 
     #[test]
     fn test_encrypt_decrypt_generator() {
@@ -241,6 +994,65 @@ mod tests {
         assert_eq!(UnsignedInteger::from(19u64), sk.decrypt(&ciphertext));
     }
 
+    #[test]
+    fn test_public_key_serialization() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let bytes = bincode::serialize(&pk).unwrap();
+        let deserialized: IntegerElGamalPK = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(pk, deserialized);
+
+        let ciphertext_a = deserialized.encrypt(&UnsignedInteger::from(6u64), &mut rng);
+        let ciphertext_b = deserialized.encrypt(&UnsignedInteger::from(7u64), &mut rng);
+        let product = &ciphertext_a * &ciphertext_b;
+        assert_eq!(UnsignedInteger::from(42u64), sk.decrypt(&product));
+    }
+
+    #[test]
+    fn test_encrypt_precomputed() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        let (pk, sk) = el_gamal.generate_keys_with_config(&ExpConfig::default(), &mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(19u64), &mut rng);
+        assert_eq!(
+            UnsignedInteger::from(19u64),
+            sk.decrypt_raw(pk.public_key(), &ciphertext)
+        );
+
+        let randomness = UnsignedInteger::from(12345u64);
+        let plaintext = pk
+            .public_key()
+            .encrypt_without_randomness(&UnsignedInteger::from(7u64));
+        assert_eq!(
+            pk.public_key()
+                .randomize_with(plaintext.clone(), &randomness),
+            pk.randomize_with(plaintext, &randomness)
+        );
+    }
+
+    #[test]
+    fn test_encrypt_multi() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let keys: Vec<_> = (0..3).map(|_| el_gamal.generate_keys(&mut rng)).collect();
+        let public_keys: Vec<_> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+
+        let ciphertext =
+            IntegerElGamalPK::encrypt_multi(&UnsignedInteger::from(19u64), &public_keys, &mut rng);
+        assert_eq!(3, ciphertext.c2s.len());
+
+        for (i, (pk, sk)) in keys.iter().enumerate() {
+            let own = ciphertext.ciphertext_for(i).associate(pk);
+            assert_eq!(UnsignedInteger::from(19u64), sk.decrypt(&own));
+        }
+    }
+
     #[test]
     fn test_encrypt_decrypt_identity() {
         let mut rng = GeneralRng::new(OsRng);
@@ -253,6 +1065,129 @@ mod tests {
         assert!(sk.decrypt_identity(&ciphertext));
     }
 
+    #[test]
+    fn test_decrypt_checked_rejects_small_subgroup() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(19u64), &mut rng);
+        assert!(ciphertext.ciphertext.is_valid_leaky(&pk));
+        assert_eq!(
+            UnsignedInteger::from(19u64),
+            sk.decrypt_checked(&ciphertext).unwrap()
+        );
+
+        // -1 generates the subgroup of order 2, so it is not a quadratic residue
+        let minus_one = pk.modulus.clone() - 1;
+        for c1 in [minus_one, UnsignedInteger::zero(1), pk.modulus.clone()] {
+            let invalid = IntegerElGamalCiphertext {
+                c1,
+                c2: ciphertext.ciphertext.c2.clone(),
+            }
+            .associate(&pk);
+
+            assert!(!invalid.ciphertext.is_valid_leaky(&pk));
+            assert!(sk.decrypt_checked(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_encrypt_with_returned_randomness() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let (ciphertext, randomness) =
+            pk.encrypt_returning_randomness(&UnsignedInteger::from(19u64), &mut rng);
+
+        assert_eq!(
+            ciphertext,
+            pk.encrypt_with_randomness(&UnsignedInteger::from(19u64), &randomness)
+        );
+        assert_eq!(
+            UnsignedInteger::from(19u64),
+            sk.decrypt(&ciphertext.associate(&pk))
+        );
+    }
+
+    #[test]
+    fn test_homomorphic_mul_assign() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(3u64), &mut rng);
+        let mut ciphertext_cubed = ciphertext.clone();
+        ciphertext_cubed *= &ciphertext;
+        let ciphertext_cubed = ciphertext_cubed * ciphertext;
+
+        assert_eq!(UnsignedInteger::from(27u64), sk.decrypt(&ciphertext_cubed));
+    }
+
+    #[test]
+    fn test_product() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertexts: Vec<_> = (2..6u64)
+            .map(|i| pk.encrypt(&UnsignedInteger::from(i), &mut rng))
+            .collect();
+
+        let product: AssociatedCiphertext<_, _> = ciphertexts.iter().product();
+        assert_eq!(UnsignedInteger::from(120u64), sk.decrypt(&product));
+    }
+
+    #[test]
+    fn test_product_with_lazy_reduction() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertexts: Vec<_> = (1..=10u64)
+            .map(|i| pk.encrypt_raw(&UnsignedInteger::from(i), &mut rng))
+            .collect();
+
+        let product = pk.product(&ciphertexts);
+        assert_eq!(
+            ciphertexts[1..]
+                .iter()
+                .fold(ciphertexts[0].clone(), |a, b| pk.mul(&a, b)),
+            product
+        );
+        assert_eq!(
+            UnsignedInteger::from(3628800u64),
+            sk.decrypt_raw(&pk, &product)
+        );
+
+        assert_eq!(
+            UnsignedInteger::from(1u64),
+            sk.decrypt_raw(&pk, &pk.product(&[]))
+        );
+    }
+
+    #[test]
+    fn test_owned_operands() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt(&UnsignedInteger::from(4u64), &mut rng);
+        let ciphertext_b = pk.encrypt(&UnsignedInteger::from(6u64), &mut rng);
+
+        let mut product = &ciphertext_a * ciphertext_b.clone();
+        product *= ciphertext_b;
+
+        assert_eq!(UnsignedInteger::from(144u64), sk.decrypt(&product));
+    }
+
     #[test]
     fn test_homomorphic_mul() {
         let mut rng = GeneralRng::new(OsRng);
@@ -281,6 +1216,61 @@ mod tests {
             UnsignedInteger::from(6561u64),
             sk.decrypt(&ciphertext_twice)
         );
+        assert_eq!(
+            ciphertext_twice,
+            ciphertext.pow_leaky(&UnsignedInteger::from(4u64))
+        );
+    }
+
+    #[test]
+    fn test_homomorphic_negative_pow() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(9u64), &mut rng);
+        let ciphertext_inverse = ciphertext
+            .pow_signed(&UnsignedInteger::from(4u64), true)
+            .unwrap();
+        let ciphertext_one = &ciphertext_inverse * &ciphertext.pow(&UnsignedInteger::from(4u64));
+
+        assert_eq!(UnsignedInteger::from(1u64), sk.decrypt(&ciphertext_one));
+        assert_eq!(
+            UnsignedInteger::from(9u64),
+            sk.decrypt(&ciphertext.invert().unwrap().invert().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_homomorphic_division_by_known_value() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(42u64), &mut rng);
+        let divisor = pk.encrypt(&UnsignedInteger::from(6u64), &mut rng);
+        let quotient = &ciphertext * &divisor.invert().unwrap();
+
+        assert_eq!(UnsignedInteger::from(7u64), sk.decrypt(&quotient));
+    }
+
+    #[test]
+    fn test_invert_invalid_ciphertext() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+
+        // A ciphertext with c1 = 0 can be deserialized, but is not valid and has no inverse
+        let ciphertext = IntegerElGamalCiphertext {
+            c1: UnsignedInteger::zero(pk.modulus.size_in_bits()),
+            c2: UnsignedInteger::from(4u64),
+        };
+
+        assert!(!ciphertext.is_valid_leaky(&pk));
+        assert!(pk.invert(&ciphertext).is_none());
     }
 
     #[test]
@@ -300,4 +1290,184 @@ mod tests {
             sk.decrypt(&ciphertext_randomized.associate(&pk))
         );
     }
+
+    #[test]
+    fn test_kem_encapsulate_decapsulate() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let (_, other_sk) = el_gamal.generate_keys(&mut rng);
+
+        let (shared_secret, encapsulation) = IntegerElGamal::encapsulate(&pk, &mut rng);
+
+        assert_eq!(
+            Some(shared_secret.clone()),
+            IntegerElGamal::decapsulate(&sk, &pk, &encapsulation)
+        );
+        assert_ne!(
+            Some(shared_secret),
+            IntegerElGamal::decapsulate(&other_sk, &pk, &encapsulation)
+        );
+    }
+
+    #[test]
+    fn test_kem_reject_invalid_encapsulations() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let (_, encapsulation) = IntegerElGamal::encapsulate(&pk, &mut rng);
+
+        // The modulus is 3 mod 4, so -1 is not a quadratic residue and negating flips the symbol
+        let non_residue = pk.modulus.clone() - &encapsulation;
+        let minus_one = pk.modulus.clone() - 1;
+        let above_modulus = pk.modulus.clone() + &encapsulation;
+
+        for invalid in [
+            UnsignedInteger::zero(64),
+            UnsignedInteger::from(1u64),
+            minus_one,
+            pk.modulus.clone(),
+            above_modulus,
+            non_residue,
+        ] {
+            assert_eq!(None, IntegerElGamal::decapsulate(&sk, &pk, &invalid));
+        }
+    }
+
+    #[test]
+    fn test_exponential_addition() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let table = DiscreteLogTable::new(&pk, 32, 32);
+
+        let ciphertext_a = pk.encrypt_exponential(300, &mut rng);
+        let ciphertext_b = pk.encrypt_exponential(423, &mut rng);
+        let sum = &ciphertext_a * &ciphertext_b;
+
+        assert_eq!(
+            Some(300),
+            sk.decrypt_exponential_leaky(&ciphertext_a, &table)
+        );
+        assert_eq!(Some(723), sk.decrypt_exponential_leaky(&sum, &table));
+        assert_eq!(
+            Some(0),
+            sk.decrypt_exponential_leaky(&pk.encrypt_exponential(0, &mut rng), &table)
+        );
+        assert_eq!(
+            None,
+            sk.decrypt_exponential_leaky(&pk.encrypt_exponential(1024, &mut rng), &table)
+        );
+    }
+
+    #[test]
+    fn test_decrypt_blinded() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt(&UnsignedInteger::from(7u64), &mut rng);
+        let ciphertext_b = pk.encrypt(&UnsignedInteger::from(6u64), &mut rng);
+
+        assert_eq!(
+            UnsignedInteger::from(7u64),
+            sk.decrypt_blinded(&ciphertext_a, &mut rng)
+        );
+        assert_eq!(
+            UnsignedInteger::from(42u64),
+            sk.decrypt_blinded(&(&ciphertext_a * &ciphertext_b), &mut rng)
+        );
+    }
+
+    #[test]
+    fn test_group_parameters() {
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        let parameters = el_gamal.group_parameters();
+        assert!(parameters.verify_leaky());
+
+        let bytes = bincode::serialize(&parameters).unwrap();
+        let deserialized: GroupParameters = bincode::deserialize(&bytes).unwrap();
+        assert!(IntegerElGamal::from_group_parameters(&deserialized).is_some());
+
+        let composite = GroupParameters {
+            modulus: parameters.modulus.clone() + 2,
+            generator: parameters.generator.clone(),
+        };
+        assert!(IntegerElGamal::from_group_parameters(&composite).is_none());
+
+        let other_generator = GroupParameters {
+            modulus: parameters.modulus.clone(),
+            generator: UnsignedInteger::from(9u64),
+        };
+        assert!(other_generator.verify_leaky());
+        let el_gamal_9 = IntegerElGamal::from_group_parameters(&other_generator).unwrap();
+        assert_eq!(other_generator, el_gamal_9.group_parameters());
+    }
+
+    #[test]
+    fn test_with_generator() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80)
+            .with_generator(UnsignedInteger::from(9u64))
+            .unwrap();
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        assert!(pk.verify_leaky());
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(5u64), &mut rng);
+        assert!(ciphertext.ciphertext.is_valid_leaky(&pk));
+        assert_eq!(UnsignedInteger::from(5u64), sk.decrypt(&ciphertext));
+
+        let table = DiscreteLogTable::new(&pk, 16, 16);
+        let exponential = pk.encrypt_exponential(42, &mut rng);
+        assert_eq!(Some(42), sk.decrypt_exponential_leaky(&exponential, &table));
+
+        let signature = sk.sign(b"message", &pk, &mut rng);
+        assert!(pk.verify(b"message", &signature));
+
+        // -1 and 1 only generate subgroups of order at most 2
+        let minus_one = pk.modulus.clone() - 1;
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        assert!(el_gamal.clone().with_generator(minus_one.clone()).is_none());
+        assert!(el_gamal
+            .with_generator(UnsignedInteger::from(1u64))
+            .is_none());
+
+        let mut invalid_pk = pk;
+        invalid_pk.h = minus_one;
+        assert!(!invalid_pk.verify_leaky());
+    }
+
+    #[test]
+    fn test_commit_and_reveal_setup() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let (seed, commitment) =
+            IntegerElGamal::commit_setup(&BitsOfSecurity::ToyParameters, &mut rng);
+        let contribution = [7u8; 32];
+
+        let el_gamal = IntegerElGamal::reveal_setup(&commitment, &seed, &contribution).unwrap();
+        let parameters = el_gamal.group_parameters();
+        assert_eq!(256, parameters.modulus.size_in_bits());
+        assert!(parameters.verify_leaky());
+
+        // Another party that runs the reveal step ends up with the same group
+        let verified = IntegerElGamal::reveal_setup(&commitment, &seed, &contribution).unwrap();
+        assert_eq!(parameters, verified.group_parameters());
+
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(5u64), &mut rng);
+        assert_eq!(UnsignedInteger::from(5u64), sk.decrypt(&ciphertext));
+
+        let other = IntegerElGamal::reveal_setup(&commitment, &seed, &[8u8; 32]).unwrap();
+        assert_ne!(parameters, other.group_parameters());
+
+        let mut wrong_seed = seed.clone();
+        wrong_seed.seed[0] ^= 1;
+        assert!(IntegerElGamal::reveal_setup(&commitment, &wrong_seed, &contribution).is_none());
+    }
 }
@@ -1,8 +1,49 @@
+/// Implementation of the Benaloh cryptosystem, which has a small plaintext space.
+#[cfg(feature = "integer")]
+pub mod benaloh;
+/// Implementation of the Castagnos-Laguillaumie cryptosystem over class groups.
+#[cfg(feature = "integer")]
+pub mod castagnos_laguillaumie;
+/// Class groups of imaginary quadratic orders to instantiate the Castagnos-Laguillaumie
+/// cryptosystem with.
+#[cfg(feature = "integer")]
+pub mod class_group;
+/// Imports of X25519 and Ed25519 secret keys as curve-based ElGamal key pairs.
+#[cfg(feature = "curve")]
+pub mod curve25519_keys;
 /// Implementation of the ElGamal cryptosystem over an elliptic curve.
+#[cfg(feature = "curve")]
 pub mod curve_el_gamal;
+/// Prime-order elliptic curve groups to instantiate `GenericCurveElGamal` with.
+#[cfg(feature = "curve")]
+pub mod curve_group;
+/// Implementation of the DGK cryptosystem, which has a small plaintext space.
+#[cfg(feature = "integer")]
+pub mod dgk;
+/// Encodings of numbers into the plaintext spaces of the integer-based cryptosystems.
+#[cfg(feature = "integer")]
+pub mod encoding;
+/// Implementation of the ElGamal cryptosystem over an arbitrary elliptic curve group.
+#[cfg(feature = "curve")]
+pub mod generic_curve_el_gamal;
+/// Implementation of the Goldwasser-Micali cryptosystem, which encrypts single bits.
+#[cfg(feature = "integer")]
+pub mod goldwasser_micali;
 /// Implementation of the ElGamal cryptosystem over a safe prime group.
+#[cfg(feature = "integer")]
 pub mod integer_el_gamal;
+/// Implementation of the Okamoto-Uchiyama cryptosystem, which works modulo $p^2 q$.
+#[cfg(feature = "integer")]
+pub mod okamoto_uchiyama;
+/// Packing of multiple small values into one Paillier plaintext.
+#[cfg(feature = "integer")]
+pub mod packing;
 /// Implementation of the Paillier cryptosystem.
+#[cfg(feature = "integer")]
 pub mod paillier;
+/// PKCS#3 encodings of ElGamal groups and keys, which are compatible with OpenSSL.
+#[cfg(feature = "integer")]
+pub mod pkcs3;
 /// Implementation of the RSA cryptosystem.
+#[cfg(feature = "integer")]
 pub mod rsa;
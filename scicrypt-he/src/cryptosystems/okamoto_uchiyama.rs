@@ -0,0 +1,349 @@
+//! The Okamoto-Uchiyama cryptosystem is additively homomorphic like Paillier, but works modulo
+//! $n = p^2 q$ instead of $n^2$. Its ciphertexts are therefore as large as the modulus, and
+//! decryption only requires an exponentiation modulo $p^2$. The plaintext space is $\mathbb{Z}_p$
+//! for the secret prime $p$, so plaintexts, including the results of homomorphic operations, must
+//! stay below $2^\text{plaintext\\_bits}$ to decrypt correctly.
+//!
+//! Here is an example of adding two encrypted numbers.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::okamoto_uchiyama::OkamotoUchiyama;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let okamoto_uchiyama = OkamotoUchiyama::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = okamoto_uchiyama.generate_keys(&mut rng);
+//!
+//! let ciphertext_a = public_key.encrypt(&UnsignedInteger::from(1234), &mut rng);
+//! let ciphertext_b = public_key.encrypt(&UnsignedInteger::from(4321), &mut rng);
+//!
+//! assert_eq!(
+//!     UnsignedInteger::from(5555),
+//!     secret_key.decrypt(&(&ciphertext_a + &ciphertext_b))
+//! );
+//! ```
+
+use crate::identifier::Identifiable;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_numbertheory::gen_p2q_modulus;
+use scicrypt_traits::cryptosystems::{
+    Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The Okamoto-Uchiyama cryptosystem.
+#[derive(Copy, Clone)]
+pub struct OkamotoUchiyama {
+    modulus_size: u32,
+    security_level: BitsOfSecurity,
+}
+
+/// Public key for the Okamoto-Uchiyama cryptosystem.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct OkamotoUchiyamaPK {
+    /// Public modulus $n = p^2 q$ for encryption
+    pub n: UnsignedInteger,
+    /// Generator whose order modulo $p^2$ is divisible by $p$, which encodes the plaintext
+    pub g: UnsignedInteger,
+    /// The element $g^n \bmod n$, which encodes the randomness
+    pub h: UnsignedInteger,
+    /// Plaintexts must be smaller than $2^\text{plaintext\\_bits}$, which is smaller than $p$
+    pub plaintext_bits: u32,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for OkamotoUchiyamaPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n)
+            & self.g.ct_eq(&other.g)
+            & self.h.ct_eq(&other.h)
+            & self.plaintext_bits.ct_eq(&other.plaintext_bits)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
+}
+
+/// Decryption key for the Okamoto-Uchiyama cryptosystem.
+pub struct OkamotoUchiyamaSK {
+    p: UnsignedInteger,
+    p_squared: UnsignedInteger,
+    /// The inverse of $L(g^{p - 1} \bmod p^2)$ modulo $p$, where $L(x) = (x - 1) / p$
+    mu: UnsignedInteger,
+}
+
+impl ConstantTimeEq for OkamotoUchiyamaSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.p.ct_eq(&other.p) & self.mu.ct_eq(&other.mu)
+    }
+}
+
+impl Debug for OkamotoUchiyamaSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OkamotoUchiyamaSK")
+            .field("p", &Redacted::new(&self.p, self.p.size_in_bits()))
+            .field("mu", &Redacted::new(&self.mu, self.mu.size_in_bits()))
+            .finish()
+    }
+}
+
+/// Ciphertext of the Okamoto-Uchiyama cryptosystem, which is additively homomorphic.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct OkamotoUchiyamaCiphertext {
+    /// Encrypted message (Ciphertext)
+    pub c: UnsignedInteger,
+}
+
+impl Identifiable for OkamotoUchiyamaCiphertext {}
+
+impl ConstantTimeEq for OkamotoUchiyamaCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c.ct_eq(&other.c)
+    }
+}
+
+impl Associable<OkamotoUchiyamaPK> for OkamotoUchiyamaCiphertext {}
+
+impl AsymmetricCryptosystem for OkamotoUchiyama {
+    type PublicKey = OkamotoUchiyamaPK;
+    type SecretKey = OkamotoUchiyamaSK;
+
+    fn setup(security_param: &BitsOfSecurity) -> Self {
+        OkamotoUchiyama {
+            modulus_size: security_param.to_public_key_bit_length(),
+            security_level: *security_param,
+        }
+    }
+
+    /// Generates a modulus $n = p^2 q$ and a random $g$ such that $g^{p - 1} \bmod p^2$ has order
+    /// $p$, which holds unless $g^{p - 1} \equiv 1 \pmod{p^2}$.
+    fn generate_keys<R: SecureRng>(
+        &self,
+        rng: &mut GeneralRng<R>,
+    ) -> (OkamotoUchiyamaPK, OkamotoUchiyamaSK) {
+        trace_span!(INFO, "generate_keys", modulus_bits = self.modulus_size);
+        let (n, factorization) = gen_p2q_modulus(self.modulus_size, rng);
+        let p = factorization.p.to_rug();
+        let p_squared = p.clone().square();
+        let p_minus_one = p.clone() - 1u32;
+
+        let (g, g_p) = loop {
+            let g = UnsignedInteger::random_below(&n, rng).to_rug();
+            let g_p = rug::Integer::from(g.pow_mod_ref(&p_minus_one, &p_squared).unwrap());
+
+            if g_p != 1 {
+                break (g, g_p);
+            }
+        };
+
+        let mu = ((g_p - 1u32) / &p).invert(&p).unwrap();
+        let n_rug = n.clone().to_rug();
+        let h = rug::Integer::from(g.pow_mod_ref(&n_rug, &n_rug).unwrap());
+
+        (
+            OkamotoUchiyamaPK {
+                n,
+                g: UnsignedInteger::from(g),
+                h: UnsignedInteger::from(h),
+                plaintext_bits: p.significant_bits() - 1,
+                security_level: self.security_level,
+            },
+            OkamotoUchiyamaSK {
+                p: UnsignedInteger::from(p),
+                p_squared: UnsignedInteger::from(p_squared),
+                mu: UnsignedInteger::from(mu),
+            },
+        )
+    }
+}
+
+impl EncryptionKey for OkamotoUchiyamaPK {
+    type Input = UnsignedInteger;
+    type Plaintext = UnsignedInteger;
+    type Ciphertext = OkamotoUchiyamaCiphertext;
+    type Randomness = UnsignedInteger;
+
+    fn encrypt_without_randomness(&self, plaintext: &Self::Plaintext) -> Self::Ciphertext {
+        OkamotoUchiyamaCiphertext {
+            c: self.g.pow_mod(plaintext, &self.n),
+        }
+    }
+
+    fn randomize<R: SecureRng>(
+        &self,
+        ciphertext: Self::Ciphertext,
+        rng: &mut GeneralRng<R>,
+    ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
+        let r = UnsignedInteger::random_below(&self.n, rng);
+
+        self.randomize_with(ciphertext, &r)
+    }
+
+    fn randomize_with(
+        &self,
+        ciphertext: Self::Ciphertext,
+        randomness: &Self::Randomness,
+    ) -> Self::Ciphertext {
+        let randomizer = self.h.pow_mod(randomness, &self.n);
+
+        OkamotoUchiyamaCiphertext {
+            c: (&ciphertext.c * &randomizer) % &self.n,
+        }
+    }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: self.plaintext_bits,
+        }
+    }
+}
+
+impl DecryptionKey<OkamotoUchiyamaPK> for OkamotoUchiyamaSK {
+    /// Decrypts the ciphertext as $L(c^{p - 1} \bmod p^2) \cdot \mu \bmod p$.
+    fn decrypt_raw(
+        &self,
+        _public_key: &OkamotoUchiyamaPK,
+        ciphertext: &OkamotoUchiyamaCiphertext,
+    ) -> UnsignedInteger {
+        trace_span!(DEBUG, "decrypt");
+        let p_minus_one = self.p.clone() - 1;
+
+        let mut inner =
+            (ciphertext.c.clone() % &self.p_squared).pow_mod(&p_minus_one, &self.p_squared);
+        inner -= 1;
+        inner = inner / &self.p;
+        inner = &inner * &self.mu;
+
+        inner % &self.p
+    }
+
+    fn decrypt_identity_raw(
+        &self,
+        public_key: &OkamotoUchiyamaPK,
+        ciphertext: &OkamotoUchiyamaCiphertext,
+    ) -> bool {
+        self.decrypt_raw(public_key, ciphertext).is_zero_leaky()
+    }
+}
+
+/// The plaintexts are only added modulo the secret prime $p$, so subtracting a larger number from a
+/// smaller one yields a result that cannot be interpreted without knowing $p$.
+impl HomomorphicAddition for OkamotoUchiyamaPK {
+    fn add(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
+        OkamotoUchiyamaCiphertext {
+            c: (&ciphertext_a.c * &ciphertext_b.c) % &self.n,
+        }
+    }
+
+    fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
+        OkamotoUchiyamaCiphertext {
+            c: ciphertext.c.pow_mod(input, &self.n),
+        }
+    }
+
+    fn sub(
+        &self,
+        ciphertext_a: &Self::Ciphertext,
+        ciphertext_b: &Self::Ciphertext,
+    ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
+        OkamotoUchiyamaCiphertext {
+            c: (&ciphertext_a.c * &ciphertext_b.c.clone().invert(&self.n).unwrap()) % &self.n,
+        }
+    }
+
+    fn add_constant(
+        &self,
+        ciphertext: &Self::Ciphertext,
+        constant: &Self::Plaintext,
+    ) -> Self::Ciphertext {
+        self.add(ciphertext, &self.encrypt_without_randomness(constant))
+    }
+
+    fn sub_constant(
+        &self,
+        ciphertext: &Self::Ciphertext,
+        constant: &Self::Plaintext,
+    ) -> Self::Ciphertext {
+        self.sub(ciphertext, &self.encrypt_without_randomness(constant))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::okamoto_uchiyama::OkamotoUchiyama;
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let okamoto_uchiyama = OkamotoUchiyama::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = okamoto_uchiyama.generate_keys(&mut rng);
+
+        let largest = UnsignedInteger::from(rug::Integer::from(1) << pk.plaintext_bits) - 1;
+        for plaintext in [UnsignedInteger::from(15u64), largest] {
+            let ciphertext = pk.encrypt(&plaintext, &mut rng);
+            assert_eq!(plaintext, sk.decrypt(&ciphertext));
+        }
+
+        assert!(sk.decrypt_identity(&pk.encrypt(&UnsignedInteger::zero(0), &mut rng)));
+    }
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let okamoto_uchiyama = OkamotoUchiyama::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = okamoto_uchiyama.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt(&UnsignedInteger::from(20u64), &mut rng);
+        let ciphertext_b = pk.encrypt(&UnsignedInteger::from(7u64), &mut rng);
+
+        assert_eq!(
+            UnsignedInteger::from(27u64),
+            sk.decrypt(&(&ciphertext_a + &ciphertext_b))
+        );
+        assert_eq!(
+            UnsignedInteger::from(13u64),
+            sk.decrypt(&(&ciphertext_a - &ciphertext_b))
+        );
+        assert_eq!(
+            UnsignedInteger::from(60u64),
+            sk.decrypt(&(&ciphertext_a * &UnsignedInteger::from(3u64)))
+        );
+        assert_eq!(
+            UnsignedInteger::from(25u64),
+            sk.decrypt(&(&ciphertext_a + &UnsignedInteger::from(5u64)))
+        );
+        assert_eq!(
+            UnsignedInteger::from(15u64),
+            sk.decrypt(&(&ciphertext_a - &UnsignedInteger::from(5u64)))
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Packing of multiple small values into one Paillier plaintext. The plaintext is split into lanes
+//! of `value_bits + spacer_bits` bits, each of which holds one value in its lower `value_bits`
+//! bits. Adding two packed ciphertexts adds the values lane by lane, and the spacer bits absorb
+//! the carries, so that up to $2^\text{spacer\_bits}$ packed values can be summed before a lane
+//! would overflow into the next. Packed ciphertexts keep track of how many values they sum, and
+//! refuse additions that would overflow.
+//!
+//! With a 2048-bit modulus and 32-bit values with 16 spacer bits, one ciphertext holds 42 values,
+//! which reduces the number of ciphertexts and the bandwidth of aggregation by the same factor.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::cryptosystems::packing::PaillierPacking;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+//! let packing = PaillierPacking::new(&pk, 16, 8);
+//!
+//! let a = packing.encrypt(&pk, &[1, 2, 3], &mut rng).unwrap();
+//! let b = packing.encrypt(&pk, &[10, 20, 30, 40], &mut rng).unwrap();
+//! let sum = packing.add(&pk, &a, &b).unwrap();
+//!
+//! assert_eq!(vec![11, 22, 33, 40], packing.decrypt(&pk, &sk, &sum));
+//! ```
+
+use crate::cryptosystems::paillier::{PaillierCiphertext, PaillierPK, PaillierSK};
+use crate::identifier::Identifiable;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::encoding::EncodingError;
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use subtle::{Choice, ConstantTimeEq};
+
+/// The layout of values in packed Paillier plaintexts for a specific public key.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct PaillierPacking {
+    value_bits: u32,
+    spacer_bits: u32,
+    lanes: usize,
+}
+
+/// A Paillier ciphertext that holds multiple packed values.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct PackedCiphertext {
+    /// The ciphertext of the packed plaintext
+    pub ciphertext: PaillierCiphertext,
+    /// The number of lanes that are in use
+    pub len: usize,
+    /// The number of encrypted vectors that were summed into this ciphertext
+    pub summands: u64,
+}
+
+impl Identifiable for PackedCiphertext {}
+
+impl ConstantTimeEq for PackedCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.ciphertext.ct_eq(&other.ciphertext)
+            & (self.len as u64).ct_eq(&(other.len as u64))
+            & self.summands.ct_eq(&other.summands)
+    }
+}
+
+impl PaillierPacking {
+    /// Creates a layout for values of `value_bits` bits, which can be summed $2^\text{spacer\_bits}$
+    /// times. Each lane may take at most 64 bits in total. As many lanes are used as fit below the
+    /// modulus of `public_key`.
+    pub fn new(public_key: &PaillierPK, value_bits: u32, spacer_bits: u32) -> Self {
+        assert!(value_bits > 0, "the values must have at least one bit");
+        assert!(
+            value_bits + spacer_bits <= 64,
+            "a lane must fit in 64 bits, including its spacer bits"
+        );
+
+        let lanes = ((public_key.n.size_in_bits() - 1) / (value_bits + spacer_bits)) as usize;
+        assert!(lanes > 0, "the modulus is too small for a single lane");
+
+        PaillierPacking {
+            value_bits,
+            spacer_bits,
+            lanes,
+        }
+    }
+
+    /// Returns the maximum number of values that fit in one ciphertext.
+    pub fn lanes(&self) -> usize {
+        self.lanes
+    }
+
+    /// Packs `values` into a plaintext, or returns an error if there are more values than lanes or
+    /// if a value has more than `value_bits` bits.
+    pub fn pack(
+        &self,
+        public_key: &PaillierPK,
+        values: &[u64],
+    ) -> Result<UnsignedInteger, EncodingError> {
+        if values.len() > self.lanes
+            || values
+                .iter()
+                .any(|&value| self.value_bits < 64 && value >> self.value_bits != 0)
+        {
+            return Err(EncodingError);
+        }
+
+        let size_in_bits = public_key.n.size_in_bits();
+        let mut limbs = vec![0u64; size_in_bits.div_ceil(64) as usize];
+
+        for (lane, &value) in values.iter().enumerate() {
+            let offset = lane * self.lane_bits() as usize;
+            let (limb, shift) = (offset / 64, offset % 64);
+
+            limbs[limb] |= value << shift;
+            if shift + self.lane_bits() as usize > 64 {
+                limbs[limb + 1] |= value >> (64 - shift);
+            }
+        }
+
+        let bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+        Ok(UnsignedInteger::from_le_bytes(&bytes, size_in_bits))
+    }
+
+    /// Unpacks the values of the first `len` lanes of `plaintext`, including any carries in their
+    /// spacer bits.
+    pub fn unpack(&self, plaintext: &UnsignedInteger, len: usize) -> Vec<u64> {
+        let mut limbs: Vec<u64> = plaintext
+            .to_le_bytes()
+            .chunks(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        limbs.resize((len * self.lane_bits() as usize).div_ceil(64) + 1, 0);
+
+        let mask = u64::MAX >> (64 - self.lane_bits());
+
+        (0..len)
+            .map(|lane| {
+                let offset = lane * self.lane_bits() as usize;
+                let (limb, shift) = (offset / 64, offset % 64);
+
+                let mut value = limbs[limb] >> shift;
+                if shift + self.lane_bits() as usize > 64 {
+                    value |= limbs[limb + 1] << (64 - shift);
+                }
+
+                value & mask
+            })
+            .collect()
+    }
+
+    /// Packs and encrypts `values`, or returns an error if they do not fit.
+    pub fn encrypt<R: SecureRng>(
+        &self,
+        public_key: &PaillierPK,
+        values: &[u64],
+        rng: &mut GeneralRng<R>,
+    ) -> Result<PackedCiphertext, EncodingError> {
+        Ok(PackedCiphertext {
+            ciphertext: public_key.encrypt_raw(&self.pack(public_key, values)?, rng),
+            len: values.len(),
+            summands: 1,
+        })
+    }
+
+    /// Adds two packed ciphertexts lane by lane. Returns an error if the sums could overflow into
+    /// the next lane, i.e. if the result would sum more than $2^\text{spacer\_bits}$ vectors.
+    pub fn add(
+        &self,
+        public_key: &PaillierPK,
+        a: &PackedCiphertext,
+        b: &PackedCiphertext,
+    ) -> Result<PackedCiphertext, EncodingError> {
+        let summands = a.summands.checked_add(b.summands).ok_or(EncodingError)?;
+        if self.spacer_bits < 64 && summands > 1 << self.spacer_bits {
+            return Err(EncodingError);
+        }
+
+        Ok(PackedCiphertext {
+            ciphertext: public_key.add(&a.ciphertext, &b.ciphertext),
+            len: a.len.max(b.len),
+            summands,
+        })
+    }
+
+    /// Decrypts and unpacks the values of `ciphertext`.
+    pub fn decrypt(
+        &self,
+        public_key: &PaillierPK,
+        secret_key: &PaillierSK,
+        ciphertext: &PackedCiphertext,
+    ) -> Vec<u64> {
+        self.unpack(
+            &secret_key.decrypt_raw(public_key, &ciphertext.ciphertext),
+            ciphertext.len,
+        )
+    }
+
+    fn lane_bits(&self) -> u32 {
+        self.value_bits + self.spacer_bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::packing::PaillierPacking;
+    use crate::cryptosystems::paillier::Paillier;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::encoding::EncodingError;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_pack_unpack() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        for (value_bits, spacer_bits) in [(1, 0), (13, 7), (32, 32), (60, 4), (64, 0)] {
+            let packing = PaillierPacking::new(&pk, value_bits, spacer_bits);
+            let max = u64::MAX >> (64 - value_bits);
+            let values: Vec<u64> = (0..packing.lanes() as u64)
+                .map(|i| if i % 2 == 0 { max } else { i & max })
+                .collect();
+
+            let plaintext = packing.pack(&pk, &values).unwrap();
+
+            assert_eq!(values, packing.unpack(&plaintext, values.len()));
+        }
+    }
+
+    #[test]
+    fn test_pack_rejects_values_that_do_not_fit() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let packing = PaillierPacking::new(&pk, 8, 8);
+
+        assert_eq!(Err(EncodingError), packing.pack(&pk, &[256]));
+        assert_eq!(
+            Err(EncodingError),
+            packing.pack(&pk, &vec![0; packing.lanes() + 1])
+        );
+    }
+
+    #[test]
+    fn test_add_respects_lanes() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let packing = PaillierPacking::new(&pk, 8, 2);
+
+        let values: Vec<u64> = vec![255; packing.lanes()];
+        let ciphertext = packing.encrypt(&pk, &values, &mut rng).unwrap();
+
+        let mut sum = ciphertext.clone();
+        for _ in 0..3 {
+            sum = packing.add(&pk, &sum, &ciphertext).unwrap();
+        }
+
+        assert_eq!(
+            vec![4 * 255; packing.lanes()],
+            packing.decrypt(&pk, &sk, &sum)
+        );
+        assert_eq!(Err(EncodingError), packing.add(&pk, &sum, &ciphertext));
+    }
+}
@@ -12,16 +12,22 @@
 //! let (public_key, secret_key) = paillier.generate_keys(&mut rng);
 //! let ciphertext = public_key.encrypt(&UnsignedInteger::from(5), &mut rng);
 //! ```
+use crate::exponentiation::ExpConfig;
+use crate::identifier::Identifiable;
 use scicrypt_bigint::UnsignedInteger;
 use scicrypt_numbertheory::gen_rsa_modulus;
 use scicrypt_traits::cryptosystems::{
     Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
 };
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
 use scicrypt_traits::homomorphic::HomomorphicAddition;
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConstantTimeEq};
 
 // FIXME: Consider adding a Paillier cryptosystem with CustomGen (custom generator)
 
@@ -29,12 +35,21 @@ use serde::{Deserialize, Serialize};
 #[derive(Copy, Clone)]
 pub struct Paillier {
     modulus_size: u32,
+    security_level: BitsOfSecurity,
 }
 
 /// A minimal version of the public key for Paillier, which can be expanded to be more computationally efficient.
 pub struct MinimalPaillierPK {
     /// Public modulus n for encryption
     pub n: UnsignedInteger,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for MinimalPaillierPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n) & Choice::from(u8::from(self.security_level == other.security_level))
+    }
 }
 
 impl MinimalPaillierPK {
@@ -45,6 +60,7 @@ impl MinimalPaillierPK {
         PaillierPK {
             n: self.n,
             n_squared,
+            security_level: self.security_level,
         }
     }
 }
@@ -56,12 +72,42 @@ pub struct PaillierPK {
     pub n: UnsignedInteger,
     /// The modulus squared, i.e. n^2
     pub n_squared: UnsignedInteger,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for PaillierPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n)
+            & self.n_squared.ct_eq(&other.n_squared)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
 }
 
 impl PaillierPK {
     /// Minimizes the public key so that only the essential information is kept. This is useful if the public key must be transmitted or stored somewhere.
     pub fn minimize(&self) -> MinimalPaillierPK {
-        MinimalPaillierPK { n: self.n.clone() }
+        MinimalPaillierPK {
+            n: self.n.clone(),
+            security_level: self.security_level,
+        }
+    }
+
+    /// Encrypts `plaintext` like `encrypt_raw`, but computes the randomizer $r^n$ as configured by
+    /// `config`. The exponent $n$ is public, so `leaky_public_exponents` speeds this up
+    /// considerably, at the cost of a running time that may depend on the secret $r$.
+    pub fn encrypt_raw_with_config<R: SecureRng>(
+        &self,
+        plaintext: &UnsignedInteger,
+        config: &ExpConfig,
+        rng: &mut GeneralRng<R>,
+    ) -> PaillierCiphertext {
+        let r = UnsignedInteger::random_below(&self.n, rng);
+        let randomizer = config.pow_public_exponent(&r, &self.n, &self.n_squared);
+
+        PaillierCiphertext {
+            c: (&self.encrypt_without_randomness(plaintext).c * &randomizer) % &self.n_squared,
+        }
     }
 }
 
@@ -71,14 +117,51 @@ pub struct PaillierSK {
     mu: UnsignedInteger,
 }
 
+impl ConstantTimeEq for PaillierSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.lambda.ct_eq(&other.lambda) & self.mu.ct_eq(&other.mu)
+    }
+}
+
+impl Debug for PaillierSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaillierSK")
+            .field(
+                "lambda",
+                &Redacted::new(&self.lambda, self.lambda.size_in_bits()),
+            )
+            .field("mu", &Redacted::new(&self.mu, self.mu.size_in_bits()))
+            .finish()
+    }
+}
+
 /// Ciphertext of the Paillier cryptosystem, which is additively homomorphic.
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
 pub struct PaillierCiphertext {
     /// Encrypted message (Ciphertext)
     pub c: UnsignedInteger,
 }
 
-impl Associable<PaillierPK> for PaillierCiphertext {}
+impl Identifiable for PaillierCiphertext {}
+
+impl ConstantTimeEq for PaillierCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c.ct_eq(&other.c)
+    }
+}
+
+impl Associable<PaillierPK> for PaillierCiphertext {
+    /// Returns true if the ciphertext is an element of $\mathbb{Z}_{n^2}^*$, i.e. it lies below
+    /// $n^2$ and is coprime to $n$. A ciphertext that shares a factor with $n$ reveals the
+    /// factorization of $n$ during decryption.
+    fn is_valid_leaky(&self, public_key: &PaillierPK) -> bool {
+        !self.c.is_zero_leaky()
+            && self.c.leak() < public_key.n_squared.leak()
+            && (self.c.clone() % &public_key.n)
+                .invert_leaky(&public_key.n)
+                .is_some()
+    }
+}
 
 impl AsymmetricCryptosystem for Paillier {
     type PublicKey = PaillierPK;
@@ -87,6 +170,7 @@ impl AsymmetricCryptosystem for Paillier {
     fn setup(security_param: &BitsOfSecurity) -> Self {
         Paillier {
             modulus_size: security_param.to_public_key_bit_length(),
+            security_level: *security_param,
         }
     }
 
@@ -102,6 +186,7 @@ impl AsymmetricCryptosystem for Paillier {
     /// let (public_key, secret_key) = paillier.generate_keys(&mut rng);
     /// ```
     fn generate_keys<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> (PaillierPK, PaillierSK) {
+        trace_span!(INFO, "generate_keys", modulus_bits = self.modulus_size);
         let (n, p, q) = gen_rsa_modulus(self.modulus_size, rng);
 
         // The generator g is implicit: n + 1
@@ -109,7 +194,12 @@ impl AsymmetricCryptosystem for Paillier {
         let lambda = &(p - 1) * &(q - 1);
         let mu = lambda.clone().invert(&n).unwrap();
 
-        (MinimalPaillierPK { n }.expand(), PaillierSK { lambda, mu })
+        let public_key = MinimalPaillierPK {
+            n,
+            security_level: self.security_level,
+        };
+
+        (public_key.expand(), PaillierSK { lambda, mu })
     }
 }
 
@@ -131,6 +221,7 @@ impl EncryptionKey for PaillierPK {
         ciphertext: Self::Ciphertext,
         rng: &mut GeneralRng<R>,
     ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "randomize");
         // r must be coprime with n_squared but this only fails with probability 2^(1 - n_in_bits)
         // 0 also only occurs with extremely low probability, so we can simply sample randomly s.t. 0 < r < n
         let r = UnsignedInteger::random_below(&self.n, rng);
@@ -149,6 +240,17 @@ impl EncryptionKey for PaillierPK {
             c: (&ciphertext.c * &randomizer) % &self.n_squared,
         }
     }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: self.n.significant_bits_leaky() - 1,
+        }
+    }
 }
 
 impl DecryptionKey<PaillierPK> for PaillierSK {
@@ -172,6 +274,7 @@ impl DecryptionKey<PaillierPK> for PaillierSK {
         public_key: &PaillierPK,
         ciphertext: &PaillierCiphertext,
     ) -> UnsignedInteger {
+        trace_span!(DEBUG, "decrypt");
         let mut inner = ciphertext.c.pow_mod(&self.lambda, &public_key.n_squared);
         inner -= 1;
         inner = inner / &public_key.n;
@@ -196,12 +299,14 @@ impl HomomorphicAddition for PaillierPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
         PaillierCiphertext {
             c: (&ciphertext_a.c * &ciphertext_b.c) % &self.n_squared,
         }
     }
 
     fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
         PaillierCiphertext {
             c: ciphertext.c.pow_mod(input, &self.n_squared),
         }
@@ -212,6 +317,7 @@ impl HomomorphicAddition for PaillierPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
         PaillierCiphertext {
             c: (&ciphertext_a.c * &ciphertext_b.c.clone().invert(&self.n_squared).unwrap())
                 % &self.n_squared,
@@ -246,14 +352,17 @@ impl HomomorphicAddition for PaillierPK {
 
 #[cfg(test)]
 mod tests {
-    use crate::cryptosystems::paillier::Paillier;
+    use crate::cryptosystems::paillier::{Paillier, PaillierCiphertext};
+    use crate::exponentiation::ExpConfig;
     use rand_core::OsRng;
     use scicrypt_bigint::UnsignedInteger;
     use scicrypt_traits::cryptosystems::{
         Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
     };
+    use scicrypt_traits::homomorphic::IncompatibleKeys;
     use scicrypt_traits::randomness::GeneralRng;
     use scicrypt_traits::security::BitsOfSecurity;
+    use std::sync::Arc;
 
     #[test]
     fn test_encrypt_decrypt() {
@@ -267,6 +376,28 @@ mod tests {
         assert_eq!(UnsignedInteger::from(15u64), sk.decrypt(&ciphertext));
     }
 
+    #[test]
+    fn test_encrypt_with_config() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = paillier.generate_keys(&mut rng);
+
+        for leaky_public_exponents in [false, true] {
+            let config = ExpConfig {
+                leaky_public_exponents,
+                ..ExpConfig::default()
+            };
+            let ciphertext =
+                pk.encrypt_raw_with_config(&UnsignedInteger::from(15u64), &config, &mut rng);
+
+            assert_eq!(
+                UnsignedInteger::from(15u64),
+                sk.decrypt_raw(&pk, &ciphertext)
+            );
+        }
+    }
+
     #[test]
     fn test_encrypt_decrypt_identity() {
         let mut rng = GeneralRng::new(OsRng);
@@ -279,6 +410,32 @@ mod tests {
         assert!(sk.decrypt_identity(&ciphertext));
     }
 
+    #[test]
+    fn test_decrypt_checked() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = paillier.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(15u64), &mut rng);
+        assert_eq!(
+            UnsignedInteger::from(15u64),
+            sk.decrypt_checked(&ciphertext).unwrap()
+        );
+
+        let multiple_of_n = &pk.n * &UnsignedInteger::from(3u64);
+        for c in [
+            UnsignedInteger::zero(1),
+            multiple_of_n,
+            pk.n_squared.clone(),
+        ] {
+            let invalid = PaillierCiphertext { c }.associate(&pk);
+
+            assert!(!invalid.ciphertext.is_valid_leaky(&pk));
+            assert!(sk.decrypt_checked(&invalid).is_err());
+        }
+    }
+
     #[test]
     fn test_homomorphic_add() {
         let mut rng = GeneralRng::new(OsRng);
@@ -320,6 +477,37 @@ mod tests {
         assert_eq!(UnsignedInteger::from(144u64), sk.decrypt(&ciphertext_twice));
     }
 
+    #[test]
+    fn test_owned_operands() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = paillier.generate_keys(&mut rng);
+        let pk = Arc::new(pk);
+
+        let ciphertext_a = pk.encrypt(&UnsignedInteger::from(7u64), &mut rng);
+        let ciphertext_b = pk.encrypt(&UnsignedInteger::from(5u64), &mut rng);
+
+        let sum = &ciphertext_a + ciphertext_b.clone();
+        let difference = ciphertext_a.clone() - ciphertext_b.clone();
+        let scaled = ciphertext_a.clone() * UnsignedInteger::from(3u64);
+        let shifted = &ciphertext_b + UnsignedInteger::from(10u64);
+
+        assert_eq!(UnsignedInteger::from(12u64), sk.decrypt(&sum));
+        assert_eq!(UnsignedInteger::from(2u64), sk.decrypt(&difference));
+        assert_eq!(UnsignedInteger::from(21u64), sk.decrypt(&scaled));
+        assert_eq!(UnsignedInteger::from(15u64), sk.decrypt(&shifted));
+
+        let mut owned = ciphertext_a.into_owned(pk.clone());
+        owned += ciphertext_b.into_owned(pk.clone());
+        let owned = (owned * UnsignedInteger::from(2u64)) - UnsignedInteger::from(4u64);
+
+        assert_eq!(
+            UnsignedInteger::from(20u64),
+            sk.decrypt(&owned.to_associated())
+        );
+    }
+
     #[test]
     fn test_homomorphic_add_constant() {
         let mut rng = GeneralRng::new(OsRng);
@@ -363,4 +551,93 @@ mod tests {
             sk.decrypt(&ciphertext_randomized.associate(&pk))
         );
     }
+
+    #[test]
+    fn test_debug_redacts_secret_key() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (_, sk) = paillier.generate_keys(&mut rng);
+
+        let formatted = format!("{sk:?}");
+
+        assert!(formatted.contains("<redacted: "));
+        assert!(!formatted.contains(&sk.lambda.to_string()));
+        assert!(!formatted.contains(&sk.mu.to_string()));
+    }
+
+    #[test]
+    fn test_security_level() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, _) = paillier.generate_keys(&mut rng);
+
+        assert_eq!(BitsOfSecurity::ToyParameters, pk.security_level());
+        pk.assert_security_at_least(&BitsOfSecurity::ToyParameters);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least AES128 is required")]
+    fn test_assert_security_at_least_refuses_toy_key() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, _) = paillier.generate_keys(&mut rng);
+
+        pk.assert_security_at_least(&BitsOfSecurity::AES128);
+    }
+
+    #[test]
+    fn test_security_level_is_recorded_at_setup() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let level = BitsOfSecurity::Custom { pk_bits: 256 };
+        let (pk, _) = Paillier::setup(&level).generate_keys(&mut rng);
+
+        assert_eq!(level, pk.security_level());
+        assert_eq!(level, pk.minimize().expand().security_level());
+    }
+
+    #[test]
+    fn test_refuse_mixing_security_levels() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        // Both moduli have the same size, but the keys were generated for different levels
+        let (toy_pk, _) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let (pk, _) =
+            Paillier::setup(&BitsOfSecurity::Custom { pk_bits: 256 }).generate_keys(&mut rng);
+
+        let toy_ciphertext = toy_pk.encrypt(&UnsignedInteger::from(1u64), &mut rng);
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(2u64), &mut rng);
+
+        assert_eq!(
+            Some(IncompatibleKeys),
+            ciphertext.try_add(&toy_ciphertext).err()
+        );
+        assert!(ciphertext.try_add(&ciphertext).is_ok());
+    }
+
+    #[test]
+    fn test_refuse_mixing_keys() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        // Both keys are generated for the same level, but independently of each other
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk_a, _) = paillier.generate_keys(&mut rng);
+        let (pk_b, _) = paillier.generate_keys(&mut rng);
+
+        let ciphertext_a = pk_a.encrypt(&UnsignedInteger::from(1u64), &mut rng);
+        let ciphertext_b = pk_b.encrypt(&UnsignedInteger::from(2u64), &mut rng);
+
+        assert_eq!(pk_a.security_level(), pk_b.security_level());
+        assert_eq!(
+            Some(IncompatibleKeys),
+            ciphertext_a.try_add(&ciphertext_b).err()
+        );
+        assert_eq!(
+            Some(IncompatibleKeys),
+            ciphertext_a.try_sub(&ciphertext_b).err()
+        );
+    }
 }
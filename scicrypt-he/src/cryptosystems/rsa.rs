@@ -1,18 +1,28 @@
+use crate::identifier::Identifiable;
 use scicrypt_bigint::UnsignedInteger;
 use scicrypt_numbertheory::gen_rsa_modulus;
 use scicrypt_traits::cryptosystems::{
     Associable, AsymmetricCryptosystem, DecryptionKey, EncryptionKey, SigningKey, VerificationKey,
 };
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
 use scicrypt_traits::homomorphic::HomomorphicMultiplication;
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::{Debug, Formatter};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// The length of a SHA-256 hash in bytes, which OAEP uses for the label hash and the seed.
+const HASH_LENGTH: usize = 32;
 
 /// The RSA cryptosystem.
 #[derive(Copy, Clone)]
 pub struct Rsa {
     modulus_size: u32,
+    security_level: BitsOfSecurity,
 }
 
 /// Public key for the RSA cryptosystem.
@@ -22,6 +32,16 @@ pub struct RsaPK {
     pub n: UnsignedInteger,
     /// Public exponentation factor
     pub e: UnsignedInteger,
+    /// The level of security that this key was generated for
+    pub security_level: BitsOfSecurity,
+}
+
+impl ConstantTimeEq for RsaPK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.n.ct_eq(&other.n)
+            & self.e.ct_eq(&other.e)
+            & Choice::from(u8::from(self.security_level == other.security_level))
+    }
 }
 
 /// Decryption key for RSA
@@ -29,13 +49,35 @@ pub struct RsaSK {
     d: UnsignedInteger,
 }
 
+impl ConstantTimeEq for RsaSK {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.d.ct_eq(&other.d)
+    }
+}
+
+impl Debug for RsaSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RsaSK")
+            .field("d", &Redacted::new(&self.d, self.d.size_in_bits()))
+            .finish()
+    }
+}
+
 /// Ciphertext of the RSA cryptosystem, which is multiplicatively homomorphic.
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
 pub struct RsaCiphertext {
     /// Ciphertext as an Integer
     pub c: UnsignedInteger,
 }
 
+impl Identifiable for RsaCiphertext {}
+
+impl ConstantTimeEq for RsaCiphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c.ct_eq(&other.c)
+    }
+}
+
 impl Associable<RsaPK> for RsaCiphertext {}
 
 impl AsymmetricCryptosystem for Rsa {
@@ -45,10 +87,12 @@ impl AsymmetricCryptosystem for Rsa {
     fn setup(security_param: &BitsOfSecurity) -> Self {
         Rsa {
             modulus_size: security_param.to_public_key_bit_length(),
+            security_level: *security_param,
         }
     }
 
     fn generate_keys<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> (RsaPK, RsaSK) {
+        trace_span!(INFO, "generate_keys", modulus_bits = self.modulus_size);
         let (n, p, q) = gen_rsa_modulus(self.modulus_size, rng);
 
         // TODO: Is this the right choice?
@@ -60,7 +104,13 @@ impl AsymmetricCryptosystem for Rsa {
             .invert_leaky(&lambda)
             .expect("e should always be invertible mod lambda.");
 
-        (RsaPK { n, e }, RsaSK { d })
+        let public_key = RsaPK {
+            n,
+            e,
+            security_level: self.security_level,
+        };
+
+        (public_key, RsaSK { d })
     }
 }
 
@@ -75,6 +125,7 @@ impl EncryptionKey for RsaPK {
         plaintext: &UnsignedInteger,
         _rng: &mut GeneralRng<R>,
     ) -> Self::Ciphertext {
+        trace_span!(DEBUG, "encrypt");
         self.encrypt_without_randomness(plaintext)
     }
 
@@ -99,10 +150,22 @@ impl EncryptionKey for RsaPK {
     ) -> Self::Ciphertext {
         panic!("Not possible to randomize Rsa ciphertext")
     }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Multiplication,
+            capacity_bits: self.n.significant_bits_leaky() - 1,
+        }
+    }
 }
 
 impl DecryptionKey<RsaPK> for RsaSK {
     fn decrypt_raw(&self, public_key: &RsaPK, ciphertext: &RsaCiphertext) -> UnsignedInteger {
+        trace_span!(DEBUG, "decrypt");
         ciphertext.c.pow_mod(&self.d, &public_key.n)
     }
 
@@ -122,17 +185,147 @@ impl HomomorphicMultiplication for RsaPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul");
         RsaCiphertext {
             c: (&ciphertext_a.c * &ciphertext_b.c) % &self.n,
         }
     }
 
     fn pow(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "pow");
         RsaCiphertext {
             c: ciphertext.c.pow_mod(input, &self.n),
         }
     }
+
+    fn pow_leaky(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "pow_leaky");
+        RsaCiphertext {
+            c: ciphertext.c.pow_mod_leaky(input, &self.n),
+        }
+    }
+
+    fn invert(&self, ciphertext: &Self::Ciphertext) -> Option<Self::Ciphertext> {
+        trace_span!(TRACE, "invert");
+        Some(RsaCiphertext {
+            c: ciphertext.c.clone().invert(&self.n)?,
+        })
+    }
+}
+/// Error returned by OAEP encryption and decryption.
+#[derive(PartialEq, Eq, Debug)]
+pub enum OaepError {
+    /// The message is longer than the modulus allows, which is the byte length of the modulus
+    /// minus 66 bytes. Moduli of less than 528 bits cannot be used with OAEP at all.
+    MessageTooLong,
+    /// The ciphertext is not a valid OAEP encryption under this key and label. The cause is
+    /// deliberately not specified, as distinguishing causes enables padding-oracle attacks.
+    DecryptionError,
+}
+
+impl RsaPK {
+    fn modulus_byte_length(&self) -> usize {
+        self.n.size_in_bits().div_ceil(8) as usize
+    }
+
+    /// Encrypts `message` using RSA-OAEP as specified in RFC 8017, with SHA-256 as the hash
+    /// function and for MGF1. The optional `label` is bound to the ciphertext and must be given
+    /// again upon decryption. Unlike raw RSA, OAEP ciphertexts are randomized and not homomorphic.
+    pub fn encrypt_oaep<R: SecureRng>(
+        &self,
+        message: &[u8],
+        label: &[u8],
+        rng: &mut GeneralRng<R>,
+    ) -> Result<RsaCiphertext, OaepError> {
+        let k = self.modulus_byte_length();
+        if k < 2 * HASH_LENGTH + 2 || message.len() > k - 2 * HASH_LENGTH - 2 {
+            return Err(OaepError::MessageTooLong);
+        }
+
+        // DB = lHash || PS || 0x01 || M
+        let mut data_block = Sha256::digest(label).to_vec();
+        data_block.resize(k - HASH_LENGTH - 1 - message.len() - 1, 0);
+        data_block.push(0x01);
+        data_block.extend_from_slice(message);
+
+        let mut seed = [0u8; HASH_LENGTH];
+        rng.rng().fill_bytes(&mut seed);
+
+        xor_mask(&mut data_block, &seed);
+        xor_mask(&mut seed, &data_block);
+
+        // EM = 0x00 || maskedSeed || maskedDB, converted to a little-endian integer
+        let mut encoded: Vec<u8> = std::iter::once(0u8).chain(seed).chain(data_block).collect();
+        encoded.reverse();
+
+        Ok(
+            self.encrypt_without_randomness(&UnsignedInteger::from_le_bytes(
+                &encoded,
+                self.n.size_in_bits(),
+            )),
+        )
+    }
+}
+
+impl RsaSK {
+    /// Decrypts a ciphertext that was created using `RsaPK::encrypt_oaep` with the same `label`.
+    /// The padding is checked without branching on secret data.
+    pub fn decrypt_oaep(
+        &self,
+        public_key: &RsaPK,
+        ciphertext: &RsaCiphertext,
+        label: &[u8],
+    ) -> Result<Vec<u8>, OaepError> {
+        let k = public_key.modulus_byte_length();
+        if k < 2 * HASH_LENGTH + 2 {
+            return Err(OaepError::DecryptionError);
+        }
+
+        let mut encoded = self.decrypt_raw(public_key, ciphertext).to_le_bytes();
+        encoded.resize(k, 0);
+        encoded.reverse();
+
+        let (first_byte, rest) = encoded.split_at_mut(1);
+        let (seed, data_block) = rest.split_at_mut(HASH_LENGTH);
+        xor_mask(seed, data_block);
+        xor_mask(data_block, seed);
+
+        let label_hash = Sha256::digest(label);
+        let mut valid = first_byte[0].ct_eq(&0) & data_block[..HASH_LENGTH].ct_eq(&label_hash);
+
+        // Find the 0x01 separator after the zero padding, visiting every byte
+        let mut found_separator = subtle::Choice::from(0);
+        let mut message_start = 0u32;
+        for (i, &byte) in data_block.iter().enumerate().skip(HASH_LENGTH) {
+            let is_separator = !found_separator & byte.ct_eq(&1);
+            message_start.conditional_assign(&(i as u32 + 1), is_separator);
+            valid &= found_separator | byte.ct_eq(&0) | byte.ct_eq(&1);
+            found_separator |= is_separator;
+        }
+        valid &= found_separator;
+
+        if bool::from(valid) {
+            Ok(data_block[message_start as usize..].to_vec())
+        } else {
+            Err(OaepError::DecryptionError)
+        }
+    }
+}
+
+/// XORs `target` with the MGF1 mask generated from `seed` using SHA-256.
+fn xor_mask(target: &mut [u8], seed: &[u8]) {
+    for (counter, chunk) in target.chunks_mut(HASH_LENGTH).enumerate() {
+        let mask = Sha256::new()
+            .chain_update(seed)
+            .chain_update((counter as u32).to_be_bytes())
+            .finalize();
+
+        for (byte, mask_byte) in chunk.iter_mut().zip(mask) {
+            *byte ^= mask_byte;
+        }
+    }
 }
+
 /// Signature of the RSA cryptosystem
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct RsaSignature {
@@ -164,7 +357,7 @@ impl SigningKey<RsaPK> for RsaSK {
 
 #[cfg(test)]
 mod tests {
-    use crate::cryptosystems::rsa::Rsa;
+    use crate::cryptosystems::rsa::{OaepError, Rsa};
     use rand_core::OsRng;
     use scicrypt_bigint::UnsignedInteger;
     use scicrypt_traits::cryptosystems::{
@@ -225,6 +418,30 @@ mod tests {
             UnsignedInteger::from(6561u64),
             sk.decrypt(&ciphertext_twice)
         );
+        assert_eq!(
+            ciphertext_twice,
+            ciphertext.pow_leaky(&UnsignedInteger::from(4u64))
+        );
+    }
+
+    #[test]
+    fn test_homomorphic_negative_pow() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let rsa = Rsa::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = rsa.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt(&UnsignedInteger::from(9u64), &mut rng);
+        let ciphertext_inverse = ciphertext
+            .pow_signed(&UnsignedInteger::from(4u64), true)
+            .unwrap();
+        let ciphertext_one = &ciphertext_inverse * &ciphertext.pow(&UnsignedInteger::from(4u64));
+
+        assert_eq!(UnsignedInteger::from(1u64), sk.decrypt(&ciphertext_one));
+        assert_eq!(
+            UnsignedInteger::from(9u64),
+            sk.decrypt(&ciphertext.invert().unwrap().invert().unwrap())
+        );
     }
 
     #[test]
@@ -252,4 +469,51 @@ mod tests {
 
         assert!(!pk.verify(&signature, &UnsignedInteger::from(11u64)));
     }
+
+    #[test]
+    fn test_oaep_encrypt_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let rsa = Rsa::setup(&BitsOfSecurity::Custom { pk_bits: 1024 });
+        let (pk, sk) = rsa.generate_keys(&mut rng);
+
+        let ciphertext_a = pk.encrypt_oaep(b"attack at dawn", b"", &mut rng).unwrap();
+        let ciphertext_b = pk.encrypt_oaep(b"attack at dawn", b"", &mut rng).unwrap();
+        assert_ne!(ciphertext_a, ciphertext_b);
+
+        assert_eq!(
+            Ok(b"attack at dawn".to_vec()),
+            sk.decrypt_oaep(&pk, &ciphertext_a, b"")
+        );
+
+        let empty = pk.encrypt_oaep(b"", b"", &mut rng).unwrap();
+        assert_eq!(Ok(vec![]), sk.decrypt_oaep(&pk, &empty, b""));
+    }
+
+    #[test]
+    fn test_oaep_rejects_invalid_ciphertexts() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let rsa = Rsa::setup(&BitsOfSecurity::Custom { pk_bits: 1024 });
+        let (pk, sk) = rsa.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_oaep(b"message", b"label", &mut rng).unwrap();
+        assert_eq!(
+            Err(OaepError::DecryptionError),
+            sk.decrypt_oaep(&pk, &ciphertext, b"other label")
+        );
+
+        // Raw RSA ciphertexts are not valid OAEP ciphertexts
+        let raw = pk.encrypt_raw(&UnsignedInteger::from(42u64), &mut rng);
+        assert_eq!(
+            Err(OaepError::DecryptionError),
+            sk.decrypt_oaep(&pk, &raw, b"")
+        );
+
+        let too_long = vec![0u8; pk.n.size_in_bits() as usize / 8];
+        assert_eq!(
+            Err(OaepError::MessageTooLong),
+            pk.encrypt_oaep(&too_long, b"", &mut rng)
+        );
+    }
 }
@@ -0,0 +1,351 @@
+//! Differential privacy for encrypted aggregates. Before an aggregate is decrypted, the aggregator
+//! homomorphically adds encrypted noise from a discrete Laplace or Gaussian distribution, so that
+//! the decrypted result reveals little about any individual contribution.
+//! ```
+//! # #[cfg(feature = "integer")]
+//! # {
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::encoding::PlaintextEncodable;
+//! # use scicrypt_traits::homomorphic::HomomorphicAddition;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::dp::{self, Mechanism};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = paillier.generate_keys(&mut rng);
+//!
+//! // Every party contributes a 0 or a 1, so the count has sensitivity 1
+//! let mut count = public_key.encrypt_raw(&0i64.encode(&public_key).unwrap(), &mut rng);
+//! for vote in [1i64, 0, 1, 1] {
+//!     let ciphertext = public_key.encrypt_raw(&vote.encode(&public_key).unwrap(), &mut rng);
+//!     count = public_key.add(&count, &ciphertext);
+//! }
+//!
+//! let mechanism = Mechanism::laplace(1.0, 1);
+//! let noisy_count = dp::add_noise(&count, &mechanism, &public_key, &mut rng).unwrap();
+//!
+//! let released = i64::decode(&secret_key.decrypt_raw(&public_key, &noisy_count), &public_key);
+//! # assert!(released.is_ok());
+//! # }
+//! ```
+//!
+//! The noise is sampled exactly, using only integer arithmetic, following Canonne, Kamath and Steinke
+//! (2020). Floating-point samplers would break the privacy guarantee (Mironov, 2012). To this end,
+//! the scale of the Laplace mechanism is rounded up to a multiple of $2^{-16}$ and the $\sigma$ of
+//! the Gaussian mechanism to a multiple of $2^{-8}$, which only adds noise. Both must be at most
+//! $2^{20}$. Sampling takes a variable amount of time, so the samplers do not protect against
+//! side-channel attacks on the party that adds the noise.
+
+use scicrypt_traits::cryptosystems::EncryptionKey;
+use scicrypt_traits::encoding::{EncodingError, PlaintextEncodable};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+
+/// A mechanism that adds integer noise to a query result. Its parameters determine the privacy
+/// guarantee together with the sensitivity of the query, i.e. how much one party can change it.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Mechanism {
+    /// Noise $x$ with probability proportional to $e^{-|x| / \text{scale}}$
+    DiscreteLaplace {
+        /// The scale of the distribution
+        scale: f64,
+    },
+    /// Noise $x$ with probability proportional to $e^{-x^2 / (2 \sigma^2)}$
+    DiscreteGaussian {
+        /// The standard deviation of the underlying continuous Gaussian
+        sigma: f64,
+    },
+}
+
+impl Mechanism {
+    /// Returns the discrete Laplace mechanism that gives $\varepsilon$-differential privacy for a
+    /// query with the given `sensitivity`.
+    pub fn laplace(epsilon: f64, sensitivity: u64) -> Self {
+        assert!(epsilon > 0.0, "epsilon must be positive");
+
+        Mechanism::DiscreteLaplace {
+            scale: sensitivity as f64 / epsilon,
+        }
+    }
+
+    /// Returns the discrete Gaussian mechanism that gives $(\varepsilon, \delta)$-differential
+    /// privacy for a query with the given `sensitivity`, using the classic calibration
+    /// $\sigma = \Delta \sqrt{2 \ln(1.25 / \delta)} / \varepsilon$, which requires
+    /// $0 < \varepsilon < 1$.
+    pub fn gaussian(epsilon: f64, delta: f64, sensitivity: u64) -> Self {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be between 0 and 1"
+        );
+        assert!(delta > 0.0 && delta < 1.0, "delta must be between 0 and 1");
+
+        Mechanism::DiscreteGaussian {
+            sigma: sensitivity as f64 * (2.0 * (1.25 / delta).ln()).sqrt() / epsilon,
+        }
+    }
+
+    /// Samples noise from this mechanism's distribution.
+    pub fn sample<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> i64 {
+        match *self {
+            Mechanism::DiscreteLaplace { scale } => sample_discrete_laplace(scale, rng),
+            Mechanism::DiscreteGaussian { sigma } => sample_discrete_gaussian(sigma, rng),
+        }
+    }
+}
+
+/// Homomorphically adds freshly encrypted noise from `mechanism` to `ciphertext`. Returns an error
+/// if the noise cannot be encoded in the plaintext space of `public_key`.
+pub fn add_noise<PK, R: SecureRng>(
+    ciphertext: &PK::Ciphertext,
+    mechanism: &Mechanism,
+    public_key: &PK,
+    rng: &mut GeneralRng<R>,
+) -> Result<PK::Ciphertext, EncodingError>
+where
+    i64: PlaintextEncodable<PK>,
+    PK: HomomorphicAddition<Input = <PK as EncryptionKey>::Plaintext>,
+{
+    let noise = mechanism.sample(rng).encode(public_key)?;
+    let encrypted_noise = public_key.encrypt_raw(&noise, rng);
+
+    Ok(public_key.add(ciphertext, &encrypted_noise))
+}
+
+/// The scale of the discrete Laplace mechanism is rounded up to a multiple of one over this.
+const SCALE_DENOMINATOR: u128 = 1 << 16;
+
+/// The $\sigma$ of the discrete Gaussian mechanism is rounded up to a multiple of one over this.
+const SIGMA_DENOMINATOR: u128 = 1 << 8;
+
+/// The largest scale or $\sigma$ for which the exact samplers cannot overflow.
+const MAX_PARAMETER: f64 = (1u64 << 20) as f64;
+
+/// Rounds `parameter` up to a multiple of `1 / denominator`, and returns the numerator.
+fn to_numerator(parameter: f64, denominator: u128) -> u128 {
+    assert!(
+        parameter > 0.0 && parameter <= MAX_PARAMETER,
+        "the parameter must be positive and at most 2^20"
+    );
+
+    (parameter * denominator as f64).ceil() as u128
+}
+
+/// Samples uniformly from $[0, \text{bound})$.
+fn sample_below<R: SecureRng>(bound: u128, rng: &mut GeneralRng<R>) -> u128 {
+    // Reject the values above the largest multiple of `bound`, so that no remainder is more likely
+    let excess = (u128::MAX % bound + 1) % bound;
+
+    loop {
+        let value = (rng.rng().next_u64() as u128) << 64 | rng.rng().next_u64() as u128;
+        if value <= u128::MAX - excess {
+            return value % bound;
+        }
+    }
+}
+
+/// Returns true with probability $\text{numerator} / \text{denominator}$.
+fn sample_bernoulli<R: SecureRng>(
+    numerator: u128,
+    denominator: u128,
+    rng: &mut GeneralRng<R>,
+) -> bool {
+    sample_below(denominator, rng) < numerator
+}
+
+/// Returns true with probability $e^{-\gamma}$ for $\gamma = \text{numerator} /
+/// \text{denominator}$, using Algorithm 1 of Canonne, Kamath and Steinke.
+fn sample_bernoulli_exp<R: SecureRng>(
+    mut numerator: u128,
+    denominator: u128,
+    rng: &mut GeneralRng<R>,
+) -> bool {
+    // Split e^-γ into factors e^-1 and one factor e^-γ' with γ' <= 1, and sample all of them
+    while numerator > denominator {
+        if !sample_bernoulli_exp_at_most_one(1, 1, rng) {
+            return false;
+        }
+        numerator -= denominator;
+    }
+
+    sample_bernoulli_exp_at_most_one(numerator, denominator, rng)
+}
+
+/// Returns true with probability $e^{-\gamma}$ for $\gamma = \text{numerator} /
+/// \text{denominator} \le 1$.
+fn sample_bernoulli_exp_at_most_one<R: SecureRng>(
+    numerator: u128,
+    denominator: u128,
+    rng: &mut GeneralRng<R>,
+) -> bool {
+    let mut k = 1;
+    while sample_bernoulli(numerator, denominator * k, rng) {
+        k += 1;
+    }
+
+    k % 2 == 1
+}
+
+/// Samples $x$ with probability proportional to $e^{-|x| s / t}$, using Algorithm 2 of Canonne,
+/// Kamath and Steinke.
+fn sample_discrete_laplace_rational<R: SecureRng>(
+    t: u128,
+    s: u128,
+    rng: &mut GeneralRng<R>,
+) -> i64 {
+    loop {
+        let u = sample_below(t, rng);
+        if !sample_bernoulli_exp(u, t, rng) {
+            continue;
+        }
+
+        let mut v = 0;
+        while sample_bernoulli_exp(1, 1, rng) {
+            v += 1;
+        }
+
+        let magnitude = ((u + t * v) / s) as i64;
+        let negative = sample_bernoulli(1, 2, rng);
+        if negative && magnitude == 0 {
+            continue;
+        }
+
+        return if negative { -magnitude } else { magnitude };
+    }
+}
+
+fn sample_discrete_laplace<R: SecureRng>(scale: f64, rng: &mut GeneralRng<R>) -> i64 {
+    let t = to_numerator(scale, SCALE_DENOMINATOR);
+
+    sample_discrete_laplace_rational(t, SCALE_DENOMINATOR, rng)
+}
+
+/// Samples from the discrete Gaussian by rejection sampling from the discrete Laplace distribution,
+/// using Algorithm 3 of Canonne, Kamath and Steinke.
+fn sample_discrete_gaussian<R: SecureRng>(sigma: f64, rng: &mut GeneralRng<R>) -> i64 {
+    // sigma = m / SIGMA_DENOMINATOR, so sigma^2 = m^2 / SIGMA_DENOMINATOR^2
+    let m = to_numerator(sigma, SIGMA_DENOMINATOR);
+    let (variance_numerator, variance_denominator) = (m * m, SIGMA_DENOMINATOR * SIGMA_DENOMINATOR);
+    let t = m / SIGMA_DENOMINATOR + 1;
+
+    // The candidate is accepted with probability e^-γ for γ = (|y| - sigma^2 / t)^2 / (2 sigma^2)
+    let denominator = 2 * variance_numerator * variance_denominator * t * t;
+
+    loop {
+        let candidate = sample_discrete_laplace_rational(t, 1, rng);
+        let distance = (candidate.unsigned_abs() as u128 * t * variance_denominator)
+            .abs_diff(variance_numerator);
+
+        // If the numerator overflows, γ exceeds 2^15 and the candidate is accepted with a
+        // probability below e^(-2^15), so rejecting it does not change the distribution noticeably
+        if let Some(numerator) = distance.checked_mul(distance) {
+            if sample_bernoulli_exp(numerator, denominator, rng) {
+                return candidate;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::{sample_bernoulli_exp, Mechanism};
+    use rand_core::OsRng;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    fn mean_and_variance(mechanism: &Mechanism) -> (f64, f64) {
+        let mut rng = GeneralRng::new(OsRng);
+        let n = 50_000;
+
+        let samples: Vec<f64> = (0..n).map(|_| mechanism.sample(&mut rng) as f64).collect();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+
+        (mean, variance)
+    }
+
+    #[test]
+    fn test_discrete_laplace_distribution() {
+        let mechanism = Mechanism::laplace(0.5, 1);
+        let (mean, variance) = mean_and_variance(&mechanism);
+
+        // The variance of the discrete Laplace distribution is 2q / (1 - q)^2 with q = e^(-1/scale)
+        let q = (-0.5f64).exp();
+        let expected_variance = 2.0 * q / ((1.0 - q) * (1.0 - q));
+
+        assert!(mean.abs() < 0.2);
+        assert!((variance / expected_variance - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_discrete_gaussian_distribution() {
+        let mechanism = Mechanism::DiscreteGaussian { sigma: 3.0 };
+        let (mean, variance) = mean_and_variance(&mechanism);
+
+        // For sigma this large, the variance is practically that of the continuous Gaussian
+        assert!(mean.abs() < 0.2);
+        assert!((variance / 9.0 - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_bernoulli_exp() {
+        let mut rng = GeneralRng::new(OsRng);
+        let n = 50_000;
+
+        for (numerator, denominator) in [(0u128, 1u128), (1, 3), (3, 2), (7, 2)] {
+            let successes = (0..n)
+                .filter(|_| sample_bernoulli_exp(numerator, denominator, &mut rng))
+                .count();
+            let expected = (-(numerator as f64) / denominator as f64).exp();
+
+            assert!((successes as f64 / n as f64 - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_calibration() {
+        match Mechanism::gaussian(0.5, 1e-5, 2) {
+            Mechanism::DiscreteGaussian { sigma } => {
+                assert!((sigma - 4.0 * (2.0 * 125_000f64.ln()).sqrt()).abs() < 1e-9)
+            }
+            _ => panic!("expected the Gaussian mechanism"),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gaussian_rejects_invalid_delta() {
+        Mechanism::gaussian(0.5, 1.0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "the parameter must be positive and at most 2^20")]
+    fn test_reject_huge_scale() {
+        let mut rng = GeneralRng::new(OsRng);
+        Mechanism::DiscreteLaplace { scale: 1e7 }.sample(&mut rng);
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_add_noise() {
+        use crate::cryptosystems::paillier::Paillier;
+        use crate::dp::add_noise;
+        use scicrypt_traits::cryptosystems::{
+            AsymmetricCryptosystem, DecryptionKey, EncryptionKey,
+        };
+        use scicrypt_traits::encoding::PlaintextEncodable;
+        use scicrypt_traits::security::BitsOfSecurity;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_raw(&42i64.encode(&pk).unwrap(), &mut rng);
+
+        // With an enormous privacy budget, the noise is zero with overwhelming probability
+        let exact = add_noise(&ciphertext, &Mechanism::laplace(1000.0, 1), &pk, &mut rng).unwrap();
+        assert_eq!(Ok(42), i64::decode(&sk.decrypt_raw(&pk, &exact), &pk));
+
+        let noisy = add_noise(&ciphertext, &Mechanism::laplace(0.1, 1), &pk, &mut rng).unwrap();
+        assert!(i64::decode(&sk.decrypt_raw(&pk, &noisy), &pk).is_ok());
+    }
+}
@@ -0,0 +1,487 @@
+//! Object-safe wrappers around the cryptosystems, so that applications can select a cryptosystem
+//! at runtime, for example from a configuration file. The traits of `scicrypt_traits` are generic
+//! over the keys, plaintexts and ciphertexts of each cryptosystem, which requires the choice of
+//! cryptosystem to be made at compile time. The traits in this module instead work on plaintexts
+//! and ciphertexts that are serialized to bytes with `bincode`, and the keys are boxed trait
+//! objects.
+//!
+//! A ciphertext is tagged with the name of its cryptosystem, so that it cannot be combined with or
+//! decrypted by keys of a different cryptosystem. Note that keys of the same cryptosystem are not
+//! told apart.
+//! ```
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::dynamic::cryptosystem_by_name;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! let name = "curve-el-gamal"; // e.g. read from a configuration file
+//! let cryptosystem = cryptosystem_by_name(name, &BitsOfSecurity::default()).unwrap();
+//! let (public_key, secret_key) = cryptosystem.generate_keys(&mut OsRng);
+//!
+//! let plaintext = bincode::serialize(&RISTRETTO_BASEPOINT_POINT).unwrap();
+//! let ciphertext_a = public_key.encrypt(&plaintext, &mut OsRng).unwrap();
+//! let ciphertext_b = public_key.encrypt(&plaintext, &mut OsRng).unwrap();
+//! let sum = public_key.combine(&ciphertext_a, &ciphertext_b).unwrap();
+//!
+//! let decrypted = secret_key.decrypt(&sum).unwrap();
+//! assert_eq!(
+//!     RISTRETTO_BASEPOINT_POINT + RISTRETTO_BASEPOINT_POINT,
+//!     bincode::deserialize(&decrypted).unwrap()
+//! );
+//! ```
+
+use rand_core::CryptoRngCore;
+use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+use scicrypt_traits::homomorphic::{HomomorphicAddition, HomomorphicMultiplication};
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Error returned by the dynamically dispatched operations.
+#[derive(PartialEq, Eq, Debug)]
+pub enum DynError {
+    /// No cryptosystem with this name is available, possibly because its feature is disabled.
+    UnknownCryptosystem(String),
+    /// The ciphertext belongs to the cryptosystem with the given name instead of that of the key.
+    WrongCryptosystem(String),
+    /// The bytes could not be deserialized into a plaintext, ciphertext or key of the cryptosystem.
+    Malformed,
+}
+
+/// The homomorphic operation that `DynPublicKey::combine` applies to the plaintexts.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Homomorphism {
+    /// Combining ciphertexts adds their plaintexts
+    Additive,
+    /// Combining ciphertexts multiplies their plaintexts
+    Multiplicative,
+}
+
+/// A ciphertext of a dynamically chosen cryptosystem, serialized to bytes.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct DynCiphertext {
+    /// The name of the cryptosystem that this ciphertext belongs to
+    pub cryptosystem: String,
+    /// The serialized ciphertext
+    pub bytes: Vec<u8>,
+}
+
+/// A cryptosystem that is chosen at runtime.
+pub trait DynCryptosystem: Debug {
+    /// Returns the name of this cryptosystem, as accepted by `cryptosystem_by_name`.
+    fn name(&self) -> &'static str;
+
+    /// Returns the homomorphic operation of this cryptosystem.
+    fn homomorphism(&self) -> Homomorphism;
+
+    /// Generates a fresh key pair using the randomness of `rng`.
+    fn generate_keys(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+    ) -> (Box<dyn DynPublicKey>, Box<dyn DynSecretKey>);
+
+    /// Deserializes a public key that was serialized with `DynPublicKey::to_bytes`.
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<Box<dyn DynPublicKey>, DynError>;
+}
+
+/// The public key of a cryptosystem that is chosen at runtime.
+pub trait DynPublicKey: Debug {
+    /// Returns the name of the cryptosystem of this key.
+    fn cryptosystem(&self) -> &'static str;
+
+    /// Serializes this public key, so that it can be restored with
+    /// `DynCryptosystem::public_key_from_bytes`.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Encrypts the serialized `plaintext`, or returns an error if it is not a serialized plaintext
+    /// of this cryptosystem.
+    fn encrypt(
+        &self,
+        plaintext: &[u8],
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<DynCiphertext, DynError>;
+
+    /// Combines two ciphertexts into a ciphertext of the sum or product of their plaintexts,
+    /// depending on the homomorphism of the cryptosystem.
+    fn combine(&self, a: &DynCiphertext, b: &DynCiphertext) -> Result<DynCiphertext, DynError>;
+
+    /// Rerandomizes `ciphertext`, so that it can no longer be linked to the original. Panics if the
+    /// cryptosystem is deterministic, like RSA.
+    fn randomize(
+        &self,
+        ciphertext: &DynCiphertext,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<DynCiphertext, DynError>;
+}
+
+/// The secret key of a cryptosystem that is chosen at runtime.
+pub trait DynSecretKey: Debug {
+    /// Returns the name of the cryptosystem of this key.
+    fn cryptosystem(&self) -> &'static str;
+
+    /// Decrypts `ciphertext` into a serialized plaintext.
+    fn decrypt(&self, ciphertext: &DynCiphertext) -> Result<Vec<u8>, DynError>;
+}
+
+/// Wraps the cryptosystem `C` so that it can be used as a `DynCryptosystem`.
+pub struct DynScheme<C: AsymmetricCryptosystem> {
+    cryptosystem: C,
+    name: &'static str,
+    homomorphism: Homomorphism,
+    combine: Combine<C::PublicKey>,
+}
+
+type Combine<PK> = fn(
+    &PK,
+    &<PK as EncryptionKey>::Ciphertext,
+    &<PK as EncryptionKey>::Ciphertext,
+) -> <PK as EncryptionKey>::Ciphertext;
+
+impl<C: AsymmetricCryptosystem> Debug for DynScheme<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynScheme")
+            .field("name", &self.name)
+            .field("homomorphism", &self.homomorphism)
+            .finish()
+    }
+}
+
+impl<C: AsymmetricCryptosystem> DynScheme<C>
+where
+    C::PublicKey: HomomorphicAddition,
+{
+    /// Wraps the additively homomorphic `cryptosystem` under the given `name`.
+    pub fn additive(cryptosystem: C, name: &'static str) -> Self {
+        DynScheme {
+            cryptosystem,
+            name,
+            homomorphism: Homomorphism::Additive,
+            combine: |public_key, a, b| public_key.add(a, b),
+        }
+    }
+}
+
+impl<C: AsymmetricCryptosystem> DynScheme<C>
+where
+    C::PublicKey: HomomorphicMultiplication,
+{
+    /// Wraps the multiplicatively homomorphic `cryptosystem` under the given `name`.
+    pub fn multiplicative(cryptosystem: C, name: &'static str) -> Self {
+        DynScheme {
+            cryptosystem,
+            name,
+            homomorphism: Homomorphism::Multiplicative,
+            combine: |public_key, a, b| public_key.mul(a, b),
+        }
+    }
+}
+
+impl<C> DynCryptosystem for DynScheme<C>
+where
+    C: AsymmetricCryptosystem,
+    C::PublicKey: Clone + Serialize + DeserializeOwned + 'static,
+    C::SecretKey: Debug + 'static,
+    <C::PublicKey as EncryptionKey>::Plaintext: Serialize + DeserializeOwned,
+    <C::PublicKey as EncryptionKey>::Ciphertext: Serialize + DeserializeOwned,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn homomorphism(&self) -> Homomorphism {
+        self.homomorphism
+    }
+
+    fn generate_keys(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+    ) -> (Box<dyn DynPublicKey>, Box<dyn DynSecretKey>) {
+        let (public_key, secret_key) = self.cryptosystem.generate_keys(&mut GeneralRng::new(rng));
+        let public_key = DynPK {
+            public_key,
+            name: self.name,
+            combine: self.combine,
+        };
+
+        (
+            Box::new(public_key.clone()),
+            Box::new(DynSK {
+                secret_key,
+                public_key,
+            }),
+        )
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<Box<dyn DynPublicKey>, DynError> {
+        Ok(Box::new(DynPK {
+            public_key: deserialize::<C::PublicKey>(bytes)?,
+            name: self.name,
+            combine: self.combine,
+        }))
+    }
+}
+
+struct DynPK<PK: EncryptionKey> {
+    public_key: PK,
+    name: &'static str,
+    combine: Combine<PK>,
+}
+
+impl<PK: EncryptionKey + Clone> Clone for DynPK<PK> {
+    fn clone(&self) -> Self {
+        DynPK {
+            public_key: self.public_key.clone(),
+            name: self.name,
+            combine: self.combine,
+        }
+    }
+}
+
+impl<PK: EncryptionKey> Debug for DynPK<PK> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynPK")
+            .field("name", &self.name)
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl<PK: EncryptionKey> DynPK<PK>
+where
+    PK::Ciphertext: Serialize + DeserializeOwned,
+{
+    fn open(&self, ciphertext: &DynCiphertext) -> Result<PK::Ciphertext, DynError> {
+        if ciphertext.cryptosystem != self.name {
+            return Err(DynError::WrongCryptosystem(ciphertext.cryptosystem.clone()));
+        }
+
+        deserialize(&ciphertext.bytes)
+    }
+
+    fn seal(&self, ciphertext: &PK::Ciphertext) -> DynCiphertext {
+        DynCiphertext {
+            cryptosystem: self.name.to_string(),
+            bytes: bincode::serialize(ciphertext).expect("ciphertexts can always be serialized"),
+        }
+    }
+}
+
+impl<PK> DynPublicKey for DynPK<PK>
+where
+    PK: EncryptionKey + Serialize,
+    PK::Plaintext: Serialize + DeserializeOwned,
+    PK::Ciphertext: Serialize + DeserializeOwned,
+{
+    fn cryptosystem(&self) -> &'static str {
+        self.name
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(&self.public_key).expect("public keys can always be serialized")
+    }
+
+    fn encrypt(
+        &self,
+        plaintext: &[u8],
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<DynCiphertext, DynError> {
+        let plaintext = deserialize::<PK::Plaintext>(plaintext)?;
+
+        Ok(self.seal(
+            &self
+                .public_key
+                .encrypt_raw(&plaintext, &mut GeneralRng::new(rng)),
+        ))
+    }
+
+    fn combine(&self, a: &DynCiphertext, b: &DynCiphertext) -> Result<DynCiphertext, DynError> {
+        Ok(self.seal(&(self.combine)(
+            &self.public_key,
+            &self.open(a)?,
+            &self.open(b)?,
+        )))
+    }
+
+    fn randomize(
+        &self,
+        ciphertext: &DynCiphertext,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<DynCiphertext, DynError> {
+        let ciphertext = self.open(ciphertext)?;
+
+        Ok(self.seal(
+            &self
+                .public_key
+                .randomize(ciphertext, &mut GeneralRng::new(rng)),
+        ))
+    }
+}
+
+struct DynSK<PK: EncryptionKey, SK> {
+    secret_key: SK,
+    public_key: DynPK<PK>,
+}
+
+impl<PK: EncryptionKey, SK: Debug> Debug for DynSK<PK, SK> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynSK")
+            .field("name", &self.public_key.name)
+            .field("secret_key", &self.secret_key)
+            .finish()
+    }
+}
+
+impl<PK, SK> DynSecretKey for DynSK<PK, SK>
+where
+    PK: EncryptionKey,
+    PK::Plaintext: Serialize,
+    PK::Ciphertext: Serialize + DeserializeOwned,
+    SK: DecryptionKey<PK> + Debug,
+{
+    fn cryptosystem(&self) -> &'static str {
+        self.public_key.name
+    }
+
+    fn decrypt(&self, ciphertext: &DynCiphertext) -> Result<Vec<u8>, DynError> {
+        let ciphertext = self.public_key.open(ciphertext)?;
+        let plaintext = self
+            .secret_key
+            .decrypt_raw(&self.public_key.public_key, &ciphertext);
+
+        Ok(bincode::serialize(&plaintext).expect("plaintexts can always be serialized"))
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DynError> {
+    bincode::deserialize(bytes).map_err(|_| DynError::Malformed)
+}
+
+/// Returns the cryptosystem with the given `name`, set up for the `security` level. The available
+/// names are `paillier`, `okamoto-uchiyama`, `goldwasser-micali`, `rsa` and `integer-el-gamal` with
+/// the `integer` feature, `curve-el-gamal` with the `curve` feature, `secp256k1-el-gamal` with the
+/// `secp256k1` feature and `p256-el-gamal` with the `p256` feature.
+pub fn cryptosystem_by_name(
+    name: &str,
+    security: &BitsOfSecurity,
+) -> Result<Box<dyn DynCryptosystem>, DynError> {
+    match name {
+        #[cfg(feature = "integer")]
+        "paillier" => Ok(Box::new(DynScheme::additive(
+            crate::cryptosystems::paillier::Paillier::setup(security),
+            "paillier",
+        ))),
+        #[cfg(feature = "integer")]
+        "okamoto-uchiyama" => Ok(Box::new(DynScheme::additive(
+            crate::cryptosystems::okamoto_uchiyama::OkamotoUchiyama::setup(security),
+            "okamoto-uchiyama",
+        ))),
+        #[cfg(feature = "integer")]
+        "goldwasser-micali" => Ok(Box::new(DynScheme::additive(
+            crate::cryptosystems::goldwasser_micali::GoldwasserMicali::setup(security),
+            "goldwasser-micali",
+        ))),
+        #[cfg(feature = "integer")]
+        "rsa" => Ok(Box::new(DynScheme::multiplicative(
+            crate::cryptosystems::rsa::Rsa::setup(security),
+            "rsa",
+        ))),
+        #[cfg(feature = "integer")]
+        "integer-el-gamal" => Ok(Box::new(DynScheme::multiplicative(
+            crate::cryptosystems::integer_el_gamal::IntegerElGamal::setup(security),
+            "integer-el-gamal",
+        ))),
+        #[cfg(feature = "curve")]
+        "curve-el-gamal" => Ok(Box::new(DynScheme::additive(
+            crate::cryptosystems::curve_el_gamal::CurveElGamal::setup(security),
+            "curve-el-gamal",
+        ))),
+        #[cfg(feature = "secp256k1")]
+        "secp256k1-el-gamal" => Ok(Box::new(DynScheme::additive(
+            crate::cryptosystems::generic_curve_el_gamal::Secp256k1ElGamal::setup(security),
+            "secp256k1-el-gamal",
+        ))),
+        #[cfg(feature = "p256")]
+        "p256-el-gamal" => Ok(Box::new(DynScheme::additive(
+            crate::cryptosystems::generic_curve_el_gamal::P256ElGamal::setup(security),
+            "p256-el-gamal",
+        ))),
+        _ => Err(DynError::UnknownCryptosystem(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dynamic::{cryptosystem_by_name, DynError};
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_integer_cryptosystems() {
+        use crate::dynamic::Homomorphism;
+        use rand_core::OsRng;
+        use scicrypt_bigint::UnsignedInteger;
+        use scicrypt_traits::security::BitsOfSecurity;
+
+        for (name, security) in [
+            ("paillier", BitsOfSecurity::ToyParameters),
+            ("integer-el-gamal", BitsOfSecurity::AES80),
+        ] {
+            let cryptosystem = cryptosystem_by_name(name, &security).unwrap();
+            let (public_key, secret_key) = cryptosystem.generate_keys(&mut OsRng);
+            let public_key = cryptosystem
+                .public_key_from_bytes(&public_key.to_bytes())
+                .unwrap();
+
+            let plaintext = bincode::serialize(&UnsignedInteger::from(6u64)).unwrap();
+            let ciphertext = public_key.encrypt(&plaintext, &mut OsRng).unwrap();
+            let combined = public_key.combine(&ciphertext, &ciphertext).unwrap();
+            let randomized = public_key.randomize(&combined, &mut OsRng).unwrap();
+            assert_ne!(combined, randomized);
+
+            let decrypted: UnsignedInteger =
+                bincode::deserialize(&secret_key.decrypt(&randomized).unwrap()).unwrap();
+            let expected = match cryptosystem.homomorphism() {
+                Homomorphism::Additive => 12u64,
+                Homomorphism::Multiplicative => 36u64,
+            };
+            assert_eq!(UnsignedInteger::from(expected), decrypted);
+        }
+    }
+
+    #[cfg(all(feature = "curve", feature = "integer"))]
+    #[test]
+    fn test_wrong_cryptosystem() {
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use rand_core::OsRng;
+        use scicrypt_traits::security::BitsOfSecurity;
+
+        let curve = cryptosystem_by_name("curve-el-gamal", &Default::default()).unwrap();
+        let paillier = cryptosystem_by_name("paillier", &BitsOfSecurity::ToyParameters).unwrap();
+        let (curve_pk, _) = curve.generate_keys(&mut OsRng);
+        let (paillier_pk, paillier_sk) = paillier.generate_keys(&mut OsRng);
+
+        let plaintext = bincode::serialize(&RISTRETTO_BASEPOINT_POINT).unwrap();
+        let ciphertext = curve_pk.encrypt(&plaintext, &mut OsRng).unwrap();
+
+        assert_eq!(
+            Err(DynError::WrongCryptosystem("curve-el-gamal".to_string())),
+            paillier_sk.decrypt(&ciphertext)
+        );
+        assert_eq!(
+            Err(DynError::WrongCryptosystem("curve-el-gamal".to_string())),
+            paillier_pk.combine(&ciphertext, &ciphertext)
+        );
+        assert_eq!(
+            Err(DynError::Malformed),
+            paillier_pk.encrypt(&[1, 2, 3], &mut OsRng)
+        );
+    }
+
+    #[test]
+    fn test_unknown_cryptosystem() {
+        assert_eq!(
+            DynError::UnknownCryptosystem("enigma".to_string()),
+            cryptosystem_by_name("enigma", &Default::default()).unwrap_err()
+        );
+    }
+}
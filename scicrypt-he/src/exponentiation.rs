@@ -0,0 +1,193 @@
+//! Modular exponentiation is the dominant cost of the integer-based cryptosystems, and the fastest
+//! way to compute it depends on the workload. An `ExpConfig` selects the window size of the tables
+//! for fixed bases, whether to build such tables at all, and whether to use the faster
+//! variable-time exponentiation for exponents that are public. The defaults for each security
+//! level are safe, and only trade memory for speed.
+//!
+//! A table for a fixed base, such as the generator or public key of ElGamal, takes some time and
+//! memory to build, but replaces all squarings by table lookups. It pays off once the same key
+//! encrypts more than a handful of messages.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey};
+//! # use scicrypt_he::cryptosystems::integer_el_gamal::IntegerElGamal;
+//! # use scicrypt_he::exponentiation::ExpConfig;
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+//!
+//! let config = ExpConfig {
+//!     window_size: 8,
+//!     ..ExpConfig::for_security_level(&BitsOfSecurity::AES80)
+//! };
+//! let (public_key, secret_key) = el_gamal.generate_keys_with_config(&config, &mut rng);
+//!
+//! let ciphertext = public_key.encrypt(&UnsignedInteger::from(5), &mut rng);
+//! assert_eq!(
+//!     UnsignedInteger::from(5),
+//!     secret_key.decrypt_raw(public_key.public_key(), &ciphertext)
+//! );
+//! ```
+
+use scicrypt_bigint::{FixedBaseTable, UnsignedInteger};
+use scicrypt_traits::security::BitsOfSecurity;
+use std::fmt::{Debug, Formatter};
+
+/// Tuning parameters for modular exponentiations.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ExpConfig {
+    /// The number of exponent bits that is handled per table lookup, which must divide 64. Every
+    /// increment halves the number of multiplications, but doubles the size of the tables.
+    pub window_size: u32,
+    /// Whether to precompute tables for bases that are used many times, such as generators and
+    /// public keys
+    pub fixed_base_tables: bool,
+    /// Whether to use variable-time exponentiation when the exponent is public, such as the modulus
+    /// $n$ in Paillier. Its running time may still depend on the base, so only enable this when
+    /// timing side channels are not a concern.
+    pub leaky_public_exponents: bool,
+}
+
+impl ExpConfig {
+    /// Returns sensible defaults for keys of the given security level. Tables use windows of 4
+    /// bits, and are only built for moduli of up to 3072 bits, above which they take tens of
+    /// megabytes. Public exponents are always handled in constant time.
+    pub fn for_security_level(security_level: &BitsOfSecurity) -> Self {
+        ExpConfig {
+            window_size: 4,
+            fixed_base_tables: security_level.to_public_key_bit_length() <= 3072,
+            leaky_public_exponents: false,
+        }
+    }
+
+    /// Returns the configuration that corresponds to the behavior of the cryptosystems without an
+    /// `ExpConfig`: no tables, and constant-time exponentiation for all exponents.
+    pub fn constant_time() -> Self {
+        ExpConfig {
+            window_size: 4,
+            fixed_base_tables: false,
+            leaky_public_exponents: false,
+        }
+    }
+
+    /// Computes `base` to the power of the public `exponent` modulo `modulus`, in variable time if
+    /// this configuration allows it.
+    pub fn pow_public_exponent(
+        &self,
+        base: &UnsignedInteger,
+        exponent: &UnsignedInteger,
+        modulus: &UnsignedInteger,
+    ) -> UnsignedInteger {
+        if self.leaky_public_exponents {
+            base.pow_mod_leaky(exponent, modulus)
+        } else {
+            base.pow_mod(exponent, modulus)
+        }
+    }
+}
+
+impl Default for ExpConfig {
+    fn default() -> Self {
+        ExpConfig::for_security_level(&BitsOfSecurity::default())
+    }
+}
+
+/// A base that is raised to many secret exponents modulo the same modulus, using a precomputed
+/// table if the configuration asks for one.
+pub struct FixedBase {
+    base: UnsignedInteger,
+    modulus: UnsignedInteger,
+    table: Option<FixedBaseTable>,
+}
+
+impl Debug for FixedBase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FixedBase")
+            .field("base", &self.base)
+            .field("modulus", &self.modulus)
+            .field("precomputed", &self.table.is_some())
+            .finish()
+    }
+}
+
+impl FixedBase {
+    /// Prepares `base` for exponentiations modulo `modulus` with exponents of at most
+    /// `exponent_size_in_bits` bits.
+    pub fn new(
+        base: &UnsignedInteger,
+        modulus: &UnsignedInteger,
+        exponent_size_in_bits: u32,
+        config: &ExpConfig,
+    ) -> Self {
+        let table = config
+            .fixed_base_tables
+            .then(|| FixedBaseTable::new(base, modulus, exponent_size_in_bits, config.window_size));
+
+        FixedBase {
+            base: base.clone(),
+            modulus: modulus.clone(),
+            table,
+        }
+    }
+
+    /// Returns the base.
+    pub fn base(&self) -> &UnsignedInteger {
+        &self.base
+    }
+
+    /// Computes the base to the power `exponent` modulo the modulus in constant time. Exponents that
+    /// are larger than the precomputed table supports are exponentiated without the table.
+    pub fn pow(&self, exponent: &UnsignedInteger) -> UnsignedInteger {
+        match &self.table {
+            Some(table) if exponent.size_in_bits() <= table.exponent_size_in_bits() => {
+                table.pow_with_table(exponent)
+            }
+            _ => self.base.pow_mod(exponent, &self.modulus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::exponentiation::{ExpConfig, FixedBase};
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_fixed_base_matches_pow_mod() {
+        let mut rng = GeneralRng::new(OsRng);
+        let modulus = UnsignedInteger::from_string_leaky("149600854933825512159828331527177109689118555212385170831387365804008437367913613643959968668965614270559113472851544758183282789643129469226548555150464780229538086590498853718102052468519876788192865092229749643546710793464305243815836267024770081889047200172952438000587807986096107675012284269101785114471".to_string(), 10, 1024);
+        let base = UnsignedInteger::from(105u64);
+
+        for window_size in [1, 4, 8] {
+            for fixed_base_tables in [false, true] {
+                let config = ExpConfig {
+                    window_size,
+                    fixed_base_tables,
+                    ..ExpConfig::default()
+                };
+                let fixed_base = FixedBase::new(&base, &modulus, 1024, &config);
+
+                let exponent = UnsignedInteger::random(1024, &mut rng);
+                assert_eq!(base.pow_mod(&exponent, &modulus), fixed_base.pow(&exponent));
+
+                let large_exponent = UnsignedInteger::random(2048, &mut rng);
+                assert_eq!(
+                    base.pow_mod(&large_exponent, &modulus),
+                    fixed_base.pow(&large_exponent)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_defaults_per_security_level() {
+        assert!(ExpConfig::for_security_level(&BitsOfSecurity::AES128).fixed_base_tables);
+        assert!(!ExpConfig::for_security_level(&BitsOfSecurity::AES192).fixed_base_tables);
+        assert!(!ExpConfig::default().leaky_public_exponents);
+    }
+}
@@ -0,0 +1,313 @@
+//! Here is an example of how to compute the average of encrypted fractional values, by adding them
+//! homomorphically and dividing by the count after decryption.
+//! ```
+//! # #[cfg(feature = "integer")]
+//! # {
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::fixed_point::EncryptedFixedPoint;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = paillier.generate_keys(&mut rng);
+//!
+//! let temperatures = [21.5, 19.25, -3.75];
+//! let encrypted: Vec<_> = temperatures
+//!     .iter()
+//!     .map(|&t| EncryptedFixedPoint::encrypt(t, 2, &public_key, &mut rng).unwrap())
+//!     .collect();
+//!
+//! let total = EncryptedFixedPoint::sum(&encrypted, &public_key);
+//! let mean = total.decrypt(&secret_key, &public_key).unwrap() / temperatures.len() as f64;
+//! assert_eq!(mean, 12.333333333333334);
+//! # }
+//! ```
+
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::encoding::{EncodingError, FixedPoint, PlaintextEncodable};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::{Deserialize, Serialize};
+
+/// The largest number of decimals, so that the scaling factor $10^\text{decimals}$ fits in an `i64`.
+pub const MAX_DECIMALS: u32 = 18;
+
+/// An encrypted fixed-point number, whose plaintext is the number multiplied by
+/// $10^\text{decimals}$ and encoded like an `i64`. The scale is public and tracked alongside the
+/// ciphertext: additions keep it, while multiplying by a fixed-point constant adds the decimals of
+/// the constant. The decrypted value must stay within the range of an `i64` at the final scale.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedFixedPoint<C> {
+    /// The encrypted scaled number
+    pub ciphertext: C,
+    /// The number of decimals after the decimal point
+    pub decimals: u32,
+}
+
+fn scale(decimals: u32) -> i64 {
+    assert!(
+        decimals <= MAX_DECIMALS,
+        "at most {} decimals are supported",
+        MAX_DECIMALS
+    );
+
+    10i64.pow(decimals)
+}
+
+pub(crate) fn to_mantissa(value: f64, decimals: u32) -> Result<i64, EncodingError> {
+    let scaled = (value * scale(decimals) as f64).round();
+
+    // `i64::MAX as f64` rounds up to 2^63, which itself does not fit
+    if !scaled.is_finite() || scaled < i64::MIN as f64 || scaled >= i64::MAX as f64 {
+        return Err(EncodingError);
+    }
+
+    Ok(scaled as i64)
+}
+
+impl<C> EncryptedFixedPoint<C> {
+    /// Encrypts `value` rounded to `decimals` decimals.
+    pub fn encrypt<PK: EncryptionKey<Ciphertext = C>, R: SecureRng>(
+        value: f64,
+        decimals: u32,
+        public_key: &PK,
+        rng: &mut GeneralRng<R>,
+    ) -> Result<Self, EncodingError>
+    where
+        i64: PlaintextEncodable<PK>,
+    {
+        let plaintext = to_mantissa(value, decimals)?.encode(public_key)?;
+
+        Ok(EncryptedFixedPoint {
+            ciphertext: public_key.encrypt_raw(&plaintext, rng),
+            decimals,
+        })
+    }
+
+    /// Decrypts this number into the closest `f64`, or returns an error if the scaled number
+    /// overflowed an `i64`.
+    pub fn decrypt<PK: EncryptionKey<Ciphertext = C>, SK: DecryptionKey<PK>>(
+        &self,
+        secret_key: &SK,
+        public_key: &PK,
+    ) -> Result<f64, EncodingError>
+    where
+        i64: PlaintextEncodable<PK>,
+    {
+        let mantissa = i64::decode(
+            &secret_key.decrypt_raw(public_key, &self.ciphertext),
+            public_key,
+        )?;
+
+        Ok(mantissa as f64 / scale(self.decimals) as f64)
+    }
+
+    /// Decrypts this number and rounds it to a `FixedPoint` with `DECIMALS` decimals, which undoes
+    /// the growth of the scale caused by multiplications.
+    pub fn decrypt_rescaled<
+        const DECIMALS: u32,
+        PK: EncryptionKey<Ciphertext = C>,
+        SK: DecryptionKey<PK>,
+    >(
+        &self,
+        secret_key: &SK,
+        public_key: &PK,
+    ) -> Result<FixedPoint<DECIMALS>, EncodingError>
+    where
+        i64: PlaintextEncodable<PK>,
+    {
+        let mantissa = i64::decode(
+            &secret_key.decrypt_raw(public_key, &self.ciphertext),
+            public_key,
+        )?;
+
+        let mantissa = if DECIMALS >= self.decimals {
+            mantissa
+                .checked_mul(scale(DECIMALS - self.decimals))
+                .ok_or(EncodingError)?
+        } else {
+            // Round half away from zero
+            let divisor = scale(self.decimals - DECIMALS);
+            let rounded = mantissa / divisor;
+            let remainder = mantissa % divisor;
+            if remainder.unsigned_abs() >= (divisor as u64).div_ceil(2) {
+                rounded + mantissa.signum()
+            } else {
+                rounded
+            }
+        };
+
+        Ok(FixedPoint { mantissa })
+    }
+}
+
+impl<C: Clone> EncryptedFixedPoint<C> {
+    /// Increases the number of decimals to `decimals` by multiplying the plaintext with a power of
+    /// ten. The number of decimals can only be decreased after decryption, using
+    /// `decrypt_rescaled`.
+    pub fn rescale<PK>(&self, decimals: u32, public_key: &PK) -> Self
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        assert!(
+            decimals >= self.decimals,
+            "the number of decimals of an encrypted number can only be increased"
+        );
+
+        if decimals == self.decimals {
+            return self.clone();
+        }
+
+        let factor = scale(decimals - self.decimals).encode(public_key).expect(
+            "powers of ten up to 10^18 fit in every plaintext space that can encode an i64",
+        );
+
+        EncryptedFixedPoint {
+            ciphertext: public_key.mul_constant(&self.ciphertext, &factor),
+            decimals,
+        }
+    }
+
+    /// Homomorphically adds two encrypted numbers. If their scales differ, the number with fewer
+    /// decimals is rescaled first.
+    pub fn add<PK>(&self, other: &Self, public_key: &PK) -> Self
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        let decimals = self.decimals.max(other.decimals);
+        let a = self.rescale(decimals, public_key);
+        let b = other.rescale(decimals, public_key);
+
+        EncryptedFixedPoint {
+            ciphertext: public_key.add(&a.ciphertext, &b.ciphertext),
+            decimals,
+        }
+    }
+
+    /// Homomorphically multiplies this number by the plaintext `constant`, rounded to `decimals`
+    /// decimals. The result has the sum of both numbers of decimals.
+    pub fn mul_constant<PK>(
+        &self,
+        constant: f64,
+        decimals: u32,
+        public_key: &PK,
+    ) -> Result<Self, EncodingError>
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        let result_decimals = self.decimals + decimals;
+        if result_decimals > MAX_DECIMALS {
+            return Err(EncodingError);
+        }
+
+        let constant = to_mantissa(constant, decimals)?.encode(public_key)?;
+
+        Ok(EncryptedFixedPoint {
+            ciphertext: public_key.mul_constant(&self.ciphertext, &constant),
+            decimals: result_decimals,
+        })
+    }
+
+    /// Homomorphically adds up all `values`, which must not be empty.
+    pub fn sum<PK>(values: &[Self], public_key: &PK) -> Self
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        let decimals = values
+            .iter()
+            .map(|value| value.decimals)
+            .max()
+            .expect("there must be at least one value to sum");
+
+        let mut total = values[0].rescale(decimals, public_key);
+        for value in &values[1..] {
+            public_key.add_assign(
+                &mut total.ciphertext,
+                &value.rescale(decimals, public_key).ciphertext,
+            );
+        }
+
+        total
+    }
+}
+
+#[cfg(all(test, feature = "integer"))]
+mod tests {
+    use crate::cryptosystems::paillier::Paillier;
+    use crate::fixed_point::EncryptedFixedPoint;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::encoding::{EncodingError, FixedPoint};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_add_different_scales() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let a = EncryptedFixedPoint::encrypt(1.5, 1, &pk, &mut rng).unwrap();
+        let b = EncryptedFixedPoint::encrypt(-0.125, 3, &pk, &mut rng).unwrap();
+
+        let sum = a.add(&b, &pk);
+        assert_eq!(3, sum.decimals);
+        assert_eq!(Ok(1.375), sum.decrypt(&sk, &pk));
+    }
+
+    #[test]
+    fn test_mul_constant_and_rescale() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let price = EncryptedFixedPoint::encrypt(19.99, 2, &pk, &mut rng).unwrap();
+        let taxed = price.mul_constant(1.21, 2, &pk).unwrap();
+        assert_eq!(4, taxed.decimals);
+
+        assert_eq!(
+            Ok(FixedPoint::<2> { mantissa: 2419 }),
+            taxed.decrypt_rescaled(&sk, &pk)
+        );
+        let negated = price.mul_constant(-1.0, 0, &pk).unwrap();
+        assert_eq!(
+            Ok(FixedPoint::<1> { mantissa: -200 }),
+            negated.decrypt_rescaled(&sk, &pk)
+        );
+
+        assert_eq!(Err(EncodingError), price.mul_constant(2.0, 17, &pk));
+    }
+
+    #[test]
+    fn test_variance() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        // Every party encrypts both its value and its square
+        let values = [2.5, 4.0, 5.5];
+        let encrypted: Vec<_> = values
+            .iter()
+            .map(|&v| EncryptedFixedPoint::encrypt(v, 1, &pk, &mut rng).unwrap())
+            .collect();
+        let squares: Vec<_> = values
+            .iter()
+            .map(|&v| EncryptedFixedPoint::encrypt(v * v, 2, &pk, &mut rng).unwrap())
+            .collect();
+
+        let n = values.len() as f64;
+        let mean = EncryptedFixedPoint::sum(&encrypted, &pk)
+            .decrypt(&sk, &pk)
+            .unwrap()
+            / n;
+        let mean_of_squares = EncryptedFixedPoint::sum(&squares, &pk)
+            .decrypt(&sk, &pk)
+            .unwrap()
+            / n;
+
+        assert!((mean_of_squares - mean * mean - 1.5).abs() < 1e-9);
+    }
+}
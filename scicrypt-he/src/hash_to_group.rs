@@ -0,0 +1,105 @@
+//! Deterministic hashing of arbitrary byte strings into the plaintext groups of the ElGamal
+//! cryptosystems, so that identifiers can be used as plaintexts or as generators with unknown
+//! discrete logarithms. Here is an example of hashing an identifier to a curve point.
+//! ```
+//! # use scicrypt_he::hash_to_group::hash_to_ristretto;
+//! let point = hash_to_ristretto(b"alice@example.com");
+//!
+//! assert_eq!(point, hash_to_ristretto(b"alice@example.com"));
+//! assert_ne!(point, hash_to_ristretto(b"bob@example.com"));
+//! ```
+
+#[cfg(feature = "curve")]
+use curve25519_dalek::ristretto::RistrettoPoint;
+#[cfg(feature = "integer")]
+use rug::{integer::Order, Integer};
+#[cfg(feature = "integer")]
+use scicrypt_bigint::UnsignedInteger;
+use sha2::{Digest, Sha512};
+
+#[cfg(feature = "curve")]
+const RISTRETTO_DOMAIN: &[u8] = b"scicrypt-he hash_to_ristretto";
+#[cfg(feature = "integer")]
+const QR_GROUP_DOMAIN: &[u8] = b"scicrypt-he hash_to_qr_group";
+
+/// Hashes `bytes` to a point on the Ristretto group using SHA-512 followed by the Elligator map of
+/// `RistrettoPoint::from_uniform_bytes`. Nobody knows the discrete logarithm of the resulting point
+/// with respect to any other point, which makes it suitable as an independent generator.
+#[cfg(feature = "curve")]
+pub fn hash_to_ristretto(bytes: &[u8]) -> RistrettoPoint {
+    let digest = Sha512::new()
+        .chain_update(RISTRETTO_DOMAIN)
+        .chain_update(bytes)
+        .finalize();
+
+    RistrettoPoint::from_uniform_bytes(&digest.into())
+}
+
+/// Hashes `bytes` to a quadratic residue modulo the safe prime `modulus`, which is the plaintext
+/// group of `IntegerElGamal`. The bytes are first expanded with SHA-512 to 128 bits more than the
+/// size of the modulus, so that the reduction is statistically close to uniform, and the result
+/// is then squared. This function is not constant-time in `bytes`, so only hash public values.
+#[cfg(feature = "integer")]
+pub fn hash_to_qr_group(bytes: &[u8], modulus: &UnsignedInteger) -> UnsignedInteger {
+    let block_count = (modulus.size_in_bits() + 128).div_ceil(512);
+
+    // A zero residue only occurs with negligible probability, in which case we try the next counter
+    for counter in 0u32.. {
+        let mut expanded = Vec::with_capacity(block_count as usize * 64);
+        for block in 0..block_count {
+            expanded.extend(
+                Sha512::new()
+                    .chain_update(QR_GROUP_DOMAIN)
+                    .chain_update(counter.to_be_bytes())
+                    .chain_update(block.to_be_bytes())
+                    .chain_update(bytes)
+                    .finalize(),
+            );
+        }
+
+        let element = UnsignedInteger::from(Integer::from_digits(&expanded, Order::Msf)) % modulus;
+        if !element.is_zero_leaky() {
+            return element.square_mod(modulus);
+        }
+    }
+
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "integer")]
+    use crate::hash_to_group::hash_to_qr_group;
+    #[cfg(feature = "curve")]
+    use crate::hash_to_group::hash_to_ristretto;
+    #[cfg(feature = "integer")]
+    use scicrypt_bigint::UnsignedInteger;
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_hash_to_ristretto_deterministic() {
+        assert_eq!(
+            hash_to_ristretto(b"identifier"),
+            hash_to_ristretto(b"identifier")
+        );
+        assert_ne!(
+            hash_to_ristretto(b"identifier"),
+            hash_to_ristretto(b"identifiers")
+        );
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_hash_to_qr_group_is_residue() {
+        // 2q + 1 for the Sophie Germain prime q = 1019
+        let modulus = UnsignedInteger::from(2039u64);
+        let q = UnsignedInteger::from(1019u64);
+
+        let element = hash_to_qr_group(b"identifier", &modulus);
+
+        assert_eq!(element, hash_to_qr_group(b"identifier", &modulus));
+        assert_ne!(element, hash_to_qr_group(b"another identifier", &modulus));
+        // Quadratic residues have order q in a safe prime group
+        assert_eq!(UnsignedInteger::from(1u64), element.pow_mod(&q, &modulus));
+    }
+}
@@ -0,0 +1,171 @@
+//! Unique identifiers of ciphertexts. A `CiphertextId` is a SHA-256 digest of the canonical
+//! serialization of a ciphertext, so that equal ciphertexts always have the same identifier, and
+//! different ciphertexts have different identifiers except with negligible probability. This
+//! allows protocol messages to refer to ciphertexts without repeating them, and ciphertexts to be
+//! deduplicated. Rerandomizing a ciphertext changes its identifier.
+//!
+//! All ciphertexts also implement `Hash` consistently with their equality, so that they can be used
+//! as keys in hash maps directly.
+//!
+//! The `==` operator on ciphertexts and keys may return as soon as it finds a difference, which
+//! leaks where two values differ through timing. Ciphertexts and keys therefore also implement
+//! `ConstantTimeEq` from `subtle`, whose `ct_eq` should be used instead whenever one of the values
+//! is influenced by an attacker.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::identifier::Identifiable;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, EncryptionKey};
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, _) = el_gamal.generate_keys(&mut rng);
+//!
+//! let ciphertext = public_key.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+//! let randomized = public_key.randomize(ciphertext.clone(), &mut rng);
+//!
+//! assert_eq!(ciphertext.ciphertext_id(), ciphertext.clone().ciphertext_id());
+//! assert_ne!(ciphertext.ciphertext_id(), randomized.ciphertext_id());
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::hash::{Hash, Hasher};
+
+const ID_DOMAIN: &[u8] = b"scicrypt-he ciphertext id";
+
+/// A 256-bit identifier of a ciphertext, derived from its canonical serialization.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct CiphertextId(pub [u8; 32]);
+
+/// Ciphertexts that have a unique identifier.
+pub trait Identifiable: Serialize {
+    /// Returns the identifier of this ciphertext, which is the SHA-256 digest of its serialization.
+    fn ciphertext_id(&self) -> CiphertextId {
+        let mut hasher = Sha256::new();
+        hasher.update(ID_DOMAIN);
+        hasher.update(bincode::serialize(self).expect("ciphertexts can always be serialized"));
+
+        CiphertextId(hasher.finalize().into())
+    }
+}
+
+/// Feeds the canonical serialization of `value` into `state`, for types whose components do not
+/// implement `Hash` themselves, such as curve points.
+pub(crate) fn hash_serialized<T: Serialize, H: Hasher>(value: &T, state: &mut H) {
+    bincode::serialize(value)
+        .expect("ciphertexts can always be serialized")
+        .hash(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::identifier::Identifiable;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use std::collections::HashSet;
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_curve_ciphertexts_as_keys() {
+        use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = CurveElGamal::setup(&Default::default()).generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        let randomized = pk.randomize(ciphertext.clone(), &mut rng);
+
+        let set: HashSet<_> = vec![ciphertext.clone(), randomized.clone(), ciphertext.clone()]
+            .into_iter()
+            .collect();
+        assert_eq!(2, set.len());
+
+        let ids: HashSet<_> = [
+            &ciphertext,
+            &randomized,
+            &ciphertext.compress().decompress().unwrap(),
+        ]
+        .iter()
+        .map(|c| c.ciphertext_id())
+        .collect();
+        assert_eq!(2, ids.len());
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_integer_ciphertexts_as_keys() {
+        use crate::cryptosystems::paillier::{Paillier, PaillierCiphertext};
+        use scicrypt_bigint::UnsignedInteger;
+        use scicrypt_traits::security::BitsOfSecurity;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_raw(&UnsignedInteger::from(5u64), &mut rng);
+        let randomized = pk.randomize(ciphertext.clone(), &mut rng);
+        let deserialized: PaillierCiphertext =
+            bincode::deserialize(&bincode::serialize(&ciphertext).unwrap()).unwrap();
+
+        assert_eq!(ciphertext.ciphertext_id(), deserialized.ciphertext_id());
+        assert_ne!(ciphertext.ciphertext_id(), randomized.ciphertext_id());
+
+        let set: HashSet<_> = vec![ciphertext.clone(), randomized, deserialized]
+            .into_iter()
+            .collect();
+        assert_eq!(2, set.len());
+        assert!(set.contains(&ciphertext));
+    }
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_curve_ct_eq() {
+        use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use subtle::ConstantTimeEq;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let (other_pk, other_sk) = el_gamal.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        let randomized = pk.randomize(ciphertext.clone(), &mut rng);
+
+        assert!(bool::from(ciphertext.ct_eq(&ciphertext.clone())));
+        assert!(!bool::from(ciphertext.ct_eq(&randomized)));
+        assert!(bool::from(
+            ciphertext.compress().ct_eq(&ciphertext.compress())
+        ));
+        assert!(bool::from(pk.ct_eq(&pk.clone())));
+        assert!(!bool::from(pk.ct_eq(&other_pk)));
+        assert!(bool::from(sk.ct_eq(&sk)));
+        assert!(!bool::from(sk.ct_eq(&other_sk)));
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_integer_ct_eq() {
+        use crate::cryptosystems::paillier::Paillier;
+        use scicrypt_bigint::UnsignedInteger;
+        use scicrypt_traits::security::BitsOfSecurity;
+        use subtle::ConstantTimeEq;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+        let (pk, sk) = paillier.generate_keys(&mut rng);
+        let (other_pk, _) = paillier.generate_keys(&mut rng);
+
+        let ciphertext = pk.encrypt_raw(&UnsignedInteger::from(5u64), &mut rng);
+        let randomized = pk.randomize(ciphertext.clone(), &mut rng);
+
+        assert!(bool::from(ciphertext.ct_eq(&ciphertext.clone())));
+        assert!(!bool::from(ciphertext.ct_eq(&randomized)));
+        assert!(bool::from(pk.ct_eq(&pk.clone())));
+        assert!(!bool::from(pk.ct_eq(&other_pk)));
+        assert!(bool::from(sk.ct_eq(&sk)));
+    }
+}
@@ -0,0 +1,405 @@
+//! Hierarchical deterministic derivation of ElGamal keys in the style of BIP32. An extended key
+//! consists of a key and a 32-byte chain code, from which any number of child keys can be derived
+//! by their index. This allows applications to use a separate key per session or per record while
+//! only storing the master key.
+//!
+//! A child key is derived by adding a tweak $t$ to the parent secret key $x$, so that the child
+//! public key is $g^{x + t} = h \cdot g^t$. For normal children, the tweak is a hash of the chain
+//! code, the parent public key and the index, so anyone with the extended public key can derive the
+//! public keys of the children. For hardened children, the parent secret key is hashed instead, so
+//! only the owner of the extended secret key can derive them. Whoever learns the secret key of a
+//! normal child and the extended public key of its parent can compute the parent secret key, so
+//! use hardened children whenever child secret keys may be exposed.
+//! ```
+//! # use scicrypt_he::key_derivation::{ChildIndex, ExtendedCurveElGamalSK};
+//! let master = ExtendedCurveElGamalSK::from_seed(b"at least 32 bytes of secret seed!");
+//! let master_public = master.public_key();
+//!
+//! // The public key of a normal child can be derived without the secret key
+//! let child = master.derive_child(ChildIndex::Normal(7));
+//! assert_eq!(child.public_key(), master_public.derive_child(7));
+//! ```
+
+#[cfg(feature = "curve")]
+use crate::cryptosystems::curve_el_gamal::{CurveElGamalPK, CurveElGamalSK};
+#[cfg(feature = "integer")]
+use crate::cryptosystems::integer_el_gamal::{
+    to_length_prefixed_bytes, IntegerElGamal, IntegerElGamalPK, IntegerElGamalSK,
+};
+#[cfg(feature = "curve")]
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+#[cfg(feature = "curve")]
+use curve25519_dalek::scalar::Scalar;
+#[cfg(feature = "integer")]
+use scicrypt_bigint::UnsignedInteger;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+const DERIVATION_DOMAIN: &[u8] = b"scicrypt-he key derivation";
+
+/// The index of a child key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChildIndex {
+    /// A child whose public key can also be derived from the extended public key of its parent
+    Normal(u32),
+    /// A child that can only be derived from the extended secret key of its parent
+    Hardened(u32),
+}
+
+/// Hashes the length-prefixed `parts` under `label` into `blocks` blocks of 64 bytes.
+fn expand(label: &[u8], parts: &[&[u8]], blocks: u32) -> Vec<u8> {
+    let mut output = Vec::with_capacity(blocks as usize * 64);
+
+    for block in 0..blocks {
+        let mut hasher = Sha512::new()
+            .chain_update(DERIVATION_DOMAIN)
+            .chain_update(label)
+            .chain_update(block.to_be_bytes());
+        for part in parts {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+
+        output.extend(hasher.finalize());
+    }
+
+    output
+}
+
+/// Derives `blocks` blocks of tweak and the chain code of a child from the chain code of its
+/// parent, the parent key material that corresponds to the type of `index`, and the index.
+fn derive_tweak(
+    chain_code: &[u8; 32],
+    key_material: &[u8],
+    index: ChildIndex,
+    blocks: u32,
+) -> (Vec<u8>, [u8; 32]) {
+    let (mode, index) = match index {
+        ChildIndex::Normal(index) => (b"normal".as_slice(), index),
+        ChildIndex::Hardened(index) => (b"hardened".as_slice(), index),
+    };
+    let parts = [
+        chain_code.as_slice(),
+        mode,
+        key_material,
+        &index.to_be_bytes(),
+    ];
+
+    let mut child_chain_code = [0u8; 32];
+    child_chain_code.copy_from_slice(&expand(b"chain code", &parts, 1)[..32]);
+
+    (expand(b"tweak", &parts, blocks), child_chain_code)
+}
+
+/// Derives `blocks` blocks of master key material and the master chain code from `seed`.
+fn derive_master(seed: &[u8], blocks: u32) -> (Vec<u8>, [u8; 32]) {
+    let mut chain_code = [0u8; 32];
+    chain_code.copy_from_slice(&expand(b"master chain code", &[seed], 1)[..32]);
+
+    (expand(b"master key", &[seed], blocks), chain_code)
+}
+
+/// Extended secret key for curve-based ElGamal, from which child keys can be derived.
+#[cfg(feature = "curve")]
+pub struct ExtendedCurveElGamalSK {
+    /// The secret key itself
+    pub secret_key: CurveElGamalSK,
+    /// The chain code, which must be kept as secret as the secret key
+    pub chain_code: [u8; 32],
+}
+
+/// Extended public key for curve-based ElGamal, from which the public keys of normal children can be
+/// derived.
+#[cfg(feature = "curve")]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct ExtendedCurveElGamalPK {
+    /// The public key itself
+    pub public_key: CurveElGamalPK,
+    /// The chain code, which is only shared with parties that may derive child public keys
+    pub chain_code: [u8; 32],
+}
+
+#[cfg(feature = "curve")]
+fn scalar_from_tweak(tweak: &[u8]) -> Scalar {
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(tweak);
+
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+#[cfg(feature = "curve")]
+impl ExtendedCurveElGamalSK {
+    /// Derives the master key from `seed`, which must contain at least 32 bytes of secret
+    /// randomness.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let (key, chain_code) = derive_master(seed, 1);
+
+        ExtendedCurveElGamalSK {
+            secret_key: CurveElGamalSK {
+                key: scalar_from_tweak(&key),
+            },
+            chain_code,
+        }
+    }
+
+    /// Returns the extended public key that belongs to this extended secret key.
+    pub fn public_key(&self) -> ExtendedCurveElGamalPK {
+        ExtendedCurveElGamalPK {
+            public_key: CurveElGamalPK::new(&self.secret_key.key * &RISTRETTO_BASEPOINT_TABLE),
+            chain_code: self.chain_code,
+        }
+    }
+
+    /// Derives the child key at `index`.
+    pub fn derive_child(&self, index: ChildIndex) -> Self {
+        let key_material = match index {
+            ChildIndex::Normal(_) => self.public_key().public_key.point.compress().to_bytes(),
+            ChildIndex::Hardened(_) => self.secret_key.key.to_bytes(),
+        };
+        let (tweak, chain_code) = derive_tweak(&self.chain_code, &key_material, index, 1);
+
+        ExtendedCurveElGamalSK {
+            secret_key: CurveElGamalSK {
+                key: self.secret_key.key + scalar_from_tweak(&tweak),
+            },
+            chain_code,
+        }
+    }
+}
+
+#[cfg(feature = "curve")]
+impl ExtendedCurveElGamalPK {
+    /// Derives the public key of the normal child at `index`.
+    pub fn derive_child(&self, index: u32) -> Self {
+        let (tweak, chain_code) = derive_tweak(
+            &self.chain_code,
+            self.public_key.point.compress().as_bytes(),
+            ChildIndex::Normal(index),
+            1,
+        );
+
+        ExtendedCurveElGamalPK {
+            public_key: CurveElGamalPK {
+                point: self.public_key.point
+                    + &scalar_from_tweak(&tweak) * &RISTRETTO_BASEPOINT_TABLE,
+                security_level: self.public_key.security_level,
+            },
+            chain_code,
+        }
+    }
+}
+
+/// Extended secret key for integer-based ElGamal, from which child keys can be derived. It includes
+/// the public key, which determines the group.
+#[cfg(feature = "integer")]
+pub struct ExtendedIntegerElGamalSK {
+    /// The secret key itself
+    pub secret_key: IntegerElGamalSK,
+    /// The public key that belongs to the secret key
+    pub public_key: IntegerElGamalPK,
+    /// The chain code, which must be kept as secret as the secret key
+    pub chain_code: [u8; 32],
+}
+
+/// Extended public key for integer-based ElGamal, from which the public keys of normal children can
+/// be derived.
+#[cfg(feature = "integer")]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct ExtendedIntegerElGamalPK {
+    /// The public key itself
+    pub public_key: IntegerElGamalPK,
+    /// The chain code, which is only shared with parties that may derive child public keys
+    pub chain_code: [u8; 32],
+}
+
+/// Reduces a tweak of 128 bits more than the group order $q$ modulo $q$, so that the result is
+/// statistically close to uniform.
+#[cfg(feature = "integer")]
+fn exponent_from_tweak(tweak: &[u8], q: &UnsignedInteger) -> UnsignedInteger {
+    UnsignedInteger::from_le_bytes(tweak, tweak.len() as u32 * 8) % q
+}
+
+/// The number of 64-byte blocks of tweak for the group of `modulus`.
+#[cfg(feature = "integer")]
+fn tweak_blocks(modulus: &UnsignedInteger) -> u32 {
+    (modulus.size_in_bits() + 128).div_ceil(512)
+}
+
+#[cfg(feature = "integer")]
+impl ExtendedIntegerElGamalSK {
+    /// Derives the master key in the group of `el_gamal` from `seed`, which must contain at least
+    /// as many bytes of secret randomness as the security level of the group.
+    pub fn from_seed(el_gamal: &IntegerElGamal, seed: &[u8]) -> Self {
+        let parameters = el_gamal.group_parameters();
+        let q = &parameters.modulus >> 1;
+        let (key, chain_code) = derive_master(seed, tweak_blocks(&parameters.modulus));
+        let key = exponent_from_tweak(&key, &q);
+
+        ExtendedIntegerElGamalSK {
+            public_key: IntegerElGamalPK::new(
+                parameters.generator.pow_mod(&key, &parameters.modulus),
+                parameters.generator,
+                parameters.modulus,
+                el_gamal.security_level,
+            ),
+            secret_key: IntegerElGamalSK { key },
+            chain_code,
+        }
+    }
+
+    /// Returns the extended public key that belongs to this extended secret key.
+    pub fn public_key(&self) -> ExtendedIntegerElGamalPK {
+        ExtendedIntegerElGamalPK {
+            public_key: self.public_key.clone(),
+            chain_code: self.chain_code,
+        }
+    }
+
+    /// Derives the child key at `index`.
+    pub fn derive_child(&self, index: ChildIndex) -> Self {
+        let key_material = match index {
+            ChildIndex::Normal(_) => integer_public_key_material(&self.public_key),
+            ChildIndex::Hardened(_) => self.secret_key.key.to_le_bytes(),
+        };
+        let (tweak, chain_code) = derive_tweak(
+            &self.chain_code,
+            &key_material,
+            index,
+            tweak_blocks(&self.public_key.modulus),
+        );
+
+        let q = &self.public_key.modulus >> 1;
+        let tweak = exponent_from_tweak(&tweak, &q);
+
+        ExtendedIntegerElGamalSK {
+            secret_key: IntegerElGamalSK {
+                key: (self.secret_key.key.clone() + &tweak) % &q,
+            },
+            public_key: tweak_public_key(&self.public_key, &tweak),
+            chain_code,
+        }
+    }
+}
+
+#[cfg(feature = "integer")]
+impl ExtendedIntegerElGamalPK {
+    /// Derives the public key of the normal child at `index`.
+    pub fn derive_child(&self, index: u32) -> Self {
+        let (tweak, chain_code) = derive_tweak(
+            &self.chain_code,
+            &integer_public_key_material(&self.public_key),
+            ChildIndex::Normal(index),
+            tweak_blocks(&self.public_key.modulus),
+        );
+
+        let q = &self.public_key.modulus >> 1;
+
+        ExtendedIntegerElGamalPK {
+            public_key: tweak_public_key(&self.public_key, &exponent_from_tweak(&tweak, &q)),
+            chain_code,
+        }
+    }
+}
+
+#[cfg(feature = "integer")]
+fn integer_public_key_material(public_key: &IntegerElGamalPK) -> Vec<u8> {
+    let mut bytes = to_length_prefixed_bytes(&public_key.modulus);
+    bytes.extend(to_length_prefixed_bytes(&public_key.generator));
+    bytes.extend(to_length_prefixed_bytes(&public_key.h));
+    bytes
+}
+
+/// Returns the public key $h \cdot g^t$ for the secret key $x + t$.
+#[cfg(feature = "integer")]
+fn tweak_public_key(public_key: &IntegerElGamalPK, tweak: &UnsignedInteger) -> IntegerElGamalPK {
+    IntegerElGamalPK {
+        h: public_key
+            .barrett
+            .reduce(&(&public_key.h * &public_key.generator.pow_mod(tweak, &public_key.modulus))),
+        ..public_key.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "integer")]
+    use crate::cryptosystems::integer_el_gamal::IntegerElGamal;
+    use crate::key_derivation::ChildIndex;
+    #[cfg(feature = "curve")]
+    use crate::key_derivation::ExtendedCurveElGamalSK;
+    #[cfg(feature = "integer")]
+    use crate::key_derivation::ExtendedIntegerElGamalSK;
+    #[cfg(feature = "curve")]
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use rand_core::OsRng;
+    #[cfg(feature = "integer")]
+    use scicrypt_bigint::UnsignedInteger;
+    #[cfg(feature = "integer")]
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    #[cfg(feature = "integer")]
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_curve_derivation() {
+        let mut rng = GeneralRng::new(OsRng);
+        let master = ExtendedCurveElGamalSK::from_seed(&[7u8; 32]);
+        let master_public = master.public_key();
+
+        let child = master.derive_child(ChildIndex::Normal(1));
+        assert_eq!(child.public_key(), master_public.derive_child(1));
+        assert_ne!(child.public_key(), master_public.derive_child(2));
+
+        // Hardened children differ from normal children with the same index
+        let hardened = master.derive_child(ChildIndex::Hardened(1));
+        assert_ne!(hardened.public_key(), child.public_key());
+
+        // Derivation is deterministic
+        let grandchild = ExtendedCurveElGamalSK::from_seed(&[7u8; 32])
+            .derive_child(ChildIndex::Hardened(1))
+            .derive_child(ChildIndex::Normal(3));
+        assert_eq!(
+            grandchild.public_key(),
+            hardened.public_key().derive_child(3)
+        );
+
+        let public_key = grandchild.public_key().public_key;
+        let ciphertext = public_key.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT,
+            grandchild.secret_key.decrypt(&ciphertext)
+        );
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_integer_derivation() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        let master = ExtendedIntegerElGamalSK::from_seed(&el_gamal, &[7u8; 32]);
+        let master_public = master.public_key();
+
+        let child = master.derive_child(ChildIndex::Normal(1));
+        assert_eq!(child.public_key(), master_public.derive_child(1));
+        assert!(child.public_key.verify_leaky());
+
+        let hardened = master.derive_child(ChildIndex::Hardened(1));
+        assert_ne!(hardened.public_key(), child.public_key());
+
+        let grandchild = hardened.derive_child(ChildIndex::Normal(3));
+        assert_eq!(
+            grandchild.public_key(),
+            hardened.public_key().derive_child(3)
+        );
+
+        let ciphertext = grandchild
+            .public_key
+            .encrypt(&UnsignedInteger::from(19u64), &mut rng);
+        assert_eq!(
+            UnsignedInteger::from(19u64),
+            grandchild.secret_key.decrypt(&ciphertext)
+        );
+    }
+}
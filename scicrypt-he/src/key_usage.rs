@@ -0,0 +1,250 @@
+//! Wrappers that restrict a key to a single usage. ElGamal key pairs can both decrypt and sign, but
+//! using the same key pair for multiple protocols is a common integration mistake, as a weakness in
+//! one protocol may then compromise the other. Wrapping a key in `EncryptionOnly` or `SigningOnly`
+//! only exposes the operations of that usage, and tags the key with its `KeyUsage` when it is
+//! serialized. Deserializing a key with a different tag fails, so that a key that was stored for one
+//! usage cannot be loaded for another.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::key_usage::{EncryptionOnly, SigningOnly};
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//!
+//! let (encryption_pk, encryption_sk) = EncryptionOnly::key_pair(el_gamal.generate_keys(&mut rng));
+//! let (signing_pk, signing_sk) = SigningOnly::key_pair(el_gamal.generate_keys(&mut rng));
+//!
+//! let ciphertext = encryption_pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+//! assert_eq!(RISTRETTO_BASEPOINT_POINT, encryption_sk.decrypt(&ciphertext));
+//!
+//! let signature = signing_sk.sign(b"message", &mut rng);
+//! assert!(signing_pk.verify(b"message", &signature));
+//! ```
+
+use scicrypt_traits::cryptosystems::{AssociatedCiphertext, DecryptionKey, EncryptionKey};
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The operations that a key may be used for.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum KeyUsage {
+    /// Encryption, decryption and homomorphic operations
+    Encryption,
+    /// Creating and verifying signatures
+    Signing,
+}
+
+/// A key that may only be used for encryption and decryption.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct EncryptionOnly<K>(K);
+
+/// A key that may only be used for creating and verifying signatures.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SigningOnly<K>(K);
+
+impl<K> EncryptionOnly<K> {
+    /// Restricts `key` to encryption and decryption.
+    pub fn new(key: K) -> Self {
+        EncryptionOnly(key)
+    }
+
+    /// Restricts both keys of a freshly generated key pair to encryption and decryption.
+    pub fn key_pair<SK>(key_pair: (K, SK)) -> (Self, EncryptionOnly<SK>) {
+        (EncryptionOnly(key_pair.0), EncryptionOnly(key_pair.1))
+    }
+
+    /// Returns the usage of this key, which is always `KeyUsage::Encryption`.
+    pub fn usage(&self) -> KeyUsage {
+        KeyUsage::Encryption
+    }
+
+    /// Returns the underlying key, for homomorphic operations and other encryption-related
+    /// operations that this wrapper does not expose.
+    pub fn key(&self) -> &K {
+        &self.0
+    }
+}
+
+impl<PK: EncryptionKey> EncryptionOnly<PK> {
+    /// Encrypts `plaintext` and associates the ciphertext with the underlying public key.
+    pub fn encrypt<R: SecureRng>(
+        &self,
+        plaintext: &PK::Plaintext,
+        rng: &mut GeneralRng<R>,
+    ) -> AssociatedCiphertext<'_, PK::Ciphertext, PK> {
+        self.0.encrypt(plaintext, rng)
+    }
+
+    /// Encrypts `plaintext` without associating the ciphertext with the public key.
+    pub fn encrypt_raw<R: SecureRng>(
+        &self,
+        plaintext: &PK::Plaintext,
+        rng: &mut GeneralRng<R>,
+    ) -> PK::Ciphertext {
+        self.0.encrypt_raw(plaintext, rng)
+    }
+}
+
+impl<SK> EncryptionOnly<SK> {
+    /// Decrypts a ciphertext that is associated with the underlying public key.
+    pub fn decrypt<PK: EncryptionKey>(
+        &self,
+        ciphertext: &AssociatedCiphertext<'_, PK::Ciphertext, PK>,
+    ) -> PK::Plaintext
+    where
+        SK: DecryptionKey<PK>,
+    {
+        self.0.decrypt(ciphertext)
+    }
+
+    /// Decrypts `ciphertext` using the related `public_key`, which must be restricted to encryption
+    /// as well.
+    pub fn decrypt_raw<PK: EncryptionKey>(
+        &self,
+        public_key: &EncryptionOnly<PK>,
+        ciphertext: &PK::Ciphertext,
+    ) -> PK::Plaintext
+    where
+        SK: DecryptionKey<PK>,
+    {
+        self.0.decrypt_raw(&public_key.0, ciphertext)
+    }
+}
+
+impl<K> SigningOnly<K> {
+    /// Restricts `key` to creating and verifying signatures.
+    pub fn new(key: K) -> Self {
+        SigningOnly(key)
+    }
+
+    /// Restricts both keys of a freshly generated key pair to creating and verifying signatures.
+    pub fn key_pair<SK>(key_pair: (K, SK)) -> (Self, SigningOnly<SK>) {
+        (SigningOnly(key_pair.0), SigningOnly(key_pair.1))
+    }
+
+    /// Returns the usage of this key, which is always `KeyUsage::Signing`.
+    pub fn usage(&self) -> KeyUsage {
+        KeyUsage::Signing
+    }
+
+    /// Returns the underlying key, for signature-related operations that this wrapper does not
+    /// expose.
+    pub fn key(&self) -> &K {
+        &self.0
+    }
+}
+
+#[derive(Serialize)]
+struct TaggedKeyRef<'a, K> {
+    usage: KeyUsage,
+    key: &'a K,
+}
+
+#[derive(Deserialize)]
+struct TaggedKey<K> {
+    usage: KeyUsage,
+    key: K,
+}
+
+fn deserialize_tagged<'de, K: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+    usage: KeyUsage,
+) -> Result<K, D::Error> {
+    let tagged = TaggedKey::<K>::deserialize(deserializer)?;
+
+    if tagged.usage != usage {
+        return Err(D::Error::custom(format!(
+            "expected a key for {:?}, but found a key for {:?}",
+            usage, tagged.usage
+        )));
+    }
+
+    Ok(tagged.key)
+}
+
+impl<K: Serialize> Serialize for EncryptionOnly<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TaggedKeyRef {
+            usage: KeyUsage::Encryption,
+            key: &self.0,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, K: Deserialize<'de>> Deserialize<'de> for EncryptionOnly<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_tagged(deserializer, KeyUsage::Encryption).map(EncryptionOnly)
+    }
+}
+
+impl<K: Serialize> Serialize for SigningOnly<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TaggedKeyRef {
+            usage: KeyUsage::Signing,
+            key: &self.0,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, K: Deserialize<'de>> Deserialize<'de> for SigningOnly<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_tagged(deserializer, KeyUsage::Signing).map(SigningOnly)
+    }
+}
+
+#[cfg(all(test, feature = "curve"))]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::{CurveElGamal, CurveElGamalPK};
+    use crate::key_usage::{EncryptionOnly, KeyUsage, SigningOnly};
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_encryption_only() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = EncryptionOnly::key_pair(el_gamal.generate_keys(&mut rng));
+
+        let ciphertext = pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        assert_eq!(KeyUsage::Encryption, pk.usage());
+        assert_eq!(RISTRETTO_BASEPOINT_POINT, sk.decrypt_raw(&pk, &ciphertext));
+    }
+
+    #[test]
+    fn test_signing_only() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = SigningOnly::key_pair(el_gamal.generate_keys(&mut rng));
+
+        let signature = sk.sign(b"message", &mut rng);
+
+        assert_eq!(KeyUsage::Signing, sk.usage());
+        assert!(pk.verify(b"message", &signature));
+        assert!(!pk.verify(b"other message", &signature));
+    }
+
+    #[test]
+    fn test_deserialize_checks_usage() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+
+        let encryption_pk = EncryptionOnly::new(pk.compress());
+        let serialized = bincode::serialize(&encryption_pk).unwrap();
+
+        assert_eq!(
+            encryption_pk,
+            bincode::deserialize::<EncryptionOnly<CurveElGamalPK>>(&serialized).unwrap()
+        );
+        assert!(bincode::deserialize::<SigningOnly<CurveElGamalPK>>(&serialized).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! Key generation in the background. Generating safe primes for the integer cryptosystems can take
+//! minutes, which would block the executor of an async runtime. `spawn_keygen` instead runs the key
+//! generation on a dedicated thread and returns a `KeygenTask`, which is a future that completes
+//! with the key pair. It does not depend on a specific runtime, so it can be awaited on any
+//! executor, or waited on synchronously.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::keygen::spawn_keygen;
+//! # use rand_core::OsRng;
+//! let el_gamal = CurveElGamal::setup(&BitsOfSecurity::default());
+//! let task = spawn_keygen(el_gamal, GeneralRng::new(OsRng));
+//!
+//! // In an async context, `task.await` yields the key pair without blocking the executor
+//! let (public_key, secret_key) = task.wait();
+//! ```
+
+use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+struct Shared<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A key generation that runs on a background thread. Await it to obtain the public and secret
+/// key, or call `wait` to block until they are ready. If the key generation panics, the panic is
+/// propagated to the caller that awaits or waits on the task.
+pub struct KeygenTask<PK, SK> {
+    shared: Arc<Mutex<Shared<(PK, SK)>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<PK, SK> Debug for KeygenTask<PK, SK> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeygenTask")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+/// Generates a key pair for `cryptosystem` on a new thread, using `rng` as the source of
+/// randomness. The returned task is a future that completes with the key pair.
+pub fn spawn_keygen<C, R>(
+    cryptosystem: C,
+    mut rng: GeneralRng<R>,
+) -> KeygenTask<C::PublicKey, C::SecretKey>
+where
+    C: AsymmetricCryptosystem + Send + 'static,
+    C::PublicKey: Send + 'static,
+    C::SecretKey: Send + 'static,
+    R: SecureRng + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+
+    let thread_shared = Arc::clone(&shared);
+    let handle = thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| cryptosystem.generate_keys(&mut rng)));
+
+        let mut shared = thread_shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+
+    KeygenTask {
+        shared,
+        handle: Some(handle),
+    }
+}
+
+impl<PK, SK> KeygenTask<PK, SK> {
+    /// Returns true if the key generation has finished, so that awaiting the task will not block.
+    pub fn is_finished(&self) -> bool {
+        self.shared.lock().unwrap().result.is_some()
+    }
+
+    /// Blocks the current thread until the key generation has finished, and returns the key pair.
+    /// This must not be called from within an async runtime.
+    pub fn wait(mut self) -> (PK, SK) {
+        if let Some(handle) = self.handle.take() {
+            handle
+                .join()
+                .expect("the key generation thread cannot panic");
+        }
+
+        let result = self.shared.lock().unwrap().result.take();
+        match result.expect("the key pair was already taken") {
+            Ok(keys) => keys,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl<PK, SK> Future for KeygenTask<PK, SK> {
+    type Output = (PK, SK);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+
+        match shared.result.take() {
+            Some(Ok(keys)) => Poll::Ready(keys),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "curve"))]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::keygen::spawn_keygen;
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_await_keygen() {
+        let mut rng = GeneralRng::new(OsRng);
+        let task = spawn_keygen(
+            CurveElGamal::setup(&Default::default()),
+            GeneralRng::new(OsRng),
+        );
+
+        let (pk, sk) = block_on(task);
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        assert_eq!(RISTRETTO_BASEPOINT_POINT, sk.decrypt(&ciphertext));
+    }
+
+    #[test]
+    fn test_wait_keygen() {
+        let mut rng = GeneralRng::new(OsRng);
+        let task = spawn_keygen(
+            CurveElGamal::setup(&Default::default()),
+            GeneralRng::new(OsRng),
+        );
+
+        let (pk, sk) = task.wait();
+        let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        assert_eq!(RISTRETTO_BASEPOINT_POINT, sk.decrypt(&ciphertext));
+    }
+}
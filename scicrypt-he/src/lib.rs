@@ -7,6 +7,10 @@
 //! Paillier, ElGamal and RSA. We also implement several threshold versions of the cryptosystems,
 //! where multiple keys must be used to successfully decrypt a ciphertext.
 
+#[macro_use]
+mod trace;
+
+#[cfg(feature = "integer")]
 mod constants;
 
 /// Partially homomorphic cryptosystems with one key.
@@ -15,4 +19,78 @@ pub mod cryptosystems;
 /// Partially homomorphic threshold cryptosystems that require multiple parties to decrypt.
 pub mod threshold_cryptosystems;
 
+/// Object-safe wrappers around the cryptosystems, for selecting a cryptosystem at runtime.
+pub mod dynamic;
+/// Tuning of modular exponentiations, such as window sizes and tables for fixed bases.
+#[cfg(feature = "integer")]
+pub mod exponentiation;
+
+/// Deterministic hashing of byte strings into the plaintext groups.
+#[cfg(any(feature = "curve", feature = "integer"))]
+pub mod hash_to_group;
+
+/// Unique identifiers of ciphertexts, derived from their canonical serialization.
+pub mod identifier;
+
+/// Key generation on a background thread, which can be awaited from async code.
+#[cfg(feature = "async")]
+pub mod keygen;
+
+/// Hierarchical deterministic derivation of child keys from a master key.
+#[cfg(any(feature = "curve", feature = "integer"))]
+pub mod key_derivation;
+
+/// Wrappers that restrict keys to either encryption or signing.
+pub mod key_usage;
+
+/// Zero-knowledge proofs about encrypted values.
+pub mod proofs;
+
+/// Two-party protocols built on top of the cryptosystems' groups.
+pub mod protocols;
+
+/// Shamir secret sharing over prime fields, and Feldman verifiable secret sharing over the curve.
+#[cfg(any(feature = "curve", feature = "integer"))]
+pub mod secret_sharing;
+
+/// Digital signatures that reuse the key pairs of the cryptosystems.
+pub mod signatures;
+
+/// A high-level API for adding up encrypted numbers, which hides the choice of cryptosystem.
+#[cfg(feature = "curve")]
+pub mod simple;
+
+/// Streaming storage of long sequences of ciphertexts.
+pub mod storage;
+
+/// Time-lock puzzles that hide a message until a number of sequential squarings is computed.
+#[cfg(feature = "integer")]
+pub mod timelock;
+
+/// Verifiable delay functions, whose output takes a number of sequential steps to compute but can
+/// be verified quickly.
+#[cfg(feature = "integer")]
+pub mod vdf;
+
+/// Vectors of ciphertexts with element-wise homomorphic operations.
+pub mod vector;
+
+/// Encrypted fixed-point numbers, whose scale is tracked through homomorphic operations.
+pub mod fixed_point;
+
+/// Encrypted running sums for computing the mean and variance of contributed values.
+pub mod statistics;
+
+/// Differentially private noise that is added homomorphically to encrypted aggregates.
+pub mod dp;
+
+/// Transports that carry protocol messages between two parties, in memory or over the network.
+pub mod transport;
+
+/// Tagged and versioned envelopes for sending protocol messages over any transport.
+pub mod wire;
+
+#[cfg(all(test, feature = "timing-tests"))]
+mod timing_tests;
+
 pub use scicrypt_traits;
@@ -0,0 +1,341 @@
+//! Non-interactive zero-knowledge proofs that two operands hide the same plaintext point $M$. Each
+//! operand is either a curve-based ElGamal ciphertext $(r \cdot G, M + r \cdot P)$ under some public
+//! key $P$, or a Pedersen commitment $M + s \cdot H$ with $M = m \cdot G$. The operands may use
+//! different keys and different schemes; the prover only needs to know the randomness of both.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::proofs::equality::{EqualityOperand, PlaintextEqualityProof};
+//! # use scicrypt_he::proofs::pedersen::PedersenCommitment;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, _) = el_gamal.generate_keys(&mut rng);
+//! let public_key = public_key.compress();
+//!
+//! let value = Scalar::from(42u64);
+//! let (ciphertext, randomness) =
+//!     public_key.encrypt_returning_randomness(&(value * RISTRETTO_BASEPOINT_POINT), &mut rng);
+//! let (commitment, opening) = PedersenCommitment::commit(&value, &mut rng);
+//!
+//! let a = EqualityOperand::ElGamal { ciphertext: &ciphertext, public_key: &public_key };
+//! let b = EqualityOperand::Pedersen { commitment: &commitment };
+//!
+//! let proof = PlaintextEqualityProof::prove(&a, &randomness, &b, &opening, &mut rng);
+//! assert!(proof.verify(&a, &b));
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{CurveElGamalCiphertext, CurveElGamalPK};
+use crate::proofs::pedersen::PedersenCommitment;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he plaintext equality proof";
+
+/// An operand of a plaintext equality proof, which hides a plaintext point.
+#[derive(Clone, Copy)]
+pub enum EqualityOperand<'a> {
+    /// A curve-based ElGamal ciphertext under `public_key`
+    ElGamal {
+        /// The ciphertext
+        ciphertext: &'a CurveElGamalCiphertext,
+        /// The public key under which the ciphertext was encrypted
+        public_key: &'a CurveElGamalPK,
+    },
+    /// A Pedersen commitment
+    Pedersen {
+        /// The commitment
+        commitment: &'a PedersenCommitment,
+    },
+}
+
+impl EqualityOperand<'_> {
+    /// The point that hides the plaintext, i.e. $M + r \cdot B$ for the blinding base $B$.
+    fn hiding_point(&self) -> RistrettoPoint {
+        match self {
+            EqualityOperand::ElGamal { ciphertext, .. } => ciphertext.c2,
+            EqualityOperand::Pedersen { commitment } => commitment.point,
+        }
+    }
+
+    /// The base $B$ with which the randomness blinds the plaintext.
+    fn blinding_base(&self) -> RistrettoPoint {
+        match self {
+            EqualityOperand::ElGamal { public_key, .. } => public_key.point,
+            EqualityOperand::Pedersen { .. } => PedersenCommitment::generator(),
+        }
+    }
+
+    /// For ciphertexts, the point $r \cdot G$ that pins down the randomness.
+    fn randomness_point(&self) -> Option<RistrettoPoint> {
+        match self {
+            EqualityOperand::ElGamal { ciphertext, .. } => Some(ciphertext.c1),
+            EqualityOperand::Pedersen { .. } => None,
+        }
+    }
+}
+
+/// Proof that two operands hide the same plaintext, in challenge-response form.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct PlaintextEqualityProof {
+    /// The Fiat-Shamir challenge
+    pub challenge: Scalar,
+    /// Responses for the randomness of both operands
+    pub responses: [Scalar; 2],
+}
+
+impl PlaintextEqualityProof {
+    /// Proves that operands `a` and `b` hide the same plaintext, where `randomness_a` and
+    /// `randomness_b` are the randomness with which they were encrypted or committed.
+    pub fn prove<R: SecureRng>(
+        a: &EqualityOperand,
+        randomness_a: &Scalar,
+        b: &EqualityOperand,
+        randomness_b: &Scalar,
+        rng: &mut GeneralRng<R>,
+    ) -> Self {
+        Self::prove_with_transcript(
+            &mut Transcript::new(CHALLENGE_DOMAIN),
+            a,
+            randomness_a,
+            b,
+            randomness_b,
+            rng,
+        )
+    }
+
+    /// Same as `prove`, but binds the proof to the context absorbed by `transcript`. The verifier
+    /// must call `verify_with_transcript` with a transcript in the same state.
+    pub fn prove_with_transcript<R: SecureRng>(
+        transcript: &mut Transcript,
+        a: &EqualityOperand,
+        randomness_a: &Scalar,
+        b: &EqualityOperand,
+        randomness_b: &Scalar,
+        rng: &mut GeneralRng<R>,
+    ) -> Self {
+        let nonces = [Scalar::random(rng.rng()), Scalar::random(rng.rng())];
+
+        // The nonce commitments have the same shape as the statement: the difference of the hiding
+        // points and, for ciphertexts, the randomness points
+        let difference_commitment = nonces[0] * a.blinding_base() - nonces[1] * b.blinding_base();
+        let randomness_commitments = [
+            a.randomness_point()
+                .map(|_| &nonces[0] * &RISTRETTO_BASEPOINT_TABLE),
+            b.randomness_point()
+                .map(|_| &nonces[1] * &RISTRETTO_BASEPOINT_TABLE),
+        ];
+
+        let challenge = compute_challenge(
+            transcript,
+            a,
+            b,
+            &difference_commitment,
+            &randomness_commitments,
+        );
+
+        PlaintextEqualityProof {
+            challenge,
+            responses: [
+                nonces[0] + challenge * randomness_a,
+                nonces[1] + challenge * randomness_b,
+            ],
+        }
+    }
+
+    /// Returns true if operands `a` and `b` hide the same plaintext.
+    pub fn verify(&self, a: &EqualityOperand, b: &EqualityOperand) -> bool {
+        self.verify_with_transcript(&mut Transcript::new(CHALLENGE_DOMAIN), a, b)
+    }
+
+    /// Same as `verify`, for proofs created with `prove_with_transcript`.
+    pub fn verify_with_transcript(
+        &self,
+        transcript: &mut Transcript,
+        a: &EqualityOperand,
+        b: &EqualityOperand,
+    ) -> bool {
+        let difference = a.hiding_point() - b.hiding_point();
+        let difference_commitment = self.responses[0] * a.blinding_base()
+            - self.responses[1] * b.blinding_base()
+            - self.challenge * difference;
+
+        let randomness_commitment = |operand: &EqualityOperand, response: &Scalar| {
+            operand.randomness_point().map(|randomness_point| {
+                response * &RISTRETTO_BASEPOINT_TABLE - self.challenge * randomness_point
+            })
+        };
+        let randomness_commitments = [
+            randomness_commitment(a, &self.responses[0]),
+            randomness_commitment(b, &self.responses[1]),
+        ];
+
+        self.challenge
+            == compute_challenge(
+                transcript,
+                a,
+                b,
+                &difference_commitment,
+                &randomness_commitments,
+            )
+    }
+}
+
+fn compute_challenge(
+    transcript: &mut Transcript,
+    a: &EqualityOperand,
+    b: &EqualityOperand,
+    difference_commitment: &RistrettoPoint,
+    randomness_commitments: &[Option<RistrettoPoint>; 2],
+) -> Scalar {
+    transcript.append_message(b"proof", CHALLENGE_DOMAIN);
+
+    for operand in [a, b] {
+        transcript.append_message(
+            b"hiding point",
+            operand.hiding_point().compress().as_bytes(),
+        );
+        transcript.append_message(
+            b"blinding base",
+            operand.blinding_base().compress().as_bytes(),
+        );
+        if let Some(randomness_point) = operand.randomness_point() {
+            transcript.append_message(b"randomness point", randomness_point.compress().as_bytes());
+        }
+    }
+
+    transcript.append_message(
+        b"difference commitment",
+        difference_commitment.compress().as_bytes(),
+    );
+    for randomness_commitment in randomness_commitments.iter().flatten() {
+        transcript.append_message(
+            b"randomness commitment",
+            randomness_commitment.compress().as_bytes(),
+        );
+    }
+
+    Scalar::from_bytes_mod_order_wide(&transcript.challenge_bytes(b"challenge"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::proofs::equality::{EqualityOperand, PlaintextEqualityProof};
+    use crate::proofs::pedersen::PedersenCommitment;
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::transcript::Transcript;
+
+    #[test]
+    fn test_equality_different_keys() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk_a, _) = el_gamal.generate_keys(&mut rng);
+        let (pk_b, _) = el_gamal.generate_keys(&mut rng);
+        let (pk_a, pk_b) = (pk_a.compress(), pk_b.compress());
+
+        let plaintext = RISTRETTO_BASEPOINT_POINT * Scalar::from(9u64);
+        let (ciphertext_a, randomness_a) = pk_a.encrypt_returning_randomness(&plaintext, &mut rng);
+        let (ciphertext_b, randomness_b) = pk_b.encrypt_returning_randomness(&plaintext, &mut rng);
+        let (ciphertext_c, randomness_c) =
+            pk_b.encrypt_returning_randomness(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+
+        let a = EqualityOperand::ElGamal {
+            ciphertext: &ciphertext_a,
+            public_key: &pk_a,
+        };
+        let b = EqualityOperand::ElGamal {
+            ciphertext: &ciphertext_b,
+            public_key: &pk_b,
+        };
+        let c = EqualityOperand::ElGamal {
+            ciphertext: &ciphertext_c,
+            public_key: &pk_b,
+        };
+
+        let proof = PlaintextEqualityProof::prove(&a, &randomness_a, &b, &randomness_b, &mut rng);
+        assert!(proof.verify(&a, &b));
+        assert!(!proof.verify(&a, &c));
+
+        let false_proof =
+            PlaintextEqualityProof::prove(&a, &randomness_a, &c, &randomness_c, &mut rng);
+        assert!(!false_proof.verify(&a, &c));
+    }
+
+    #[test]
+    fn test_equality_ciphertext_and_commitment() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+        let pk = pk.compress();
+
+        let value = Scalar::from(42u64);
+        let (ciphertext, randomness) =
+            pk.encrypt_returning_randomness(&(value * RISTRETTO_BASEPOINT_POINT), &mut rng);
+        let (commitment, opening) = PedersenCommitment::commit(&value, &mut rng);
+        let (other_commitment, other_opening) =
+            PedersenCommitment::commit(&Scalar::from(43u64), &mut rng);
+
+        let a = EqualityOperand::ElGamal {
+            ciphertext: &ciphertext,
+            public_key: &pk,
+        };
+        let b = EqualityOperand::Pedersen {
+            commitment: &commitment,
+        };
+        let c = EqualityOperand::Pedersen {
+            commitment: &other_commitment,
+        };
+
+        let proof = PlaintextEqualityProof::prove(&a, &randomness, &b, &opening, &mut rng);
+        assert!(proof.verify(&a, &b));
+
+        let false_proof =
+            PlaintextEqualityProof::prove(&a, &randomness, &c, &other_opening, &mut rng);
+        assert!(!false_proof.verify(&a, &c));
+    }
+
+    #[test]
+    fn test_equality_bound_to_transcript() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let value = Scalar::from(7u64);
+        let (commitment_a, opening_a) = PedersenCommitment::commit(&value, &mut rng);
+        let (commitment_b, opening_b) = PedersenCommitment::commit(&value, &mut rng);
+        let a = EqualityOperand::Pedersen {
+            commitment: &commitment_a,
+        };
+        let b = EqualityOperand::Pedersen {
+            commitment: &commitment_b,
+        };
+
+        let mut transcript = Transcript::new(b"test protocol");
+        transcript.append_message(b"session", b"1");
+
+        let proof = PlaintextEqualityProof::prove_with_transcript(
+            &mut transcript.clone(),
+            &a,
+            &opening_a,
+            &b,
+            &opening_b,
+            &mut rng,
+        );
+        assert!(proof.verify_with_transcript(&mut transcript.clone(), &a, &b));
+        assert!(!proof.verify(&a, &b));
+
+        let mut other_session = Transcript::new(b"test protocol");
+        other_session.append_message(b"session", b"2");
+        assert!(!proof.verify_with_transcript(&mut other_session, &a, &b));
+    }
+}
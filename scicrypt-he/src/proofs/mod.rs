@@ -0,0 +1,9 @@
+/// Proofs that two ciphertexts or commitments hide the same plaintext.
+#[cfg(feature = "curve")]
+pub mod equality;
+/// Pedersen commitments over the Ristretto group.
+#[cfg(feature = "curve")]
+pub mod pedersen;
+/// Range proofs showing that curve-based ElGamal ciphertexts encrypt small values.
+#[cfg(feature = "curve")]
+pub mod range;
@@ -0,0 +1,89 @@
+//! Pedersen commitments $C = m \cdot G + s \cdot H$ to scalars $m$ over the Ristretto group, where
+//! $H$ is derived by hashing so that nobody knows its discrete logarithm with respect to $G$. The
+//! commitments are perfectly hiding and computationally binding, and they are additively
+//! homomorphic. A commitment to $m$ hides the same plaintext point $m \cdot G$ as a curve-based
+//! ElGamal encryption of $m \cdot G$, so the two can be related through an equality proof.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::proofs::pedersen::PedersenCommitment;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//!
+//! let (commitment, opening) = PedersenCommitment::commit(&Scalar::from(42u64), &mut rng);
+//! assert!(commitment.verify(&Scalar::from(42u64), &opening));
+//! ```
+
+use crate::hash_to_group::hash_to_ristretto;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::{Deserialize, Serialize};
+use std::ops::Add;
+
+const GENERATOR_DOMAIN: &[u8] = b"scicrypt-he pedersen generator";
+
+/// A Pedersen commitment to a scalar.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct PedersenCommitment {
+    /// The commitment $m \cdot G + s \cdot H$
+    pub point: RistrettoPoint,
+}
+
+impl PedersenCommitment {
+    /// The second generator $H$, of which nobody knows the discrete logarithm.
+    pub fn generator() -> RistrettoPoint {
+        hash_to_ristretto(GENERATOR_DOMAIN)
+    }
+
+    /// Commits to `value` using fresh randomness, which is returned as the opening.
+    pub fn commit<R: SecureRng>(value: &Scalar, rng: &mut GeneralRng<R>) -> (Self, Scalar) {
+        let opening = Scalar::random(rng.rng());
+
+        (Self::commit_with_randomness(value, &opening), opening)
+    }
+
+    /// Commits to `value` using the given `opening` as randomness.
+    pub fn commit_with_randomness(value: &Scalar, opening: &Scalar) -> Self {
+        PedersenCommitment {
+            point: value * &RISTRETTO_BASEPOINT_TABLE + opening * Self::generator(),
+        }
+    }
+
+    /// Returns true if this commitment opens to `value` using `opening`.
+    pub fn verify(&self, value: &Scalar, opening: &Scalar) -> bool {
+        *self == Self::commit_with_randomness(value, opening)
+    }
+}
+
+impl Add for &PedersenCommitment {
+    type Output = PedersenCommitment;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        PedersenCommitment {
+            point: self.point + rhs.point,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proofs::pedersen::PedersenCommitment;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_commit_homomorphic() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let (commitment_a, opening_a) = PedersenCommitment::commit(&Scalar::from(5u64), &mut rng);
+        let (commitment_b, opening_b) = PedersenCommitment::commit(&Scalar::from(7u64), &mut rng);
+
+        let sum = &commitment_a + &commitment_b;
+
+        assert!(sum.verify(&Scalar::from(12u64), &(opening_a + opening_b)));
+        assert!(!sum.verify(&Scalar::from(13u64), &(opening_a + opening_b)));
+    }
+}
@@ -0,0 +1,353 @@
+//! Range proofs for curve-based ElGamal ciphertexts that encrypt a value $m$ in the exponent, i.e.
+//! $(r \cdot G, m \cdot G + r \cdot H)$ for public key $H$. The prover shows that
+//! $0 \leq m < 2^n$ without revealing anything else about $m$, so that a party aggregating
+//! encrypted contributions (e.g. bids or votes) can reject out-of-range values without decrypting.
+//!
+//! The proof encrypts each bit of $m$ separately and proves with a disjunctive Chaum-Pedersen
+//! proof that each of those ciphertexts encrypts either 0 or 1. The verifier then checks that the
+//! bit ciphertexts recombine into the original ciphertext. The proof size is linear in $n$.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::proofs::range::RangeProof;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, _) = el_gamal.generate_keys(&mut rng);
+//! let public_key = public_key.compress();
+//!
+//! let bid = 1337u64;
+//! let (ciphertext, randomness) = public_key
+//!     .encrypt_returning_randomness(&(Scalar::from(bid) * RISTRETTO_BASEPOINT_POINT), &mut rng);
+//!
+//! let proof = RangeProof::prove(bid, &randomness, 16, &public_key, &mut rng);
+//! assert!(proof.verify(&ciphertext, 16, &public_key));
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{CurveElGamalCiphertext, CurveElGamalPK};
+use curve25519_dalek::constants::{RISTRETTO_BASEPOINT_POINT, RISTRETTO_BASEPOINT_TABLE};
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+use subtle::{Choice, ConditionallySelectable};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he range proof bit";
+
+/// Proof that a curve-based ElGamal ciphertext encrypts a value in $[0, 2^n)$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct RangeProof {
+    /// Encryptions of the individual bits of the value, least significant first
+    pub bit_ciphertexts: Vec<CurveElGamalCiphertext>,
+    /// Proofs that each bit ciphertext encrypts either 0 or 1
+    pub bit_proofs: Vec<BitProof>,
+}
+
+/// Disjunctive Chaum-Pedersen proof that a ciphertext encrypts 0 or 1. Index $j$ of each array
+/// belongs to the statement that the ciphertext encrypts $j$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BitProof {
+    /// Challenges of both branches, which must add up to the Fiat-Shamir challenge
+    pub challenges: [Scalar; 2],
+    /// Responses of both branches
+    pub responses: [Scalar; 2],
+}
+
+impl RangeProof {
+    /// Proves that the ciphertext $(r \cdot G, m \cdot G + r \cdot H)$ encrypts a `value` $m$ that
+    /// fits in `bit_length` bits, where $r$ is the `randomness` used for encryption and $H$ is the
+    /// `public_key`. This function is constant-time in the value and the randomness.
+    pub fn prove<R: SecureRng>(
+        value: u64,
+        randomness: &Scalar,
+        bit_length: usize,
+        public_key: &CurveElGamalPK,
+        rng: &mut GeneralRng<R>,
+    ) -> RangeProof {
+        Self::prove_with_transcript(
+            &mut Transcript::new(CHALLENGE_DOMAIN),
+            value,
+            randomness,
+            bit_length,
+            public_key,
+            rng,
+        )
+    }
+
+    /// Same as `prove`, but binds the proof to the context absorbed by `transcript`. The verifier
+    /// must call `verify_with_transcript` with a transcript in the same state.
+    pub fn prove_with_transcript<R: SecureRng>(
+        transcript: &mut Transcript,
+        value: u64,
+        randomness: &Scalar,
+        bit_length: usize,
+        public_key: &CurveElGamalPK,
+        rng: &mut GeneralRng<R>,
+    ) -> RangeProof {
+        assert!(
+            (1..=64).contains(&bit_length),
+            "the bit length must lie between 1 and 64"
+        );
+        debug_assert!(bit_length == 64 || value >> bit_length == 0);
+
+        // Choose the bits' randomness so that it recombines into the ciphertext's randomness
+        let mut bit_randomness: Vec<Scalar> = (0..bit_length - 1)
+            .map(|_| Scalar::random(rng.rng()))
+            .collect();
+        let partial_sum: Scalar = bit_randomness
+            .iter()
+            .enumerate()
+            .map(|(i, r)| power_of_two(i) * r)
+            .sum();
+        bit_randomness.push((randomness - partial_sum) * power_of_two(bit_length - 1).invert());
+
+        append_statement(transcript, bit_length, public_key);
+
+        let (bit_ciphertexts, bit_proofs) = bit_randomness
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let bit = Choice::from(((value >> i) & 1) as u8);
+                let plaintext = RistrettoPoint::conditional_select(
+                    &RistrettoPoint::identity(),
+                    &RISTRETTO_BASEPOINT_POINT,
+                    bit,
+                );
+
+                let ciphertext = CurveElGamalCiphertext {
+                    c1: r * &RISTRETTO_BASEPOINT_TABLE,
+                    c2: plaintext + r * public_key.point,
+                };
+                let proof = BitProof::prove(
+                    &mut transcript.clone(),
+                    &ciphertext,
+                    bit,
+                    r,
+                    i,
+                    public_key,
+                    rng,
+                );
+
+                (ciphertext, proof)
+            })
+            .unzip();
+
+        RangeProof {
+            bit_ciphertexts,
+            bit_proofs,
+        }
+    }
+
+    /// Returns true if `ciphertext` encrypts a value in $[0, 2^n)$ under `public_key`, where $n$ is
+    /// the `bit_length`.
+    pub fn verify(
+        &self,
+        ciphertext: &CurveElGamalCiphertext,
+        bit_length: usize,
+        public_key: &CurveElGamalPK,
+    ) -> bool {
+        self.verify_with_transcript(
+            &mut Transcript::new(CHALLENGE_DOMAIN),
+            ciphertext,
+            bit_length,
+            public_key,
+        )
+    }
+
+    /// Same as `verify`, for proofs created with `prove_with_transcript`.
+    pub fn verify_with_transcript(
+        &self,
+        transcript: &mut Transcript,
+        ciphertext: &CurveElGamalCiphertext,
+        bit_length: usize,
+        public_key: &CurveElGamalPK,
+    ) -> bool {
+        if self.bit_ciphertexts.len() != bit_length || self.bit_proofs.len() != bit_length {
+            return false;
+        }
+
+        let powers: Vec<Scalar> = (0..bit_length).map(power_of_two).collect();
+        if CurveElGamalCiphertext::msm_leaky(&self.bit_ciphertexts, &powers) != *ciphertext {
+            return false;
+        }
+
+        append_statement(transcript, bit_length, public_key);
+
+        self.bit_ciphertexts
+            .iter()
+            .zip(&self.bit_proofs)
+            .enumerate()
+            .all(|(i, (bit_ciphertext, proof))| {
+                proof.verify(&mut transcript.clone(), bit_ciphertext, i, public_key)
+            })
+    }
+}
+
+impl BitProof {
+    fn prove<R: SecureRng>(
+        transcript: &mut Transcript,
+        ciphertext: &CurveElGamalCiphertext,
+        bit: Choice,
+        randomness: &Scalar,
+        index: usize,
+        public_key: &CurveElGamalPK,
+        rng: &mut GeneralRng<R>,
+    ) -> BitProof {
+        // The branch that does not match the bit is simulated with a chosen challenge and response
+        let simulated_challenge = Scalar::random(rng.rng());
+        let simulated_response = Scalar::random(rng.rng());
+        let simulated_c2 = RistrettoPoint::conditional_select(
+            &(ciphertext.c2 - RISTRETTO_BASEPOINT_POINT),
+            &ciphertext.c2,
+            bit,
+        );
+        let simulated_commitments = (
+            &simulated_response * &RISTRETTO_BASEPOINT_TABLE - simulated_challenge * ciphertext.c1,
+            simulated_response * public_key.point - simulated_challenge * simulated_c2,
+        );
+
+        let nonce = Scalar::random(rng.rng());
+        let real_commitments = (
+            &nonce * &RISTRETTO_BASEPOINT_TABLE,
+            nonce * public_key.point,
+        );
+
+        let commitments = [
+            select_pair(&real_commitments, &simulated_commitments, bit),
+            select_pair(&simulated_commitments, &real_commitments, bit),
+        ];
+        let challenge = compute_challenge(transcript, ciphertext, index, &commitments);
+
+        let real_challenge = challenge - simulated_challenge;
+        let real_response = nonce + real_challenge * randomness;
+
+        BitProof {
+            challenges: [
+                Scalar::conditional_select(&real_challenge, &simulated_challenge, bit),
+                Scalar::conditional_select(&simulated_challenge, &real_challenge, bit),
+            ],
+            responses: [
+                Scalar::conditional_select(&real_response, &simulated_response, bit),
+                Scalar::conditional_select(&simulated_response, &real_response, bit),
+            ],
+        }
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut Transcript,
+        ciphertext: &CurveElGamalCiphertext,
+        index: usize,
+        public_key: &CurveElGamalPK,
+    ) -> bool {
+        let c2_per_branch = [ciphertext.c2, ciphertext.c2 - RISTRETTO_BASEPOINT_POINT];
+
+        let commitments = [0, 1].map(|j| {
+            (
+                &self.responses[j] * &RISTRETTO_BASEPOINT_TABLE
+                    - self.challenges[j] * ciphertext.c1,
+                self.responses[j] * public_key.point - self.challenges[j] * c2_per_branch[j],
+            )
+        });
+
+        self.challenges[0] + self.challenges[1]
+            == compute_challenge(transcript, ciphertext, index, &commitments)
+    }
+}
+
+fn select_pair(
+    a: &(RistrettoPoint, RistrettoPoint),
+    b: &(RistrettoPoint, RistrettoPoint),
+    choice: Choice,
+) -> (RistrettoPoint, RistrettoPoint) {
+    (
+        RistrettoPoint::conditional_select(&a.0, &b.0, choice),
+        RistrettoPoint::conditional_select(&a.1, &b.1, choice),
+    )
+}
+
+fn power_of_two(exponent: usize) -> Scalar {
+    Scalar::from(1u128 << exponent)
+}
+
+/// Absorbs the parts of the statement that all bits share. Each bit's challenge is then derived
+/// from a separate copy of the transcript.
+fn append_statement(transcript: &mut Transcript, bit_length: usize, public_key: &CurveElGamalPK) {
+    transcript.append_message(b"proof", CHALLENGE_DOMAIN);
+    transcript.append_message(b"public key", public_key.point.compress().as_bytes());
+    transcript.append_u64(b"bit length", bit_length as u64);
+}
+
+fn compute_challenge(
+    transcript: &mut Transcript,
+    ciphertext: &CurveElGamalCiphertext,
+    index: usize,
+    commitments: &[(RistrettoPoint, RistrettoPoint); 2],
+) -> Scalar {
+    transcript.append_u64(b"bit index", index as u64);
+    transcript.append_message(b"c1", ciphertext.c1.compress().as_bytes());
+    transcript.append_message(b"c2", ciphertext.c2.compress().as_bytes());
+
+    for (a, b) in commitments {
+        transcript.append_message(b"commitment", a.compress().as_bytes());
+        transcript.append_message(b"commitment", b.compress().as_bytes());
+    }
+
+    Scalar::from_bytes_mod_order_wide(&transcript.challenge_bytes(b"challenge"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::proofs::range::RangeProof;
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_range_proof_valid() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+        let pk = pk.compress();
+
+        for value in [0u64, 1, 170, 255] {
+            let (ciphertext, randomness) = pk.encrypt_returning_randomness(
+                &(Scalar::from(value) * RISTRETTO_BASEPOINT_POINT),
+                &mut rng,
+            );
+
+            let proof = RangeProof::prove(value, &randomness, 8, &pk, &mut rng);
+            assert!(proof.verify(&ciphertext, 8, &pk));
+            assert!(!proof.verify(&ciphertext, 9, &pk));
+        }
+    }
+
+    #[test]
+    fn test_range_proof_rejects_other_ciphertext() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+        let pk = pk.compress();
+
+        let (_, randomness) = pk.encrypt_returning_randomness(
+            &(Scalar::from(5u64) * RISTRETTO_BASEPOINT_POINT),
+            &mut rng,
+        );
+        // A value outside of the range, for which the prover lies about the bits
+        let (out_of_range, _) = pk.encrypt_returning_randomness(
+            &(Scalar::from(256u64) * RISTRETTO_BASEPOINT_POINT),
+            &mut rng,
+        );
+
+        let proof = RangeProof::prove(5, &randomness, 8, &pk, &mut rng);
+        assert!(!proof.verify(&out_of_range, 8, &pk));
+    }
+}
@@ -0,0 +1,226 @@
+//! Aggregation of ciphertexts from multiple parties in two phases, which prevents rushing attacks.
+//! Without it, a party that waits for the others' ciphertexts before sending its own can choose its
+//! contribution based on theirs, for example by homomorphically negating them. Instead, every party
+//! first sends a hash commitment to its ciphertext. Only once the aggregator has received all
+//! commitments, the parties open them, and the aggregator checks each opening before adding up the
+//! ciphertexts.
+//! ```
+//! # #[cfg(feature = "integer")]
+//! # {
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::protocols::aggregation::{self, CommitmentAggregator};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let (public_key, secret_key) =
+//!     Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+//!
+//! // Every party commits to its encrypted contribution and sends the commitment
+//! let (commitments, openings): (Vec<_>, Vec<_>) = [3u64, 5, 8]
+//!     .iter()
+//!     .enumerate()
+//!     .map(|(party, &value)| {
+//!         let ciphertext = public_key.encrypt_raw(&UnsignedInteger::from(value), &mut rng);
+//!         aggregation::commit(party, ciphertext, &mut rng)
+//!     })
+//!     .unzip();
+//!
+//! // Once all commitments are in, the parties send their openings
+//! let aggregator = CommitmentAggregator::new(commitments);
+//! let sum = aggregator.aggregate(&public_key, &openings).unwrap();
+//!
+//! assert_eq!(secret_key.decrypt_raw(&public_key, &sum), UnsignedInteger::from(16));
+//! # }
+//! ```
+
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const COMMITMENT_DOMAIN: &[u8] = b"scicrypt-he ciphertext commitment";
+
+/// A hash commitment to a ciphertext, which is bound to the index of the party that created it.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct CiphertextCommitment(pub [u8; 32]);
+
+/// The ciphertext together with the random nonce that opens its commitment.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct CommitmentOpening<C> {
+    /// The random nonce that hides the ciphertext in the commitment
+    pub nonce: [u8; 32],
+    /// The committed ciphertext
+    pub ciphertext: C,
+}
+
+/// Error returned when some openings do not match their commitments. It holds the indices of the
+/// parties whose openings are invalid or missing, so that they can be identified and excluded.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidOpenings(pub Vec<usize>);
+
+/// The aggregator, which holds the commitments of all parties before it accepts any openings.
+#[derive(Debug)]
+pub struct CommitmentAggregator {
+    commitments: Vec<CiphertextCommitment>,
+}
+
+/// Commits to `ciphertext` as the party with index `party`.
+pub fn commit<C: Serialize, R: SecureRng>(
+    party: usize,
+    ciphertext: C,
+    rng: &mut GeneralRng<R>,
+) -> (CiphertextCommitment, CommitmentOpening<C>) {
+    let mut nonce = [0u8; 32];
+    rng.rng().fill_bytes(&mut nonce);
+
+    let opening = CommitmentOpening { nonce, ciphertext };
+
+    (compute_commitment(party, &opening), opening)
+}
+
+impl CommitmentAggregator {
+    /// Starts the reveal phase with the commitments of all parties, where the commitment at index
+    /// $i$ belongs to party $i$.
+    pub fn new(commitments: Vec<CiphertextCommitment>) -> Self {
+        assert!(
+            !commitments.is_empty(),
+            "at least one party must contribute a ciphertext"
+        );
+
+        CommitmentAggregator { commitments }
+    }
+
+    /// Returns true if `opening` matches the commitment of the party with index `party`. This allows
+    /// checking openings as they arrive.
+    pub fn verify_opening<C: Serialize>(
+        &self,
+        party: usize,
+        opening: &CommitmentOpening<C>,
+    ) -> bool {
+        match self.commitments.get(party) {
+            Some(commitment) => compute_commitment(party, opening) == *commitment,
+            None => false,
+        }
+    }
+
+    /// Checks the openings of all parties, where the opening at index $i$ belongs to party $i$, and
+    /// adds up their ciphertexts. If any opening is invalid or missing, the indices of those
+    /// parties are returned instead.
+    pub fn aggregate<PK: HomomorphicAddition>(
+        &self,
+        public_key: &PK,
+        openings: &[CommitmentOpening<PK::Ciphertext>],
+    ) -> Result<PK::Ciphertext, InvalidOpenings>
+    where
+        PK::Ciphertext: Serialize + Clone,
+    {
+        let invalid: Vec<usize> = (0..self.commitments.len())
+            .filter(|&party| match openings.get(party) {
+                Some(opening) => !self.verify_opening(party, opening),
+                None => true,
+            })
+            .collect();
+
+        if !invalid.is_empty() || openings.len() != self.commitments.len() {
+            return Err(InvalidOpenings(invalid));
+        }
+
+        let mut sum = openings[0].ciphertext.clone();
+        for opening in &openings[1..] {
+            public_key.add_assign(&mut sum, &opening.ciphertext);
+        }
+
+        Ok(sum)
+    }
+}
+
+fn compute_commitment<C: Serialize>(
+    party: usize,
+    opening: &CommitmentOpening<C>,
+) -> CiphertextCommitment {
+    let serialized =
+        bincode::serialize(&opening.ciphertext).expect("ciphertexts can always be serialized");
+
+    let digest = Sha256::new()
+        .chain_update(COMMITMENT_DOMAIN)
+        .chain_update((party as u64).to_le_bytes())
+        .chain_update(opening.nonce)
+        .chain_update((serialized.len() as u64).to_le_bytes())
+        .chain_update(&serialized)
+        .finalize();
+
+    CiphertextCommitment(digest.into())
+}
+
+#[cfg(all(test, feature = "curve"))]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::protocols::aggregation::{
+        self, CiphertextCommitment, CommitmentAggregator, InvalidOpenings,
+    };
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_aggregate() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = CurveElGamal::setup(&Default::default()).generate_keys(&mut rng);
+
+        let (commitments, openings): (Vec<_>, Vec<_>) = (1..=4u64)
+            .map(|value| {
+                let ciphertext =
+                    pk.encrypt_raw(&(Scalar::from(value) * RISTRETTO_BASEPOINT_POINT), &mut rng);
+                aggregation::commit(value as usize - 1, ciphertext, &mut rng)
+            })
+            .unzip();
+
+        let bytes = bincode::serialize(&commitments[0]).unwrap();
+        assert_eq!(
+            commitments[0],
+            bincode::deserialize::<CiphertextCommitment>(&bytes).unwrap()
+        );
+
+        let aggregator = CommitmentAggregator::new(commitments);
+        assert!(aggregator.verify_opening(2, &openings[2]));
+        assert!(!aggregator.verify_opening(1, &openings[2]));
+
+        let sum = aggregator.aggregate(&pk, &openings).unwrap();
+        assert_eq!(
+            Scalar::from(10u64) * RISTRETTO_BASEPOINT_POINT,
+            sk.decrypt_raw(&pk, &sum)
+        );
+    }
+
+    #[test]
+    fn test_aggregate_rejects_changed_ciphertexts() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = CurveElGamal::setup(&Default::default()).generate_keys(&mut rng);
+
+        let (commitments, mut openings): (Vec<_>, Vec<_>) = (0..3)
+            .map(|party| {
+                let ciphertext = pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+                aggregation::commit(party, ciphertext, &mut rng)
+            })
+            .unzip();
+        let aggregator = CommitmentAggregator::new(commitments);
+
+        // The last party tries to replace its ciphertext after seeing the others
+        openings[2].ciphertext = pk.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+        assert_eq!(
+            aggregator.aggregate(&pk, &openings),
+            Err(InvalidOpenings(vec![2]))
+        );
+
+        // A missing opening is also reported
+        assert_eq!(
+            aggregator.aggregate(&pk, &openings[..1]),
+            Err(InvalidOpenings(vec![1, 2]))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Blind decryption, where a client obtains the plaintext of a ciphertext from the key holder
+//! without the key holder learning anything about it. The client blinds the plaintext with a random
+//! value and rerandomizes the ciphertext, so that the key holder can neither link it to the original
+//! ciphertext nor learn anything from the plaintext it decrypts. Here is an example with
+//! curve-based ElGamal.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, EncryptionKey};
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::protocols::blind_decryption;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, secret_key) = el_gamal.generate_keys(&mut rng);
+//!
+//! let plaintext = Scalar::from(42u64) * RISTRETTO_BASEPOINT_POINT;
+//! let ciphertext = public_key.encrypt_raw(&plaintext, &mut rng);
+//!
+//! // The client blinds the ciphertext and sends it to the key holder
+//! let (unblinder, blinded_ciphertext) = blind_decryption::blind(&public_key, &ciphertext, &mut rng);
+//! // The key holder decrypts the blinded ciphertext without learning the plaintext
+//! let blinded_plaintext =
+//!     blind_decryption::decrypt_blinded(&public_key, &secret_key, &blinded_ciphertext);
+//! // The client removes the blind
+//! assert_eq!(unblinder.unblind(&public_key, &blinded_plaintext), plaintext);
+//! ```
+
+use crate::transport::{Transport, TransportError};
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// An encryption key whose plaintexts can be blinded through the ciphertext, such that the blinded
+/// plaintext is uniformly random and independent of the original plaintext.
+pub trait PlaintextBlinding: EncryptionKey {
+    /// The secret value with which a plaintext is blinded
+    type Blind;
+
+    /// Samples a uniformly random blind.
+    fn random_blind<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> Self::Blind;
+
+    /// Blinds the plaintext of `ciphertext` with `blind`, without rerandomizing it.
+    fn blind_ciphertext(
+        &self,
+        ciphertext: &Self::Ciphertext,
+        blind: &Self::Blind,
+    ) -> Self::Ciphertext;
+
+    /// Removes `blind` from a decrypted blinded plaintext.
+    fn unblind_plaintext(
+        &self,
+        plaintext: &Self::Plaintext,
+        blind: &Self::Blind,
+    ) -> Self::Plaintext;
+}
+
+/// The blinded and rerandomized ciphertext, which the client sends to the key holder.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct BlindedCiphertext<C>(pub C);
+
+/// The decryption of the blinded ciphertext, which the key holder sends back to the client.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BlindedPlaintext<P>(pub P);
+
+/// Secret blind that the client keeps to unblind the key holder's response.
+pub struct Unblinder<B> {
+    blind: B,
+}
+
+impl<B> Debug for Unblinder<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Unblinder").finish_non_exhaustive()
+    }
+}
+
+/// Blinds the plaintext of `ciphertext` with a fresh random blind and rerandomizes it.
+pub fn blind<PK: PlaintextBlinding, R: SecureRng>(
+    public_key: &PK,
+    ciphertext: &PK::Ciphertext,
+    rng: &mut GeneralRng<R>,
+) -> (Unblinder<PK::Blind>, BlindedCiphertext<PK::Ciphertext>) {
+    let blind = public_key.random_blind(rng);
+    let blinded = public_key.randomize(public_key.blind_ciphertext(ciphertext, &blind), rng);
+
+    (Unblinder { blind }, BlindedCiphertext(blinded))
+}
+
+/// Decrypts the client's blinded ciphertext with the secret key.
+pub fn decrypt_blinded<PK: PlaintextBlinding, SK: DecryptionKey<PK>>(
+    public_key: &PK,
+    secret_key: &SK,
+    blinded_ciphertext: &BlindedCiphertext<PK::Ciphertext>,
+) -> BlindedPlaintext<PK::Plaintext> {
+    BlindedPlaintext(secret_key.decrypt_raw(public_key, &blinded_ciphertext.0))
+}
+
+impl<B> Unblinder<B> {
+    /// Removes the blind from the key holder's response, which yields the original plaintext.
+    pub fn unblind<PK: PlaintextBlinding<Blind = B>>(
+        &self,
+        public_key: &PK,
+        blinded_plaintext: &BlindedPlaintext<PK::Plaintext>,
+    ) -> PK::Plaintext {
+        public_key.unblind_plaintext(&blinded_plaintext.0, &self.blind)
+    }
+}
+
+/// Runs the client's side of blind decryption over `transport`, and returns the plaintext of
+/// `ciphertext`.
+pub fn request<PK: PlaintextBlinding, T: Transport, R: SecureRng>(
+    transport: &mut T,
+    public_key: &PK,
+    ciphertext: &PK::Ciphertext,
+    rng: &mut GeneralRng<R>,
+) -> Result<PK::Plaintext, TransportError>
+where
+    PK::Ciphertext: Serialize + DeserializeOwned,
+    PK::Plaintext: Serialize + DeserializeOwned,
+{
+    let (unblinder, blinded_ciphertext) = blind(public_key, ciphertext, rng);
+    transport.send_message(&blinded_ciphertext)?;
+    let blinded_plaintext = transport.receive_message()?;
+
+    Ok(unblinder.unblind(public_key, &blinded_plaintext))
+}
+
+/// Runs the key holder's side of blind decryption over `transport`, answering a single request.
+pub fn serve<PK: PlaintextBlinding, SK: DecryptionKey<PK>, T: Transport>(
+    transport: &mut T,
+    public_key: &PK,
+    secret_key: &SK,
+) -> Result<(), TransportError>
+where
+    PK::Ciphertext: Serialize + DeserializeOwned,
+    PK::Plaintext: Serialize + DeserializeOwned,
+{
+    let blinded_ciphertext = transport.receive_message()?;
+
+    transport.send_message(&decrypt_blinded(
+        public_key,
+        secret_key,
+        &blinded_ciphertext,
+    ))
+}
+
+#[cfg(feature = "curve")]
+mod curve {
+    use super::PlaintextBlinding;
+    use crate::cryptosystems::curve_el_gamal::{
+        CurveElGamalCiphertext, CurveElGamalPK, PrecomputedCurveElGamalPK,
+    };
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+
+    /// Plaintexts are blinded additively with a uniformly random point.
+    macro_rules! impl_curve_plaintext_blinding {
+        ($public_key:ty) => {
+            impl PlaintextBlinding for $public_key {
+                type Blind = RistrettoPoint;
+
+                fn random_blind<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> RistrettoPoint {
+                    RistrettoPoint::random(rng.rng())
+                }
+
+                fn blind_ciphertext(
+                    &self,
+                    ciphertext: &CurveElGamalCiphertext,
+                    blind: &RistrettoPoint,
+                ) -> CurveElGamalCiphertext {
+                    CurveElGamalCiphertext {
+                        c1: ciphertext.c1,
+                        c2: ciphertext.c2 + blind,
+                    }
+                }
+
+                fn unblind_plaintext(
+                    &self,
+                    plaintext: &RistrettoPoint,
+                    blind: &RistrettoPoint,
+                ) -> RistrettoPoint {
+                    plaintext - blind
+                }
+            }
+        };
+    }
+
+    impl_curve_plaintext_blinding!(CurveElGamalPK);
+    impl_curve_plaintext_blinding!(PrecomputedCurveElGamalPK);
+}
+
+#[cfg(feature = "integer")]
+mod integer {
+    use super::PlaintextBlinding;
+    use crate::cryptosystems::integer_el_gamal::{IntegerElGamalCiphertext, IntegerElGamalPK};
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+
+    /// Plaintexts are blinded multiplicatively with a uniformly random unit modulo $p$.
+    impl PlaintextBlinding for IntegerElGamalPK {
+        type Blind = UnsignedInteger;
+
+        fn random_blind<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> UnsignedInteger {
+            let mut blind = UnsignedInteger::random_below(&(self.modulus.clone() - 1), rng) + 1;
+
+            // The sum is declared one bit larger, but the blind is below the modulus
+            blind.resize_to(self.modulus.size_in_bits());
+            blind
+        }
+
+        fn blind_ciphertext(
+            &self,
+            ciphertext: &IntegerElGamalCiphertext,
+            blind: &UnsignedInteger,
+        ) -> IntegerElGamalCiphertext {
+            IntegerElGamalCiphertext {
+                c1: ciphertext.c1.clone(),
+                c2: self.barrett.reduce(&(&ciphertext.c2 * blind)),
+            }
+        }
+
+        fn unblind_plaintext(
+            &self,
+            plaintext: &UnsignedInteger,
+            blind: &UnsignedInteger,
+        ) -> UnsignedInteger {
+            let inverse = blind.clone().invert(&self.modulus).unwrap();
+
+            self.barrett.reduce(&(plaintext * &inverse))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocols::blind_decryption::{self, PlaintextBlinding};
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+
+    fn blind_decrypt<PK: PlaintextBlinding, SK: DecryptionKey<PK>>(
+        pk: &PK,
+        sk: &SK,
+        plaintext: &PK::Plaintext,
+    ) -> PK::Plaintext
+    where
+        PK::Plaintext: PartialEq + std::fmt::Debug,
+    {
+        let mut rng = GeneralRng::new(OsRng);
+        let ciphertext = pk.encrypt_raw(plaintext, &mut rng);
+
+        let (unblinder, blinded_ciphertext) = blind_decryption::blind(pk, &ciphertext, &mut rng);
+        let blinded_plaintext = blind_decryption::decrypt_blinded(pk, sk, &blinded_ciphertext);
+        assert_ne!(blinded_plaintext.0, *plaintext);
+
+        unblinder.unblind(pk, &blinded_plaintext)
+    }
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_blind_decryption_curve_el_gamal() {
+        use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use curve25519_dalek::scalar::Scalar;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = CurveElGamal::setup(&Default::default()).generate_keys(&mut rng);
+        let plaintext = Scalar::from(19u64) * RISTRETTO_BASEPOINT_POINT;
+
+        assert_eq!(blind_decrypt(&pk, &sk, &plaintext), plaintext);
+        assert_eq!(blind_decrypt(&pk.compress(), &sk, &plaintext), plaintext);
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_blind_decryption_integer_el_gamal() {
+        use crate::cryptosystems::integer_el_gamal::IntegerElGamal;
+        use scicrypt_bigint::UnsignedInteger;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = IntegerElGamal::setup(&Default::default()).generate_keys(&mut rng);
+        let plaintext = UnsignedInteger::from(123456u64);
+
+        assert_eq!(blind_decrypt(&pk, &sk, &plaintext), plaintext);
+    }
+}
@@ -0,0 +1,557 @@
+//! Secure comparison of two Paillier-encrypted integers, following the protocol of Veugen (2012)
+//! that uses the bitwise DGK comparison as a subprotocol. The evaluator holds encryptions $[a]$ and
+//! $[b]$ of two $\ell$-bit integers under the Paillier key of the key holder, and obtains an
+//! encryption of the bit $a < b$. The evaluator learns nothing about $a$ and $b$, while the key
+//! holder only sees values that are statistically blinded. Both parties are assumed to follow the
+//! protocol (semi-honest security).
+//!
+//! Every step consumes the state of the party and returns the state for the next step, so the
+//! steps can only be taken in order and only once per comparison. In particular, the key holder
+//! answers exactly one zero test per decomposition.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::dgk::Dgk;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::protocols::comparison::{ComparisonEvaluator, ComparisonKeyHolder};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let (paillier_pk, paillier_sk) =
+//!     Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+//! let (dgk_pk, dgk_sk) = Dgk::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+//! let key_holder = ComparisonKeyHolder::new(&paillier_pk, &paillier_sk, &dgk_pk, &dgk_sk);
+//!
+//! let a = paillier_pk.encrypt_raw(&UnsignedInteger::from(12), &mut rng);
+//! let b = paillier_pk.encrypt_raw(&UnsignedInteger::from(30), &mut rng);
+//!
+//! // The evaluator blinds the difference of its two ciphertexts
+//! let (evaluator, blinded_difference) =
+//!     ComparisonEvaluator::start(&paillier_pk, &dgk_pk, &a, &b, 8, &mut rng);
+//! // The key holder decrypts the blinded difference and encrypts its bits
+//! let (key_holder, encrypted_bits) = key_holder.decompose(&blinded_difference, 8, &mut rng);
+//! // The evaluator compares these bits to its blinding value and masks the results
+//! let (evaluator, masked_bits) = evaluator.mask_bits(&encrypted_bits, &mut rng);
+//! // The key holder checks whether any of the masked results is zero
+//! let zero_test = key_holder.zero_test(&masked_bits, &mut rng);
+//! // The evaluator combines everything into an encryption of a < b
+//! let less_than = evaluator.finish(&zero_test, &mut rng);
+//!
+//! assert_eq!(paillier_sk.decrypt_raw(&paillier_pk, &less_than), UnsignedInteger::from(1));
+//! ```
+
+use crate::cryptosystems::dgk::{DgkCiphertext, DgkPK, DgkSK};
+use crate::cryptosystems::paillier::{PaillierCiphertext, PaillierPK, PaillierSK};
+use crate::transport::{Transport, TransportError};
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The statistical security parameter $\kappa$: the blinded difference reveals at most a
+/// $2^{-\kappa}$ advantage about the compared values.
+const STATISTICAL_SECURITY: u32 = 40;
+
+/// The largest supported bit length $\ell$ of the compared values.
+pub const MAX_BIT_LENGTH: u32 = 64;
+
+/// The encryption $[d] = [2^\ell + a - b + r]$ of the blinded difference, which the evaluator sends
+/// to the key holder.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BlindedDifference(pub PaillierCiphertext);
+
+/// The key holder's response to the blinded difference $d$: a Paillier encryption of
+/// $\lfloor d / 2^\ell \rfloor$ and DGK encryptions of the $\ell$ least significant bits of $d$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct EncryptedBits {
+    /// Encryption of $\lfloor d / 2^\ell \rfloor$
+    pub high: PaillierCiphertext,
+    /// Encryptions of the bits of $d \bmod 2^\ell$, least significant first
+    pub bits: Vec<DgkCiphertext>,
+}
+
+/// The DGK comparison values, multiplied by random factors and shuffled, which the evaluator sends
+/// to the key holder. One of them encrypts zero exactly when the key holder's bit is set.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct MaskedBits(pub Vec<DgkCiphertext>);
+
+/// A Paillier encryption of whether any of the masked values encrypts zero, which the key holder
+/// sends back to the evaluator.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct ZeroTest(pub PaillierCiphertext);
+
+/// The party that holds the encrypted values and obtains the encrypted comparison result.
+pub struct ComparisonEvaluator<'pk> {
+    paillier_pk: &'pk PaillierPK,
+    dgk_pk: &'pk DgkPK,
+    bit_length: u32,
+    blind: u128,
+    flip: bool,
+}
+
+impl Debug for ComparisonEvaluator<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComparisonEvaluator")
+            .field("bit_length", &self.bit_length)
+            .field(
+                "blind",
+                &Redacted::new(&self.blind, self.bit_length + STATISTICAL_SECURITY),
+            )
+            .field("flip", &Redacted::new(&self.flip, 1))
+            .finish()
+    }
+}
+
+/// The state of the evaluator after masking the bits, which waits for the key holder's zero test.
+pub struct PendingComparison<'pk> {
+    paillier_pk: &'pk PaillierPK,
+    bit_length: u32,
+    blind: u128,
+    flip: bool,
+    high: PaillierCiphertext,
+}
+
+impl Debug for PendingComparison<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingComparison")
+            .field("bit_length", &self.bit_length)
+            .field(
+                "blind",
+                &Redacted::new(&self.blind, self.bit_length + STATISTICAL_SECURITY),
+            )
+            .field("flip", &Redacted::new(&self.flip, 1))
+            .finish()
+    }
+}
+
+/// The party that holds the Paillier and DGK secret keys and helps the evaluator compare.
+pub struct ComparisonKeyHolder<'k> {
+    paillier_pk: &'k PaillierPK,
+    paillier_sk: &'k PaillierSK,
+    dgk_pk: &'k DgkPK,
+    dgk_sk: &'k DgkSK,
+}
+
+impl Debug for ComparisonKeyHolder<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComparisonKeyHolder")
+            .field("paillier_sk", &self.paillier_sk)
+            .field("dgk_sk", &self.dgk_sk)
+            .finish()
+    }
+}
+
+/// The state of the key holder after decomposing a blinded difference, which answers the
+/// evaluator's masked bits with a single zero test.
+pub struct PendingZeroTest<'k> {
+    paillier_pk: &'k PaillierPK,
+    dgk_sk: &'k DgkSK,
+}
+
+impl Debug for PendingZeroTest<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingZeroTest")
+            .field("dgk_sk", &self.dgk_sk)
+            .finish()
+    }
+}
+
+impl<'pk> ComparisonEvaluator<'pk> {
+    /// Starts a comparison of the $\ell$-bit values encrypted in `ciphertext_a` and `ciphertext_b`,
+    /// where $\ell$ is `bit_length`. The DGK plaintext space must be larger than $3\ell + 3$ and the
+    /// Paillier modulus must have more than $\ell + 42$ bits.
+    pub fn start<R: SecureRng>(
+        paillier_pk: &'pk PaillierPK,
+        dgk_pk: &'pk DgkPK,
+        ciphertext_a: &PaillierCiphertext,
+        ciphertext_b: &PaillierCiphertext,
+        bit_length: u32,
+        rng: &mut GeneralRng<R>,
+    ) -> (Self, BlindedDifference) {
+        assert!(
+            (1..=MAX_BIT_LENGTH).contains(&bit_length),
+            "the bit length must be between 1 and {}",
+            MAX_BIT_LENGTH
+        );
+        assert!(
+            dgk_pk.u > 3 * bit_length as u64 + 3,
+            "the DGK plaintext space is too small for this bit length"
+        );
+        assert!(
+            paillier_pk.n.size_in_bits() > bit_length + STATISTICAL_SECURITY + 2,
+            "the Paillier modulus is too small for this bit length"
+        );
+
+        let blind_bits = bit_length + STATISTICAL_SECURITY;
+        let blind = random_u128(rng) & ((1u128 << blind_bits) - 1);
+        let flip = rng.rng().next_u32() & 1 == 1;
+
+        // [d] = [2^l + a - b + r], which is positive because a and b are smaller than 2^l
+        let difference = paillier_pk.sub(ciphertext_a, ciphertext_b);
+        let offset = (1u128 << bit_length) + blind;
+        let blinded = paillier_pk.randomize(
+            paillier_pk.add_constant(&difference, &u128_to_unsigned(offset)),
+            rng,
+        );
+
+        (
+            ComparisonEvaluator {
+                paillier_pk,
+                dgk_pk,
+                bit_length,
+                blind,
+                flip,
+            },
+            BlindedDifference(blinded),
+        )
+    }
+
+    /// Computes the DGK comparison values between the key holder's bits $d_i$ and the bits $r_i$ of
+    /// the blind. With $s = 1$, or $s = -1$ when the evaluator secretly flips the comparison, these
+    /// are $c_i = s + d_i - r_i + 3 \sum_{j > i} (d_j \oplus r_j)$ together with
+    /// $c_{-1} = [s = 1] + \sum_j (d_j \oplus r_j)$, where the latter handles $d = r$.
+    pub fn mask_bits<R: SecureRng>(
+        self,
+        encrypted_bits: &EncryptedBits,
+        rng: &mut GeneralRng<R>,
+    ) -> (PendingComparison<'pk>, MaskedBits) {
+        assert_eq!(
+            encrypted_bits.bits.len(),
+            self.bit_length as usize,
+            "the key holder must send exactly one ciphertext per bit"
+        );
+
+        let u = self.dgk_pk.u;
+        let s = if self.flip { u - 1 } else { 1 };
+
+        let mut masked = Vec::with_capacity(self.bit_length as usize + 1);
+        let mut xor_sum = self.dgk_pk.encrypt_without_randomness(&0);
+
+        for (i, d_i) in encrypted_bits.bits.iter().enumerate().rev() {
+            let r_i = ((self.blind >> i) & 1) as u64;
+
+            let c_i = self.dgk_pk.add(
+                &self.dgk_pk.add_constant(d_i, &((s + u - r_i) % u)),
+                &self.dgk_pk.mul_constant(&xor_sum, &3),
+            );
+            masked.push(c_i);
+
+            let xor = if r_i == 1 {
+                self.dgk_pk
+                    .sub(&self.dgk_pk.encrypt_without_randomness(&1), d_i)
+            } else {
+                d_i.clone()
+            };
+            xor_sum = self.dgk_pk.add(&xor_sum, &xor);
+        }
+
+        masked.push(
+            self.dgk_pk
+                .add_constant(&xor_sum, &if self.flip { 0 } else { 1 }),
+        );
+
+        let mut masked: Vec<DgkCiphertext> = masked
+            .iter()
+            .map(|c| {
+                let factor = 1 + random_u128(rng) as u64 % (u - 1);
+                self.dgk_pk
+                    .randomize(self.dgk_pk.mul_constant(c, &factor), rng)
+            })
+            .collect();
+
+        // Fisher-Yates shuffle, so that the position of a zero does not reveal anything
+        for i in (1..masked.len()).rev() {
+            let j = (random_u128(rng) % (i as u128 + 1)) as usize;
+            masked.swap(i, j);
+        }
+
+        (
+            PendingComparison {
+                paillier_pk: self.paillier_pk,
+                bit_length: self.bit_length,
+                blind: self.blind,
+                flip: self.flip,
+                high: encrypted_bits.high.clone(),
+            },
+            MaskedBits(masked),
+        )
+    }
+
+    /// Runs the evaluator's side of the comparison over `transport`, like `start`, `mask_bits` and
+    /// `finish` combined, and returns an encryption of $a < b$. Returns an error if the key holder
+    /// does not send one ciphertext per bit.
+    pub fn run<T: Transport, R: SecureRng>(
+        transport: &mut T,
+        paillier_pk: &'pk PaillierPK,
+        dgk_pk: &'pk DgkPK,
+        ciphertext_a: &PaillierCiphertext,
+        ciphertext_b: &PaillierCiphertext,
+        bit_length: u32,
+        rng: &mut GeneralRng<R>,
+    ) -> Result<PaillierCiphertext, TransportError> {
+        let (evaluator, blinded_difference) = ComparisonEvaluator::start(
+            paillier_pk,
+            dgk_pk,
+            ciphertext_a,
+            ciphertext_b,
+            bit_length,
+            rng,
+        );
+        transport.send_message(&blinded_difference)?;
+
+        let encrypted_bits: EncryptedBits = transport.receive_message()?;
+        if encrypted_bits.bits.len() != bit_length as usize {
+            return Err(TransportError::Rejected);
+        }
+        let (evaluator, masked_bits) = evaluator.mask_bits(&encrypted_bits, rng);
+        transport.send_message(&masked_bits)?;
+
+        let zero_test = transport.receive_message()?;
+
+        Ok(evaluator.finish(&zero_test, rng))
+    }
+}
+
+impl PendingComparison<'_> {
+    /// Combines the key holder's responses into a fresh encryption of $1$ if $a < b$ and $0$
+    /// otherwise.
+    pub fn finish<R: SecureRng>(
+        self,
+        zero_test: &ZeroTest,
+        rng: &mut GeneralRng<R>,
+    ) -> PaillierCiphertext {
+        // t = [d mod 2^l < r mod 2^l], which is the zero test result, flipped if the evaluator
+        // flipped the comparison
+        let t = if self.flip {
+            self.paillier_pk.sub(
+                &self
+                    .paillier_pk
+                    .encrypt_without_randomness(&UnsignedInteger::from(1)),
+                &zero_test.0,
+            )
+        } else {
+            zero_test.0.clone()
+        };
+
+        // a < b exactly when bit l of 2^l + a - b is unset, which equals
+        // 1 - floor(d / 2^l) + floor(r / 2^l) + t
+        let blind_high = (self.blind >> self.bit_length) as u64;
+        let less_than = self.paillier_pk.sub(
+            &self
+                .paillier_pk
+                .add_constant(&t, &UnsignedInteger::from(blind_high + 1)),
+            &self.high,
+        );
+
+        self.paillier_pk.randomize(less_than, rng)
+    }
+}
+
+impl<'k> ComparisonKeyHolder<'k> {
+    /// Sets up the key holder with its Paillier and DGK key pairs.
+    pub fn new(
+        paillier_pk: &'k PaillierPK,
+        paillier_sk: &'k PaillierSK,
+        dgk_pk: &'k DgkPK,
+        dgk_sk: &'k DgkSK,
+    ) -> Self {
+        ComparisonKeyHolder {
+            paillier_pk,
+            paillier_sk,
+            dgk_pk,
+            dgk_sk,
+        }
+    }
+
+    /// Decrypts the blinded difference $d$ and encrypts $\lfloor d / 2^\ell \rfloor$ and the
+    /// $\ell$ least significant bits of $d$, where $\ell$ is `bit_length`.
+    pub fn decompose<R: SecureRng>(
+        &self,
+        blinded_difference: &BlindedDifference,
+        bit_length: u32,
+        rng: &mut GeneralRng<R>,
+    ) -> (PendingZeroTest<'k>, EncryptedBits) {
+        let bytes = self
+            .paillier_sk
+            .decrypt_raw(self.paillier_pk, &blinded_difference.0)
+            .to_le_bytes();
+        assert!(
+            bytes.iter().skip(16).all(|&byte| byte == 0),
+            "the blinded difference is too large"
+        );
+
+        let mut low_bytes = [0u8; 16];
+        let length = bytes.len().min(16);
+        low_bytes[..length].copy_from_slice(&bytes[..length]);
+        let d = u128::from_le_bytes(low_bytes);
+
+        let high = self
+            .paillier_pk
+            .encrypt_raw(&u128_to_unsigned(d >> bit_length), rng);
+        let bits = (0..bit_length)
+            .map(|i| self.dgk_pk.encrypt_raw(&(((d >> i) & 1) as u64), rng))
+            .collect();
+
+        (
+            PendingZeroTest {
+                paillier_pk: self.paillier_pk,
+                dgk_sk: self.dgk_sk,
+            },
+            EncryptedBits { high, bits },
+        )
+    }
+
+    /// Runs the key holder's side of a single comparison of `bit_length`-bit values over
+    /// `transport`.
+    pub fn serve<T: Transport, R: SecureRng>(
+        &self,
+        transport: &mut T,
+        bit_length: u32,
+        rng: &mut GeneralRng<R>,
+    ) -> Result<(), TransportError> {
+        let blinded_difference = transport.receive_message()?;
+        let (pending, encrypted_bits) = self.decompose(&blinded_difference, bit_length, rng);
+        transport.send_message(&encrypted_bits)?;
+
+        let masked_bits = transport.receive_message()?;
+        transport.send_message(&pending.zero_test(&masked_bits, rng))
+    }
+}
+
+impl PendingZeroTest<'_> {
+    /// Checks whether any of the masked values encrypts zero, and encrypts the outcome as a bit.
+    /// This is not constant-time, and leaks through its running time where the first zero was
+    /// found; the evaluator shuffled the values so that this position is random.
+    pub fn zero_test<R: SecureRng>(
+        self,
+        masked_bits: &MaskedBits,
+        rng: &mut GeneralRng<R>,
+    ) -> ZeroTest {
+        let found_zero = masked_bits.0.iter().any(|c| self.dgk_sk.is_zero_decrypt(c));
+
+        ZeroTest(
+            self.paillier_pk
+                .encrypt_raw(&UnsignedInteger::from(found_zero as u64), rng),
+        )
+    }
+}
+
+fn random_u128<R: SecureRng>(rng: &mut GeneralRng<R>) -> u128 {
+    let mut bytes = [0u8; 16];
+    rng.rng().fill_bytes(&mut bytes);
+    u128::from_le_bytes(bytes)
+}
+
+fn u128_to_unsigned(value: u128) -> UnsignedInteger {
+    UnsignedInteger::from_le_bytes(&value.to_le_bytes(), 128)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::dgk::Dgk;
+    use crate::cryptosystems::paillier::Paillier;
+    use crate::protocols::comparison::{ComparisonEvaluator, ComparisonKeyHolder};
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    fn compare(a: u64, b: u64, bit_length: u32) -> bool {
+        let mut rng = GeneralRng::new(OsRng);
+        let (paillier_pk, paillier_sk) =
+            Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let (dgk_pk, dgk_sk) = Dgk::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let key_holder = ComparisonKeyHolder::new(&paillier_pk, &paillier_sk, &dgk_pk, &dgk_sk);
+
+        let ciphertext_a = paillier_pk.encrypt_raw(&UnsignedInteger::from(a), &mut rng);
+        let ciphertext_b = paillier_pk.encrypt_raw(&UnsignedInteger::from(b), &mut rng);
+
+        let (evaluator, blinded_difference) = ComparisonEvaluator::start(
+            &paillier_pk,
+            &dgk_pk,
+            &ciphertext_a,
+            &ciphertext_b,
+            bit_length,
+            &mut rng,
+        );
+        let (pending, encrypted_bits) =
+            key_holder.decompose(&blinded_difference, bit_length, &mut rng);
+        let (evaluator, masked_bits) = evaluator.mask_bits(&encrypted_bits, &mut rng);
+        let zero_test = pending.zero_test(&masked_bits, &mut rng);
+        let less_than = evaluator.finish(&zero_test, &mut rng);
+
+        let result = paillier_sk.decrypt_raw(&paillier_pk, &less_than);
+        assert!(result == UnsignedInteger::from(0) || result == UnsignedInteger::from(1));
+        result == UnsignedInteger::from(1)
+    }
+
+    #[test]
+    fn test_less_than() {
+        for _ in 0..4 {
+            assert!(compare(3, 200, 8));
+            assert!(compare(0, 1, 8));
+            assert!(compare(254, 255, 8));
+        }
+    }
+
+    #[test]
+    fn test_compare_over_transport() {
+        use crate::transport::MemoryTransport;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (paillier_pk, paillier_sk) =
+            Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let (dgk_pk, dgk_sk) = Dgk::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+        let key_holder = ComparisonKeyHolder::new(&paillier_pk, &paillier_sk, &dgk_pk, &dgk_sk);
+
+        let ciphertext_a = paillier_pk.encrypt_raw(&UnsignedInteger::from(12), &mut rng);
+        let ciphertext_b = paillier_pk.encrypt_raw(&UnsignedInteger::from(30), &mut rng);
+
+        let (mut evaluator, mut server) = MemoryTransport::pair();
+        let less_than = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                key_holder
+                    .serve(&mut server, 8, &mut GeneralRng::new(OsRng))
+                    .unwrap()
+            });
+            ComparisonEvaluator::run(
+                &mut evaluator,
+                &paillier_pk,
+                &dgk_pk,
+                &ciphertext_a,
+                &ciphertext_b,
+                8,
+                &mut rng,
+            )
+            .unwrap()
+        });
+
+        assert_eq!(
+            paillier_sk.decrypt_raw(&paillier_pk, &less_than),
+            UnsignedInteger::from(1)
+        );
+    }
+
+    #[test]
+    fn test_equal() {
+        for _ in 0..4 {
+            assert!(!compare(0, 0, 8));
+            assert!(!compare(77, 77, 8));
+            assert!(!compare(255, 255, 8));
+        }
+    }
+
+    #[test]
+    fn test_greater_than() {
+        for _ in 0..4 {
+            assert!(!compare(200, 3, 8));
+            assert!(!compare(1, 0, 8));
+            assert!(!compare(65535, 65534, 16));
+        }
+    }
+}
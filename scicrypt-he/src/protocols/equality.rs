@@ -0,0 +1,396 @@
+//! Private equality tests between additively homomorphic ciphertexts. The evaluator holds pairs of
+//! ciphertexts $([a_i], [b_i])$ under the key holder's public key, and obtains encryptions of the
+//! bits $a_i = b_i$. It sends the key holder the randomized differences $[\rho_i (a_i - b_i)]$ in a
+//! random order, which the key holder zero-tests and answers with fresh encryptions of the
+//! outcomes. The key holder learns how many of the pairs are equal, but not which ones, while the
+//! evaluator learns nothing. This makes it suitable for private lookups and deduplication, where
+//! one value is compared to many.
+//! ```
+//! # #[cfg(feature = "integer")]
+//! # {
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::protocols::equality::{self, EqualityEvaluator};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+//!
+//! let needle = pk.encrypt_raw(&UnsignedInteger::from(7), &mut rng);
+//! let pairs: Vec<_> = [3u64, 7, 12]
+//!     .iter()
+//!     .map(|&x| (needle.clone(), pk.encrypt_raw(&UnsignedInteger::from(x), &mut rng)))
+//!     .collect();
+//!
+//! // The evaluator blinds and shuffles the differences
+//! let (evaluator, blinded_differences) = EqualityEvaluator::start(&pk, &pairs, &mut rng);
+//! // The key holder zero-tests them and encrypts the outcomes
+//! let zero_tests = equality::zero_test(&pk, &sk, &blinded_differences, &mut rng);
+//! // The evaluator restores the original order
+//! let equal = evaluator.finish(&pk, &zero_tests, &mut rng);
+//!
+//! let decrypted: Vec<_> = equal.iter().map(|c| sk.decrypt_raw(&pk, c)).collect();
+//! assert_eq!(
+//!     decrypted,
+//!     vec![UnsignedInteger::from(0), UnsignedInteger::from(1), UnsignedInteger::from(0)]
+//! );
+//! # }
+//! ```
+
+use crate::transport::{Transport, TransportError};
+use scicrypt_traits::cryptosystems::DecryptionKey;
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// An additively homomorphic public key that can be used in the equality test.
+pub trait EqualityTestKey: HomomorphicAddition {
+    /// Samples a random multiplier $\rho$, such that $\rho x$ is zero only if $x$ is zero and is
+    /// otherwise (close to) uniformly distributed.
+    fn random_nonzero_input<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> Self::Input;
+
+    /// Encodes a bit as a plaintext.
+    fn plaintext_from_bit(&self, bit: bool) -> Self::Plaintext;
+}
+
+/// The randomized differences $[\rho_i (a_i - b_i)]$ in a random order, which the evaluator sends
+/// to the key holder.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BlindedDifferences<C>(pub Vec<C>);
+
+/// Fresh encryptions of whether each of the blinded differences is zero, in the same order, which
+/// the key holder sends back to the evaluator.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct ZeroTests<C>(pub Vec<C>);
+
+/// The state of the evaluator, which remembers how it shuffled the differences.
+#[derive(Debug)]
+pub struct EqualityEvaluator {
+    permutation: Vec<usize>,
+}
+
+impl EqualityEvaluator {
+    /// Starts equality tests for each of the ciphertext `pairs`.
+    pub fn start<PK: EqualityTestKey, R: SecureRng>(
+        public_key: &PK,
+        pairs: &[(PK::Ciphertext, PK::Ciphertext)],
+        rng: &mut GeneralRng<R>,
+    ) -> (Self, BlindedDifferences<PK::Ciphertext>) {
+        let mut permutation: Vec<usize> = (0..pairs.len()).collect();
+
+        // Fisher-Yates shuffle, so that the key holder does not learn which pairs are equal
+        for i in (1..permutation.len()).rev() {
+            let j = (rng.rng().next_u64() % (i as u64 + 1)) as usize;
+            permutation.swap(i, j);
+        }
+
+        let differences = permutation
+            .iter()
+            .map(|&index| {
+                let (a, b) = &pairs[index];
+                let rho = public_key.random_nonzero_input(rng);
+                public_key.randomize(public_key.mul_constant(&public_key.sub(a, b), &rho), rng)
+            })
+            .collect();
+
+        (
+            EqualityEvaluator { permutation },
+            BlindedDifferences(differences),
+        )
+    }
+
+    /// Undoes the shuffle on the key holder's zero tests and randomizes them, returning for each of
+    /// the original pairs an encryption of $1$ if they are equal and $0$ otherwise. This consumes
+    /// the evaluator, so that a permutation is never reused for another round of zero tests.
+    pub fn finish<PK: EqualityTestKey, R: SecureRng>(
+        self,
+        public_key: &PK,
+        zero_tests: &ZeroTests<PK::Ciphertext>,
+        rng: &mut GeneralRng<R>,
+    ) -> Vec<PK::Ciphertext>
+    where
+        PK::Ciphertext: Clone,
+    {
+        assert_eq!(
+            zero_tests.0.len(),
+            self.permutation.len(),
+            "the key holder must send exactly one zero test per pair"
+        );
+
+        let mut results: Vec<(usize, PK::Ciphertext)> = self
+            .permutation
+            .iter()
+            .zip(&zero_tests.0)
+            .map(|(&index, zero_test)| (index, public_key.randomize(zero_test.clone(), rng)))
+            .collect();
+        results.sort_by_key(|(index, _)| *index);
+
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Runs the evaluator's side of the equality tests over `transport`, like `start` followed by
+    /// `finish`. Returns an error if the key holder does not send one zero test per pair.
+    pub fn run<PK: EqualityTestKey, T: Transport, R: SecureRng>(
+        transport: &mut T,
+        public_key: &PK,
+        pairs: &[(PK::Ciphertext, PK::Ciphertext)],
+        rng: &mut GeneralRng<R>,
+    ) -> Result<Vec<PK::Ciphertext>, TransportError>
+    where
+        PK::Ciphertext: Clone + Serialize + DeserializeOwned,
+    {
+        let (evaluator, blinded_differences) = EqualityEvaluator::start(public_key, pairs, rng);
+        transport.send_message(&blinded_differences)?;
+
+        let zero_tests: ZeroTests<PK::Ciphertext> = transport.receive_message()?;
+        if zero_tests.0.len() != pairs.len() {
+            return Err(TransportError::Rejected);
+        }
+
+        Ok(evaluator.finish(public_key, &zero_tests, rng))
+    }
+}
+
+/// Zero-tests each of the blinded differences with the secret key, and encrypts the outcomes.
+pub fn zero_test<PK: EqualityTestKey, SK: DecryptionKey<PK>, R: SecureRng>(
+    public_key: &PK,
+    secret_key: &SK,
+    blinded_differences: &BlindedDifferences<PK::Ciphertext>,
+    rng: &mut GeneralRng<R>,
+) -> ZeroTests<PK::Ciphertext> {
+    ZeroTests(
+        blinded_differences
+            .0
+            .iter()
+            .map(|difference| {
+                let is_zero = secret_key.decrypt_identity_raw(public_key, difference);
+                public_key.encrypt_raw(&public_key.plaintext_from_bit(is_zero), rng)
+            })
+            .collect(),
+    )
+}
+
+/// Runs the key holder's side of the equality tests over `transport`, answering a single request.
+pub fn serve<PK: EqualityTestKey, SK: DecryptionKey<PK>, T: Transport, R: SecureRng>(
+    transport: &mut T,
+    public_key: &PK,
+    secret_key: &SK,
+    rng: &mut GeneralRng<R>,
+) -> Result<(), TransportError>
+where
+    PK::Ciphertext: Serialize + DeserializeOwned,
+{
+    let blinded_differences = transport.receive_message()?;
+
+    transport.send_message(&zero_test(
+        public_key,
+        secret_key,
+        &blinded_differences,
+        rng,
+    ))
+}
+
+#[cfg(feature = "integer")]
+mod integer {
+    use super::EqualityTestKey;
+    use crate::cryptosystems::dgk::DgkPK;
+    use crate::cryptosystems::paillier::PaillierPK;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+
+    impl EqualityTestKey for PaillierPK {
+        fn random_nonzero_input<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> UnsignedInteger {
+            // A multiple of p or q only occurs with negligible probability
+            let mut input = UnsignedInteger::random_below(&(self.n.clone() - 1), rng) + 1;
+
+            // The sum is declared one bit larger, but the input is below n
+            input.resize_to(self.n.size_in_bits());
+            input
+        }
+
+        fn plaintext_from_bit(&self, bit: bool) -> UnsignedInteger {
+            UnsignedInteger::from(bit as u64)
+        }
+    }
+
+    impl EqualityTestKey for DgkPK {
+        fn random_nonzero_input<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> u64 {
+            1 + rng.rng().next_u64() % (self.u - 1)
+        }
+
+        fn plaintext_from_bit(&self, bit: bool) -> u64 {
+            bit as u64
+        }
+    }
+}
+
+#[cfg(feature = "curve")]
+mod curve {
+    use super::EqualityTestKey;
+    use crate::cryptosystems::curve_el_gamal::{CurveElGamalPK, PrecomputedCurveElGamalPK};
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+    use curve25519_dalek::traits::Identity;
+    use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+
+    fn bit_to_point(bit: bool) -> RistrettoPoint {
+        if bit {
+            RISTRETTO_BASEPOINT_POINT
+        } else {
+            RistrettoPoint::identity()
+        }
+    }
+
+    impl EqualityTestKey for CurveElGamalPK {
+        fn random_nonzero_input<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> Scalar {
+            // Zero only occurs with negligible probability
+            Scalar::random(rng.rng())
+        }
+
+        fn plaintext_from_bit(&self, bit: bool) -> RistrettoPoint {
+            bit_to_point(bit)
+        }
+    }
+
+    impl EqualityTestKey for PrecomputedCurveElGamalPK {
+        fn random_nonzero_input<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> Scalar {
+            // Zero only occurs with negligible probability
+            Scalar::random(rng.rng())
+        }
+
+        fn plaintext_from_bit(&self, bit: bool) -> RistrettoPoint {
+            bit_to_point(bit)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocols::equality::{self, EqualityEvaluator, EqualityTestKey};
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    fn equality_test<PK: EqualityTestKey, SK: DecryptionKey<PK>>(
+        pk: &PK,
+        sk: &SK,
+        values: &[(PK::Plaintext, PK::Plaintext)],
+    ) -> Vec<bool>
+    where
+        PK::Ciphertext: Clone,
+        PK::Plaintext: PartialEq,
+    {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let pairs: Vec<_> = values
+            .iter()
+            .map(|(a, b)| (pk.encrypt_raw(a, &mut rng), pk.encrypt_raw(b, &mut rng)))
+            .collect();
+
+        let (evaluator, blinded_differences) = EqualityEvaluator::start(pk, &pairs, &mut rng);
+        let zero_tests = equality::zero_test(pk, sk, &blinded_differences, &mut rng);
+        let results = evaluator.finish(pk, &zero_tests, &mut rng);
+
+        results
+            .iter()
+            .map(|result| {
+                let bit = sk.decrypt_raw(pk, result);
+                assert!(bit == pk.plaintext_from_bit(false) || bit == pk.plaintext_from_bit(true));
+                bit == pk.plaintext_from_bit(true)
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_equality_paillier() {
+        use crate::cryptosystems::paillier::Paillier;
+        use scicrypt_bigint::UnsignedInteger;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let values: Vec<_> = [(5u64, 5u64), (5, 6), (0, 0), (1000, 7), (42, 42)]
+            .iter()
+            .map(|&(a, b)| (UnsignedInteger::from(a), UnsignedInteger::from(b)))
+            .collect();
+
+        assert_eq!(
+            equality_test(&pk, &sk, &values),
+            vec![true, false, true, false, true]
+        );
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_equality_over_transport() {
+        use crate::cryptosystems::paillier::Paillier;
+        use crate::transport::MemoryTransport;
+        use scicrypt_bigint::UnsignedInteger;
+        use scicrypt_traits::cryptosystems::EncryptionKey;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let pairs: Vec<_> = [(1u64, 1u64), (1, 2)]
+            .iter()
+            .map(|&(a, b)| {
+                (
+                    pk.encrypt_raw(&UnsignedInteger::from(a), &mut rng),
+                    pk.encrypt_raw(&UnsignedInteger::from(b), &mut rng),
+                )
+            })
+            .collect();
+
+        let (mut evaluator, mut key_holder) = MemoryTransport::pair();
+        let results = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                equality::serve(&mut key_holder, &pk, &sk, &mut GeneralRng::new(OsRng)).unwrap()
+            });
+            EqualityEvaluator::run(&mut evaluator, &pk, &pairs, &mut rng).unwrap()
+        });
+
+        let decrypted: Vec<_> = results.iter().map(|c| sk.decrypt_raw(&pk, c)).collect();
+        assert_eq!(
+            decrypted,
+            vec![UnsignedInteger::from(1), UnsignedInteger::from(0)]
+        );
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_equality_dgk() {
+        use crate::cryptosystems::dgk::Dgk;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Dgk::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        assert_eq!(
+            equality_test(&pk, &sk, &[(3, 3), (3, 4), (100, 99)]),
+            vec![true, false, false]
+        );
+    }
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_equality_curve_el_gamal() {
+        use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use curve25519_dalek::scalar::Scalar;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = CurveElGamal::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let point = |x: u64| Scalar::from(x) * RISTRETTO_BASEPOINT_POINT;
+
+        assert_eq!(
+            equality_test(&pk, &sk, &[(point(9), point(9)), (point(9), point(10))]),
+            vec![true, false]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+/// Aggregation of ciphertexts from multiple parties that first commit to their contributions.
+pub mod aggregation;
+/// Blind decryption, where the key holder decrypts without learning the plaintext.
+#[cfg(any(feature = "curve", feature = "integer"))]
+pub mod blind_decryption;
+/// Secure comparison of Paillier-encrypted integers using the DGK cryptosystem.
+#[cfg(feature = "integer")]
+pub mod comparison;
+/// Private equality tests between additively homomorphic ciphertexts.
+#[cfg(any(feature = "curve", feature = "integer"))]
+pub mod equality;
+/// Oblivious pseudorandom function based on Diffie-Hellman over an elliptic curve.
+#[cfg(feature = "curve")]
+pub mod oprf;
+/// Signcryption, which signs and encrypts a message in one go using curve-based ElGamal keys.
+#[cfg(feature = "curve")]
+pub mod signcryption;
+/// Generation of Beaver multiplication triples between two parties using Paillier encryption.
+#[cfg(feature = "integer")]
+pub mod triples;
+/// Verifiable encryption of discrete logarithms under Paillier keys.
+#[cfg(all(feature = "curve", feature = "integer"))]
+pub mod verifiable_encryption;
+/// Verifiable elections with homomorphic tallying and threshold decryption.
+#[cfg(feature = "curve")]
+pub mod voting;
@@ -0,0 +1,205 @@
+//! The 2HashDH oblivious pseudorandom function (OPRF) over the Ristretto group. A client learns
+//! $F_k(x) = H_2(x, k \cdot H_1(x))$ for its input $x$ without revealing $x$ to the server, while the
+//! server keeps its key $k$ to itself. Here is an example of a single evaluation.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::protocols::oprf::{self, OprfKey};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let server_key = OprfKey::generate(&mut rng);
+//!
+//! // The client blinds its input and sends the blinded element to the server
+//! let (blind, blinded_element) = oprf::blind(b"my password", &mut rng);
+//! // The server evaluates the blinded element without learning the input
+//! let evaluated_element = server_key.evaluate(&blinded_element);
+//! // The client removes the blind and derives the output
+//! let unblinded = oprf::unblind(&blind, &evaluated_element);
+//! let output = oprf::finalize(b"my password", &unblinded);
+//!
+//! assert_eq!(output, server_key.evaluate_directly(b"my password"));
+//! ```
+
+use crate::hash_to_group::hash_to_ristretto;
+use crate::transport::{Transport, TransportError};
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::fmt::{Debug, Formatter};
+
+const FINALIZE_DOMAIN: &[u8] = b"scicrypt-he oprf finalize";
+
+/// The output of the OPRF, which is pseudorandom to anyone who does not know the server's key.
+pub type OprfOutput = [u8; 64];
+
+/// Secret key of the server, with which it evaluates the pseudorandom function.
+pub struct OprfKey {
+    key: Scalar,
+}
+
+impl Debug for OprfKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OprfKey")
+            .field("key", &Redacted::new(&self.key, 253))
+            .finish()
+    }
+}
+
+/// Secret blinding factor that the client keeps to unblind the server's response.
+pub struct Blind {
+    blind: Scalar,
+}
+
+impl Debug for Blind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Blind")
+            .field("blind", &Redacted::new(&self.blind, 253))
+            .finish()
+    }
+}
+
+/// The client's hashed input multiplied by its blind, which is sent to the server.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct BlindedElement(pub RistrettoPoint);
+
+/// The blinded element multiplied by the server's key, which is sent back to the client.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct EvaluatedElement(pub RistrettoPoint);
+
+impl OprfKey {
+    /// Generates a fresh random key for the server.
+    pub fn generate<R: SecureRng>(rng: &mut GeneralRng<R>) -> Self {
+        OprfKey {
+            key: Scalar::random(rng.rng()),
+        }
+    }
+
+    /// Evaluates the client's blinded element under this key.
+    pub fn evaluate(&self, blinded_element: &BlindedElement) -> EvaluatedElement {
+        EvaluatedElement(self.key * blinded_element.0)
+    }
+
+    /// Computes the OPRF output for an `input` that the server knows itself, without any
+    /// interaction. In private set intersection, the server uses this to evaluate its own set.
+    pub fn evaluate_directly(&self, input: &[u8]) -> OprfOutput {
+        finalize(input, &(self.key * hash_to_ristretto(input)))
+    }
+
+    /// Runs the server's side of the OPRF over `transport`, evaluating a single blinded element.
+    pub fn serve<T: Transport>(&self, transport: &mut T) -> Result<(), TransportError> {
+        let blinded_element = transport.receive_message()?;
+
+        transport.send_message(&self.evaluate(&blinded_element))
+    }
+}
+
+/// Hashes the client's `input` to the group and blinds it with a fresh random scalar. The blinded
+/// element reveals nothing about the input, so it can be sent to the server.
+pub fn blind<R: SecureRng>(input: &[u8], rng: &mut GeneralRng<R>) -> (Blind, BlindedElement) {
+    // A zero blind would reveal that the input is hashed to the identity, so we resample it
+    let blind = loop {
+        let blind = Scalar::random(rng.rng());
+        if blind != Scalar::zero() {
+            break blind;
+        }
+    };
+
+    (
+        Blind { blind },
+        BlindedElement(blind * hash_to_ristretto(input)),
+    )
+}
+
+/// Removes the `blind` from the server's response, resulting in the server's key applied to the
+/// hashed input.
+pub fn unblind(blind: &Blind, evaluated_element: &EvaluatedElement) -> RistrettoPoint {
+    blind.blind.invert() * evaluated_element.0
+}
+
+/// Runs the client's side of the OPRF over `transport`, and returns the output for `input`.
+pub fn request<T: Transport, R: SecureRng>(
+    transport: &mut T,
+    input: &[u8],
+    rng: &mut GeneralRng<R>,
+) -> Result<OprfOutput, TransportError> {
+    let (blind, blinded_element) = blind(input, rng);
+    transport.send_message(&blinded_element)?;
+    let evaluated_element = transport.receive_message()?;
+
+    Ok(finalize(input, &unblind(&blind, &evaluated_element)))
+}
+
+/// Derives the OPRF output from the `input` and the unblinded server response. Hashing the input
+/// once more binds the output to the input itself rather than only to its hash in the group.
+pub fn finalize(input: &[u8], unblinded: &RistrettoPoint) -> OprfOutput {
+    Sha512::new()
+        .chain_update(FINALIZE_DOMAIN)
+        .chain_update((input.len() as u64).to_be_bytes())
+        .chain_update(input)
+        .chain_update(unblinded.compress().as_bytes())
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocols::oprf::{self, OprfKey};
+    use rand_core::OsRng;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_oprf_matches_direct_evaluation() {
+        let mut rng = GeneralRng::new(OsRng);
+        let server_key = OprfKey::generate(&mut rng);
+
+        let (blind, blinded_element) = oprf::blind(b"input", &mut rng);
+        let evaluated_element = server_key.evaluate(&blinded_element);
+        let output = oprf::finalize(b"input", &oprf::unblind(&blind, &evaluated_element));
+
+        assert_eq!(output, server_key.evaluate_directly(b"input"));
+    }
+
+    #[test]
+    fn test_oprf_over_transport() {
+        use crate::transport::MemoryTransport;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let server_key = OprfKey::generate(&mut rng);
+
+        let (mut client, mut server) = MemoryTransport::pair();
+        let output = std::thread::scope(|scope| {
+            scope.spawn(|| server_key.serve(&mut server).unwrap());
+            oprf::request(&mut client, b"input", &mut rng).unwrap()
+        });
+
+        assert_eq!(output, server_key.evaluate_directly(b"input"));
+    }
+
+    #[test]
+    fn test_oprf_blinding_is_randomized() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let (_, blinded_element_1) = oprf::blind(b"input", &mut rng);
+        let (_, blinded_element_2) = oprf::blind(b"input", &mut rng);
+
+        assert_ne!(blinded_element_1, blinded_element_2);
+    }
+
+    #[test]
+    fn test_oprf_depends_on_key_and_input() {
+        let mut rng = GeneralRng::new(OsRng);
+        let server_key_1 = OprfKey::generate(&mut rng);
+        let server_key_2 = OprfKey::generate(&mut rng);
+
+        assert_ne!(
+            server_key_1.evaluate_directly(b"input"),
+            server_key_2.evaluate_directly(b"input")
+        );
+        assert_ne!(
+            server_key_1.evaluate_directly(b"input"),
+            server_key_1.evaluate_directly(b"other input")
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Signcryption of curve points, which encrypts a message under the receiver's curve-based ElGamal
+//! key and signs the resulting ciphertext with a Schnorr signature under the sender's ElGamal key.
+//! Both parties therefore use a single key pair for encrypting as well as signing.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::protocols::signcryption::{signcrypt, unsigncrypt};
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (sender_pk, sender_sk) = el_gamal.generate_keys(&mut rng);
+//! let (receiver_pk, receiver_sk) = el_gamal.generate_keys(&mut rng);
+//! let (sender_pk, receiver_pk) = (sender_pk.compress(), receiver_pk.compress());
+//!
+//! let message = RISTRETTO_BASEPOINT_POINT;
+//! let signcrypted = signcrypt(&message, &sender_sk, &receiver_pk, &mut rng);
+//!
+//! assert_eq!(
+//!     unsigncrypt(&signcrypted, &sender_pk, &receiver_sk).unwrap(),
+//!     message
+//! );
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{
+    CurveElGamalCiphertext, CurveElGamalPK, CurveElGamalSK,
+};
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::transcript::Transcript;
+use scicrypt_traits::DecryptionError;
+use serde::{Deserialize, Serialize};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he signcryption challenge";
+
+/// A message that is encrypted for one receiver and signed by one sender.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct SigncryptedMessage {
+    /// Encryption of the message under the receiver's public key
+    pub ciphertext: CurveElGamalCiphertext,
+    /// Challenge of the Schnorr signature on the ciphertext
+    pub challenge: Scalar,
+    /// Response of the Schnorr signature on the ciphertext
+    pub response: Scalar,
+}
+
+/// Encrypts `message` under `receiver_key` and signs the ciphertext using `sender_key`.
+pub fn signcrypt<R: SecureRng>(
+    message: &RistrettoPoint,
+    sender_key: &CurveElGamalSK,
+    receiver_key: &CurveElGamalPK,
+    rng: &mut GeneralRng<R>,
+) -> SigncryptedMessage {
+    let ciphertext = receiver_key.encrypt_raw(message, rng);
+    let sender_point = &sender_key.key * &RISTRETTO_BASEPOINT_TABLE;
+
+    let nonce = Scalar::random(rng.rng());
+    let commitment = &nonce * &RISTRETTO_BASEPOINT_TABLE;
+    let challenge = compute_challenge(&commitment, &sender_point, receiver_key, &ciphertext);
+
+    SigncryptedMessage {
+        ciphertext,
+        challenge,
+        response: nonce + challenge * sender_key.key,
+    }
+}
+
+/// Verifies that `signcrypted` was signed by the owner of `sender_key` and decrypts it using
+/// `receiver_key`. Returns a `DecryptionError` when the signature is invalid, in which case the
+/// ciphertext is not decrypted at all.
+pub fn unsigncrypt(
+    signcrypted: &SigncryptedMessage,
+    sender_key: &CurveElGamalPK,
+    receiver_key: &CurveElGamalSK,
+) -> Result<RistrettoPoint, DecryptionError> {
+    let receiver_point = CurveElGamalPK::new(&receiver_key.key * &RISTRETTO_BASEPOINT_TABLE);
+
+    let commitment = &signcrypted.response * &RISTRETTO_BASEPOINT_TABLE
+        - signcrypted.challenge * sender_key.point;
+    let challenge = compute_challenge(
+        &commitment,
+        &sender_key.point,
+        &receiver_point,
+        &signcrypted.ciphertext,
+    );
+
+    if challenge != signcrypted.challenge {
+        return Err(DecryptionError);
+    }
+
+    Ok(receiver_key.decrypt_raw(&receiver_point, &signcrypted.ciphertext))
+}
+
+/// Hashes the signature's commitment together with both parties' keys and the ciphertext, so that
+/// a signature cannot be transplanted to another sender, receiver or ciphertext.
+fn compute_challenge(
+    commitment: &RistrettoPoint,
+    sender_point: &RistrettoPoint,
+    receiver_key: &CurveElGamalPK,
+    ciphertext: &CurveElGamalCiphertext,
+) -> Scalar {
+    let mut transcript = Transcript::new(CHALLENGE_DOMAIN);
+    transcript.append_message(b"commitment", commitment.compress().as_bytes());
+    transcript.append_message(b"sender key", sender_point.compress().as_bytes());
+    transcript.append_message(b"receiver key", receiver_key.point.compress().as_bytes());
+    transcript.append_message(b"c1", ciphertext.c1.compress().as_bytes());
+    transcript.append_message(b"c2", ciphertext.c2.compress().as_bytes());
+
+    Scalar::from_bytes_mod_order_wide(&transcript.challenge_bytes(b"challenge"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::protocols::signcryption::{signcrypt, unsigncrypt};
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_signcrypt_unsigncrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (sender_pk, sender_sk) = el_gamal.generate_keys(&mut rng);
+        let (receiver_pk, receiver_sk) = el_gamal.generate_keys(&mut rng);
+        let (sender_pk, receiver_pk) = (sender_pk.compress(), receiver_pk.compress());
+
+        let message = RISTRETTO_BASEPOINT_POINT * Scalar::from(7u64);
+        let signcrypted = signcrypt(&message, &sender_sk, &receiver_pk, &mut rng);
+
+        assert_eq!(
+            message,
+            unsigncrypt(&signcrypted, &sender_pk, &receiver_sk).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unsigncrypt_wrong_sender() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (_, sender_sk) = el_gamal.generate_keys(&mut rng);
+        let (other_pk, _) = el_gamal.generate_keys(&mut rng);
+        let (receiver_pk, receiver_sk) = el_gamal.generate_keys(&mut rng);
+        let (other_pk, receiver_pk) = (other_pk.compress(), receiver_pk.compress());
+
+        let signcrypted = signcrypt(
+            &RISTRETTO_BASEPOINT_POINT,
+            &sender_sk,
+            &receiver_pk,
+            &mut rng,
+        );
+
+        assert!(unsigncrypt(&signcrypted, &other_pk, &receiver_sk).is_err());
+    }
+
+    #[test]
+    fn test_unsigncrypt_tampered_ciphertext() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (sender_pk, sender_sk) = el_gamal.generate_keys(&mut rng);
+        let (receiver_pk, receiver_sk) = el_gamal.generate_keys(&mut rng);
+        let (sender_pk, receiver_pk) = (sender_pk.compress(), receiver_pk.compress());
+
+        let mut signcrypted = signcrypt(
+            &RISTRETTO_BASEPOINT_POINT,
+            &sender_sk,
+            &receiver_pk,
+            &mut rng,
+        );
+        signcrypted.ciphertext.c2 += RISTRETTO_BASEPOINT_POINT;
+
+        assert!(unsigncrypt(&signcrypted, &sender_pk, &receiver_sk).is_err());
+    }
+}
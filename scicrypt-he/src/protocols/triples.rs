@@ -0,0 +1,490 @@
+//! Generation of Beaver multiplication triples between two parties using Paillier encryption, in
+//! the style of Gilboa's oblivious product sharing. Both parties end up with additive shares
+//! modulo $M$ of random values $a$, $b$ and $c = a b$, which MPC engines consume to multiply secret
+//! shared values with a single round of openings.
+//!
+//! The key holder samples its shares $a_1, b_1$ and sends their encryptions. The other party
+//! samples $a_2, b_2$ and a large mask $r$, and homomorphically computes
+//! $[a_1 b_2 + b_1 a_2 + r]$ over the integers. The mask statistically hides the cross terms, and
+//! the Paillier modulus is large enough that the sum never wraps around. The key holder sets
+//! $c_1 = a_1 b_1 + (a_1 b_2 + b_1 a_2 + r) \bmod M$, while the other party sets
+//! $c_2 = a_2 b_2 - r \bmod M$. Exponential ElGamal cannot be used here, because decrypting the
+//! masked cross terms would require a discrete logarithm of a large value.
+//!
+//! Without further checks, the protocol is secure against semi-honest parties. A malicious key
+//! holder could encrypt values larger than $M$ to learn about the other party's shares from the
+//! decryption. To prevent this, the key holder can optionally attach proofs that its plaintexts are
+//! small, which the other party can require. A malicious responder can only make the triples
+//! incorrect, which MPC engines detect by sacrificing triples, so that check is left to them.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::protocols::triples::{self, TripleKeyHolder};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let (pk, sk) = Paillier::setup(&BitsOfSecurity::AES80).generate_keys(&mut rng);
+//! let modulus = UnsignedInteger::from(18446744073709551557u64); // 2^64 - 59
+//!
+//! // The key holder encrypts its shares and proves that they are small
+//! let (key_holder, request) = TripleKeyHolder::start(&pk, &modulus, 10, true, &mut rng);
+//! // The other party verifies the proofs and computes its triples
+//! let (triples_2, response) = triples::respond(&pk, &modulus, &request, true, &mut rng).unwrap();
+//! // The key holder decrypts the masked cross terms
+//! let triples_1 = key_holder.finish(&pk, &sk, &response);
+//! # assert_eq!(triples_1.len(), triples_2.len());
+//! ```
+
+use crate::cryptosystems::integer_el_gamal::to_length_prefixed_bytes;
+use crate::cryptosystems::paillier::{PaillierCiphertext, PaillierPK, PaillierSK};
+use crate::transport::{Transport, TransportError};
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{Associable, DecryptionKey, EncryptionKey};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::security::Redacted;
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he beaver triple plaintext range";
+
+/// Number of bits in the challenge, which determines the soundness error.
+const CHALLENGE_BITS: u32 = 128;
+/// Number of bits of statistical security with which nonces and masks hide secrets.
+const STATISTICAL_BITS: u32 = 40;
+
+/// One party's additive shares modulo $M$ of a multiplication triple $(a, b, c = a b)$.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BeaverTriple {
+    /// Share of $a$
+    pub a: UnsignedInteger,
+    /// Share of $b$
+    pub b: UnsignedInteger,
+    /// Share of $c$
+    pub c: UnsignedInteger,
+}
+
+impl Debug for BeaverTriple {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BeaverTriple")
+            .field("a", &Redacted::new(&self.a, self.a.size_in_bits()))
+            .field("b", &Redacted::new(&self.b, self.b.size_in_bits()))
+            .field("c", &Redacted::new(&self.c, self.c.size_in_bits()))
+            .finish()
+    }
+}
+
+/// Non-interactive proof that a Paillier ciphertext encrypts a plaintext of at most
+/// `nonce_bits + 1` bits. The bound has slack: honest plaintexts are smaller than $M$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct PlaintextRangeProof {
+    /// Paillier encryption of the nonce $k$
+    pub nonce_ciphertext: PaillierCiphertext,
+    /// Response $z = k + e \cdot x$ over the integers
+    pub response: UnsignedInteger,
+    /// Response $w = \rho \cdot r^e \bmod n$ for the Paillier randomness
+    pub response_randomness: UnsignedInteger,
+}
+
+/// The encryptions $([a_1], [b_1])$ of the key holder's shares, which it sends to the other party.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct TripleRequest {
+    /// Encryptions of the key holder's shares of $a$ and $b$ for every triple
+    pub ciphertexts: Vec<(PaillierCiphertext, PaillierCiphertext)>,
+    /// Optional proofs that the shares are small, one pair for every triple
+    pub proofs: Option<Vec<(PlaintextRangeProof, PlaintextRangeProof)>>,
+}
+
+/// The masked cross terms $[a_1 b_2 + b_1 a_2 + r]$, which the other party sends back to the key
+/// holder.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct TripleResponse(pub Vec<PaillierCiphertext>);
+
+/// Error returned when a request holds invalid ciphertexts, or when its proofs are missing but
+/// required, or are invalid.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidTripleRequest;
+
+/// The state of the key holder, which remembers its shares of $a$ and $b$.
+pub struct TripleKeyHolder {
+    modulus: UnsignedInteger,
+    shares: Vec<(UnsignedInteger, UnsignedInteger)>,
+}
+
+impl Debug for TripleKeyHolder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TripleKeyHolder")
+            .field("modulus", &self.modulus)
+            .field("triple_count", &self.shares.len())
+            .finish()
+    }
+}
+
+impl TripleKeyHolder {
+    /// Starts generating `count` triples modulo `modulus` under the key holder's `public_key`. If
+    /// `prove` is true, the request includes proofs that the encrypted shares are small, which the
+    /// other party needs for security against a malicious key holder.
+    pub fn start<R: SecureRng>(
+        public_key: &PaillierPK,
+        modulus: &UnsignedInteger,
+        count: usize,
+        prove: bool,
+        rng: &mut GeneralRng<R>,
+    ) -> (Self, TripleRequest) {
+        assert_parameters(public_key, modulus);
+
+        let mut shares = vec![];
+        let mut ciphertexts = vec![];
+        let mut proofs = vec![];
+        for _ in 0..count {
+            let a = UnsignedInteger::random_below(modulus, rng);
+            let b = UnsignedInteger::random_below(modulus, rng);
+
+            let (a_ciphertext, a_proof) = encrypt_share(public_key, modulus, &a, prove, rng);
+            let (b_ciphertext, b_proof) = encrypt_share(public_key, modulus, &b, prove, rng);
+
+            ciphertexts.push((a_ciphertext, b_ciphertext));
+            proofs.push(a_proof.zip(b_proof));
+            shares.push((a, b));
+        }
+
+        (
+            TripleKeyHolder {
+                modulus: modulus.clone(),
+                shares,
+            },
+            TripleRequest {
+                ciphertexts,
+                proofs: proofs.into_iter().collect(),
+            },
+        )
+    }
+
+    /// Decrypts the masked cross terms of the other party's `response`, and returns the key
+    /// holder's shares of the triples.
+    pub fn finish(
+        self,
+        public_key: &PaillierPK,
+        secret_key: &PaillierSK,
+        response: &TripleResponse,
+    ) -> Vec<BeaverTriple> {
+        assert_eq!(
+            response.0.len(),
+            self.shares.len(),
+            "the other party must send exactly one response per triple"
+        );
+
+        let modulus = &self.modulus;
+        self.shares
+            .into_iter()
+            .zip(&response.0)
+            .map(|((a, b), masked)| {
+                let cross_terms = secret_key.decrypt_raw(public_key, masked) % modulus;
+                let c = ((&a * &b) % modulus + &cross_terms) % modulus;
+
+                BeaverTriple { a, b, c }
+            })
+            .collect()
+    }
+
+    /// Runs the key holder's side of generating `count` triples over `transport`, like `start`
+    /// followed by `finish`. Returns an error if the other party rejects the request or does not
+    /// send one response per triple.
+    pub fn run<T: Transport, R: SecureRng>(
+        transport: &mut T,
+        public_key: &PaillierPK,
+        secret_key: &PaillierSK,
+        modulus: &UnsignedInteger,
+        count: usize,
+        prove: bool,
+        rng: &mut GeneralRng<R>,
+    ) -> Result<Vec<BeaverTriple>, TransportError> {
+        let (key_holder, request) = TripleKeyHolder::start(public_key, modulus, count, prove, rng);
+        transport.send_message(&request)?;
+
+        let response: TripleResponse = transport.receive_message()?;
+        if response.0.len() != count {
+            return Err(TransportError::Rejected);
+        }
+
+        Ok(key_holder.finish(public_key, secret_key, &response))
+    }
+}
+
+/// Computes the other party's shares of the triples in `request` and the response for the key
+/// holder. If `require_proofs` is true, the request must hold valid proofs that the key holder's
+/// shares are small.
+pub fn respond<R: SecureRng>(
+    public_key: &PaillierPK,
+    modulus: &UnsignedInteger,
+    request: &TripleRequest,
+    require_proofs: bool,
+    rng: &mut GeneralRng<R>,
+) -> Result<(Vec<BeaverTriple>, TripleResponse), InvalidTripleRequest> {
+    assert_parameters(public_key, modulus);
+
+    if request
+        .ciphertexts
+        .iter()
+        .any(|(a, b)| !a.is_valid_leaky(public_key) || !b.is_valid_leaky(public_key))
+    {
+        return Err(InvalidTripleRequest);
+    }
+
+    match &request.proofs {
+        Some(proofs) => {
+            if proofs.len() != request.ciphertexts.len()
+                || !proofs.iter().zip(&request.ciphertexts).all(
+                    |((a_proof, b_proof), (a_ciphertext, b_ciphertext))| {
+                        a_proof.verify(public_key, modulus, a_ciphertext)
+                            && b_proof.verify(public_key, modulus, b_ciphertext)
+                    },
+                )
+            {
+                return Err(InvalidTripleRequest);
+            }
+        }
+        None if require_proofs => return Err(InvalidTripleRequest),
+        None => (),
+    }
+
+    let mask_bits = mask_bits(modulus);
+
+    let (triples, masked) = request
+        .ciphertexts
+        .iter()
+        .map(|(a_ciphertext, b_ciphertext)| {
+            let a = UnsignedInteger::random_below(modulus, rng);
+            let b = UnsignedInteger::random_below(modulus, rng);
+            let mask = UnsignedInteger::random(mask_bits, rng);
+
+            let cross_terms = public_key.add(
+                &public_key.mul_constant(a_ciphertext, &b),
+                &public_key.mul_constant(b_ciphertext, &a),
+            );
+            let masked = public_key.add(&cross_terms, &public_key.encrypt_raw(&mask, rng));
+
+            let c = ((&a * &b) % modulus + &(modulus.clone() - &(mask % modulus))) % modulus;
+
+            (BeaverTriple { a, b, c }, masked)
+        })
+        .unzip();
+
+    Ok((triples, TripleResponse(masked)))
+}
+
+/// Runs the other party's side of generating triples over `transport`, like `respond`, and returns
+/// its shares of the triples. If the request is invalid, no response is sent and
+/// `TransportError::Rejected` is returned.
+pub fn serve<T: Transport, R: SecureRng>(
+    transport: &mut T,
+    public_key: &PaillierPK,
+    modulus: &UnsignedInteger,
+    require_proofs: bool,
+    rng: &mut GeneralRng<R>,
+) -> Result<Vec<BeaverTriple>, TransportError> {
+    let request = transport.receive_message()?;
+    let (triples, response) = respond(public_key, modulus, &request, require_proofs, rng)
+        .map_err(|InvalidTripleRequest| TransportError::Rejected)?;
+    transport.send_message(&response)?;
+
+    Ok(triples)
+}
+
+impl PlaintextRangeProof {
+    fn prove<R: SecureRng>(
+        public_key: &PaillierPK,
+        modulus: &UnsignedInteger,
+        ciphertext: &PaillierCiphertext,
+        plaintext: &UnsignedInteger,
+        randomness: &UnsignedInteger,
+        rng: &mut GeneralRng<R>,
+    ) -> Self {
+        let nonce = UnsignedInteger::random(nonce_bits(modulus), rng);
+        let nonce_randomness = UnsignedInteger::random_below(&public_key.n, rng);
+        let nonce_ciphertext =
+            public_key.encrypt_with_randomness(&(nonce.clone() % &public_key.n), &nonce_randomness);
+
+        let challenge = compute_challenge(public_key, modulus, ciphertext, &nonce_ciphertext);
+
+        PlaintextRangeProof {
+            nonce_ciphertext,
+            response: nonce + &(&challenge * plaintext),
+            response_randomness: (&nonce_randomness
+                * &randomness.pow_mod(&challenge, &public_key.n))
+                % &public_key.n,
+        }
+    }
+
+    /// Returns true if `ciphertext` encrypts a plaintext of at most `nonce_bits + 1` bits. This
+    /// function is not constant-time, but it only operates on public values.
+    fn verify(
+        &self,
+        public_key: &PaillierPK,
+        modulus: &UnsignedInteger,
+        ciphertext: &PaillierCiphertext,
+    ) -> bool {
+        if !self.nonce_ciphertext.is_valid_leaky(public_key)
+            || self.response_randomness.is_zero_leaky()
+            || self.response_randomness.leak() >= public_key.n.leak()
+            || self.response.significant_bits_leaky() > nonce_bits(modulus) + 1
+        {
+            return false;
+        }
+
+        let challenge = compute_challenge(public_key, modulus, ciphertext, &self.nonce_ciphertext);
+
+        // (1 + n)^z * w^n = A * c^e mod n^2
+        let lhs = public_key.encrypt_with_randomness(
+            &(self.response.clone() % &public_key.n),
+            &self.response_randomness,
+        );
+        let rhs = (&self.nonce_ciphertext.c
+            * &ciphertext.c.pow_mod(&challenge, &public_key.n_squared))
+            % &public_key.n_squared;
+
+        lhs.c == rhs
+    }
+}
+
+/// Encrypts one of the key holder's shares, and proves that it is small if `prove` is true.
+fn encrypt_share<R: SecureRng>(
+    public_key: &PaillierPK,
+    modulus: &UnsignedInteger,
+    share: &UnsignedInteger,
+    prove: bool,
+    rng: &mut GeneralRng<R>,
+) -> (PaillierCiphertext, Option<PlaintextRangeProof>) {
+    let randomness = UnsignedInteger::random_below(&public_key.n, rng);
+    let ciphertext = public_key.encrypt_with_randomness(share, &randomness);
+    let proof = prove.then(|| {
+        PlaintextRangeProof::prove(public_key, modulus, &ciphertext, share, &randomness, rng)
+    });
+
+    (ciphertext, proof)
+}
+
+/// The size of the proof nonces, which exceeds the size of the challenge times a share by
+/// `STATISTICAL_BITS`, rounded up to whole limbs.
+fn nonce_bits(modulus: &UnsignedInteger) -> u32 {
+    (modulus.size_in_bits() + CHALLENGE_BITS + STATISTICAL_BITS).div_ceil(64) * 64
+}
+
+/// The size of the masks, which exceeds the size of the cross terms by `STATISTICAL_BITS`, rounded
+/// up to whole limbs. The cross terms are bounded using the proven size of the key holder's shares.
+fn mask_bits(modulus: &UnsignedInteger) -> u32 {
+    (nonce_bits(modulus) + 1 + modulus.size_in_bits() + 1 + STATISTICAL_BITS).div_ceil(64) * 64
+}
+
+fn assert_parameters(public_key: &PaillierPK, modulus: &UnsignedInteger) {
+    assert!(
+        public_key.n.size_in_bits() > mask_bits(modulus) + 1,
+        "the Paillier modulus is too small for triples of this size"
+    );
+}
+
+fn compute_challenge(
+    public_key: &PaillierPK,
+    modulus: &UnsignedInteger,
+    ciphertext: &PaillierCiphertext,
+    nonce_ciphertext: &PaillierCiphertext,
+) -> UnsignedInteger {
+    let mut transcript = Transcript::new(CHALLENGE_DOMAIN);
+    transcript.append_message(b"public key", &to_length_prefixed_bytes(&public_key.n));
+    transcript.append_message(b"modulus", &to_length_prefixed_bytes(modulus));
+    transcript.append_message(b"ciphertext", &to_length_prefixed_bytes(&ciphertext.c));
+    transcript.append_message(
+        b"nonce ciphertext",
+        &to_length_prefixed_bytes(&nonce_ciphertext.c),
+    );
+
+    let challenge = transcript.challenge_bytes(b"challenge");
+    UnsignedInteger::from_le_bytes(&challenge[..CHALLENGE_BITS as usize / 8], CHALLENGE_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::paillier::Paillier;
+    use crate::protocols::triples::{self, InvalidTripleRequest, TripleKeyHolder};
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_triples_multiply() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::AES80).generate_keys(&mut rng);
+        // 2^61 - 1
+        let modulus = UnsignedInteger::from(2305843009213693951u64);
+
+        for prove in [false, true] {
+            let (key_holder, request) = TripleKeyHolder::start(&pk, &modulus, 5, prove, &mut rng);
+            let (triples_2, response) =
+                triples::respond(&pk, &modulus, &request, prove, &mut rng).unwrap();
+            let triples_1 = key_holder.finish(&pk, &sk, &response);
+
+            assert_eq!(5, triples_1.len());
+            for (t1, t2) in triples_1.iter().zip(&triples_2) {
+                let open = |x: &UnsignedInteger, y: &UnsignedInteger| {
+                    (x.clone().to_rug() + y.clone().to_rug()) % modulus.clone().to_rug()
+                };
+
+                assert_eq!(
+                    open(&t1.c, &t2.c),
+                    (open(&t1.a, &t2.a) * open(&t1.b, &t2.b)) % modulus.clone().to_rug()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_triples_over_transport() {
+        use crate::transport::MemoryTransport;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::AES80).generate_keys(&mut rng);
+        let modulus = UnsignedInteger::from(2305843009213693951u64);
+
+        let (mut key_holder, mut other) = MemoryTransport::pair();
+        let (triples_1, triples_2) = std::thread::scope(|scope| {
+            let other = scope.spawn(|| {
+                triples::serve(&mut other, &pk, &modulus, true, &mut GeneralRng::new(OsRng))
+                    .unwrap()
+            });
+            let triples_1 =
+                TripleKeyHolder::run(&mut key_holder, &pk, &sk, &modulus, 3, true, &mut rng)
+                    .unwrap();
+
+            (triples_1, other.join().unwrap())
+        });
+
+        assert_eq!(3, triples_1.len());
+        assert_eq!(3, triples_2.len());
+    }
+
+    #[test]
+    fn test_respond_requires_valid_proofs() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = Paillier::setup(&BitsOfSecurity::AES80).generate_keys(&mut rng);
+        let modulus = UnsignedInteger::from(2305843009213693951u64);
+
+        let (_, request) = TripleKeyHolder::start(&pk, &modulus, 2, false, &mut rng);
+        assert_eq!(
+            Err(InvalidTripleRequest),
+            triples::respond(&pk, &modulus, &request, true, &mut rng).map(|_| ())
+        );
+
+        // A share that is much larger than the modulus cannot be proven small
+        let (_, mut request) = TripleKeyHolder::start(&pk, &modulus, 2, true, &mut rng);
+        request.ciphertexts[1].0 = pk.encrypt_raw(&(pk.n.clone() - 1), &mut rng);
+        assert_eq!(
+            Err(InvalidTripleRequest),
+            triples::respond(&pk, &modulus, &request, true, &mut rng).map(|_| ())
+        );
+    }
+}
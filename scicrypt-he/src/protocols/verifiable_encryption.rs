@@ -0,0 +1,281 @@
+//! Verifiable encryption of discrete logarithms in the style of Camenisch and Shoup. A prover who
+//! knows the discrete logarithm $x$ of a public point $Y = x \cdot G$ encrypts $x$ under a third
+//! party's Paillier key, and proves in zero knowledge that the ciphertext indeed contains the
+//! discrete logarithm of $Y$. Anyone can verify the proof, while only the third party can recover
+//! $x$, e.g. to resolve a dispute in an escrow or fair-exchange protocol.
+//!
+//! The proof is a Fiat-Shamir transformed sigma protocol over the integers, with a 128-bit
+//! challenge and 128 bits of statistical slack to hide the secret.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::protocols::verifiable_encryption::VerifiableEncryption;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let paillier = Paillier::setup(&BitsOfSecurity::AES80);
+//! let (escrow_pk, escrow_sk) = paillier.generate_keys(&mut rng);
+//!
+//! let secret = Scalar::random(rng.rng());
+//! let public_point = &secret * &RISTRETTO_BASEPOINT_TABLE;
+//!
+//! let encryption = VerifiableEncryption::encrypt(&secret, &escrow_pk, &mut rng);
+//! assert!(encryption.verify(&public_point, &escrow_pk));
+//! assert_eq!(encryption.decrypt(&escrow_sk, &escrow_pk), secret);
+//! ```
+
+use crate::cryptosystems::integer_el_gamal::to_length_prefixed_bytes;
+use crate::cryptosystems::paillier::{PaillierCiphertext, PaillierPK, PaillierSK};
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he verifiable encryption of discrete logarithms";
+
+/// Number of bits in the challenge, which determines the soundness error.
+const CHALLENGE_BITS: u32 = 128;
+/// Number of bits of the nonce. It exceeds the size of the challenge times the secret by 128 bits,
+/// so that the response statistically hides the secret.
+const NONCE_BITS: u32 = 256 + CHALLENGE_BITS + 128;
+
+/// A Paillier encryption of a discrete logarithm, together with a proof that it encrypts the
+/// discrete logarithm of a given point.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct VerifiableEncryption {
+    /// Paillier encryption of the discrete logarithm
+    pub ciphertext: PaillierCiphertext,
+    /// Proof that the ciphertext encrypts the discrete logarithm
+    pub proof: DiscreteLogEncryptionProof,
+}
+
+/// Non-interactive proof that a Paillier ciphertext encrypts the discrete logarithm of a point.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct DiscreteLogEncryptionProof {
+    /// Paillier encryption of the nonce $k$
+    pub nonce_ciphertext: PaillierCiphertext,
+    /// The nonce in the exponent, $k \cdot G$
+    pub nonce_point: RistrettoPoint,
+    /// Response $z = k + e \cdot x$ over the integers
+    pub response: UnsignedInteger,
+    /// Response $w = \rho \cdot r^e \bmod n$ for the Paillier randomness
+    pub response_randomness: UnsignedInteger,
+}
+
+impl VerifiableEncryption {
+    /// Encrypts the discrete logarithm `secret` under the Paillier `public_key` and proves that the
+    /// result encrypts the discrete logarithm of `secret * G`.
+    pub fn encrypt<R: SecureRng>(
+        secret: &Scalar,
+        public_key: &PaillierPK,
+        rng: &mut GeneralRng<R>,
+    ) -> Self {
+        Self::encrypt_with_transcript(
+            &mut Transcript::new(CHALLENGE_DOMAIN),
+            secret,
+            public_key,
+            rng,
+        )
+    }
+
+    /// Same as `encrypt`, but binds the proof to the context absorbed by `transcript`. The verifier
+    /// must call `verify_with_transcript` with a transcript in the same state.
+    pub fn encrypt_with_transcript<R: SecureRng>(
+        transcript: &mut Transcript,
+        secret: &Scalar,
+        public_key: &PaillierPK,
+        rng: &mut GeneralRng<R>,
+    ) -> Self {
+        let secret_integer = UnsignedInteger::from_le_bytes(secret.as_bytes(), 256);
+        let randomness = UnsignedInteger::random_below(&public_key.n, rng);
+        let ciphertext = public_key.encrypt_with_randomness(&secret_integer, &randomness);
+
+        let nonce = UnsignedInteger::random(NONCE_BITS, rng);
+        let nonce_randomness = UnsignedInteger::random_below(&public_key.n, rng);
+        let nonce_ciphertext =
+            public_key.encrypt_with_randomness(&(nonce.clone() % &public_key.n), &nonce_randomness);
+        let nonce_point = &to_scalar(&nonce) * &RISTRETTO_BASEPOINT_TABLE;
+
+        let public_point = secret * &RISTRETTO_BASEPOINT_TABLE;
+        let challenge = compute_challenge(
+            transcript,
+            &public_point,
+            public_key,
+            &ciphertext,
+            &nonce_ciphertext,
+            &nonce_point,
+        );
+
+        let response = nonce + &(&challenge * &secret_integer);
+        let response_randomness =
+            (&nonce_randomness * &randomness.pow_mod(&challenge, &public_key.n)) % &public_key.n;
+
+        VerifiableEncryption {
+            ciphertext,
+            proof: DiscreteLogEncryptionProof {
+                nonce_ciphertext,
+                nonce_point,
+                response,
+                response_randomness,
+            },
+        }
+    }
+
+    /// Returns true if the ciphertext encrypts the discrete logarithm of `public_point` under the
+    /// Paillier `public_key`. This function is not constant-time, but it only operates on public
+    /// values.
+    pub fn verify(&self, public_point: &RistrettoPoint, public_key: &PaillierPK) -> bool {
+        self.verify_with_transcript(
+            &mut Transcript::new(CHALLENGE_DOMAIN),
+            public_point,
+            public_key,
+        )
+    }
+
+    /// Same as `verify`, for encryptions created with `encrypt_with_transcript`.
+    pub fn verify_with_transcript(
+        &self,
+        transcript: &mut Transcript,
+        public_point: &RistrettoPoint,
+        public_key: &PaillierPK,
+    ) -> bool {
+        let proof = &self.proof;
+
+        if self.ciphertext.c.leak() >= public_key.n_squared.leak()
+            || proof.nonce_ciphertext.c.leak() >= public_key.n_squared.leak()
+            || proof.response_randomness.is_zero_leaky()
+            || proof.response_randomness.leak() >= public_key.n.leak()
+            || proof.response.size_in_bits() > NONCE_BITS + 1
+        {
+            return false;
+        }
+
+        let challenge = compute_challenge(
+            transcript,
+            public_point,
+            public_key,
+            &self.ciphertext,
+            &proof.nonce_ciphertext,
+            &proof.nonce_point,
+        );
+
+        // (1 + n)^z * w^n = A * c^e mod n^2
+        let lhs = public_key.encrypt_with_randomness(
+            &(proof.response.clone() % &public_key.n),
+            &proof.response_randomness,
+        );
+        let rhs = (&proof.nonce_ciphertext.c
+            * &self.ciphertext.c.pow_mod(&challenge, &public_key.n_squared))
+            % &public_key.n_squared;
+
+        // z * G = T + e * Y
+        let challenge_scalar = to_scalar(&challenge);
+
+        lhs.c == rhs
+            && &to_scalar(&proof.response) * &RISTRETTO_BASEPOINT_TABLE
+                == proof.nonce_point + challenge_scalar * public_point
+    }
+
+    /// Decrypts the discrete logarithm using the Paillier `secret_key`. Only use this after
+    /// verifying the proof.
+    pub fn decrypt(&self, secret_key: &PaillierSK, public_key: &PaillierPK) -> Scalar {
+        to_scalar(&secret_key.decrypt_raw(public_key, &self.ciphertext))
+    }
+}
+
+/// Reduces `integer` modulo the order of the Ristretto group.
+fn to_scalar(integer: &UnsignedInteger) -> Scalar {
+    integer
+        .to_le_bytes()
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |accumulator, &byte| {
+            accumulator * Scalar::from(256u64) + Scalar::from(byte)
+        })
+}
+
+fn compute_challenge(
+    transcript: &mut Transcript,
+    public_point: &RistrettoPoint,
+    public_key: &PaillierPK,
+    ciphertext: &PaillierCiphertext,
+    nonce_ciphertext: &PaillierCiphertext,
+    nonce_point: &RistrettoPoint,
+) -> UnsignedInteger {
+    transcript.append_message(b"proof", CHALLENGE_DOMAIN);
+    transcript.append_message(b"public key", &to_length_prefixed_bytes(&public_key.n));
+    transcript.append_message(b"public point", public_point.compress().as_bytes());
+    transcript.append_message(b"ciphertext", &to_length_prefixed_bytes(&ciphertext.c));
+    transcript.append_message(
+        b"nonce ciphertext",
+        &to_length_prefixed_bytes(&nonce_ciphertext.c),
+    );
+    transcript.append_message(b"nonce point", nonce_point.compress().as_bytes());
+
+    let challenge = transcript.challenge_bytes(b"challenge");
+    UnsignedInteger::from_le_bytes(&challenge[..CHALLENGE_BITS as usize / 8], CHALLENGE_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::paillier::Paillier;
+    use crate::protocols::verifiable_encryption::VerifiableEncryption;
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_verify_and_decrypt() {
+        let mut rng = GeneralRng::new(OsRng);
+        let paillier = Paillier::setup(&BitsOfSecurity::AES80);
+        let (pk, sk) = paillier.generate_keys(&mut rng);
+
+        let secret = Scalar::random(rng.rng());
+        let public_point = &secret * &RISTRETTO_BASEPOINT_TABLE;
+
+        let encryption = VerifiableEncryption::encrypt(&secret, &pk, &mut rng);
+
+        assert!(encryption.verify(&public_point, &pk));
+        assert_eq!(secret, encryption.decrypt(&sk, &pk));
+    }
+
+    #[test]
+    fn test_verify_rejects_other_point() {
+        let mut rng = GeneralRng::new(OsRng);
+        let paillier = Paillier::setup(&BitsOfSecurity::AES80);
+        let (pk, _) = paillier.generate_keys(&mut rng);
+
+        let secret = Scalar::random(rng.rng());
+        let other_point = &Scalar::random(rng.rng()) * &RISTRETTO_BASEPOINT_TABLE;
+
+        let encryption = VerifiableEncryption::encrypt(&secret, &pk, &mut rng);
+
+        assert!(!encryption.verify(&other_point, &pk));
+    }
+
+    #[test]
+    fn test_verify_rejects_other_ciphertext() {
+        let mut rng = GeneralRng::new(OsRng);
+        let paillier = Paillier::setup(&BitsOfSecurity::AES80);
+        let (pk, _) = paillier.generate_keys(&mut rng);
+
+        let secret = Scalar::random(rng.rng());
+        let public_point = &secret * &RISTRETTO_BASEPOINT_TABLE;
+
+        let mut encryption = VerifiableEncryption::encrypt(&secret, &pk, &mut rng);
+        encryption.ciphertext =
+            VerifiableEncryption::encrypt(&Scalar::random(rng.rng()), &pk, &mut rng).ciphertext;
+
+        assert!(!encryption.verify(&public_point, &pk));
+    }
+}
@@ -0,0 +1,459 @@
+//! Verifiable elections with homomorphic tallying over threshold curve-based ElGamal. Voters
+//! encrypt a one-hot vector over the options, together with proofs that every entry and their sum
+//! encrypt 0 or 1. Anyone can verify the ballots and add them up to an encrypted tally, of which
+//! $t$ out of $n$ trustees jointly decrypt the counts. The whole election is captured in an
+//! `ElectionTranscript`, which anyone can verify after the fact. Here is an example of a
+//! referendum with two trustees out of three.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::threshold_cryptosystems::TOfNCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::threshold_cryptosystems::curve_el_gamal::TOfNCurveElGamal;
+//! # use scicrypt_he::protocols::voting::{Election, ElectionTranscript};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+//! let (public_key, trustee_keys) = el_gamal.generate_keys(2, 3, &mut rng);
+//! let verification_keys: Vec<_> = trustee_keys.iter().map(|sk| sk.verification_key()).collect();
+//!
+//! // Every voter casts a ballot for "no" (0) or "yes" (1), or a blank ballot
+//! let election = Election::new(b"referendum", 2, public_key);
+//! let ballots = vec![
+//!     election.cast_ballot(1, Some(1), &mut rng),
+//!     election.cast_ballot(2, Some(1), &mut rng),
+//!     election.cast_ballot(3, Some(0), &mut rng),
+//!     election.cast_ballot(4, None, &mut rng),
+//! ];
+//!
+//! // The ballots are verified and added up, after which two trustees decrypt the tally
+//! let tally = election.tally(&ballots).unwrap();
+//! let tally_shares = vec![
+//!     election.decrypt_tally_share(&tally, &trustee_keys[0]),
+//!     election.decrypt_tally_share(&tally, &trustee_keys[2]),
+//! ];
+//! let counts = election
+//!     .combine_tally(&tally, &tally_shares, &verification_keys, ballots.len() as u64)
+//!     .unwrap();
+//! assert_eq!(counts, vec![1, 2]);
+//!
+//! // Anyone can verify the published transcript of the election
+//! let transcript = ElectionTranscript {
+//!     election,
+//!     ballots,
+//!     tally_shares,
+//!     verification_keys,
+//!     counts,
+//! };
+//! assert!(transcript.verify());
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{
+    CurveDiscreteLogTable, CurveElGamalCiphertext, CurveElGamalPK,
+};
+use crate::proofs::range::RangeProof;
+use crate::threshold_cryptosystems::curve_el_gamal::{
+    InvalidDecryptionShares, TOfNCurveElGamalSK, TOfNCurveElGamalShare,
+};
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::threshold_cryptosystems::PartialDecryptionKey;
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const BALLOT_DOMAIN: &[u8] = b"scicrypt-he voting ballot";
+
+/// The public parameters of an election.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Election {
+    /// Unique identifier of the election, to which all ballot proofs are bound
+    pub id: Vec<u8>,
+    /// The number of options that voters can choose from
+    pub option_count: usize,
+    /// The joint public key of the trustees
+    pub public_key: CurveElGamalPK,
+}
+
+/// An encrypted vote of a single voter, with proofs that it selects at most one option.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Ballot {
+    /// Identifier of the voter, which must be unique within the election
+    pub voter_id: u64,
+    /// Encryptions of 1 for the chosen option and 0 for all other options
+    pub votes: Vec<CurveElGamalCiphertext>,
+    /// Proofs that each vote encrypts either 0 or 1
+    pub vote_proofs: Vec<RangeProof>,
+    /// Proof that the sum of the votes encrypts either 0 (a blank ballot) or 1
+    pub sum_proof: RangeProof,
+}
+
+/// The encrypted number of votes for each option.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Tally(pub Vec<CurveElGamalCiphertext>);
+
+/// One trustee's decryption shares of the tally, one for each option.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct TallyShares(pub Vec<TOfNCurveElGamalShare>);
+
+/// Error returned when some ballots are invalid. It holds the positions of the invalid ballots,
+/// which either carry an invalid proof, have the wrong number of votes, or reuse the id of an
+/// earlier voter.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidBallots(pub Vec<usize>);
+
+/// Everything that is published during an election, so that anyone can verify its outcome.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct ElectionTranscript {
+    /// The public parameters of the election
+    pub election: Election,
+    /// All ballots that were cast
+    pub ballots: Vec<Ballot>,
+    /// The tally shares of the trustees that took part in decrypting
+    pub tally_shares: Vec<TallyShares>,
+    /// The verification keys of all trustees, ordered by their id
+    pub verification_keys: Vec<RistrettoPoint>,
+    /// The announced number of votes for each option
+    pub counts: Vec<u64>,
+}
+
+impl Election {
+    /// Sets up an election with `option_count` options, identified by `id`, where ballots are
+    /// encrypted under the trustees' joint `public_key`.
+    pub fn new(id: &[u8], option_count: usize, public_key: CurveElGamalPK) -> Self {
+        assert!(option_count > 0, "an election needs at least one option");
+
+        Election {
+            id: id.to_vec(),
+            option_count,
+            public_key,
+        }
+    }
+
+    /// Casts a ballot for the option with index `choice`, or a blank ballot if `choice` is `None`.
+    pub fn cast_ballot<R: SecureRng>(
+        &self,
+        voter_id: u64,
+        choice: Option<usize>,
+        rng: &mut GeneralRng<R>,
+    ) -> Ballot {
+        if let Some(choice) = choice {
+            assert!(
+                choice < self.option_count,
+                "the choice is not a valid option"
+            );
+        }
+
+        let mut transcript = self.ballot_transcript(voter_id);
+
+        let mut votes = Vec::with_capacity(self.option_count);
+        let mut vote_proofs = Vec::with_capacity(self.option_count);
+        let mut randomness_sum = Scalar::zero();
+
+        for option in 0..self.option_count {
+            let vote = (choice == Some(option)) as u64;
+            let (ciphertext, randomness) = self.public_key.encrypt_returning_randomness(
+                &(Scalar::from(vote) * RISTRETTO_BASEPOINT_POINT),
+                rng,
+            );
+
+            let mut option_transcript = transcript.clone();
+            option_transcript.append_u64(b"option", option as u64);
+            vote_proofs.push(RangeProof::prove_with_transcript(
+                &mut option_transcript,
+                vote,
+                &randomness,
+                1,
+                &self.public_key,
+                rng,
+            ));
+
+            votes.push(ciphertext);
+            randomness_sum += randomness;
+        }
+
+        transcript.append_message(b"sum", b"");
+        let sum_proof = RangeProof::prove_with_transcript(
+            &mut transcript,
+            choice.is_some() as u64,
+            &randomness_sum,
+            1,
+            &self.public_key,
+            rng,
+        );
+
+        Ballot {
+            voter_id,
+            votes,
+            vote_proofs,
+            sum_proof,
+        }
+    }
+
+    /// Returns true if the ballot selects at most one of this election's options.
+    pub fn verify_ballot(&self, ballot: &Ballot) -> bool {
+        if ballot.votes.len() != self.option_count || ballot.vote_proofs.len() != self.option_count
+        {
+            return false;
+        }
+
+        let mut transcript = self.ballot_transcript(ballot.voter_id);
+
+        let votes_valid = ballot
+            .votes
+            .iter()
+            .zip(&ballot.vote_proofs)
+            .enumerate()
+            .all(|(option, (vote, proof))| {
+                let mut option_transcript = transcript.clone();
+                option_transcript.append_u64(b"option", option as u64);
+                proof.verify_with_transcript(&mut option_transcript, vote, 1, &self.public_key)
+            });
+
+        transcript.append_message(b"sum", b"");
+        let sum: CurveElGamalCiphertext = ballot.votes.iter().sum();
+
+        votes_valid
+            && ballot
+                .sum_proof
+                .verify_with_transcript(&mut transcript, &sum, 1, &self.public_key)
+    }
+
+    /// Verifies all ballots and adds up their votes into an encrypted tally. If any ballot is
+    /// invalid, the positions of all invalid ballots in `ballots` are returned instead.
+    pub fn tally(&self, ballots: &[Ballot]) -> Result<Tally, InvalidBallots> {
+        let mut voters = HashSet::with_capacity(ballots.len());
+
+        let invalid: Vec<usize> = ballots
+            .iter()
+            .enumerate()
+            .filter(|(_, ballot)| !voters.insert(ballot.voter_id) || !self.verify_ballot(ballot))
+            .map(|(i, _)| i)
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(InvalidBallots(invalid));
+        }
+
+        Ok(Tally(
+            (0..self.option_count)
+                .map(|option| ballots.iter().map(|ballot| &ballot.votes[option]).sum())
+                .collect(),
+        ))
+    }
+
+    /// Computes a trustee's decryption shares of the tally with its partial key.
+    pub fn decrypt_tally_share(
+        &self,
+        tally: &Tally,
+        secret_key: &TOfNCurveElGamalSK,
+    ) -> TallyShares {
+        TallyShares(
+            tally
+                .0
+                .iter()
+                .map(|count| secret_key.partial_decrypt_raw(&self.public_key, count))
+                .collect(),
+        )
+    }
+
+    /// Verifies and combines the trustees' tally shares into the number of votes for each option.
+    /// At least $t$ trustees must provide their shares, and the `verification_keys` hold the
+    /// verification keys of all $n$ trustees, ordered by their id. If any trustee's shares are
+    /// invalid, the positions of those trustees in `tally_shares` are returned instead.
+    ///
+    /// The counts are found with a discrete logarithm up to `max_count`, which is typically the
+    /// number of ballots. This is not constant-time, which is fine because the counts are public.
+    /// Panics if `max_count` is $2^{48}$ or more, because the table would not fit in memory.
+    pub fn combine_tally(
+        &self,
+        tally: &Tally,
+        tally_shares: &[TallyShares],
+        verification_keys: &[RistrettoPoint],
+        max_count: u64,
+    ) -> Result<Vec<u64>, InvalidDecryptionShares> {
+        let invalid: Vec<usize> = tally_shares
+            .iter()
+            .enumerate()
+            .filter(|(_, shares)| shares.0.len() != self.option_count)
+            .map(|(i, _)| i)
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(InvalidDecryptionShares(invalid));
+        }
+
+        let discrete_log = CurveDiscreteLogTable::with_max(max_count)
+            .expect("the maximum count must be below 2^48");
+        let mut counts = Vec::with_capacity(self.option_count);
+
+        for (option, count) in tally.0.iter().enumerate() {
+            let shares: Vec<TOfNCurveElGamalShare> = tally_shares
+                .iter()
+                .map(|shares| shares.0[option].clone())
+                .collect();
+
+            let decrypted = TOfNCurveElGamalShare::combine_verified(
+                &shares,
+                count,
+                verification_keys,
+                &self.public_key,
+            )?;
+
+            counts.push(
+                discrete_log
+                    .discrete_log_leaky(&decrypted)
+                    .filter(|count| *count <= max_count)
+                    .expect("the tally of valid ballots cannot exceed the maximum count"),
+            );
+        }
+
+        Ok(counts)
+    }
+
+    /// The transcript to which every proof in a ballot is bound, so that ballots cannot be replayed
+    /// in other elections or by other voters.
+    fn ballot_transcript(&self, voter_id: u64) -> Transcript {
+        let mut transcript = Transcript::new(BALLOT_DOMAIN);
+        transcript.append_message(b"election", &self.id);
+        transcript.append_u64(b"option count", self.option_count as u64);
+        transcript.append_u64(b"voter", voter_id);
+        transcript
+    }
+}
+
+impl ElectionTranscript {
+    /// Returns true if all ballots are valid and the tally shares decrypt to the announced counts.
+    pub fn verify(&self) -> bool {
+        let tally = match self.election.tally(&self.ballots) {
+            Ok(tally) => tally,
+            Err(_) => return false,
+        };
+
+        match self.election.combine_tally(
+            &tally,
+            &self.tally_shares,
+            &self.verification_keys,
+            self.ballots.len() as u64,
+        ) {
+            Ok(counts) => counts == self.counts,
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocols::voting::{Election, ElectionTranscript, InvalidBallots};
+    use crate::threshold_cryptosystems::curve_el_gamal::{
+        InvalidDecryptionShares, TOfNCurveElGamal, TOfNCurveElGamalSK,
+    };
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use rand_core::OsRng;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+    use scicrypt_traits::threshold_cryptosystems::TOfNCryptosystem;
+
+    fn setup() -> (Election, Vec<TOfNCurveElGamalSK>, Vec<RistrettoPoint>) {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+        let (pk, sks) = el_gamal.generate_keys(2, 3, &mut rng);
+        let verification_keys = sks.iter().map(|sk| sk.verification_key()).collect();
+
+        (
+            Election::new(b"test election", 3, pk),
+            sks,
+            verification_keys,
+        )
+    }
+
+    #[test]
+    fn test_election() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (election, sks, verification_keys) = setup();
+
+        let choices = [Some(0), Some(2), Some(2), None, Some(2), Some(0)];
+        let ballots: Vec<_> = choices
+            .iter()
+            .enumerate()
+            .map(|(voter, &choice)| election.cast_ballot(voter as u64, choice, &mut rng))
+            .collect();
+
+        let tally = election.tally(&ballots).unwrap();
+        let tally_shares = vec![
+            election.decrypt_tally_share(&tally, &sks[1]),
+            election.decrypt_tally_share(&tally, &sks[2]),
+        ];
+        let counts = election
+            .combine_tally(&tally, &tally_shares, &verification_keys, 6)
+            .unwrap();
+        assert_eq!(counts, vec![2, 0, 3]);
+
+        let transcript = ElectionTranscript {
+            election,
+            ballots,
+            tally_shares,
+            verification_keys,
+            counts,
+        };
+        assert!(transcript.verify());
+
+        // The transcript survives serialization, and wrong counts are rejected
+        let bytes = bincode::serialize(&transcript).unwrap();
+        let mut deserialized: ElectionTranscript = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, transcript);
+        deserialized.counts = vec![2, 1, 2];
+        assert!(!deserialized.verify());
+    }
+
+    #[test]
+    fn test_invalid_ballots() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (election, _, _) = setup();
+        let other_election = Election::new(b"other election", 3, election.public_key.clone());
+
+        let valid = election.cast_ballot(1, Some(1), &mut rng);
+        let duplicate = election.cast_ballot(1, Some(2), &mut rng);
+        let replayed = other_election.cast_ballot(2, Some(0), &mut rng);
+        let mut stolen = election.cast_ballot(3, Some(0), &mut rng);
+        stolen.voter_id = 4;
+
+        // A ballot that votes twice by copying a vote of 1 onto another option
+        let mut double = election.cast_ballot(5, Some(0), &mut rng);
+        double.votes[1] = double.votes[0].clone();
+        double.vote_proofs[1] = double.vote_proofs[0].clone();
+
+        assert!(election.verify_ballot(&valid));
+        assert!(election.verify_ballot(&duplicate));
+        assert!(!election.verify_ballot(&replayed));
+        assert!(!election.verify_ballot(&stolen));
+        assert!(!election.verify_ballot(&double));
+
+        assert_eq!(
+            election.tally(&[valid, duplicate, replayed, stolen, double]),
+            Err(InvalidBallots(vec![1, 2, 3, 4]))
+        );
+    }
+
+    #[test]
+    fn test_invalid_tally_shares() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (election, sks, verification_keys) = setup();
+
+        let ballots = vec![election.cast_ballot(1, Some(1), &mut rng)];
+        let other_ballots = vec![election.cast_ballot(1, Some(2), &mut rng)];
+        let tally = election.tally(&ballots).unwrap();
+        let other_tally = election.tally(&other_ballots).unwrap();
+
+        let tally_shares = vec![
+            election.decrypt_tally_share(&tally, &sks[0]),
+            election.decrypt_tally_share(&other_tally, &sks[1]),
+        ];
+
+        assert_eq!(
+            election.combine_tally(&tally, &tally_shares, &verification_keys, 1),
+            Err(InvalidDecryptionShares(vec![1]))
+        );
+    }
+}
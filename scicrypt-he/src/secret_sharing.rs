@@ -0,0 +1,565 @@
+//! Shamir's $(t, n)$-threshold secret sharing. The dealer hides the secret in the constant term of a
+//! random polynomial $f$ of degree $t - 1$ and hands party $i$ the share $f(i)$. Any $t$ shares
+//! determine $f$ and therefore the secret through Lagrange interpolation at zero, while fewer shares
+//! reveal nothing about it.
+//!
+//! With the `integer` feature, secrets are shared over the integers modulo an arbitrary prime. With
+//! the `curve` feature, secrets are scalars of the Ristretto group, which additionally allows
+//! Feldman's verifiable secret sharing: the dealer publishes commitments to the coefficients of $f$
+//! in the exponent, so that every party can check its share without learning anything about the
+//! secret beyond $s \cdot G$. Pedersen's variant commits to the coefficients with an additional
+//! random polynomial, so that the commitments do not even reveal $s \cdot G$.
+//! ```
+//! # #[cfg(feature = "integer")]
+//! # {
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::secret_sharing::{reconstruct, share};
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let modulus = UnsignedInteger::from(2305843009213693951u64); // 2^61 - 1
+//!
+//! let shares = share(&UnsignedInteger::from(42u64), 2, 3, &modulus, &mut rng);
+//!
+//! assert_eq!(
+//!     Some(UnsignedInteger::from(42u64)),
+//!     reconstruct(&shares[1..], &modulus)
+//! );
+//! # }
+//! ```
+
+#[cfg(feature = "curve")]
+use crate::proofs::pedersen::PedersenCommitment;
+#[cfg(feature = "curve")]
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+#[cfg(feature = "curve")]
+use curve25519_dalek::ristretto::RistrettoPoint;
+#[cfg(feature = "curve")]
+use curve25519_dalek::scalar::Scalar;
+#[cfg(feature = "curve")]
+use curve25519_dalek::traits::Identity;
+#[cfg(feature = "integer")]
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// A share of a secret modulo a prime, which is the evaluation of the dealer's polynomial at
+/// `index`.
+#[cfg(feature = "integer")]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Share {
+    /// The non-zero point at which the polynomial was evaluated
+    pub index: u64,
+    /// The evaluation $f(i)$ of the polynomial
+    pub value: UnsignedInteger,
+}
+
+#[cfg(feature = "integer")]
+impl Debug for Share {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Share")
+            .field("index", &self.index)
+            .field(
+                "value",
+                &Redacted::new(&self.value, self.value.size_in_bits()),
+            )
+            .finish()
+    }
+}
+
+/// Splits `secret`, which must be smaller than the prime `modulus`, into `count` shares of which
+/// `threshold` are needed to reconstruct it. The shares are ordered by their index, which runs from
+/// 1 to `count`.
+#[cfg(feature = "integer")]
+pub fn share<R: SecureRng>(
+    secret: &UnsignedInteger,
+    threshold: usize,
+    count: usize,
+    modulus: &UnsignedInteger,
+    rng: &mut GeneralRng<R>,
+) -> Vec<Share> {
+    assert!(
+        0 < threshold && threshold <= count,
+        "the threshold must be between 1 and the number of shares"
+    );
+
+    let mut coefficients: Vec<UnsignedInteger> = (1..threshold)
+        .map(|_| UnsignedInteger::random_below(modulus, rng))
+        .collect();
+    coefficients.insert(0, secret.clone() % modulus);
+
+    (1..=count as u64)
+        .map(|index| Share {
+            index,
+            value: evaluate(&coefficients, index, modulus),
+        })
+        .collect()
+}
+
+/// Reconstructs the secret from at least `threshold` shares that were created with the same prime
+/// `modulus`. If more shares are given than needed, all of them must be correct. Returns None if
+/// there are no shares or if two shares have the same index.
+#[cfg(feature = "integer")]
+pub fn reconstruct(shares: &[Share], modulus: &UnsignedInteger) -> Option<UnsignedInteger> {
+    let indices: Vec<u64> = shares.iter().map(|share| share.index).collect();
+    if indices.is_empty() || has_duplicates(&indices) {
+        return None;
+    }
+
+    shares
+        .iter()
+        .map(|share| {
+            let coefficient = lagrange_coefficient(share.index, &indices, modulus)?;
+            Some((&share.value * &coefficient) % modulus)
+        })
+        .reduce(|sum, term| Some((sum? + &term?) % modulus))?
+}
+
+/// Re-randomizes `shares` by adding a fresh sharing of zero with the same `threshold` to them. The
+/// refreshed shares encode the same secret, but cannot be combined with shares from before the
+/// refresh.
+#[cfg(feature = "integer")]
+pub fn refresh<R: SecureRng>(
+    shares: &[Share],
+    threshold: usize,
+    modulus: &UnsignedInteger,
+    rng: &mut GeneralRng<R>,
+) -> Vec<Share> {
+    let mut coefficients: Vec<UnsignedInteger> = (1..threshold)
+        .map(|_| UnsignedInteger::random_below(modulus, rng))
+        .collect();
+    coefficients.insert(0, UnsignedInteger::zero(modulus.size_in_bits()));
+
+    shares
+        .iter()
+        .map(|share| Share {
+            index: share.index,
+            value: (share.value.clone() % modulus + &evaluate(&coefficients, share.index, modulus))
+                % modulus,
+        })
+        .collect()
+}
+
+/// Evaluates the polynomial with `coefficients`, lowest degree first, at `x` using Horner's rule.
+#[cfg(feature = "integer")]
+fn evaluate(
+    coefficients: &[UnsignedInteger],
+    x: u64,
+    modulus: &UnsignedInteger,
+) -> UnsignedInteger {
+    let (highest, rest) = coefficients.split_last().unwrap();
+
+    rest.iter()
+        .rev()
+        .fold(highest.clone(), |accumulator, coefficient| {
+            (accumulator.mul_u64(x) + coefficient) % modulus
+        })
+}
+
+/// Computes the Lagrange coefficient $\prod_{j \neq i} j / (j - i) \bmod p$ of `index` for
+/// interpolating at zero. The indices are public, so this function is not constant-time. Returns
+/// None if some difference of indices is not invertible, i.e. if two indices coincide modulo $p$.
+#[cfg(feature = "integer")]
+fn lagrange_coefficient(
+    index: u64,
+    indices: &[u64],
+    modulus: &UnsignedInteger,
+) -> Option<UnsignedInteger> {
+    let mut numerator = UnsignedInteger::from(1u64);
+    let mut denominator = UnsignedInteger::from(1u64);
+    let mut negative = false;
+
+    for &other in indices.iter().filter(|&&other| other != index) {
+        numerator = numerator.mul_u64(other) % modulus;
+        denominator = denominator.mul_u64(other.abs_diff(index)) % modulus;
+        negative ^= other < index;
+    }
+
+    let coefficient = (&numerator * &denominator.invert_leaky(modulus)?) % modulus;
+
+    if negative && !coefficient.is_zero_leaky() {
+        Some(modulus.clone() - &coefficient)
+    } else {
+        Some(coefficient)
+    }
+}
+
+/// A share of a Ristretto scalar, which is the evaluation of the dealer's polynomial at `index`.
+#[cfg(feature = "curve")]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScalarShare {
+    /// The non-zero point at which the polynomial was evaluated
+    pub index: u64,
+    /// The evaluation $f(i)$ of the polynomial
+    pub value: Scalar,
+}
+
+#[cfg(feature = "curve")]
+impl Debug for ScalarShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScalarShare")
+            .field("index", &self.index)
+            .field("value", &Redacted::new(&self.value, 253))
+            .finish()
+    }
+}
+
+/// Feldman commitments $a_k \cdot G$ to the coefficients of the dealer's polynomial, which the
+/// dealer broadcasts so that every party can verify its share.
+#[cfg(feature = "curve")]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct FeldmanCommitment {
+    /// Commitments to all coefficients of the polynomial, lowest degree first. The first one is
+    /// the commitment $s \cdot G$ to the secret.
+    pub coefficients: Vec<RistrettoPoint>,
+}
+
+/// Splits `secret` into `count` verifiable shares of which `threshold` are needed to reconstruct
+/// it. Returns the commitment to broadcast and the shares, ordered by their index, which runs from
+/// 1 to `count`.
+#[cfg(feature = "curve")]
+pub fn feldman_share<R: SecureRng>(
+    secret: &Scalar,
+    threshold: usize,
+    count: usize,
+    rng: &mut GeneralRng<R>,
+) -> (FeldmanCommitment, Vec<ScalarShare>) {
+    assert!(
+        0 < threshold && threshold <= count,
+        "the threshold must be between 1 and the number of shares"
+    );
+
+    let coefficients = random_polynomial(secret, threshold, rng);
+
+    let shares = (1..=count as u64)
+        .map(|index| ScalarShare {
+            index,
+            value: evaluate_scalars(&coefficients, index),
+        })
+        .collect();
+
+    let commitment = FeldmanCommitment {
+        coefficients: coefficients
+            .iter()
+            .map(|a| a * &RISTRETTO_BASEPOINT_TABLE)
+            .collect(),
+    };
+
+    (commitment, shares)
+}
+
+#[cfg(feature = "curve")]
+impl FeldmanCommitment {
+    /// Returns true if `share` is the evaluation of the committed polynomial at its index.
+    pub fn verify(&self, share: &ScalarShare) -> bool {
+        share.index != 0 && &share.value * &RISTRETTO_BASEPOINT_TABLE == self.evaluate(share.index)
+    }
+
+    /// Returns the commitment $s \cdot G$ to the shared secret.
+    pub fn secret_commitment(&self) -> RistrettoPoint {
+        self.coefficients[0]
+    }
+
+    /// Evaluates the committed polynomial in the exponent, i.e. returns $f(i) \cdot G$.
+    fn evaluate(&self, index: u64) -> RistrettoPoint {
+        evaluate_points(&self.coefficients, index)
+    }
+}
+
+/// A share of a Ristretto scalar together with the evaluation of the dealer's blinding polynomial
+/// at the same index, which is needed to verify it against a Pedersen commitment.
+#[cfg(feature = "curve")]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PedersenShare {
+    /// The share of the secret
+    pub share: ScalarShare,
+    /// The evaluation $g(i)$ of the blinding polynomial
+    pub blinding: Scalar,
+}
+
+#[cfg(feature = "curve")]
+impl Debug for PedersenShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PedersenShare")
+            .field("share", &self.share)
+            .field("blinding", &Redacted::new(&self.blinding, 253))
+            .finish()
+    }
+}
+
+/// Pedersen commitments $a_k \cdot G + b_k \cdot H$ to the coefficients of the dealer's polynomial
+/// $f$ and of a random blinding polynomial $g$. Unlike Feldman commitments, they reveal nothing
+/// about the secret, not even $s \cdot G$.
+#[cfg(feature = "curve")]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct PedersenVssCommitment {
+    /// Commitments to all coefficients of both polynomials, lowest degree first. The first one is
+    /// a Pedersen commitment to the secret.
+    pub coefficients: Vec<RistrettoPoint>,
+}
+
+/// Splits `secret` into `count` verifiable shares of which `threshold` are needed to reconstruct
+/// it, using hiding commitments. Returns the commitment to broadcast and the shares, ordered by
+/// their index, which runs from 1 to `count`.
+#[cfg(feature = "curve")]
+pub fn pedersen_share<R: SecureRng>(
+    secret: &Scalar,
+    threshold: usize,
+    count: usize,
+    rng: &mut GeneralRng<R>,
+) -> (PedersenVssCommitment, Vec<PedersenShare>) {
+    assert!(
+        0 < threshold && threshold <= count,
+        "the threshold must be between 1 and the number of shares"
+    );
+
+    let coefficients = random_polynomial(secret, threshold, rng);
+    let blinding_coefficients = random_polynomial(&Scalar::random(rng.rng()), threshold, rng);
+
+    let shares = (1..=count as u64)
+        .map(|index| PedersenShare {
+            share: ScalarShare {
+                index,
+                value: evaluate_scalars(&coefficients, index),
+            },
+            blinding: evaluate_scalars(&blinding_coefficients, index),
+        })
+        .collect();
+
+    let commitment = PedersenVssCommitment {
+        coefficients: coefficients
+            .iter()
+            .zip(&blinding_coefficients)
+            .map(|(a, b)| PedersenCommitment::commit_with_randomness(a, b).point)
+            .collect(),
+    };
+
+    (commitment, shares)
+}
+
+#[cfg(feature = "curve")]
+impl PedersenVssCommitment {
+    /// Returns true if `share` and its blinding are the evaluations of the committed polynomials at
+    /// its index.
+    pub fn verify(&self, share: &PedersenShare) -> bool {
+        share.share.index != 0
+            && PedersenCommitment::commit_with_randomness(&share.share.value, &share.blinding).point
+                == evaluate_points(&self.coefficients, share.share.index)
+    }
+
+    /// Returns the Pedersen commitment to the shared secret.
+    pub fn secret_commitment(&self) -> PedersenCommitment {
+        PedersenCommitment {
+            point: self.coefficients[0],
+        }
+    }
+}
+
+/// Draws a random polynomial of degree `threshold - 1` with `constant` as its constant term.
+#[cfg(feature = "curve")]
+fn random_polynomial<R: SecureRng>(
+    constant: &Scalar,
+    threshold: usize,
+    rng: &mut GeneralRng<R>,
+) -> Vec<Scalar> {
+    let mut coefficients: Vec<Scalar> = (1..threshold).map(|_| Scalar::random(rng.rng())).collect();
+    coefficients.insert(0, *constant);
+
+    coefficients
+}
+
+/// Evaluates the polynomial with `coefficients`, lowest degree first, at `index`.
+#[cfg(feature = "curve")]
+fn evaluate_scalars(coefficients: &[Scalar], index: u64) -> Scalar {
+    let x = Scalar::from(index);
+
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |accumulator, a| accumulator * x + a)
+}
+
+/// Evaluates the polynomial with committed `coefficients` in the exponent at `index`.
+#[cfg(feature = "curve")]
+fn evaluate_points(coefficients: &[RistrettoPoint], index: u64) -> RistrettoPoint {
+    let x = Scalar::from(index);
+
+    coefficients
+        .iter()
+        .rev()
+        .fold(RistrettoPoint::identity(), |accumulator, coefficient| {
+            x * accumulator + coefficient
+        })
+}
+
+/// Reconstructs the secret scalar from at least `threshold` shares. Returns None if there are no
+/// shares or if two shares have the same index.
+#[cfg(feature = "curve")]
+pub fn reconstruct_scalar(shares: &[ScalarShare]) -> Option<Scalar> {
+    let indices: Vec<u64> = shares.iter().map(|share| share.index).collect();
+    if indices.is_empty() || has_duplicates(&indices) {
+        return None;
+    }
+
+    Some(
+        shares
+            .iter()
+            .map(|share| {
+                let (numerator, denominator) = indices
+                    .iter()
+                    .filter(|&&other| other != share.index)
+                    .fold((Scalar::one(), Scalar::one()), |(n, d), &other| {
+                        (
+                            n * Scalar::from(other),
+                            d * (Scalar::from(other) - Scalar::from(share.index)),
+                        )
+                    });
+
+                share.value * numerator * denominator.invert()
+            })
+            .sum(),
+    )
+}
+
+fn has_duplicates(indices: &[u64]) -> bool {
+    indices
+        .iter()
+        .enumerate()
+        .any(|(i, index)| indices[..i].contains(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_share_reconstruct() {
+        use crate::secret_sharing::{reconstruct, refresh, share};
+        use scicrypt_bigint::UnsignedInteger;
+
+        let mut rng = GeneralRng::new(OsRng);
+        // 2^127 - 1
+        let mut bytes = [0xff; 16];
+        bytes[15] = 0x7f;
+        let modulus = UnsignedInteger::from_le_bytes(&bytes, 127);
+        let secret = UnsignedInteger::from(123456789u64);
+
+        let shares = share(&secret, 3, 5, &modulus, &mut rng);
+
+        assert_eq!(Some(secret.clone()), reconstruct(&shares[..3], &modulus));
+        assert_eq!(Some(secret.clone()), reconstruct(&shares[2..], &modulus));
+        assert_eq!(
+            Some(secret.clone()),
+            reconstruct(
+                &[shares[4].clone(), shares[0].clone(), shares[2].clone()],
+                &modulus
+            )
+        );
+        assert_ne!(Some(secret.clone()), reconstruct(&shares[..2], &modulus));
+        assert_eq!(
+            None,
+            reconstruct(&[shares[0].clone(), shares[0].clone()], &modulus)
+        );
+
+        let refreshed = refresh(&shares, 3, &modulus, &mut rng);
+        assert_ne!(shares, refreshed);
+        assert_eq!(
+            Some(secret.clone()),
+            reconstruct(&refreshed[1..4], &modulus)
+        );
+        assert_ne!(
+            Some(secret),
+            reconstruct(
+                &[shares[0].clone(), shares[1].clone(), refreshed[2].clone()],
+                &modulus
+            )
+        );
+    }
+
+    #[cfg(feature = "integer")]
+    #[test]
+    fn test_share_small_prime() {
+        use crate::secret_sharing::{reconstruct, share};
+        use scicrypt_bigint::UnsignedInteger;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let modulus = UnsignedInteger::from(17u64);
+
+        for secret in 0..17u64 {
+            let shares = share(&UnsignedInteger::from(secret), 2, 4, &modulus, &mut rng);
+
+            assert_eq!(
+                Some(UnsignedInteger::from(secret)),
+                reconstruct(&shares[2..], &modulus)
+            );
+        }
+    }
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_feldman_share() {
+        use crate::secret_sharing::{feldman_share, reconstruct_scalar};
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+        use curve25519_dalek::scalar::Scalar;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let secret = Scalar::random(rng.rng());
+
+        let (commitment, shares) = feldman_share(&secret, 3, 5, &mut rng);
+
+        assert!(shares.iter().all(|share| commitment.verify(share)));
+        assert_eq!(
+            &secret * &RISTRETTO_BASEPOINT_TABLE,
+            commitment.secret_commitment()
+        );
+        assert_eq!(Some(secret), reconstruct_scalar(&shares[1..4]));
+        assert_ne!(Some(secret), reconstruct_scalar(&shares[..2]));
+
+        let mut tampered = shares[0].clone();
+        tampered.value += Scalar::one();
+        assert!(!commitment.verify(&tampered));
+
+        let mut moved = shares[0].clone();
+        moved.index = 2;
+        assert!(!commitment.verify(&moved));
+    }
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_pedersen_share() {
+        use crate::secret_sharing::{pedersen_share, reconstruct_scalar, ScalarShare};
+        use curve25519_dalek::scalar::Scalar;
+
+        let mut rng = GeneralRng::new(OsRng);
+        let secret = Scalar::random(rng.rng());
+
+        let (commitment, shares) = pedersen_share(&secret, 3, 5, &mut rng);
+
+        assert!(shares.iter().all(|share| commitment.verify(share)));
+        let scalar_shares: Vec<_> = shares.iter().map(|share| share.share.clone()).collect();
+        assert_eq!(Some(secret), reconstruct_scalar(&scalar_shares[2..]));
+
+        // The commitment to the secret opens with the reconstructed blinding
+        let blindings: Vec<_> = shares
+            .iter()
+            .map(|share| ScalarShare {
+                index: share.share.index,
+                value: share.blinding,
+            })
+            .collect();
+        let blinding = reconstruct_scalar(&blindings[..3]).unwrap();
+        assert!(commitment.secret_commitment().verify(&secret, &blinding));
+
+        let mut tampered = shares[1].clone();
+        tampered.blinding += Scalar::one();
+        assert!(!commitment.verify(&tampered));
+
+        let mut tampered = shares[1].clone();
+        tampered.share.value += Scalar::one();
+        assert!(!commitment.verify(&tampered));
+    }
+}
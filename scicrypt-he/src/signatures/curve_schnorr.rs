@@ -0,0 +1,156 @@
+//! Schnorr signatures over the Ristretto group. A curve-based ElGamal key pair can both decrypt and
+//! sign; the challenge hash is domain-separated so that signatures cannot be confused with any
+//! other use of the key.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, secret_key) = el_gamal.generate_keys(&mut rng);
+//!
+//! let signature = secret_key.sign(b"message", &mut rng);
+//! assert!(public_key.verify(b"message", &signature));
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{
+    CurveElGamalPK, CurveElGamalSK, PrecomputedCurveElGamalPK,
+};
+use crate::key_usage::SigningOnly;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he curve schnorr signature";
+
+/// Schnorr signature consisting of a commitment to a random nonce and the response to the challenge.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct CurveSchnorrSignature {
+    /// Commitment $R = k \cdot G$ to the nonce $k$
+    pub commitment: RistrettoPoint,
+    /// Response $s = k + e \cdot x$ for challenge $e$ and secret key $x$
+    pub response: Scalar,
+}
+
+impl CurveElGamalSK {
+    /// Signs `message` using this secret key, so that anyone holding the corresponding public key
+    /// can verify it.
+    pub fn sign<R: SecureRng>(
+        &self,
+        message: &[u8],
+        rng: &mut GeneralRng<R>,
+    ) -> CurveSchnorrSignature {
+        let public_point = &self.key * &RISTRETTO_BASEPOINT_TABLE;
+
+        let nonce = Scalar::random(rng.rng());
+        let commitment = &nonce * &RISTRETTO_BASEPOINT_TABLE;
+        let challenge = compute_challenge(&commitment, &public_point, message);
+
+        CurveSchnorrSignature {
+            commitment,
+            response: nonce + challenge * self.key,
+        }
+    }
+}
+
+impl CurveElGamalPK {
+    /// Returns true if `signature` is a valid signature on `message` under this public key.
+    pub fn verify(&self, message: &[u8], signature: &CurveSchnorrSignature) -> bool {
+        verify(&self.point, message, signature)
+    }
+}
+
+impl SigningOnly<CurveElGamalSK> {
+    /// Signs `message` using the underlying secret key.
+    pub fn sign<R: SecureRng>(
+        &self,
+        message: &[u8],
+        rng: &mut GeneralRng<R>,
+    ) -> CurveSchnorrSignature {
+        self.key().sign(message, rng)
+    }
+}
+
+impl SigningOnly<CurveElGamalPK> {
+    /// Returns true if `signature` is a valid signature on `message` under the underlying public
+    /// key.
+    pub fn verify(&self, message: &[u8], signature: &CurveSchnorrSignature) -> bool {
+        self.key().verify(message, signature)
+    }
+}
+
+impl PrecomputedCurveElGamalPK {
+    /// Returns true if `signature` is a valid signature on `message` under this public key.
+    pub fn verify(&self, message: &[u8], signature: &CurveSchnorrSignature) -> bool {
+        verify(&self.point.basepoint(), message, signature)
+    }
+}
+
+impl SigningOnly<PrecomputedCurveElGamalPK> {
+    /// Returns true if `signature` is a valid signature on `message` under the underlying public
+    /// key.
+    pub fn verify(&self, message: &[u8], signature: &CurveSchnorrSignature) -> bool {
+        self.key().verify(message, signature)
+    }
+}
+
+fn verify(
+    public_point: &RistrettoPoint,
+    message: &[u8],
+    signature: &CurveSchnorrSignature,
+) -> bool {
+    let challenge = compute_challenge(&signature.commitment, public_point, message);
+
+    &signature.response * &RISTRETTO_BASEPOINT_TABLE
+        == signature.commitment + challenge * public_point
+}
+
+fn compute_challenge(
+    commitment: &RistrettoPoint,
+    public_point: &RistrettoPoint,
+    message: &[u8],
+) -> Scalar {
+    let mut transcript = Transcript::new(CHALLENGE_DOMAIN);
+    transcript.append_message(b"commitment", commitment.compress().as_bytes());
+    transcript.append_message(b"public key", public_point.compress().as_bytes());
+    transcript.append_message(b"message", message);
+
+    Scalar::from_bytes_mod_order_wide(&transcript.challenge_bytes(b"challenge"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    #[test]
+    fn test_sign_verify() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let signature = sk.sign(b"message", &mut rng);
+
+        assert!(pk.verify(b"message", &signature));
+        assert!(pk.compress().verify(b"message", &signature));
+    }
+
+    #[test]
+    fn test_verify_rejects_other_message_or_key() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let (other_pk, _) = el_gamal.generate_keys(&mut rng);
+
+        let signature = sk.sign(b"message", &mut rng);
+
+        assert!(!pk.verify(b"other message", &signature));
+        assert!(!other_pk.verify(b"message", &signature));
+    }
+}
@@ -0,0 +1,159 @@
+//! Schnorr signatures over the quadratic residues modulo a safe prime, with the generator of the
+//! ElGamal key. An integer-based ElGamal key pair can both decrypt and sign; the challenge hash is
+//! domain-separated so that signatures cannot be confused with any other use of the key.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::integer_el_gamal::IntegerElGamal;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = IntegerElGamal::setup(&Default::default());
+//! let (public_key, secret_key) = el_gamal.generate_keys(&mut rng);
+//!
+//! let signature = secret_key.sign(b"message", &public_key, &mut rng);
+//! assert!(public_key.verify(b"message", &signature));
+//! ```
+
+use crate::cryptosystems::integer_el_gamal::{
+    to_length_prefixed_bytes, IntegerElGamalPK, IntegerElGamalSK,
+};
+use crate::key_usage::SigningOnly;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::transcript::Transcript;
+use serde::{Deserialize, Serialize};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he integer schnorr signature";
+
+/// Schnorr signature consisting of a commitment to a random nonce and the response to the challenge.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct IntegerSchnorrSignature {
+    /// Commitment $R = g^k \bmod p$ to the nonce $k$
+    pub commitment: UnsignedInteger,
+    /// Response $s = k + e \cdot x \bmod q$ for challenge $e$ and secret key $x$
+    pub response: UnsignedInteger,
+}
+
+impl IntegerElGamalSK {
+    /// Signs `message` using this secret key. The `public_key` must be the one that belongs to this
+    /// secret key, as it determines the group and is bound to the signature.
+    pub fn sign<R: SecureRng>(
+        &self,
+        message: &[u8],
+        public_key: &IntegerElGamalPK,
+        rng: &mut GeneralRng<R>,
+    ) -> IntegerSchnorrSignature {
+        let q = &public_key.modulus >> 1;
+
+        let nonce = UnsignedInteger::random_below(&q, rng);
+        let commitment = public_key.generator.pow_mod(&nonce, &public_key.modulus);
+        let challenge = compute_challenge(&commitment, public_key, message, &q);
+
+        IntegerSchnorrSignature {
+            commitment,
+            response: (&challenge * &self.key + &nonce) % &q,
+        }
+    }
+}
+
+impl IntegerElGamalPK {
+    /// Returns true if `signature` is a valid signature on `message` under this public key. This
+    /// function is not constant-time, but it only operates on public values.
+    pub fn verify(&self, message: &[u8], signature: &IntegerSchnorrSignature) -> bool {
+        let q = &self.modulus >> 1;
+
+        if signature.commitment.is_zero_leaky()
+            || signature.commitment.leak() >= self.modulus.leak()
+            || signature.response.leak() >= q.leak()
+        {
+            return false;
+        }
+
+        let challenge = compute_challenge(&signature.commitment, self, message, &q);
+
+        let lhs = self.generator.pow_mod(&signature.response, &self.modulus);
+        let rhs = self
+            .barrett
+            .reduce(&(&signature.commitment * &self.h.pow_mod(&challenge, &self.modulus)));
+
+        lhs == rhs
+    }
+}
+
+impl SigningOnly<IntegerElGamalSK> {
+    /// Signs `message` using the underlying secret key. The `public_key` must be the one that
+    /// belongs to it.
+    pub fn sign<R: SecureRng>(
+        &self,
+        message: &[u8],
+        public_key: &SigningOnly<IntegerElGamalPK>,
+        rng: &mut GeneralRng<R>,
+    ) -> IntegerSchnorrSignature {
+        self.key().sign(message, public_key.key(), rng)
+    }
+}
+
+impl SigningOnly<IntegerElGamalPK> {
+    /// Returns true if `signature` is a valid signature on `message` under the underlying public
+    /// key.
+    pub fn verify(&self, message: &[u8], signature: &IntegerSchnorrSignature) -> bool {
+        self.key().verify(message, signature)
+    }
+}
+
+fn compute_challenge(
+    commitment: &UnsignedInteger,
+    public_key: &IntegerElGamalPK,
+    message: &[u8],
+    q: &UnsignedInteger,
+) -> UnsignedInteger {
+    let mut transcript = Transcript::new(CHALLENGE_DOMAIN);
+    transcript.append_message(b"modulus", &to_length_prefixed_bytes(&public_key.modulus));
+    transcript.append_message(
+        b"generator",
+        &to_length_prefixed_bytes(&public_key.generator),
+    );
+    transcript.append_message(b"commitment", &to_length_prefixed_bytes(commitment));
+    transcript.append_message(b"public key", &to_length_prefixed_bytes(&public_key.h));
+    transcript.append_message(b"message", message);
+
+    let challenge = transcript.challenge_bytes(b"challenge");
+    UnsignedInteger::from_le_bytes(&challenge, 512) % q
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptosystems::integer_el_gamal::IntegerElGamal;
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_sign_verify() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let signature = sk.sign(b"message", &pk, &mut rng);
+
+        assert!(pk.verify(b"message", &signature));
+    }
+
+    #[test]
+    fn test_verify_rejects_forgeries() {
+        let mut rng = GeneralRng::new(OsRng);
+        let el_gamal = IntegerElGamal::setup(&BitsOfSecurity::AES80);
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+        let (other_pk, _) = el_gamal.generate_keys(&mut rng);
+
+        let mut signature = sk.sign(b"message", &pk, &mut rng);
+
+        assert!(!pk.verify(b"other message", &signature));
+        assert!(!other_pk.verify(b"message", &signature));
+
+        signature.commitment = UnsignedInteger::zero(signature.commitment.size_in_bits());
+        assert!(!pk.verify(b"message", &signature));
+    }
+}
@@ -0,0 +1,6 @@
+/// Schnorr signatures over an elliptic curve using the keys of curve-based ElGamal.
+#[cfg(feature = "curve")]
+pub mod curve_schnorr;
+/// Schnorr signatures over a safe prime group using the keys of integer-based ElGamal.
+#[cfg(feature = "integer")]
+pub mod integer_schnorr;
@@ -0,0 +1,209 @@
+//! A high-level API for applications that want to add up encrypted numbers without choosing a
+//! cryptosystem. A `Context` generates and holds a key pair, and encrypts and decrypts `u64` values
+//! into `EncryptedValue`s, which can be added together with `+` and multiplied by plaintext
+//! constants with `*`. Randomness is drawn from the operating system.
+//!
+//! Under the hood, values are encrypted with curve-based ElGamal as $m \cdot G$, which makes the
+//! cryptosystem additively homomorphic. Decryption then has to solve a discrete logarithm, so the
+//! decrypted value must not exceed `MAX_VALUE`. Applications that need larger values, other
+//! operations or threshold decryption should use the cryptosystems in `cryptosystems` directly.
+//! ```
+//! # use scicrypt_he::simple::Context;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! let context = Context::new(BitsOfSecurity::default());
+//!
+//! let value_a = context.encrypt(42);
+//! let value_b = context.encrypt(8);
+//! let total = value_a + value_b * 3;
+//!
+//! assert_eq!(Ok(66), context.decrypt(&total));
+//! ```
+
+use crate::cryptosystems::curve_el_gamal::{
+    CurveDiscreteLogTable, CurveElGamal, CurveElGamalCiphertext, CurveElGamalSK,
+    PrecomputedCurveElGamalPK,
+};
+use crate::identifier::Identifiable;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::scalar::Scalar;
+use rand_core::OsRng;
+use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+use scicrypt_traits::encoding::EncodingError;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, Mul};
+use std::sync::OnceLock;
+
+/// The number of baby steps in the discrete logarithm, whose square is the number of values that
+/// can be decrypted.
+const BABY_STEPS: u64 = 1 << 16;
+
+/// The largest value that `Context::decrypt` can recover.
+pub const MAX_VALUE: u64 = BABY_STEPS * BABY_STEPS - 1;
+
+/// A key pair for encrypting, adding and decrypting `u64` values.
+pub struct Context {
+    public_key: PrecomputedCurveElGamalPK,
+    secret_key: CurveElGamalSK,
+    /// Solves the discrete logarithms up to `MAX_VALUE`, which is computed on the first decryption
+    discrete_log: OnceLock<CurveDiscreteLogTable>,
+}
+
+impl Debug for Context {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Context")
+            .field("public_key", &self.public_key)
+            .field("secret_key", &self.secret_key)
+            .finish()
+    }
+}
+
+/// An encrypted `u64` value. Adding two encrypted values encrypts the sum of their values, and
+/// multiplying an encrypted value by a constant encrypts their product. Only values of the same
+/// `Context` can be combined in a meaningful way.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, Hash)]
+pub struct EncryptedValue {
+    ciphertext: CurveElGamalCiphertext,
+}
+
+impl Identifiable for EncryptedValue {}
+
+impl Context {
+    /// Generates a fresh key pair with the given level of security. Panics if the level of security
+    /// is not supported by the underlying cryptosystem, which currently only supports
+    /// `BitsOfSecurity::AES128`.
+    pub fn new(security: BitsOfSecurity) -> Self {
+        let (public_key, secret_key) =
+            CurveElGamal::setup(&security).generate_keys(&mut GeneralRng::new(OsRng));
+
+        Context {
+            public_key,
+            secret_key,
+            discrete_log: OnceLock::new(),
+        }
+    }
+
+    /// Encrypts `value` using fresh randomness.
+    pub fn encrypt(&self, value: u64) -> EncryptedValue {
+        let plaintext = &Scalar::from(value) * &RISTRETTO_BASEPOINT_TABLE;
+
+        EncryptedValue {
+            ciphertext: self
+                .public_key
+                .encrypt_raw(&plaintext, &mut GeneralRng::new(OsRng)),
+        }
+    }
+
+    /// Decrypts `value`, or returns an error if the encrypted value exceeds `MAX_VALUE`. The
+    /// running time depends on the decrypted value, and the first decryption also computes a table
+    /// of $2^{16}$ points.
+    pub fn decrypt(&self, value: &EncryptedValue) -> Result<u64, EncodingError> {
+        let point = self
+            .secret_key
+            .decrypt_raw(&self.public_key, &value.ciphertext);
+
+        self.discrete_log
+            .get_or_init(|| {
+                CurveDiscreteLogTable::new(BABY_STEPS, BABY_STEPS)
+                    .expect("the table size is below the maximum")
+            })
+            .discrete_log_leaky(&point)
+            .ok_or(EncodingError)
+    }
+}
+
+impl EncryptedValue {
+    /// Returns the underlying curve-based ElGamal ciphertext, which encrypts the point $m \cdot G$.
+    pub fn ciphertext(&self) -> &CurveElGamalCiphertext {
+        &self.ciphertext
+    }
+}
+
+impl Add<&EncryptedValue> for &EncryptedValue {
+    type Output = EncryptedValue;
+
+    fn add(self, rhs: &EncryptedValue) -> EncryptedValue {
+        EncryptedValue {
+            ciphertext: CurveElGamalCiphertext {
+                c1: self.ciphertext.c1 + rhs.ciphertext.c1,
+                c2: self.ciphertext.c2 + rhs.ciphertext.c2,
+            },
+        }
+    }
+}
+
+impl Add for EncryptedValue {
+    type Output = EncryptedValue;
+
+    fn add(self, rhs: EncryptedValue) -> EncryptedValue {
+        &self + &rhs
+    }
+}
+
+impl Mul<u64> for &EncryptedValue {
+    type Output = EncryptedValue;
+
+    fn mul(self, rhs: u64) -> EncryptedValue {
+        let constant = Scalar::from(rhs);
+
+        EncryptedValue {
+            ciphertext: CurveElGamalCiphertext {
+                c1: self.ciphertext.c1 * constant,
+                c2: self.ciphertext.c2 * constant,
+            },
+        }
+    }
+}
+
+impl Mul<u64> for EncryptedValue {
+    type Output = EncryptedValue;
+
+    fn mul(self, rhs: u64) -> EncryptedValue {
+        &self * rhs
+    }
+}
+
+impl Sum for EncryptedValue {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        EncryptedValue {
+            ciphertext: iter.map(|value| value.ciphertext).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simple::{Context, EncryptedValue, MAX_VALUE};
+    use scicrypt_traits::encoding::EncodingError;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_add_and_multiply() {
+        let context = Context::new(BitsOfSecurity::default());
+
+        let values = vec![
+            context.encrypt(1),
+            context.encrypt(20),
+            context.encrypt(300),
+        ];
+        let sum: EncryptedValue = values.into_iter().sum();
+        let total = &sum * 2 + context.encrypt(5);
+
+        assert_eq!(Ok(647), context.decrypt(&total));
+    }
+
+    #[test]
+    fn test_decrypt_bounds() {
+        let context = Context::new(BitsOfSecurity::default());
+
+        assert_eq!(Ok(0), context.decrypt(&context.encrypt(0)));
+        assert_eq!(Ok(MAX_VALUE), context.decrypt(&context.encrypt(MAX_VALUE)));
+        assert_eq!(
+            Err(EncodingError),
+            context.decrypt(&context.encrypt(MAX_VALUE + 1))
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Here is an example of how an aggregator computes the mean and variance of values that are
+//! contributed in encrypted form, for example telemetry from many devices.
+//! ```
+//! # #[cfg(feature = "integer")]
+//! # {
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::cryptosystems::paillier::Paillier;
+//! # use scicrypt_he::statistics::{EncryptedContribution, EncryptedStatistics};
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let paillier = Paillier::setup(&BitsOfSecurity::ToyParameters);
+//! let (public_key, secret_key) = paillier.generate_keys(&mut rng);
+//!
+//! // The aggregator keeps encrypted running sums
+//! let mut statistics = EncryptedStatistics::new(2, &public_key);
+//!
+//! // Every device encrypts its measurement together with its square
+//! for latency in [12.5, 15.0, 10.25, 14.25] {
+//!     let contribution = EncryptedContribution::encrypt(latency, 2, &public_key, &mut rng).unwrap();
+//!     statistics.add_contribution(&contribution, &public_key);
+//! }
+//!
+//! // Only the key holder learns the aggregates
+//! let result = statistics.decrypt(&secret_key, &public_key).unwrap();
+//! assert_eq!(result.count, 4);
+//! assert_eq!(result.mean, 13.0);
+//! assert_eq!(result.variance, 3.34375);
+//! # }
+//! ```
+
+use crate::fixed_point::{to_mantissa, EncryptedFixedPoint, MAX_DECIMALS};
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::encoding::{EncodingError, PlaintextEncodable};
+use scicrypt_traits::homomorphic::HomomorphicAddition;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::{Deserialize, Serialize};
+
+/// A single encrypted value together with its encrypted square, which is what one party
+/// contributes to the statistics. The square has twice as many decimals as the value.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedContribution<C> {
+    /// The encrypted value
+    pub value: EncryptedFixedPoint<C>,
+    /// The encrypted square of the value
+    pub square: EncryptedFixedPoint<C>,
+}
+
+impl<C> EncryptedContribution<C> {
+    /// Encrypts `value` rounded to `decimals` decimals, and its square with twice as many decimals.
+    /// Returns an error if the square does not fit at that scale.
+    pub fn encrypt<PK: EncryptionKey<Ciphertext = C>, R: SecureRng>(
+        value: f64,
+        decimals: u32,
+        public_key: &PK,
+        rng: &mut GeneralRng<R>,
+    ) -> Result<Self, EncodingError>
+    where
+        i64: PlaintextEncodable<PK>,
+    {
+        if 2 * decimals > MAX_DECIMALS {
+            return Err(EncodingError);
+        }
+
+        // Square the rounded value, so that the value and its square are consistent
+        let mantissa = to_mantissa(value, decimals)?;
+        let square = mantissa.checked_mul(mantissa).ok_or(EncodingError)?;
+
+        Ok(EncryptedContribution {
+            value: EncryptedFixedPoint {
+                ciphertext: public_key.encrypt_raw(&mantissa.encode(public_key)?, rng),
+                decimals,
+            },
+            square: EncryptedFixedPoint {
+                ciphertext: public_key.encrypt_raw(&square.encode(public_key)?, rng),
+                decimals: 2 * decimals,
+            },
+        })
+    }
+}
+
+/// Encrypted running sums of the values and of their squares, from which the mean and variance of
+/// all contributions follow. Partial statistics that were aggregated separately (for example per
+/// region) can be merged.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedStatistics<C> {
+    /// The number of contributions
+    pub count: u64,
+    /// The encrypted sum of all values
+    pub sum: EncryptedFixedPoint<C>,
+    /// The encrypted sum of the squares of all values
+    pub sum_of_squares: EncryptedFixedPoint<C>,
+}
+
+/// The decrypted statistics.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Statistics {
+    /// The number of contributions
+    pub count: u64,
+    /// The mean of all values
+    pub mean: f64,
+    /// The (population) variance of all values
+    pub variance: f64,
+}
+
+impl Statistics {
+    /// Computes the statistics from the decrypted sums, for when the sums are decrypted separately,
+    /// e.g. with a threshold key.
+    pub fn from_sums(count: u64, sum: f64, sum_of_squares: f64) -> Self {
+        assert!(count > 0, "there must be at least one contribution");
+
+        let n = count as f64;
+        let mean = sum / n;
+        // Rounding errors can make the variance of (nearly) equal values slightly negative
+        let variance = (sum_of_squares / n - mean * mean).max(0.0);
+
+        Statistics {
+            count,
+            mean,
+            variance,
+        }
+    }
+}
+
+impl<C: Clone> EncryptedStatistics<C> {
+    /// Creates empty statistics for values with `decimals` decimals.
+    pub fn new<PK>(decimals: u32, public_key: &PK) -> Self
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        // The sums are public sums of zero contributions, so they need no randomness
+        let zero = 0i64
+            .encode(public_key)
+            .expect("zero fits in every plaintext space");
+
+        EncryptedStatistics {
+            count: 0,
+            sum: EncryptedFixedPoint {
+                ciphertext: public_key.encrypt_without_randomness(&zero),
+                decimals,
+            },
+            sum_of_squares: EncryptedFixedPoint {
+                ciphertext: public_key.encrypt_without_randomness(&zero),
+                decimals: 2 * decimals,
+            },
+        }
+    }
+
+    /// Adds one party's contribution to the running sums.
+    pub fn add_contribution<PK>(&mut self, contribution: &EncryptedContribution<C>, public_key: &PK)
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        self.count += 1;
+        self.sum = self.sum.add(&contribution.value, public_key);
+        self.sum_of_squares = self.sum_of_squares.add(&contribution.square, public_key);
+    }
+
+    /// Merges statistics that were aggregated separately into these statistics.
+    pub fn merge<PK>(&mut self, other: &Self, public_key: &PK)
+    where
+        i64: PlaintextEncodable<PK>,
+        PK: HomomorphicAddition<Ciphertext = C, Input = <PK as EncryptionKey>::Plaintext>,
+    {
+        self.count += other.count;
+        self.sum = self.sum.add(&other.sum, public_key);
+        self.sum_of_squares = self.sum_of_squares.add(&other.sum_of_squares, public_key);
+    }
+
+    /// Decrypts the sums and computes the mean and variance. Returns an error if a sum overflowed.
+    pub fn decrypt<PK: EncryptionKey<Ciphertext = C>, SK: DecryptionKey<PK>>(
+        &self,
+        secret_key: &SK,
+        public_key: &PK,
+    ) -> Result<Statistics, EncodingError>
+    where
+        i64: PlaintextEncodable<PK>,
+    {
+        Ok(Statistics::from_sums(
+            self.count,
+            self.sum.decrypt(secret_key, public_key)?,
+            self.sum_of_squares.decrypt(secret_key, public_key)?,
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "integer"))]
+mod tests {
+    use crate::cryptosystems::paillier::Paillier;
+    use crate::statistics::{EncryptedContribution, EncryptedStatistics, Statistics};
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+    use scicrypt_traits::encoding::EncodingError;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_mean_and_variance() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let mut statistics = EncryptedStatistics::new(1, &pk);
+        for value in [2.5, 4.0, -5.5] {
+            let contribution = EncryptedContribution::encrypt(value, 1, &pk, &mut rng).unwrap();
+            statistics.add_contribution(&contribution, &pk);
+        }
+
+        let result = statistics.decrypt(&sk, &pk).unwrap();
+        assert_eq!(3, result.count);
+        assert!((result.mean - 1.0 / 3.0).abs() < 1e-9);
+        assert!((result.variance - (17.5 - 1.0 / 9.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        let mut first = EncryptedStatistics::new(2, &pk);
+        let mut second = EncryptedStatistics::new(2, &pk);
+        for (i, value) in [1.25, 2.75, 3.0, 5.0].iter().enumerate() {
+            let contribution = EncryptedContribution::encrypt(*value, 2, &pk, &mut rng).unwrap();
+            if i % 2 == 0 {
+                first.add_contribution(&contribution, &pk);
+            } else {
+                second.add_contribution(&contribution, &pk);
+            }
+        }
+
+        first.merge(&second, &pk);
+        assert_eq!(
+            Ok(Statistics::from_sums(4, 12.0, 43.125)),
+            first.decrypt(&sk, &pk)
+        );
+    }
+
+    #[test]
+    fn test_contribution_out_of_range() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (pk, _) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+        assert_eq!(
+            Err(EncodingError),
+            EncryptedContribution::encrypt(1.0, 10, &pk, &mut rng)
+        );
+        assert_eq!(
+            Err(EncodingError),
+            EncryptedContribution::encrypt(1e10, 0, &pk, &mut rng)
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! A framed binary format to store long sequences of ciphertexts on disk. Ciphertexts are written one
+//! at a time and can be read back one at a time, so that a homomorphic aggregate over millions of
+//! ciphertexts only ever holds a single ciphertext in memory. An index at the end of the file allows
+//! random access to individual ciphertexts.
+//!
+//! The format consists of a magic header, followed by one frame per ciphertext (a little-endian
+//! `u64` length and the `bincode` encoding of the ciphertext), followed by the index (the
+//! little-endian `u64` offset of each frame) and a footer holding the offset of the index and the
+//! number of ciphertexts.
+//!
+//! Here is an example that sums an encrypted column in a streaming fashion.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::storage::{CiphertextReader, CiphertextWriter};
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+//! # use scicrypt_traits::homomorphic::HomomorphicAddition;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! # use std::io::Cursor;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, secret_key) = el_gamal.generate_keys(&mut rng);
+//!
+//! // Any `Write + Seek`, such as a `File`, can be used instead of a `Cursor`
+//! let mut writer = CiphertextWriter::new(Cursor::new(vec![])).unwrap();
+//! for _ in 0..10 {
+//!     let ciphertext = public_key.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+//!     writer.write(&ciphertext).unwrap();
+//! }
+//! let file = writer.finish().unwrap();
+//!
+//! let mut reader = CiphertextReader::open(file).unwrap();
+//! let sum = reader
+//!     .iter()
+//!     .unwrap()
+//!     .map(|ciphertext| ciphertext.unwrap())
+//!     .reduce(|a, b| public_key.add(&a, &b))
+//!     .unwrap();
+//!
+//! assert_eq!(
+//!     Scalar::from(10u64) * RISTRETTO_BASEPOINT_POINT,
+//!     secret_key.decrypt_raw(&public_key, &sum)
+//! );
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+
+const MAGIC: &[u8; 8] = b"SCICTXT1";
+/// Size of the footer, which holds the offset of the index and the number of ciphertexts.
+const FOOTER_SIZE: u64 = 16;
+
+/// Writes ciphertexts to a sink one at a time. Call `finish` to write the index, without which the
+/// result cannot be read.
+pub struct CiphertextWriter<W: Write + Seek, C> {
+    sink: W,
+    offsets: Vec<u64>,
+    position: u64,
+    ciphertext_type: PhantomData<C>,
+}
+
+impl<W: Write + Seek, C: Serialize> CiphertextWriter<W, C> {
+    /// Starts a new sequence of ciphertexts in `sink`, which must be empty.
+    pub fn new(mut sink: W) -> Result<Self> {
+        sink.write_all(MAGIC)?;
+        let position = sink.stream_position()?;
+
+        Ok(CiphertextWriter {
+            sink,
+            offsets: vec![],
+            position,
+            ciphertext_type: PhantomData,
+        })
+    }
+
+    /// Appends `ciphertext` to the sequence.
+    pub fn write(&mut self, ciphertext: &C) -> Result<()> {
+        let frame =
+            bincode::serialize(ciphertext).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        self.sink.write_all(&(frame.len() as u64).to_le_bytes())?;
+        self.sink.write_all(&frame)?;
+
+        self.offsets.push(self.position);
+        self.position += 8 + frame.len() as u64;
+        Ok(())
+    }
+
+    /// Writes the index and the footer, and returns the underlying sink.
+    pub fn finish(mut self) -> Result<W> {
+        let index_offset = self.position;
+
+        for offset in &self.offsets {
+            self.sink.write_all(&offset.to_le_bytes())?;
+        }
+        self.sink.write_all(&index_offset.to_le_bytes())?;
+        self.sink
+            .write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        self.sink.flush()?;
+
+        Ok(self.sink)
+    }
+}
+
+/// Reads ciphertexts that were written by a `CiphertextWriter`, either sequentially or by index.
+/// Only the footer is kept in memory.
+pub struct CiphertextReader<R: Read + Seek, C> {
+    source: R,
+    index_offset: u64,
+    len: u64,
+    ciphertext_type: PhantomData<C>,
+}
+
+impl<R: Read + Seek, C: DeserializeOwned> CiphertextReader<R, C> {
+    /// Opens a sequence of ciphertexts that starts at the beginning of `source`, validating the
+    /// header and the footer.
+    pub fn open(mut source: R) -> Result<Self> {
+        source.seek(SeekFrom::Start(0))?;
+        let mut magic = [0u8; 8];
+        source.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "not a ciphertext sequence",
+            ));
+        }
+
+        let end = source.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+        let index_offset = read_u64(&mut source)?;
+        let len = read_u64(&mut source)?;
+
+        if index_offset < MAGIC.len() as u64
+            || len.checked_mul(8).and_then(|l| l.checked_add(index_offset)) != Some(end)
+        {
+            return Err(Error::new(ErrorKind::InvalidData, "corrupted index"));
+        }
+
+        Ok(CiphertextReader {
+            source,
+            index_offset,
+            len,
+            ciphertext_type: PhantomData,
+        })
+    }
+
+    /// The number of ciphertexts in the sequence.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if the sequence contains no ciphertexts.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads the ciphertext at position `index`, using the index to seek to it directly.
+    pub fn get(&mut self, index: u64) -> Result<C> {
+        if index >= self.len {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "ciphertext index out of bounds",
+            ));
+        }
+
+        self.source
+            .seek(SeekFrom::Start(self.index_offset + 8 * index))?;
+        let offset = read_u64(&mut self.source)?;
+
+        self.source.seek(SeekFrom::Start(offset))?;
+        read_frame(&mut self.source, self.index_offset)
+    }
+
+    /// Returns an iterator that reads the ciphertexts one by one, in the order in which they were
+    /// written.
+    pub fn iter(&mut self) -> Result<CiphertextIter<'_, R, C>> {
+        self.source.seek(SeekFrom::Start(MAGIC.len() as u64))?;
+
+        Ok(CiphertextIter {
+            remaining: self.len,
+            reader: self,
+        })
+    }
+}
+
+/// Iterator over the ciphertexts of a `CiphertextReader`, which reads one frame at a time.
+pub struct CiphertextIter<'a, R: Read + Seek, C> {
+    reader: &'a mut CiphertextReader<R, C>,
+    remaining: u64,
+}
+
+impl<R: Read + Seek, C: DeserializeOwned> Iterator for CiphertextIter<'_, R, C> {
+    type Item = Result<C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        Some(read_frame(
+            &mut self.reader.source,
+            self.reader.index_offset,
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+fn read_u64<R: Read>(source: &mut R) -> Result<u64> {
+    let mut bytes = [0u8; 8];
+    source.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads the frame at the current position, refusing frames that extend into the index at
+/// `index_offset` so that a corrupted length cannot cause a huge allocation.
+fn read_frame<R: Read + Seek, C: DeserializeOwned>(source: &mut R, index_offset: u64) -> Result<C> {
+    let length = read_u64(source)?;
+    let position = source.stream_position()?;
+
+    if length > index_offset.saturating_sub(position) {
+        return Err(Error::new(ErrorKind::InvalidData, "corrupted frame length"));
+    }
+
+    let mut frame = vec![0u8; length as usize];
+    source.read_exact(&mut frame)?;
+
+    bincode::deserialize(&frame).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{CiphertextReader, CiphertextWriter};
+    use std::io::{Cursor, ErrorKind};
+
+    #[test]
+    fn test_write_read_sequential_and_random_access() {
+        let values: Vec<(u64, Vec<u8>)> =
+            (0..20u64).map(|i| (i, vec![i as u8; i as usize])).collect();
+
+        let mut writer = CiphertextWriter::new(Cursor::new(vec![])).unwrap();
+        for value in &values {
+            writer.write(value).unwrap();
+        }
+        let sink = writer.finish().unwrap();
+
+        let mut reader = CiphertextReader::<_, (u64, Vec<u8>)>::open(sink).unwrap();
+        assert_eq!(20, reader.len());
+
+        let read: Vec<_> = reader.iter().unwrap().map(|v| v.unwrap()).collect();
+        assert_eq!(values, read);
+
+        assert_eq!(values[13], reader.get(13).unwrap());
+        assert_eq!(values[0], reader.get(0).unwrap());
+        assert_eq!(ErrorKind::InvalidInput, reader.get(20).unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_empty_sequence() {
+        let writer = CiphertextWriter::<_, u64>::new(Cursor::new(vec![])).unwrap();
+        let sink = writer.finish().unwrap();
+
+        let mut reader = CiphertextReader::<_, u64>::open(sink).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(0, reader.iter().unwrap().count());
+    }
+
+    #[test]
+    fn test_open_rejects_corrupted_data() {
+        let mut writer = CiphertextWriter::new(Cursor::new(vec![])).unwrap();
+        writer.write(&5u64).unwrap();
+        let mut bytes = writer.finish().unwrap().into_inner();
+
+        let mut truncated = bytes.clone();
+        truncated.pop();
+        assert!(CiphertextReader::<_, u64>::open(Cursor::new(truncated)).is_err());
+
+        bytes[0] = b'X';
+        assert!(CiphertextReader::<_, u64>::open(Cursor::new(bytes)).is_err());
+    }
+}
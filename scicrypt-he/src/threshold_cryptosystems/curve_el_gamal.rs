@@ -5,24 +5,65 @@ use curve25519_dalek::scalar::Scalar;
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
 use scicrypt_traits::threshold_cryptosystems::PartialDecryptionKey;
 use scicrypt_traits::threshold_cryptosystems::{
     DecryptionShare, NOfNCryptosystem, TOfNCryptosystem,
 };
+use scicrypt_traits::transcript::Transcript;
 use scicrypt_traits::DecryptionError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::convert::TryFrom;
+use std::fmt::{Debug, Formatter};
+
+const NONCE_DOMAIN: &[u8] = b"scicrypt-he threshold curve el gamal decryption nonce";
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he threshold curve el gamal decryption proof";
 
 /// N-out-of-N Threshold ElGamal cryptosystem over elliptic curves: Extension of ElGamal that requires n out of n parties to
 /// successfully decrypt. For this scheme there exists an efficient distributed key generation protocol.
 #[derive(Copy, Clone)]
-pub struct NOfNCurveElGamal;
+pub struct NOfNCurveElGamal {
+    security_level: BitsOfSecurity,
+}
 
 /// Decryption key of N-out-of-N curve-based ElGamal
 pub struct NOfNCurveElGamalSK {
     key: Scalar,
 }
 
-/// Decryption share of N-out-of-N curve-based ElGamal
-pub struct NOfNCurveElGamalShare(CurveElGamalCiphertext);
+impl Debug for NOfNCurveElGamalSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NOfNCurveElGamalSK")
+            .field("key", &Redacted::new(&self.key, 253))
+            .finish()
+    }
+}
+
+/// Decryption share of N-out-of-N curve-based ElGamal, together with a proof that it was computed
+/// correctly.
+#[derive(Serialize, Deserialize)]
+pub struct NOfNCurveElGamalShare {
+    c1: RistrettoPoint,
+    c2: RistrettoPoint,
+    proof: DecryptionShareProof,
+}
+
+/// Chaum-Pedersen proof that a decryption share $D = x \cdot c_1$ uses the same partial key $x$ as
+/// the party's verification key $V = x \cdot G$.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct DecryptionShareProof {
+    /// The Fiat-Shamir challenge
+    pub challenge: Scalar,
+    /// The response $s = k + e \cdot x$ for nonce $k$ and challenge $e$
+    pub response: Scalar,
+}
+
+/// Error returned when some decryption shares do not carry a valid proof. It holds the positions of
+/// the invalid shares in the slice that was passed to be combined, so that the misbehaving parties
+/// can be identified and excluded.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidDecryptionShares(pub Vec<usize>);
 
 impl NOfNCryptosystem for NOfNCurveElGamal {
     type PublicKey = CurveElGamalPK;
@@ -36,7 +77,9 @@ impl NOfNCryptosystem for NOfNCurveElGamal {
             ),
         }
 
-        NOfNCurveElGamal {}
+        NOfNCurveElGamal {
+            security_level: *security_param,
+        }
     }
 
     fn generate_keys<R: SecureRng>(
@@ -44,6 +87,7 @@ impl NOfNCryptosystem for NOfNCurveElGamal {
         key_count_n: usize,
         rng: &mut GeneralRng<R>,
     ) -> (CurveElGamalPK, Vec<NOfNCurveElGamalSK>) {
+        trace_span!(INFO, "generate_keys", parties = key_count_n);
         let partial_keys: Vec<NOfNCurveElGamalSK> = (0..key_count_n)
             .map(|_| NOfNCurveElGamalSK {
                 key: Scalar::random(rng.rng()),
@@ -53,7 +97,20 @@ impl NOfNCryptosystem for NOfNCurveElGamal {
         let master_key: Scalar = partial_keys.iter().map(|k| k.key).sum();
         let public_key = &master_key * &RISTRETTO_BASEPOINT_TABLE;
 
-        (CurveElGamalPK { point: public_key }, partial_keys)
+        let public_key = CurveElGamalPK {
+            point: public_key,
+            security_level: self.security_level,
+        };
+
+        (public_key, partial_keys)
+    }
+}
+
+impl NOfNCurveElGamalSK {
+    /// Returns the verification key $x \cdot G$ of this partial key $x$. Each party publishes its
+    /// verification key after key generation, so that its decryption shares can be verified.
+    pub fn verification_key(&self) -> RistrettoPoint {
+        &self.key * &RISTRETTO_BASEPOINT_TABLE
     }
 }
 
@@ -65,10 +122,14 @@ impl PartialDecryptionKey<CurveElGamalPK> for NOfNCurveElGamalSK {
         _public_key: &CurveElGamalPK,
         ciphertext: &CurveElGamalCiphertext,
     ) -> NOfNCurveElGamalShare {
-        NOfNCurveElGamalShare(CurveElGamalCiphertext {
-            c1: self.key * ciphertext.c1,
+        trace_span!(DEBUG, "partial_decrypt");
+        let (c1, proof) = decrypt_with_proof(&self.key, ciphertext);
+
+        NOfNCurveElGamalShare {
+            c1,
             c2: ciphertext.c2,
-        })
+            proof,
+        }
     }
 }
 
@@ -78,20 +139,58 @@ impl DecryptionShare<CurveElGamalPK> for NOfNCurveElGamalShare {
         decryption_shares: &[Self],
         _public_key: &CurveElGamalPK,
     ) -> Result<RistrettoPoint, DecryptionError> {
-        Ok(decryption_shares[0].0.c2 - &decryption_shares.iter().map(|share| share.0.c1).sum())
+        trace_span!(DEBUG, "combine");
+        Ok(decryption_shares[0].c2 - &decryption_shares.iter().map(|share| share.c1).sum())
+    }
+}
+
+impl NOfNCurveElGamalShare {
+    /// Verifies the proof of every decryption share of `ciphertext` before combining them. The
+    /// shares must be given in the same order as the parties' `verification_keys`. If any share is
+    /// invalid, the positions of all invalid shares are returned instead of the decryption.
+    pub fn combine_verified(
+        decryption_shares: &[Self],
+        ciphertext: &CurveElGamalCiphertext,
+        verification_keys: &[RistrettoPoint],
+        public_key: &CurveElGamalPK,
+    ) -> Result<RistrettoPoint, InvalidDecryptionShares> {
+        let invalid: Vec<usize> = (0..decryption_shares.len().max(verification_keys.len()))
+            .filter(
+                |&i| match (decryption_shares.get(i), verification_keys.get(i)) {
+                    (Some(share), Some(verification_key)) => {
+                        share.c2 != ciphertext.c2
+                            || !share
+                                .proof
+                                .verify(verification_key, &ciphertext.c1, &share.c1)
+                    }
+                    _ => true,
+                },
+            )
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(InvalidDecryptionShares(invalid));
+        }
+
+        Ok(Self::combine(decryption_shares, public_key).unwrap())
     }
 }
 
 /// Threshold ElGamal cryptosystem over elliptic curves: Extension of ElGamal that requires t out of n parties to
 /// successfully decrypt.
 #[derive(Copy, Clone)]
-pub struct TOfNCurveElGamal;
+pub struct TOfNCurveElGamal {
+    security_level: BitsOfSecurity,
+}
 
-/// A partially decrypted ciphertext, of which t must be combined to decrypt successfully.
+/// A partially decrypted ciphertext, of which t must be combined to decrypt successfully. It
+/// carries a proof that it was computed correctly.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct TOfNCurveElGamalShare {
     id: i32,
     c1: RistrettoPoint,
     c2: RistrettoPoint,
+    proof: DecryptionShareProof,
 }
 
 impl TOfNCryptosystem for TOfNCurveElGamal {
@@ -106,7 +205,9 @@ impl TOfNCryptosystem for TOfNCurveElGamal {
             ),
         }
 
-        TOfNCurveElGamal {}
+        TOfNCurveElGamal {
+            security_level: *security_param,
+        }
     }
 
     fn generate_keys<R: SecureRng>(
@@ -115,6 +216,12 @@ impl TOfNCryptosystem for TOfNCurveElGamal {
         key_count_n: usize,
         rng: &mut GeneralRng<R>,
     ) -> (CurveElGamalPK, Vec<TOfNCurveElGamalSK>) {
+        trace_span!(
+            INFO,
+            "generate_keys",
+            threshold = threshold_t,
+            parties = key_count_n
+        );
         let master_key = Scalar::random(rng.rng());
 
         let coefficients: Vec<Scalar> = (0..(threshold_t - 1))
@@ -136,6 +243,7 @@ impl TOfNCryptosystem for TOfNCurveElGamal {
         (
             CurveElGamalPK {
                 point: &master_key * &RISTRETTO_BASEPOINT_TABLE,
+                security_level: self.security_level,
             },
             partial_keys,
         )
@@ -144,8 +252,25 @@ impl TOfNCryptosystem for TOfNCurveElGamal {
 
 /// One of the partial keys, of which t must be used to decrypt successfully.
 pub struct TOfNCurveElGamalSK {
-    id: i32,
-    key: Scalar,
+    pub(crate) id: i32,
+    pub(crate) key: Scalar,
+}
+
+impl Debug for TOfNCurveElGamalSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TOfNCurveElGamalSK")
+            .field("id", &self.id)
+            .field("key", &Redacted::new(&self.key, 253))
+            .finish()
+    }
+}
+
+impl TOfNCurveElGamalSK {
+    /// Returns the verification key $x_i \cdot G$ of this partial key $x_i$. Each party publishes
+    /// its verification key after key generation, so that its decryption shares can be verified.
+    pub fn verification_key(&self) -> RistrettoPoint {
+        &self.key * &RISTRETTO_BASEPOINT_TABLE
+    }
 }
 
 impl PartialDecryptionKey<CurveElGamalPK> for TOfNCurveElGamalSK {
@@ -156,10 +281,14 @@ impl PartialDecryptionKey<CurveElGamalPK> for TOfNCurveElGamalSK {
         _public_key: &CurveElGamalPK,
         ciphertext: &CurveElGamalCiphertext,
     ) -> TOfNCurveElGamalShare {
+        trace_span!(DEBUG, "partial_decrypt");
+        let (c1, proof) = decrypt_with_proof(&self.key, ciphertext);
+
         TOfNCurveElGamalShare {
             id: self.id,
-            c1: self.key * ciphertext.c1,
+            c1,
             c2: ciphertext.c2,
+            proof,
         }
     }
 }
@@ -169,6 +298,7 @@ impl DecryptionShare<CurveElGamalPK> for TOfNCurveElGamalShare {
         decryption_shares: &[Self],
         _public_key: &CurveElGamalPK,
     ) -> Result<RistrettoPoint, DecryptionError> {
+        trace_span!(DEBUG, "combine");
         let summed: RistrettoPoint = decryption_shares
             .iter()
             .enumerate()
@@ -198,10 +328,124 @@ impl DecryptionShare<CurveElGamalPK> for TOfNCurveElGamalShare {
     }
 }
 
+impl TOfNCurveElGamalShare {
+    /// Verifies the proof of every decryption share of `ciphertext` before combining them. The
+    /// `verification_keys` hold the verification keys of all $n$ parties, ordered by their id. If
+    /// any share is invalid, the positions of all invalid shares in `decryption_shares` are
+    /// returned instead of the decryption.
+    pub fn combine_verified(
+        decryption_shares: &[Self],
+        ciphertext: &CurveElGamalCiphertext,
+        verification_keys: &[RistrettoPoint],
+        public_key: &CurveElGamalPK,
+    ) -> Result<RistrettoPoint, InvalidDecryptionShares> {
+        let invalid: Vec<usize> = decryption_shares
+            .iter()
+            .enumerate()
+            .filter(|(_, share)| {
+                let verification_key = usize::try_from(share.id - 1)
+                    .ok()
+                    .and_then(|index| verification_keys.get(index));
+
+                match verification_key {
+                    Some(verification_key) => {
+                        share.c2 != ciphertext.c2
+                            || !share
+                                .proof
+                                .verify(verification_key, &ciphertext.c1, &share.c1)
+                    }
+                    None => true,
+                }
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(InvalidDecryptionShares(invalid));
+        }
+
+        Ok(Self::combine(decryption_shares, public_key).unwrap())
+    }
+}
+
+/// Computes the decryption share $x \cdot c_1$ together with a proof of its correctness. The nonce
+/// is derived deterministically from the partial key and the ciphertext, so that no randomness is
+/// needed and repeated decryptions of the same ciphertext produce the same proof.
+fn decrypt_with_proof(
+    key: &Scalar,
+    ciphertext: &CurveElGamalCiphertext,
+) -> (RistrettoPoint, DecryptionShareProof) {
+    let share = key * ciphertext.c1;
+
+    let nonce_digest = Sha512::new()
+        .chain_update(NONCE_DOMAIN)
+        .chain_update(key.as_bytes())
+        .chain_update(ciphertext.c1.compress().as_bytes())
+        .finalize();
+    let nonce = Scalar::from_bytes_mod_order_wide(&nonce_digest.into());
+
+    let verification_key = key * &RISTRETTO_BASEPOINT_TABLE;
+    let challenge = compute_challenge(
+        &verification_key,
+        &ciphertext.c1,
+        &share,
+        &(&nonce * &RISTRETTO_BASEPOINT_TABLE),
+        &(nonce * ciphertext.c1),
+    );
+
+    (
+        share,
+        DecryptionShareProof {
+            challenge,
+            response: nonce + challenge * key,
+        },
+    )
+}
+
+impl DecryptionShareProof {
+    fn verify(
+        &self,
+        verification_key: &RistrettoPoint,
+        c1: &RistrettoPoint,
+        share: &RistrettoPoint,
+    ) -> bool {
+        let key_commitment =
+            &self.response * &RISTRETTO_BASEPOINT_TABLE - self.challenge * verification_key;
+        let share_commitment = self.response * c1 - self.challenge * share;
+
+        self.challenge
+            == compute_challenge(
+                verification_key,
+                c1,
+                share,
+                &key_commitment,
+                &share_commitment,
+            )
+    }
+}
+
+fn compute_challenge(
+    verification_key: &RistrettoPoint,
+    c1: &RistrettoPoint,
+    share: &RistrettoPoint,
+    key_commitment: &RistrettoPoint,
+    share_commitment: &RistrettoPoint,
+) -> Scalar {
+    let mut transcript = Transcript::new(CHALLENGE_DOMAIN);
+    transcript.append_message(b"verification key", verification_key.compress().as_bytes());
+    transcript.append_message(b"c1", c1.compress().as_bytes());
+    transcript.append_message(b"share", share.compress().as_bytes());
+    transcript.append_message(b"key commitment", key_commitment.compress().as_bytes());
+    transcript.append_message(b"share commitment", share_commitment.compress().as_bytes());
+
+    Scalar::from_bytes_mod_order_wide(&transcript.challenge_bytes(b"challenge"))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::threshold_cryptosystems::curve_el_gamal::{
-        NOfNCurveElGamal, NOfNCurveElGamalShare, TOfNCurveElGamal, TOfNCurveElGamalShare,
+        InvalidDecryptionShares, NOfNCurveElGamal, NOfNCurveElGamalShare, TOfNCurveElGamal,
+        TOfNCurveElGamalShare,
     };
     use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
     use curve25519_dalek::scalar::Scalar;
@@ -253,4 +497,85 @@ mod tests {
             TOfNCurveElGamalShare::combine(&[share_1, share_3], &pk).unwrap()
         );
     }
+
+    #[test]
+    fn test_combine_verified_3_of_3() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = NOfNCurveElGamal::setup(&BitsOfSecurity::default());
+        let (pk, sks) = el_gamal.generate_keys(3, &mut rng);
+        let verification_keys: Vec<_> = sks.iter().map(|sk| sk.verification_key()).collect();
+
+        let plaintext = &Scalar::from(19u64) * &RISTRETTO_BASEPOINT_TABLE;
+        let ciphertext = pk.encrypt(&plaintext, &mut rng);
+
+        let shares: Vec<_> = sks
+            .iter()
+            .map(|sk| sk.partial_decrypt(&ciphertext))
+            .collect();
+        assert_eq!(
+            plaintext,
+            NOfNCurveElGamalShare::combine_verified(
+                &shares,
+                &ciphertext.ciphertext,
+                &verification_keys,
+                &pk
+            )
+            .unwrap()
+        );
+
+        // The second party multiplies with a different key, while reusing its valid proof
+        let mut shares = shares;
+        shares[1].c1 += RISTRETTO_BASEPOINT_TABLE.basepoint();
+        assert_eq!(
+            Err(InvalidDecryptionShares(vec![1])),
+            NOfNCurveElGamalShare::combine_verified(
+                &shares,
+                &ciphertext.ciphertext,
+                &verification_keys,
+                &pk
+            )
+        );
+    }
+
+    #[test]
+    fn test_combine_verified_2_of_3() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+        let (pk, sks) = el_gamal.generate_keys(2, 3, &mut rng);
+        let verification_keys: Vec<_> = sks.iter().map(|sk| sk.verification_key()).collect();
+
+        let plaintext = &Scalar::from(21u64) * &RISTRETTO_BASEPOINT_TABLE;
+        let ciphertext = pk.encrypt(&plaintext, &mut rng);
+        let other_ciphertext = pk.encrypt(&plaintext, &mut rng);
+
+        let share_1 = sks[0].partial_decrypt(&ciphertext);
+        let share_3 = sks[2].partial_decrypt(&ciphertext);
+        assert_eq!(
+            plaintext,
+            TOfNCurveElGamalShare::combine_verified(
+                &[share_1, share_3],
+                &ciphertext.ciphertext,
+                &verification_keys,
+                &pk
+            )
+            .unwrap()
+        );
+
+        // A share of another ciphertext and a share with an unknown id are both rejected
+        let share_1 = sks[0].partial_decrypt(&ciphertext);
+        let share_2 = sks[1].partial_decrypt(&other_ciphertext);
+        let mut share_3 = sks[2].partial_decrypt(&ciphertext);
+        share_3.id = 4;
+        assert_eq!(
+            Err(InvalidDecryptionShares(vec![1, 2])),
+            TOfNCurveElGamalShare::combine_verified(
+                &[share_1, share_2, share_3],
+                &ciphertext.ciphertext,
+                &verification_keys,
+                &pk
+            )
+        );
+    }
 }
@@ -1,21 +1,23 @@
 use crate::constants::{SAFE_PRIME_1024, SAFE_PRIME_2048, SAFE_PRIME_3072};
 use crate::cryptosystems::integer_el_gamal::{IntegerElGamalCiphertext, IntegerElGamalPK};
-use rug::Integer;
 use scicrypt_bigint::UnsignedInteger;
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
 use scicrypt_traits::threshold_cryptosystems::{
     DecryptionShare, NOfNCryptosystem, PartialDecryptionKey, TOfNCryptosystem,
 };
 use scicrypt_traits::DecryptionError;
-use std::ops::Rem;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
 
 /// N-out-of-N Threshold ElGamal cryptosystem over integers: Extension of ElGamal that requires n out of n parties to
 /// successfully decrypt. For this scheme there exists an efficient distributed key generation protocol.
 #[derive(Clone)]
 pub struct NOfNIntegerElGamal {
     modulus: UnsignedInteger,
+    security_level: BitsOfSecurity,
 }
 
 /// Decryption key for N-out-of-N Integer-based ElGamal
@@ -23,6 +25,14 @@ pub struct NOfNIntegerElGamalSK {
     key: UnsignedInteger,
 }
 
+impl Debug for NOfNIntegerElGamalSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NOfNIntegerElGamalSK")
+            .field("key", &Redacted::new(&self.key, self.key.size_in_bits()))
+            .finish()
+    }
+}
+
 impl NOfNCryptosystem for NOfNIntegerElGamal {
     type PublicKey = IntegerElGamalPK;
     type SecretKey = NOfNIntegerElGamalSK;
@@ -42,6 +52,7 @@ impl NOfNCryptosystem for NOfNIntegerElGamal {
                 16,
                 public_key_len,
             ),
+            security_level: *security_param,
         }
     }
 
@@ -50,6 +61,7 @@ impl NOfNCryptosystem for NOfNIntegerElGamal {
         key_count_n: usize,
         rng: &mut GeneralRng<R>,
     ) -> (IntegerElGamalPK, Vec<NOfNIntegerElGamalSK>) {
+        trace_span!(INFO, "generate_keys", parties = key_count_n);
         let q = &self.modulus >> 1;
         let partial_keys: Vec<NOfNIntegerElGamalSK> = (0..key_count_n)
             .map(|_| NOfNIntegerElGamalSK {
@@ -59,19 +71,23 @@ impl NOfNCryptosystem for NOfNIntegerElGamal {
 
         let master_key: UnsignedInteger =
             partial_keys.iter().map(|k| &k.key).sum::<UnsignedInteger>() % &q;
-        let public_key = UnsignedInteger::new(4, 3).pow_mod(&master_key, &self.modulus);
+        let generator = UnsignedInteger::from(4u64);
+        let public_key = generator.pow_mod(&master_key, &self.modulus);
 
         (
-            IntegerElGamalPK {
-                h: public_key,
-                modulus: self.modulus.clone(),
-            },
+            IntegerElGamalPK::new(
+                public_key,
+                generator,
+                self.modulus.clone(),
+                self.security_level,
+            ),
             partial_keys,
         )
     }
 }
 
 /// Decryption share of N-out-of-N integer-based ElGamal
+#[derive(Serialize, Deserialize)]
 pub struct NOfNIntegerElGamalShare(IntegerElGamalCiphertext);
 
 impl PartialDecryptionKey<IntegerElGamalPK> for NOfNIntegerElGamalSK {
@@ -82,6 +98,7 @@ impl PartialDecryptionKey<IntegerElGamalPK> for NOfNIntegerElGamalSK {
         public_key: &IntegerElGamalPK,
         ciphertext: &IntegerElGamalCiphertext,
     ) -> NOfNIntegerElGamalShare {
+        trace_span!(DEBUG, "partial_decrypt");
         NOfNIntegerElGamalShare(IntegerElGamalCiphertext {
             c1: ciphertext.c1.pow_mod(&self.key, &public_key.modulus),
             c2: ciphertext.c2.clone(), // TODO: Now, all c2 are cloned. We only need one in decryption.
@@ -94,12 +111,14 @@ impl DecryptionShare<IntegerElGamalPK> for NOfNIntegerElGamalShare {
         decryption_shares: &[Self],
         public_key: &IntegerElGamalPK,
     ) -> Result<UnsignedInteger, DecryptionError> {
+        trace_span!(DEBUG, "combine");
+        let c1s: Vec<UnsignedInteger> = decryption_shares
+            .iter()
+            .map(|share| share.0.c1.clone())
+            .collect();
+
         Ok((&decryption_shares[0].0.c2
-            * &decryption_shares
-                .iter()
-                .map(|share| &share.0.c1)
-                .product::<UnsignedInteger>() // TODO: We should probably keep reducing this value during aggregation
-                .rem(&public_key.modulus)
+            * &UnsignedInteger::product_mod(&c1s, &public_key.modulus)
                 .invert(&public_key.modulus)
                 .unwrap())
             % &public_key.modulus)
@@ -111,6 +130,7 @@ impl DecryptionShare<IntegerElGamalPK> for NOfNIntegerElGamalShare {
 #[derive(Clone)]
 pub struct TOfNIntegerElGamal {
     modulus: UnsignedInteger,
+    security_level: BitsOfSecurity,
 }
 
 /// One of the partial keys, of which t must be used to decrypt successfully.
@@ -119,7 +139,17 @@ pub struct TOfNIntegerElGamalSK {
     pub(crate) key: UnsignedInteger,
 }
 
+impl Debug for TOfNIntegerElGamalSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TOfNIntegerElGamalSK")
+            .field("id", &self.id)
+            .field("key", &Redacted::new(&self.key, self.key.size_in_bits()))
+            .finish()
+    }
+}
+
 /// A partially decrypted ciphertext, of which t must be combined to decrypt successfully.
+#[derive(Serialize, Deserialize)]
 pub struct TOfNIntegerElGamalShare {
     id: i32,
     c1: UnsignedInteger,
@@ -145,6 +175,7 @@ impl TOfNCryptosystem for TOfNIntegerElGamal {
                 16,
                 public_key_len,
             ),
+            security_level: *security_param,
         }
     }
 
@@ -154,6 +185,12 @@ impl TOfNCryptosystem for TOfNIntegerElGamal {
         key_count_n: usize,
         rng: &mut GeneralRng<R>,
     ) -> (IntegerElGamalPK, Vec<TOfNIntegerElGamalSK>) {
+        trace_span!(
+            INFO,
+            "generate_keys",
+            threshold = threshold_t,
+            parties = key_count_n
+        );
         let q = &self.modulus >> 1;
         let master_key = UnsignedInteger::random_below(&q, rng);
 
@@ -176,13 +213,16 @@ impl TOfNCryptosystem for TOfNIntegerElGamal {
             })
             .collect();
 
-        let public_key = UnsignedInteger::new(4, 3).pow_mod(&master_key, &self.modulus);
+        let generator = UnsignedInteger::from(4u64);
+        let public_key = generator.pow_mod(&master_key, &self.modulus);
 
         (
-            IntegerElGamalPK {
-                h: public_key,
-                modulus: self.modulus.clone(),
-            },
+            IntegerElGamalPK::new(
+                public_key,
+                generator,
+                self.modulus.clone(),
+                self.security_level,
+            ),
             partial_keys,
         )
     }
@@ -196,6 +236,7 @@ impl PartialDecryptionKey<IntegerElGamalPK> for TOfNIntegerElGamalSK {
         public_key: &IntegerElGamalPK,
         ciphertext: &IntegerElGamalCiphertext,
     ) -> TOfNIntegerElGamalShare {
+        trace_span!(DEBUG, "partial_decrypt");
         TOfNIntegerElGamalShare {
             id: self.id,
             c1: ciphertext.c1.pow_mod(&self.key, &public_key.modulus),
@@ -209,35 +250,19 @@ impl DecryptionShare<IntegerElGamalPK> for TOfNIntegerElGamalShare {
         decryption_shares: &[Self],
         public_key: &IntegerElGamalPK,
     ) -> Result<UnsignedInteger, DecryptionError> {
-        let q = (&public_key.modulus >> 1).to_rug();
+        trace_span!(DEBUG, "combine");
+        let q = &public_key.modulus >> 1;
 
         let multiplied: UnsignedInteger = decryption_shares
             .iter()
-            .enumerate()
-            .map(|(i, share)| {
-                let mut b = Integer::from(1);
-
-                for i_prime in 0..decryption_shares.len() {
-                    if i == i_prime {
-                        continue;
-                    }
-
-                    if decryption_shares[i].id == decryption_shares[i_prime].id {
-                        continue;
-                    }
-
-                    b = (b * Integer::from(decryption_shares[i_prime].id)).rem(&q);
-                    b = (b
-                        * (Integer::from(decryption_shares[i_prime].id)
-                            - Integer::from(decryption_shares[i].id))
-                        .invert(&q)
-                        .unwrap())
-                    .rem(&q);
-                }
-
-                share
-                    .c1
-                    .pow_mod(&UnsignedInteger::from(b), &public_key.modulus)
+            .map(|share| {
+                let b = lagrange_coefficient(
+                    share.id,
+                    decryption_shares.iter().map(|other| other.id),
+                    &q,
+                );
+
+                share.c1.pow_mod(&b, &public_key.modulus)
             })
             .reduce(|a, b| (&a * &b) % &public_key.modulus)
             .unwrap();
@@ -249,6 +274,34 @@ impl DecryptionShare<IntegerElGamalPK> for TOfNIntegerElGamalShare {
     }
 }
 
+/// Computes the Lagrange coefficient $\prod_{j \neq i} j / (j - i) \bmod q$ of party `id` for
+/// interpolating at zero over the parties with `ids`. The ids are public, so only the final
+/// inversion needs care; it uses Fermat's little theorem since $q$ is prime.
+fn lagrange_coefficient(
+    id: i32,
+    ids: impl Iterator<Item = i32>,
+    q: &UnsignedInteger,
+) -> UnsignedInteger {
+    let mut numerator = UnsignedInteger::from(1u64);
+    let mut denominator = UnsignedInteger::from(1u64);
+    let mut negative = false;
+
+    for other in ids.filter(|&other| other != id) {
+        numerator = (&numerator * &UnsignedInteger::from(other as u64)) % q;
+        denominator = (&denominator * &UnsignedInteger::from(other.abs_diff(id) as u64)) % q;
+        negative ^= other < id;
+    }
+
+    let coefficient = (&numerator * &denominator.pow_mod(&(q.clone() - 2), q)) % q;
+
+    if negative {
+        // Multiply by q - 1, which is -1 modulo q
+        (&coefficient * &(q.clone() - 1)) % q
+    } else {
+        coefficient
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::threshold_cryptosystems::integer_el_gamal::{
@@ -302,4 +355,25 @@ mod tests {
             TOfNIntegerElGamalShare::combine(&[share_1, share_3], &pk).unwrap()
         );
     }
+
+    #[test]
+    fn test_encrypt_decrypt_3_of_5_unordered() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = TOfNIntegerElGamal::setup(&Default::default());
+        let (pk, sks) = el_gamal.generate_keys(3, 5, &mut rng);
+
+        let plaintext = UnsignedInteger::from(2100u64);
+
+        let ciphertext = pk.encrypt(&plaintext, &mut rng);
+
+        let share_5 = sks[4].partial_decrypt(&ciphertext);
+        let share_2 = sks[1].partial_decrypt(&ciphertext);
+        let share_4 = sks[3].partial_decrypt(&ciphertext);
+
+        assert_eq!(
+            plaintext,
+            TOfNIntegerElGamalShare::combine(&[share_5, share_2, share_4], &pk).unwrap()
+        );
+    }
 }
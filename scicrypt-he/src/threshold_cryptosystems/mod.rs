@@ -1,6 +1,12 @@
 /// Threshold ElGamal cryptosystem over an elliptic curve
+#[cfg(feature = "curve")]
 pub mod curve_el_gamal;
 /// Threshold ElGamal cryptosystem over the integers modulo a prime
+#[cfg(feature = "integer")]
 pub mod integer_el_gamal;
 /// Threshold Paillier cryptosystem.
+#[cfg(feature = "integer")]
 pub mod paillier;
+/// Proactive refresh of the partial keys of threshold curve-based ElGamal.
+#[cfg(feature = "curve")]
+pub mod refresh;
@@ -2,14 +2,18 @@ use rug::Integer;
 use scicrypt_bigint::UnsignedInteger;
 use scicrypt_numbertheory::gen_safe_prime;
 use scicrypt_traits::cryptosystems::{Associable, EncryptionKey};
+use scicrypt_traits::encoding::{PlaintextOperation, PlaintextSpace};
 use scicrypt_traits::homomorphic::HomomorphicAddition;
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
 use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
 use scicrypt_traits::threshold_cryptosystems::{
     DecryptionShare, PartialDecryptionKey, TOfNCryptosystem,
 };
 use scicrypt_traits::DecryptionError;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
 use std::ops::Rem;
 
 use crate::cryptosystems::paillier::PaillierCiphertext;
@@ -19,6 +23,7 @@ use crate::cryptosystems::paillier::PaillierCiphertext;
 #[derive(Copy, Clone)]
 pub struct ThresholdPaillier {
     modulus_size: u32,
+    security_level: BitsOfSecurity,
 }
 
 /// The public key for encryption.
@@ -28,6 +33,7 @@ pub struct ThresholdPaillierPK {
     modulus: UnsignedInteger,
     theta: UnsignedInteger,
     delta: UnsignedInteger,
+    security_level: BitsOfSecurity,
 }
 
 /// One of the partial keys, of which t must be used to decrypt successfully.
@@ -36,7 +42,17 @@ pub struct ThresholdPaillierSK {
     key: UnsignedInteger,
 }
 
+impl Debug for ThresholdPaillierSK {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThresholdPaillierSK")
+            .field("id", &self.id)
+            .field("key", &Redacted::new(&self.key, self.key.size_in_bits()))
+            .finish()
+    }
+}
+
 /// A partially decrypted ciphertext, of which t must be combined to decrypt successfully.
+#[derive(Serialize, Deserialize)]
 pub struct ThresholdPaillierShare {
     id: i32,
     share: UnsignedInteger,
@@ -49,6 +65,7 @@ impl TOfNCryptosystem for ThresholdPaillier {
     fn setup(security_param: &BitsOfSecurity) -> Self {
         ThresholdPaillier {
             modulus_size: security_param.to_public_key_bit_length(),
+            security_level: *security_param,
         }
     }
 
@@ -58,6 +75,12 @@ impl TOfNCryptosystem for ThresholdPaillier {
         key_count_n: usize,
         rng: &mut GeneralRng<R>,
     ) -> (ThresholdPaillierPK, Vec<ThresholdPaillierSK>) {
+        trace_span!(
+            INFO,
+            "generate_keys",
+            threshold = threshold_t,
+            parties = key_count_n
+        );
         let prime_p = gen_safe_prime(self.modulus_size / 2, rng);
         let prime_q = gen_safe_prime(self.modulus_size / 2, rng);
 
@@ -101,6 +124,7 @@ impl TOfNCryptosystem for ThresholdPaillier {
                 modulus,
                 theta,
                 delta,
+                security_level: self.security_level,
             },
             partial_keys,
         )
@@ -130,6 +154,7 @@ impl EncryptionKey for ThresholdPaillierPK {
     where
         Self: Sized,
     {
+        trace_span!(DEBUG, "randomize");
         // r must be coprime with n_squared but this only fails with probability 2^(1 - n_in_bits)
         // 0 also only occurs with extremely low probability, so we can simply sample randomly s.t. 0 < r < n
         let r = UnsignedInteger::random_below(&self.modulus, rng);
@@ -149,6 +174,17 @@ impl EncryptionKey for ThresholdPaillierPK {
             c: (&ciphertext.c * &randomizer) % &n_squared,
         }
     }
+
+    fn security_level(&self) -> BitsOfSecurity {
+        self.security_level
+    }
+
+    fn plaintext_space(&self) -> PlaintextSpace {
+        PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: self.modulus.significant_bits_leaky() - 1,
+        }
+    }
 }
 
 impl HomomorphicAddition for ThresholdPaillierPK {
@@ -157,12 +193,14 @@ impl HomomorphicAddition for ThresholdPaillierPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "add");
         PaillierCiphertext {
             c: (&ciphertext_a.c * &ciphertext_b.c) % &self.modulus.square(),
         }
     }
 
     fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        trace_span!(TRACE, "mul_constant");
         let modulus = self.modulus.square();
 
         PaillierCiphertext {
@@ -175,6 +213,7 @@ impl HomomorphicAddition for ThresholdPaillierPK {
         ciphertext_a: &Self::Ciphertext,
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext {
+        trace_span!(TRACE, "sub");
         let modulus = self.modulus.square();
         PaillierCiphertext {
             c: (&ciphertext_a.c * &ciphertext_b.c.clone().invert(&modulus).unwrap()) % &modulus,
@@ -218,6 +257,7 @@ impl PartialDecryptionKey<ThresholdPaillierPK> for ThresholdPaillierSK {
         public_key: &ThresholdPaillierPK,
         ciphertext: &PaillierCiphertext,
     ) -> ThresholdPaillierShare {
+        trace_span!(DEBUG, "partial_decrypt");
         let n_squared = public_key.modulus.square();
         ThresholdPaillierShare {
             id: self.id,
@@ -234,6 +274,7 @@ impl DecryptionShare<ThresholdPaillierPK> for ThresholdPaillierShare {
         decryption_shares: &[Self],
         public_key: &ThresholdPaillierPK,
     ) -> Result<UnsignedInteger, DecryptionError> {
+        trace_span!(DEBUG, "combine");
         let lambdas: Vec<Integer> = (0..decryption_shares.len())
             .map(|i| {
                 let mut lambda = public_key.delta.clone().to_rug();
@@ -0,0 +1,345 @@
+//! Proactive refresh of the partial keys of threshold curve-based ElGamal. In every refresh, each
+//! party deals a random sharing of zero and adds the sub-shares it receives to its partial key.
+//! The public key stays the same, but partial keys from before a refresh cannot be combined with
+//! partial keys from after it. An adversary therefore has to compromise $t$ parties within a single
+//! period to learn the secret key.
+//!
+//! The protocol has two rounds. In the first round, each party broadcasts a `RefreshCommitment` and
+//! privately sends a `RefreshSubShare` to every party. In the second round, each party verifies the
+//! sub-shares it received against the commitments and derives its new partial key. All parties must
+//! use the same set of commitments, which is why they should be sent over a broadcast channel.
+//! The first round returns a `PendingRefresh`, which can only be applied once and remembers the
+//! threshold, so that the second round cannot be skipped, repeated or run with other parameters.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::threshold_cryptosystems::curve_el_gamal::TOfNCurveElGamal;
+//! # use scicrypt_traits::threshold_cryptosystems::TOfNCryptosystem;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+//! let (public_key, secret_keys) = el_gamal.generate_keys(2, 3, &mut rng);
+//!
+//! // Round 1: every party deals a sharing of zero
+//! let mut pending = vec![];
+//! let mut commitments = vec![];
+//! let mut sub_shares = vec![];
+//! for secret_key in &secret_keys {
+//!     let (refresh, commitment, shares) = secret_key.refresh_shares(2, 3, &mut rng);
+//!     pending.push(refresh);
+//!     commitments.push(commitment);
+//!     sub_shares.push(shares);
+//! }
+//!
+//! // Round 2: every party verifies its sub-shares and refreshes its partial key
+//! let refreshed_keys: Vec<_> = pending
+//!     .into_iter()
+//!     .enumerate()
+//!     .map(|(i, refresh)| {
+//!         let received: Vec<_> = sub_shares.iter().map(|shares| shares[i].clone()).collect();
+//!         refresh.apply(&commitments, &received).unwrap()
+//!     })
+//!     .collect();
+//! ```
+
+use crate::threshold_cryptosystems::curve_el_gamal::TOfNCurveElGamalSK;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// Broadcast message of the first round: Feldman commitments $a_k \cdot G$ to the coefficients of
+/// the dealer's random polynomial $\delta(X) = \sum_{k=1}^{t-1} a_k X^k$, whose constant term is zero.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct RefreshCommitment {
+    /// The id of the dealing party
+    pub dealer: i32,
+    /// Commitments to the non-constant coefficients of the polynomial, lowest degree first
+    pub coefficients: Vec<RistrettoPoint>,
+}
+
+/// Private message of the first round: the evaluation $\delta(j)$ of the dealer's polynomial for
+/// recipient $j$. It must be sent over a confidential channel.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RefreshSubShare {
+    /// The id of the dealing party
+    pub dealer: i32,
+    /// The id of the receiving party
+    pub recipient: i32,
+    value: Scalar,
+}
+
+impl Debug for RefreshSubShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RefreshSubShare")
+            .field("dealer", &self.dealer)
+            .field("recipient", &self.recipient)
+            .field("value", &Redacted::new(&self.value, 253))
+            .finish()
+    }
+}
+
+/// Error returned when a refresh cannot be applied. It holds the ids of the dealers whose sub-share
+/// is missing, duplicated or does not match their commitment, or whose commitment has the wrong
+/// degree.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidRefresh(pub Vec<i32>);
+
+/// The state of a party between the two rounds of a refresh, which is consumed when the refresh is
+/// applied.
+#[derive(Debug)]
+pub struct PendingRefresh<'a> {
+    secret_key: &'a TOfNCurveElGamalSK,
+    threshold_t: usize,
+}
+
+impl TOfNCurveElGamalSK {
+    /// Runs the first round of a refresh for a (t, n)-threshold key. Returns the state for the
+    /// second round, the commitment to broadcast and one sub-share for each of the $n$ parties,
+    /// ordered by their id.
+    pub fn refresh_shares<R: SecureRng>(
+        &self,
+        threshold_t: usize,
+        key_count_n: usize,
+        rng: &mut GeneralRng<R>,
+    ) -> (PendingRefresh<'_>, RefreshCommitment, Vec<RefreshSubShare>) {
+        let coefficients: Vec<Scalar> = (1..threshold_t)
+            .map(|_| Scalar::random(rng.rng()))
+            .collect();
+
+        let sub_shares = (1..=key_count_n)
+            .map(|j| {
+                let x = Scalar::from(j as u64);
+                let value = coefficients
+                    .iter()
+                    .rev()
+                    .fold(Scalar::zero(), |accumulator, a| (accumulator + a) * x);
+
+                RefreshSubShare {
+                    dealer: self.id,
+                    recipient: j as i32,
+                    value,
+                }
+            })
+            .collect();
+
+        let commitment = RefreshCommitment {
+            dealer: self.id,
+            coefficients: coefficients
+                .iter()
+                .map(|a| a * &RISTRETTO_BASEPOINT_TABLE)
+                .collect(),
+        };
+
+        (
+            PendingRefresh {
+                secret_key: self,
+                threshold_t,
+            },
+            commitment,
+            sub_shares,
+        )
+    }
+}
+
+impl PendingRefresh<'_> {
+    /// Runs the second round of the refresh. Verifies that every dealer in `commitments` committed
+    /// to a polynomial of degree $t - 1$ and sent exactly one valid sub-share to this party, and
+    /// returns the refreshed partial key. If any dealer misbehaved, their ids are returned instead.
+    pub fn apply(
+        self,
+        commitments: &[RefreshCommitment],
+        sub_shares: &[RefreshSubShare],
+    ) -> Result<TOfNCurveElGamalSK, InvalidRefresh> {
+        let secret_key = self.secret_key;
+        let mut invalid = vec![];
+        let mut key = secret_key.key;
+
+        for commitment in commitments {
+            let mut received = sub_shares
+                .iter()
+                .filter(|sub_share| sub_share.dealer == commitment.dealer);
+
+            match (received.next(), received.next()) {
+                (Some(sub_share), None)
+                    if commitment.coefficients.len() == self.threshold_t - 1
+                        && sub_share.verify(secret_key.id, commitment) =>
+                {
+                    key += sub_share.value;
+                }
+                _ => invalid.push(commitment.dealer),
+            }
+        }
+
+        for sub_share in sub_shares {
+            if !commitments
+                .iter()
+                .any(|commitment| commitment.dealer == sub_share.dealer)
+            {
+                invalid.push(sub_share.dealer);
+            }
+        }
+
+        if !invalid.is_empty() {
+            return Err(InvalidRefresh(invalid));
+        }
+
+        Ok(TOfNCurveElGamalSK {
+            id: secret_key.id,
+            key,
+        })
+    }
+}
+
+impl RefreshSubShare {
+    fn verify(&self, recipient: i32, commitment: &RefreshCommitment) -> bool {
+        self.recipient == recipient
+            && &self.value * &RISTRETTO_BASEPOINT_TABLE == commitment.evaluate(recipient)
+    }
+}
+
+impl RefreshCommitment {
+    /// Evaluates the committed polynomial in the exponent, i.e. returns $\delta(j) \cdot G$ for
+    /// party $j$.
+    fn evaluate(&self, party: i32) -> RistrettoPoint {
+        let x = Scalar::from(party as u64);
+
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(RistrettoPoint::identity(), |accumulator, coefficient| {
+                x * (accumulator + coefficient)
+            })
+    }
+}
+
+/// Returns the verification key of party `id` after the refresh with the given `commitments`, given
+/// its `verification_key` from before the refresh. Anyone can compute this from the broadcast
+/// messages alone.
+pub fn refresh_verification_key(
+    verification_key: &RistrettoPoint,
+    id: i32,
+    commitments: &[RefreshCommitment],
+) -> RistrettoPoint {
+    verification_key
+        + commitments
+            .iter()
+            .map(|commitment| commitment.evaluate(id))
+            .sum::<RistrettoPoint>()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::threshold_cryptosystems::curve_el_gamal::{TOfNCurveElGamal, TOfNCurveElGamalShare};
+    use crate::threshold_cryptosystems::refresh::{refresh_verification_key, InvalidRefresh};
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::EncryptionKey;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+    use scicrypt_traits::threshold_cryptosystems::{
+        DecryptionShare, PartialDecryptionKey, TOfNCryptosystem,
+    };
+
+    #[test]
+    fn test_refresh_keeps_public_key() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+        let (pk, sks) = el_gamal.generate_keys(2, 3, &mut rng);
+
+        let mut pending = vec![];
+        let mut commitments = vec![];
+        let mut sub_shares = vec![];
+        for sk in &sks {
+            let (refresh, commitment, shares) = sk.refresh_shares(2, 3, &mut rng);
+            pending.push(refresh);
+            commitments.push(commitment);
+            sub_shares.push(shares);
+        }
+
+        let refreshed: Vec<_> = pending
+            .into_iter()
+            .enumerate()
+            .map(|(i, refresh)| {
+                let received: Vec<_> = sub_shares.iter().map(|s| s[i].clone()).collect();
+                refresh.apply(&commitments, &received).unwrap()
+            })
+            .collect();
+
+        for (i, (sk, refreshed_sk)) in sks.iter().zip(&refreshed).enumerate() {
+            assert_ne!(sk.verification_key(), refreshed_sk.verification_key());
+            assert_eq!(
+                refreshed_sk.verification_key(),
+                refresh_verification_key(&sk.verification_key(), i as i32 + 1, &commitments)
+            );
+        }
+
+        let plaintext = &Scalar::from(21u64) * &RISTRETTO_BASEPOINT_TABLE;
+        let ciphertext = pk.encrypt(&plaintext, &mut rng);
+
+        let share_1 = refreshed[0].partial_decrypt(&ciphertext);
+        let share_3 = refreshed[2].partial_decrypt(&ciphertext);
+        assert_eq!(
+            plaintext,
+            TOfNCurveElGamalShare::combine(&[share_1, share_3], &pk).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_refresh_rejects_invalid_sub_share() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = TOfNCurveElGamal::setup(&BitsOfSecurity::default());
+        let (_, sks) = el_gamal.generate_keys(2, 3, &mut rng);
+
+        let (commitments, sub_shares): (Vec<_>, Vec<_>) = sks
+            .iter()
+            .map(|sk| {
+                let (_, commitment, shares) = sk.refresh_shares(2, 3, &mut rng);
+                (commitment, shares)
+            })
+            .unzip();
+
+        // Each attempt needs its own pending refresh, because applying one consumes it
+        let pending = || sks[0].refresh_shares(2, 3, &mut GeneralRng::new(OsRng)).0;
+
+        // The second dealer sends the sub-share meant for the third party to the first party
+        let received = vec![
+            sub_shares[0][0].clone(),
+            sub_shares[1][2].clone(),
+            sub_shares[2][0].clone(),
+        ];
+        assert_eq!(
+            InvalidRefresh(vec![2]),
+            pending().apply(&commitments, &received).unwrap_err()
+        );
+
+        // The third dealer's sub-share is missing
+        assert_eq!(
+            InvalidRefresh(vec![3]),
+            pending()
+                .apply(
+                    &commitments,
+                    &[sub_shares[0][0].clone(), sub_shares[1][0].clone()]
+                )
+                .unwrap_err()
+        );
+
+        // The first dealer raises the degree of its polynomial
+        let mut commitments = commitments;
+        commitments[0]
+            .coefficients
+            .push(RISTRETTO_BASEPOINT_TABLE.basepoint());
+        let received: Vec<_> = sub_shares.iter().map(|s| s[0].clone()).collect();
+        assert_eq!(
+            InvalidRefresh(vec![1]),
+            pending().apply(&commitments, &received).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Time-lock puzzles of Rivest, Shamir and Wagner ("Time-lock puzzles and timed-release crypto").
+//! A message is locked under the value $a^{2^t} \bmod n$ for an RSA modulus $n$, which anyone can
+//! compute using $t$ sequential squarings. Squarings cannot be parallelized, so the message stays
+//! hidden for roughly the time that $t$ squarings take. The creator of the puzzle knows the
+//! factorization of $n$, which lets them reduce the exponent $2^t$ modulo the order of the group
+//! and lock or unlock messages with a single exponentiation.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::timelock::TimeLock;
+//! # use scicrypt_bigint::UnsignedInteger;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let time_lock = TimeLock::setup(&BitsOfSecurity::ToyParameters, &mut rng);
+//!
+//! let puzzle = time_lock.lock(&UnsignedInteger::from(42u64), 10_000, &mut rng);
+//!
+//! assert_eq!(UnsignedInteger::from(42u64), puzzle.solve());
+//! ```
+
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_numbertheory::gen_rsa_modulus;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use scicrypt_traits::security::Redacted;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The creator of time-lock puzzles, who knows the factorization of the modulus. One `TimeLock`
+/// can lock any number of puzzles.
+pub struct TimeLock {
+    modulus: UnsignedInteger,
+    /// The order $p' q'$ of the quadratic residues modulo $n = (2 p' + 1)(2 q' + 1)$
+    order: UnsignedInteger,
+}
+
+impl Debug for TimeLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeLock")
+            .field("modulus", &self.modulus)
+            .field(
+                "order",
+                &Redacted::new(&self.order, self.order.size_in_bits()),
+            )
+            .finish()
+    }
+}
+
+/// A message that is locked until $t$ sequential squarings have been computed.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct TimeLockPuzzle {
+    /// RSA modulus $n$
+    pub modulus: UnsignedInteger,
+    /// Random quadratic residue $a$ that is squared
+    pub base: UnsignedInteger,
+    /// Number of squarings $t$ that is needed to solve the puzzle
+    pub squarings: u64,
+    /// The message plus $a^{2^t}$, modulo $n$
+    pub ciphertext: UnsignedInteger,
+}
+
+impl TimeLock {
+    /// Generates a fresh RSA modulus of the size that corresponds to `security_param`.
+    pub fn setup<R: SecureRng>(security_param: &BitsOfSecurity, rng: &mut GeneralRng<R>) -> Self {
+        let (modulus, p, q) = gen_rsa_modulus(security_param.to_public_key_bit_length(), rng);
+
+        TimeLock {
+            order: &(&p >> 1) * &(&q >> 1),
+            modulus,
+        }
+    }
+
+    /// Locks `message`, which must be smaller than the modulus, such that solving the puzzle takes
+    /// `squarings` sequential squarings. This takes a single exponentiation.
+    pub fn lock<R: SecureRng>(
+        &self,
+        message: &UnsignedInteger,
+        squarings: u64,
+        rng: &mut GeneralRng<R>,
+    ) -> TimeLockPuzzle {
+        let base = UnsignedInteger::random_below(&self.modulus, rng).square_mod(&self.modulus);
+        let unlocking_value = self.unlocking_value(&base, squarings);
+
+        TimeLockPuzzle {
+            modulus: self.modulus.clone(),
+            ciphertext: (unlocking_value + message) % &self.modulus,
+            base,
+            squarings,
+        }
+    }
+
+    /// Recovers the message of `puzzle` immediately using the factorization of the modulus. The
+    /// puzzle must have been locked by this `TimeLock`.
+    pub fn solve_with_trapdoor(&self, puzzle: &TimeLockPuzzle) -> UnsignedInteger {
+        assert!(
+            puzzle.modulus == self.modulus,
+            "the puzzle was locked under a different modulus"
+        );
+
+        puzzle.unlock(&self.unlocking_value(&puzzle.base, puzzle.squarings))
+    }
+
+    /// Computes $a^{2^t} = a^{2^t \bmod p' q'}$, which holds because $a$ is a quadratic residue.
+    fn unlocking_value(&self, base: &UnsignedInteger, squarings: u64) -> UnsignedInteger {
+        let exponent =
+            UnsignedInteger::from(2u64).pow_mod(&UnsignedInteger::new(squarings, 64), &self.order);
+
+        base.pow_mod(&exponent, &self.modulus)
+    }
+}
+
+impl TimeLockPuzzle {
+    /// Recovers the message by computing the required number of squarings one after the other. This
+    /// function is not constant-time, but it only operates on public values.
+    pub fn solve(&self) -> UnsignedInteger {
+        let mut value = self.base.clone();
+        for _ in 0..self.squarings {
+            value = value.square_leaky() % &self.modulus;
+        }
+
+        self.unlock(&value)
+    }
+
+    fn unlock(&self, unlocking_value: &UnsignedInteger) -> UnsignedInteger {
+        (self.ciphertext.clone() + &(self.modulus.clone() - unlocking_value)) % &self.modulus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timelock::TimeLock;
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_solve() {
+        let mut rng = GeneralRng::new(OsRng);
+        let time_lock = TimeLock::setup(&BitsOfSecurity::ToyParameters, &mut rng);
+
+        for squarings in [0, 1, 2, 1000] {
+            let puzzle = time_lock.lock(&UnsignedInteger::from(42u64), squarings, &mut rng);
+
+            assert_eq!(UnsignedInteger::from(42u64), puzzle.solve());
+            assert_eq!(
+                UnsignedInteger::from(42u64),
+                time_lock.solve_with_trapdoor(&puzzle)
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_large_message() {
+        let mut rng = GeneralRng::new(OsRng);
+        let time_lock = TimeLock::setup(&BitsOfSecurity::ToyParameters, &mut rng);
+
+        let message = time_lock.modulus.clone() - 1;
+        let puzzle = time_lock.lock(&message, 100, &mut rng);
+
+        assert_eq!(message, puzzle.solve());
+        assert_eq!(message, time_lock.solve_with_trapdoor(&puzzle));
+    }
+}
@@ -0,0 +1,65 @@
+//! Checks that decryption does not take time that depends on the ciphertext, using the harness in
+//! [`scicrypt_bigint::timing`]. Run these in release mode on an idle machine:
+//! `cargo test --release --features timing-tests timing_tests`.
+
+use crate::cryptosystems::integer_el_gamal::IntegerElGamal;
+use crate::cryptosystems::paillier::Paillier;
+use rand_core::OsRng;
+use scicrypt_bigint::timing::{measure, InputClass};
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::security::BitsOfSecurity;
+
+/// Draws a class for every sample and creates the corresponding ciphertexts: the fixed class always
+/// uses the same ciphertext, the random class a fresh encryption of a random plaintext.
+fn ciphertexts<C: Clone>(
+    samples: usize,
+    fixed: C,
+    mut random: impl FnMut(&mut GeneralRng<OsRng>) -> C,
+) -> Vec<(InputClass, C)> {
+    let mut rng = GeneralRng::new(OsRng);
+
+    (0..samples)
+        .map(|_| {
+            if UnsignedInteger::random(8, &mut rng).mod_u_leaky(2) == 0 {
+                (InputClass::Fixed, fixed.clone())
+            } else {
+                (InputClass::Random, random(&mut rng))
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_paillier_decryption_is_constant_time() {
+    let mut rng = GeneralRng::new(OsRng);
+    let (pk, sk) = Paillier::setup(&BitsOfSecurity::ToyParameters).generate_keys(&mut rng);
+
+    let fixed = pk.encrypt_raw(&UnsignedInteger::from(1u64), &mut rng);
+    let inputs = ciphertexts(20000, fixed, |rng| {
+        let plaintext = UnsignedInteger::random_below(&pk.n, rng);
+        pk.encrypt_raw(&plaintext, rng)
+    });
+
+    let report = measure(&inputs, |ciphertext| sk.decrypt_raw(&pk, ciphertext));
+
+    assert!(!report.is_leaky(), "{:?}", report);
+}
+
+#[test]
+fn test_integer_el_gamal_decryption_is_constant_time() {
+    let mut rng = GeneralRng::new(OsRng);
+    let (pk, sk) = IntegerElGamal::setup(&Default::default()).generate_keys(&mut rng);
+
+    let fixed = pk.encrypt_raw(&UnsignedInteger::from(1u64), &mut rng);
+    // Decryption under the default 3072-bit group is slow, so this uses fewer samples
+    let inputs = ciphertexts(2000, fixed, |rng| {
+        let plaintext = UnsignedInteger::random_below(&pk.modulus, rng);
+        pk.encrypt_raw(&plaintext, rng)
+    });
+
+    let report = measure(&inputs, |ciphertext| sk.decrypt_raw(&pk, ciphertext));
+
+    assert!(!report.is_leaky(), "{:?}", report);
+}
@@ -0,0 +1,79 @@
+//! Observability through `tracing`. With the `trace` feature, key generation, encryption,
+//! decryption and homomorphic operations enter spans that record the type of the key and
+//! non-sensitive metadata, such as modulus sizes and batch sizes. Subscribers can derive durations
+//! and counts from these spans. Key generation is traced at the `INFO` level, encryption and
+//! decryption at `DEBUG`, and the cheap homomorphic operations at `TRACE`.
+
+/// Enters a `tracing` span at `level` for the rest of the enclosing function when the `trace`
+/// feature is enabled, and does nothing otherwise. The span records the implementing type along
+/// with the given `fields`, which must never contain secret values.
+macro_rules! trace_span {
+    ($level:ident, $name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "trace")]
+        let _span = tracing::span!(
+            tracing::Level::$level,
+            $name,
+            cryptosystem = std::any::type_name::<Self>()
+            $(, $($fields)*)?
+        )
+        .entered();
+    };
+}
+
+#[cfg(all(test, feature = "trace", feature = "curve"))]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+    use scicrypt_traits::randomness::GeneralRng;
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records the names of all spans that are created.
+    struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+    impl Subscriber for SpanNames {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut names = self.0.lock().unwrap();
+            names.push(span.metadata().name());
+            Id::from_u64(names.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_operations_enter_spans() {
+        let names = Arc::new(Mutex::new(vec![]));
+        let subscriber = SpanNames(Arc::clone(&names));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut rng = GeneralRng::new(OsRng);
+            let el_gamal = CurveElGamal::setup(&Default::default());
+            let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+            let ciphertext = pk.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+            let sum = &ciphertext + &ciphertext;
+            sk.decrypt(&sum);
+        });
+
+        let names = names.lock().unwrap();
+        for name in ["generate_keys", "encrypt", "randomize", "add", "decrypt"] {
+            assert!(names.contains(&name), "no span named {}", name);
+        }
+    }
+}
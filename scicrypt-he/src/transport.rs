@@ -0,0 +1,421 @@
+//! A minimal transport for running the two-party protocols end-to-end. A `Transport` sends and
+//! receives frames of bytes, and the protocol modules use it to exchange their messages in
+//! `wire` envelopes, so that each protocol can be run with a single call per party.
+//!
+//! Three reference implementations are included: `MemoryTransport` connects two parties in the
+//! same process, which is useful in tests, while `TcpTransport` (behind the `tcp` feature) and
+//! `TlsTransport` (behind the `tls` feature) connect parties over the network. Streams frame each
+//! message with its little-endian `u32` length. Plain TCP provides neither confidentiality nor
+//! authentication, so it should only be used on trusted networks.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, EncryptionKey};
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::protocols::blind_decryption;
+//! # use scicrypt_he::transport::MemoryTransport;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let (public_key, secret_key) = CurveElGamal::setup(&Default::default()).generate_keys(&mut rng);
+//! let ciphertext = public_key.encrypt_raw(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+//!
+//! let (mut client, mut key_holder) = MemoryTransport::pair();
+//! let plaintext = std::thread::scope(|scope| {
+//!     scope.spawn(|| blind_decryption::serve(&mut key_holder, &public_key, &secret_key).unwrap());
+//!     blind_decryption::request(&mut client, &public_key, &ciphertext, &mut rng).unwrap()
+//! });
+//!
+//! assert_eq!(RISTRETTO_BASEPOINT_POINT, plaintext);
+//! ```
+
+use crate::wire::{Envelope, WireError, WireMessage};
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// The largest frame that a `StreamTransport` accepts by default, which limits how much memory the
+/// other party can make us allocate.
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 64 << 20;
+
+/// Error returned when a protocol cannot be completed over a transport.
+#[derive(Debug)]
+pub enum TransportError {
+    /// Sending or receiving failed, for example because the other party closed the connection
+    Io(Error),
+    /// The received frame is not a valid envelope, or holds a different message than expected
+    Wire(WireError),
+    /// The other party's message is well-formed, but does not pass the checks of the protocol
+    Rejected,
+}
+
+impl From<Error> for TransportError {
+    fn from(error: Error) -> Self {
+        TransportError::Io(error)
+    }
+}
+
+impl From<WireError> for TransportError {
+    fn from(error: WireError) -> Self {
+        TransportError::Wire(error)
+    }
+}
+
+/// A reliable, ordered channel between two parties that carries frames of bytes.
+pub trait Transport {
+    /// Sends `frame` to the other party.
+    fn send(&mut self, frame: &[u8]) -> Result<(), Error>;
+
+    /// Blocks until the next frame from the other party arrives.
+    fn receive(&mut self) -> Result<Vec<u8>, Error>;
+
+    /// Sends `message` to the other party in an envelope.
+    fn send_message<M: WireMessage>(&mut self, message: &M) -> Result<(), TransportError> {
+        Ok(self.send(&Envelope::new(message).to_bytes())?)
+    }
+
+    /// Receives the next envelope from the other party, and returns an error if it does not hold a
+    /// message of type `M`.
+    fn receive_message<M: WireMessage>(&mut self) -> Result<M, TransportError> {
+        Ok(Envelope::from_bytes(&self.receive()?)?.open()?)
+    }
+}
+
+/// One end of an in-memory connection between two parties, which may live on different threads.
+#[derive(Debug)]
+pub struct MemoryTransport {
+    sender: Sender<Vec<u8>>,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl MemoryTransport {
+    /// Returns both ends of a new connection.
+    pub fn pair() -> (Self, Self) {
+        let (sender_a, receiver_b) = channel();
+        let (sender_b, receiver_a) = channel();
+
+        (
+            MemoryTransport {
+                sender: sender_a,
+                receiver: receiver_a,
+            },
+            MemoryTransport {
+                sender: sender_b,
+                receiver: receiver_b,
+            },
+        )
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&mut self, frame: &[u8]) -> Result<(), Error> {
+        self.sender
+            .send(frame.to_vec())
+            .map_err(|_| Error::new(ErrorKind::BrokenPipe, "the other party hung up"))
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.receiver
+            .recv()
+            .map_err(|_| Error::new(ErrorKind::UnexpectedEof, "the other party hung up"))
+    }
+}
+
+/// A transport over a byte stream, which prefixes every frame with its little-endian `u32` length.
+#[derive(Debug)]
+pub struct StreamTransport<S> {
+    stream: S,
+    max_frame_length: usize,
+}
+
+impl<S: Read + Write> StreamTransport<S> {
+    /// Frames messages over `stream`, accepting frames of up to `DEFAULT_MAX_FRAME_LENGTH` bytes.
+    pub fn new(stream: S) -> Self {
+        StreamTransport {
+            stream,
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+        }
+    }
+
+    /// Changes the length of the largest frame that is accepted.
+    pub fn with_max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read + Write> Transport for StreamTransport<S> {
+    fn send(&mut self, frame: &[u8]) -> Result<(), Error> {
+        let length = u32::try_from(frame.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "frames cannot exceed 4 GiB"))?;
+
+        self.stream.write_all(&length.to_le_bytes())?;
+        self.stream.write_all(frame)?;
+        self.stream.flush()
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let mut length = [0u8; 4];
+        self.stream.read_exact(&mut length)?;
+
+        let length = u32::from_le_bytes(length) as usize;
+        if length > self.max_frame_length {
+            return Err(Error::new(ErrorKind::InvalidData, "the frame is too long"));
+        }
+
+        let mut frame = vec![0u8; length];
+        self.stream.read_exact(&mut frame)?;
+
+        Ok(frame)
+    }
+}
+
+#[cfg(feature = "tcp")]
+pub use self::tcp::TcpTransport;
+
+#[cfg(feature = "tcp")]
+mod tcp {
+    use super::StreamTransport;
+    use std::io::Error;
+    use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+    /// A transport over an unencrypted TCP connection.
+    pub type TcpTransport = StreamTransport<TcpStream>;
+
+    impl TcpTransport {
+        /// Connects to the party listening on `address`.
+        pub fn connect<A: ToSocketAddrs>(address: A) -> Result<Self, Error> {
+            Self::from_stream(TcpStream::connect(address)?)
+        }
+
+        /// Waits for the next party to connect to `listener`.
+        pub fn accept(listener: &TcpListener) -> Result<Self, Error> {
+            Self::from_stream(listener.accept()?.0)
+        }
+
+        /// Disables Nagle's algorithm, because the protocols send small messages and wait for the
+        /// response.
+        fn from_stream(stream: TcpStream) -> Result<Self, Error> {
+            stream.set_nodelay(true)?;
+
+            Ok(StreamTransport::new(stream))
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+pub use self::tls::TlsTransport;
+
+#[cfg(feature = "tls")]
+mod tls {
+    use super::StreamTransport;
+    use openssl::ssl::{SslAcceptor, SslConnector, SslStream};
+    use std::io::{Error, ErrorKind};
+    use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+    /// A transport over a TLS connection, using OpenSSL. The certificates and verification settings
+    /// are taken from the `SslConnector` and `SslAcceptor` that the application configures.
+    pub type TlsTransport = StreamTransport<SslStream<TcpStream>>;
+
+    impl TlsTransport {
+        /// Connects to the party listening on `address`, and verifies that its certificate is valid
+        /// for `domain`.
+        pub fn connect<A: ToSocketAddrs>(
+            address: A,
+            domain: &str,
+            connector: &SslConnector,
+        ) -> Result<Self, Error> {
+            let stream = TcpStream::connect(address)?;
+            stream.set_nodelay(true)?;
+
+            let stream = connector
+                .connect(domain, stream)
+                .map_err(|error| Error::new(ErrorKind::ConnectionAborted, error.to_string()))?;
+
+            Ok(StreamTransport::new(stream))
+        }
+
+        /// Waits for the next party to connect to `listener`, and completes the TLS handshake.
+        pub fn accept(listener: &TcpListener, acceptor: &SslAcceptor) -> Result<Self, Error> {
+            let stream = listener.accept()?.0;
+            stream.set_nodelay(true)?;
+
+            let stream = acceptor
+                .accept(stream)
+                .map_err(|error| Error::new(ErrorKind::ConnectionAborted, error.to_string()))?;
+
+            Ok(StreamTransport::new(stream))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transport::{MemoryTransport, StreamTransport, Transport, TransportError};
+    use crate::wire::{VersionRange, WireError, WireMessage};
+    use std::io::{Cursor, ErrorKind};
+
+    #[test]
+    fn test_memory_transport() {
+        let (mut a, mut b) = MemoryTransport::pair();
+
+        a.send_message(&VersionRange::supported()).unwrap();
+        b.send(b"hello").unwrap();
+
+        assert_eq!(b"hello".to_vec(), a.receive().unwrap());
+        assert_eq!(
+            VersionRange::supported(),
+            b.receive_message::<VersionRange>().unwrap()
+        );
+
+        drop(a);
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            match b.receive() {
+                Err(error) => error.kind(),
+                Ok(_) => panic!("the other end was dropped"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_stream_framing() {
+        let mut writer = StreamTransport::new(Cursor::new(vec![]));
+        writer.send(b"first").unwrap();
+        writer.send(b"").unwrap();
+        writer.send_message(&VersionRange::supported()).unwrap();
+
+        let mut reader = StreamTransport::new(Cursor::new(writer.into_inner().into_inner()));
+        assert_eq!(b"first".to_vec(), reader.receive().unwrap());
+        assert_eq!(Vec::<u8>::new(), reader.receive().unwrap());
+        assert_eq!(
+            VersionRange::supported(),
+            reader.receive_message::<VersionRange>().unwrap()
+        );
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            reader.receive().unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn test_rejects_long_and_unexpected_frames() {
+        let mut writer = StreamTransport::new(Cursor::new(vec![]));
+        writer.send(&[0u8; 100]).unwrap();
+        writer.send(b"not an envelope").unwrap();
+
+        let mut reader = StreamTransport::new(Cursor::new(writer.into_inner().into_inner()))
+            .with_max_frame_length(99);
+        assert_eq!(ErrorKind::InvalidData, reader.receive().unwrap_err().kind());
+
+        let (mut a, mut b) = MemoryTransport::pair();
+        a.send(b"not an envelope").unwrap();
+        assert!(matches!(
+            b.receive_message::<VersionRange>(),
+            Err(TransportError::Wire(WireError::Malformed))
+        ));
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Other;
+        impl WireMessage for Other {
+            const TAG: u16 = 0x8000;
+        }
+
+        a.send_message(&Other).unwrap();
+        assert!(matches!(
+            b.receive_message::<VersionRange>(),
+            Err(TransportError::Wire(WireError::UnexpectedMessage { .. }))
+        ));
+    }
+
+    #[cfg(feature = "tcp")]
+    #[test]
+    fn test_tcp_transport() {
+        use crate::transport::TcpTransport;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let mut server = TcpTransport::accept(&listener).unwrap();
+                let frame = server.receive().unwrap();
+                server.send(&frame).unwrap();
+            });
+
+            let mut client = TcpTransport::connect(address).unwrap();
+            client.send(b"echo").unwrap();
+            assert_eq!(b"echo".to_vec(), client.receive().unwrap());
+        });
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_tls_transport() {
+        use crate::transport::TlsTransport;
+        use openssl::asn1::Asn1Time;
+        use openssl::ec::{EcGroup, EcKey};
+        use openssl::hash::MessageDigest;
+        use openssl::nid::Nid;
+        use openssl::pkey::PKey;
+        use openssl::ssl::{SslAcceptor, SslConnector, SslMethod};
+        use openssl::x509::extension::SubjectAlternativeName;
+        use openssl::x509::{X509Builder, X509NameBuilder};
+        use std::net::TcpListener;
+
+        // A self-signed certificate for localhost
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "localhost").unwrap();
+        let name = name.build();
+
+        let mut certificate = X509Builder::new().unwrap();
+        certificate.set_version(2).unwrap();
+        certificate.set_subject_name(&name).unwrap();
+        certificate.set_issuer_name(&name).unwrap();
+        certificate.set_pubkey(&key).unwrap();
+        certificate
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        certificate
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let alternative_name = SubjectAlternativeName::new()
+            .dns("localhost")
+            .build(&certificate.x509v3_context(None, None))
+            .unwrap();
+        certificate.append_extension(alternative_name).unwrap();
+        certificate.sign(&key, MessageDigest::sha256()).unwrap();
+        let certificate = certificate.build();
+
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_private_key(&key).unwrap();
+        acceptor.set_certificate(&certificate).unwrap();
+        let acceptor = acceptor.build();
+
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.cert_store_mut().add_cert(certificate).unwrap();
+        let connector = connector.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let mut server = TlsTransport::accept(&listener, &acceptor).unwrap();
+                let frame = server.receive().unwrap();
+                server.send(&frame).unwrap();
+            });
+
+            let mut client = TlsTransport::connect(address, "localhost", &connector).unwrap();
+            client.send(b"echo").unwrap();
+            assert_eq!(b"echo".to_vec(), client.receive().unwrap());
+        });
+    }
+}
@@ -0,0 +1,217 @@
+//! Wesolowski's verifiable delay function ("Efficient verifiable delay functions") over an RSA
+//! group. Evaluating the function on an input $x$ computes $y = x^{2^T} \bmod N$ using $T$
+//! sequential squarings, which cannot be parallelized. Alongside the output, the evaluator
+//! produces a short proof $\pi = x^{\lfloor 2^T / \ell \rfloor}$ for a prime $\ell$ that is derived
+//! from the statement, which anyone can verify with two small exponentiations by checking that
+//! $\pi^\ell x^{2^T \bmod \ell} = y$.
+//!
+//! Whoever knows the factorization of $N$ can evaluate the function without delay. The party that
+//! runs `Vdf::setup` discards the factors, but it must be trusted to do so. Since $-1$ is an element
+//! of small order whose square root is known, elements are only compared up to their sign, i.e.
+//! the group is $\mathbb{Z}_N^* / \{\pm 1\}$.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_traits::security::BitsOfSecurity;
+//! # use scicrypt_he::vdf::Vdf;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let vdf = Vdf::setup(&BitsOfSecurity::ToyParameters, &mut rng);
+//!
+//! let evaluation = vdf.evaluate(b"block 1234", 10_000);
+//!
+//! assert!(vdf.verify(b"block 1234", 10_000, &evaluation));
+//! ```
+
+use crate::cryptosystems::integer_el_gamal::to_length_prefixed_bytes;
+use crate::hash_to_group::hash_to_qr_group;
+use rug::Integer;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_numbertheory::gen_rsa_modulus;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+const CHALLENGE_DOMAIN: &[u8] = b"scicrypt-he wesolowski vdf";
+
+/// The size of the challenge prime $\ell$ in bits.
+const CHALLENGE_BITS: u32 = 256;
+
+/// The public parameters of the verifiable delay function, which is an RSA modulus of unknown
+/// factorization.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct Vdf {
+    /// RSA modulus $N$
+    pub modulus: UnsignedInteger,
+}
+
+/// The output of the verifiable delay function together with the proof that it was computed
+/// correctly.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct VdfEvaluation {
+    /// Output $y = x^{2^T}$, up to its sign
+    pub output: UnsignedInteger,
+    /// Proof $\pi = x^{\lfloor 2^T / \ell \rfloor}$, up to its sign
+    pub proof: UnsignedInteger,
+}
+
+impl Vdf {
+    /// Generates a fresh RSA modulus of the size that corresponds to `security_param`, and discards
+    /// its factorization.
+    pub fn setup<R: SecureRng>(security_param: &BitsOfSecurity, rng: &mut GeneralRng<R>) -> Self {
+        let (modulus, _, _) = gen_rsa_modulus(security_param.to_public_key_bit_length(), rng);
+
+        Vdf { modulus }
+    }
+
+    /// Evaluates the function on `input` using `squarings` sequential squarings, and proves that
+    /// the output is correct. Computing the proof takes another `squarings` squarings. This function
+    /// is not constant-time, but it only operates on public values.
+    pub fn evaluate(&self, input: &[u8], squarings: u64) -> VdfEvaluation {
+        let x = hash_to_qr_group(input, &self.modulus);
+
+        let mut y = x.clone();
+        for _ in 0..squarings {
+            y = y.square_leaky() % &self.modulus;
+        }
+        let y = self.normalize_leaky(y);
+
+        let challenge = self.challenge(&x, &y, squarings).to_rug();
+
+        // Computes x^floor(2^T / l) by long division of 2^T by l, one bit of the quotient at a time
+        let mut proof = UnsignedInteger::from(1u64);
+        let mut remainder = Integer::from(1);
+        for _ in 0..squarings {
+            proof = proof.square_leaky() % &self.modulus;
+
+            remainder <<= 1;
+            if remainder >= challenge {
+                remainder -= &challenge;
+                proof = proof.mul_leaky(&x) % &self.modulus;
+            }
+        }
+
+        VdfEvaluation {
+            output: y,
+            proof: self.normalize_leaky(proof),
+        }
+    }
+
+    /// Returns true if `evaluation` holds the correct output of the function on `input` with
+    /// `squarings` squarings. This takes two exponentiations with small exponents, regardless of
+    /// the number of squarings. This function is not constant-time, but it only operates on public
+    /// values.
+    pub fn verify(&self, input: &[u8], squarings: u64, evaluation: &VdfEvaluation) -> bool {
+        let in_group = |element: &UnsignedInteger| {
+            !element.is_zero_leaky() && element.leak() < self.modulus.leak()
+        };
+        if !in_group(&evaluation.output) || !in_group(&evaluation.proof) {
+            return false;
+        }
+
+        let x = hash_to_qr_group(input, &self.modulus);
+        let y = self.normalize_leaky(evaluation.output.clone());
+        let challenge = self.challenge(&x, &y, squarings);
+
+        let remainder =
+            UnsignedInteger::from(2u64).pow_mod(&UnsignedInteger::new(squarings, 64), &challenge);
+        let expected = evaluation
+            .proof
+            .pow_mod(&challenge, &self.modulus)
+            .mul_leaky(&x.pow_mod(&remainder, &self.modulus))
+            % &self.modulus;
+
+        self.normalize_leaky(expected).leak() == y.leak()
+    }
+
+    /// Returns the representative $\min(a, N - a)$ of the class $\{a, -a\}$.
+    fn normalize_leaky(&self, element: UnsignedInteger) -> UnsignedInteger {
+        let negated = self.modulus.clone() - &element;
+
+        if negated.leak() < element.leak() {
+            negated
+        } else {
+            element
+        }
+    }
+
+    /// Hashes the statement to a prime $\ell$ of `CHALLENGE_BITS` bits, by trying consecutive
+    /// counters until the hash is prime.
+    fn challenge(
+        &self,
+        x: &UnsignedInteger,
+        y: &UnsignedInteger,
+        squarings: u64,
+    ) -> UnsignedInteger {
+        for counter in 0u64.. {
+            let digest = Sha512::new()
+                .chain_update(CHALLENGE_DOMAIN)
+                .chain_update(to_length_prefixed_bytes(&self.modulus))
+                .chain_update(to_length_prefixed_bytes(x))
+                .chain_update(to_length_prefixed_bytes(y))
+                .chain_update(squarings.to_be_bytes())
+                .chain_update(counter.to_be_bytes())
+                .finalize();
+
+            let mut candidate = UnsignedInteger::from_le_bytes(
+                &digest[..CHALLENGE_BITS as usize / 8],
+                CHALLENGE_BITS,
+            );
+            candidate.set_bit_leaky(CHALLENGE_BITS - 1);
+            candidate.set_bit_leaky(0);
+
+            if candidate.is_probably_prime_with_leaky(
+                BitsOfSecurity::from_public_key_bit_length(CHALLENGE_BITS)
+                    .to_miller_rabin_repetitions(),
+            ) {
+                return candidate;
+            }
+        }
+
+        unreachable!("a prime is found before the counter overflows")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vdf::Vdf;
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::randomness::GeneralRng;
+    use scicrypt_traits::security::BitsOfSecurity;
+
+    #[test]
+    fn test_evaluate_verify() {
+        let mut rng = GeneralRng::new(OsRng);
+        let vdf = Vdf::setup(&BitsOfSecurity::ToyParameters, &mut rng);
+
+        for squarings in [0, 1, 2, 1000] {
+            let evaluation = vdf.evaluate(b"input", squarings);
+
+            assert!(vdf.verify(b"input", squarings, &evaluation));
+            assert!(!vdf.verify(b"other input", squarings, &evaluation));
+            assert!(!vdf.verify(b"input", squarings + 1, &evaluation));
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_output() {
+        let mut rng = GeneralRng::new(OsRng);
+        let vdf = Vdf::setup(&BitsOfSecurity::ToyParameters, &mut rng);
+        let evaluation = vdf.evaluate(b"input", 1000);
+
+        let mut wrong_output = evaluation.clone();
+        wrong_output.output = wrong_output.output.square_leaky() % &vdf.modulus;
+        assert!(!vdf.verify(b"input", 1000, &wrong_output));
+
+        let mut wrong_proof = evaluation.clone();
+        wrong_proof.proof = UnsignedInteger::from(1u64);
+        assert!(!vdf.verify(b"input", 1000, &wrong_proof));
+
+        // The output and the proof are only defined up to their sign
+        let mut negated = evaluation;
+        negated.output = vdf.modulus.clone() - &negated.output;
+        assert!(vdf.verify(b"input", 1000, &negated));
+    }
+}
@@ -0,0 +1,673 @@
+//! Here is an example of how to add two vectors of ciphertexts element-wise.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::cryptosystems::curve_el_gamal::CurveElGamal;
+//! # use scicrypt_he::vector::CiphertextVector;
+//! # use scicrypt_traits::cryptosystems::AsymmetricCryptosystem;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use curve25519_dalek::scalar::Scalar;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = CurveElGamal::setup(&Default::default());
+//! let (public_key, secret_key) = el_gamal.generate_keys(&mut rng);
+//!
+//! let plaintexts = vec![RISTRETTO_BASEPOINT_POINT; 3];
+//! let vector_a = CiphertextVector::encrypt(&plaintexts, &public_key, &mut rng);
+//! let vector_b = CiphertextVector::encrypt(&plaintexts, &public_key, &mut rng);
+//!
+//! let sum = vector_a.add(&vector_b, &public_key);
+//! let expected = RISTRETTO_BASEPOINT_POINT * Scalar::from(2u64);
+//! assert_eq!(sum.decrypt(&secret_key, &public_key), vec![expected; 3]);
+//! ```
+
+use scicrypt_traits::cryptosystems::{DecryptionKey, EncryptionKey};
+use scicrypt_traits::homomorphic::{HomomorphicAddition, HomomorphicMultiplication};
+use scicrypt_traits::randomness::{GeneralRng, SecureRng};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "parallel")]
+use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "parallel")]
+use rand_core::SeedableRng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Types that can take part in batch operations. With the `parallel` feature enabled, batch
+/// operations are spread over multiple threads, so these types must be `Send` and `Sync`.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> MaybeSync for T {}
+
+/// Types that can take part in batch operations. With the `parallel` feature enabled, batch
+/// operations are spread over multiple threads, so these types must be `Send` and `Sync`.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+/// A vector of ciphertexts that all belong to the same public key. Homomorphic operations are
+/// applied element-wise, and plaintext vectors and scalars can be combined with it directly.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct CiphertextVector<C> {
+    /// The ciphertexts in this vector
+    pub ciphertexts: Vec<C>,
+}
+
+impl<C> From<Vec<C>> for CiphertextVector<C> {
+    fn from(ciphertexts: Vec<C>) -> Self {
+        CiphertextVector { ciphertexts }
+    }
+}
+
+impl<C> IntoIterator for CiphertextVector<C> {
+    type Item = C;
+    type IntoIter = std::vec::IntoIter<C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ciphertexts.into_iter()
+    }
+}
+
+impl<C: MaybeSync> CiphertextVector<C> {
+    /// Encrypts every plaintext under the `public_key`. With the `parallel` feature, the
+    /// encryptions happen in parallel using RNGs that are seeded from `rng` in order, so the output
+    /// only depends on `rng`.
+    pub fn encrypt<PK: EncryptionKey<Ciphertext = C> + MaybeSync, R: SecureRng>(
+        plaintexts: &[PK::Plaintext],
+        public_key: &PK,
+        rng: &mut GeneralRng<R>,
+    ) -> Self
+    where
+        PK::Plaintext: MaybeSync,
+    {
+        #[cfg(feature = "trace")]
+        let _span = tracing::debug_span!(
+            "encrypt_batch",
+            cryptosystem = std::any::type_name::<PK>(),
+            count = plaintexts.len()
+        )
+        .entered();
+
+        #[cfg(feature = "parallel")]
+        let ciphertexts = plaintexts
+            .par_iter()
+            .zip(seeds(plaintexts.len(), rng))
+            .map(|(plaintext, seed)| {
+                let mut rng = GeneralRng::new(ChaCha20Rng::from_seed(seed));
+                public_key.encrypt_raw(plaintext, &mut rng)
+            })
+            .collect();
+
+        #[cfg(not(feature = "parallel"))]
+        let ciphertexts = plaintexts
+            .iter()
+            .map(|plaintext| public_key.encrypt_raw(plaintext, rng))
+            .collect();
+
+        CiphertextVector { ciphertexts }
+    }
+
+    /// Rerandomizes every ciphertext, so that they can no longer be linked to the originals. With
+    /// the `parallel` feature, the rerandomizations happen in parallel like in `encrypt`.
+    pub fn randomize<PK: EncryptionKey<Ciphertext = C> + MaybeSync, R: SecureRng>(
+        self,
+        public_key: &PK,
+        rng: &mut GeneralRng<R>,
+    ) -> Self {
+        #[cfg(feature = "trace")]
+        let _span = tracing::debug_span!(
+            "randomize_batch",
+            cryptosystem = std::any::type_name::<PK>(),
+            count = self.len()
+        )
+        .entered();
+
+        #[cfg(feature = "parallel")]
+        let ciphertexts = {
+            let seeds = seeds(self.len(), rng);
+            self.ciphertexts
+                .into_par_iter()
+                .zip(seeds)
+                .map(|(ciphertext, seed)| {
+                    let mut rng = GeneralRng::new(ChaCha20Rng::from_seed(seed));
+                    public_key.randomize(ciphertext, &mut rng)
+                })
+                .collect()
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let ciphertexts = self
+            .ciphertexts
+            .into_iter()
+            .map(|ciphertext| public_key.randomize(ciphertext, rng))
+            .collect();
+
+        CiphertextVector { ciphertexts }
+    }
+
+    /// Decrypts every ciphertext using the `secret_key` and its related `public_key`.
+    pub fn decrypt<
+        PK: EncryptionKey<Ciphertext = C> + MaybeSync,
+        SK: DecryptionKey<PK> + MaybeSync,
+    >(
+        &self,
+        secret_key: &SK,
+        public_key: &PK,
+    ) -> Vec<PK::Plaintext>
+    where
+        PK::Plaintext: MaybeSync,
+    {
+        #[cfg(feature = "trace")]
+        let _span = tracing::debug_span!(
+            "decrypt_batch",
+            cryptosystem = std::any::type_name::<PK>(),
+            count = self.len()
+        )
+        .entered();
+
+        #[cfg(feature = "parallel")]
+        let iter = self.ciphertexts.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = self.ciphertexts.iter();
+
+        iter.map(|ciphertext| secret_key.decrypt_raw(public_key, ciphertext))
+            .collect()
+    }
+
+    /// The number of ciphertexts in this vector.
+    pub fn len(&self) -> usize {
+        self.ciphertexts.len()
+    }
+
+    /// Returns true if this vector contains no ciphertexts.
+    pub fn is_empty(&self) -> bool {
+        self.ciphertexts.is_empty()
+    }
+
+    /// Returns an iterator over the ciphertexts.
+    pub fn iter(&self) -> std::slice::Iter<'_, C> {
+        self.ciphertexts.iter()
+    }
+
+    fn zip_with<T: MaybeSync, F: Fn(&C, &T) -> C + MaybeSync>(
+        &self,
+        other: &[T],
+        operation: F,
+    ) -> Self {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "the vectors must have the same length"
+        );
+
+        #[cfg(feature = "parallel")]
+        let iter = self.ciphertexts.par_iter().zip(other);
+        #[cfg(not(feature = "parallel"))]
+        let iter = self.ciphertexts.iter().zip(other);
+
+        CiphertextVector {
+            ciphertexts: iter.map(|(a, b)| operation(a, b)).collect(),
+        }
+    }
+
+    fn map<F: Fn(&C) -> C + MaybeSync>(&self, operation: F) -> Self {
+        #[cfg(feature = "parallel")]
+        let iter = self.ciphertexts.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = self.ciphertexts.iter();
+
+        CiphertextVector {
+            ciphertexts: iter.map(operation).collect(),
+        }
+    }
+}
+
+/// Draws one seed per element from `rng`, in order, so that parallel batch operations are deterministic given `rng`.
+#[cfg(feature = "parallel")]
+fn seeds<R: SecureRng>(count: usize, rng: &mut GeneralRng<R>) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|_| {
+            let mut seed = [0u8; 32];
+            rng.rng().fill_bytes(&mut seed);
+            seed
+        })
+        .collect()
+}
+
+impl<C: MaybeSync> CiphertextVector<C> {
+    /// Adds `other` element-wise, so that each decrypted value reflects the addition of the plaintexts.
+    pub fn add<PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+        &self,
+        other: &Self,
+        public_key: &PK,
+    ) -> Self {
+        self.zip_with(&other.ciphertexts, |a, b| public_key.add(a, b))
+    }
+
+    /// Subtracts `other` element-wise, so that each decrypted value reflects the subtraction of the plaintexts.
+    pub fn sub<PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+        &self,
+        other: &Self,
+        public_key: &PK,
+    ) -> Self {
+        self.zip_with(&other.ciphertexts, |a, b| public_key.sub(a, b))
+    }
+
+    /// Adds a vector of plaintext `constants` element-wise.
+    pub fn add_constants<PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+        &self,
+        constants: &[PK::Plaintext],
+        public_key: &PK,
+    ) -> Self
+    where
+        PK::Plaintext: MaybeSync,
+    {
+        self.zip_with(constants, |a, b| public_key.add_constant(a, b))
+    }
+
+    /// Multiplies each ciphertext with the corresponding element of `inputs`.
+    pub fn mul_constants<PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+        &self,
+        inputs: &[PK::Input],
+        public_key: &PK,
+    ) -> Self
+    where
+        PK::Input: MaybeSync,
+    {
+        self.zip_with(inputs, |a, b| public_key.mul_constant(a, b))
+    }
+
+    /// Multiplies every ciphertext with the same scalar `input`.
+    pub fn mul_constant_broadcast<PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+        &self,
+        input: &PK::Input,
+        public_key: &PK,
+    ) -> Self
+    where
+        PK::Input: MaybeSync,
+    {
+        self.map(|a| public_key.mul_constant(a, input))
+    }
+
+    /// Computes the inner product between this vector and a vector of plaintext `inputs`, which
+    /// results in a single ciphertext. Returns `None` if the vectors are empty.
+    pub fn inner_product<PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+        &self,
+        inputs: &[PK::Input],
+        public_key: &PK,
+    ) -> Option<C> {
+        public_key.linear_combination(&self.ciphertexts, inputs)
+    }
+}
+
+/// Computes the dot product between a plaintext vector of `inputs` and an encrypted `vector`, which
+/// results in a single ciphertext. This uses the cryptosystem's multi-scalar multiplication when
+/// available. Returns `None` if the vectors are empty.
+pub fn dot<C: MaybeSync, PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+    inputs: &[PK::Input],
+    vector: &CiphertextVector<C>,
+    public_key: &PK,
+) -> Option<C> {
+    vector.inner_product(inputs, public_key)
+}
+
+/// Multiplies a plaintext `matrix` (given as a list of rows) with an encrypted `vector`, which
+/// results in an encrypted vector with one element per row. Each row is combined using the
+/// cryptosystem's multi-scalar multiplication when available.
+pub fn matvec<C: MaybeSync, PK: HomomorphicAddition<Ciphertext = C> + MaybeSync>(
+    matrix: &[Vec<PK::Input>],
+    vector: &CiphertextVector<C>,
+    public_key: &PK,
+) -> CiphertextVector<C>
+where
+    PK::Input: MaybeSync,
+{
+    assert!(!vector.is_empty(), "the vector must not be empty");
+
+    #[cfg(feature = "parallel")]
+    let rows = matrix.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let rows = matrix.iter();
+
+    CiphertextVector {
+        ciphertexts: rows
+            .map(|row| dot(row, vector, public_key).unwrap())
+            .collect(),
+    }
+}
+
+impl<C: MaybeSync> CiphertextVector<C> {
+    /// Multiplies `other` element-wise, so that each decrypted value reflects the product of the plaintexts.
+    pub fn mul<PK: HomomorphicMultiplication<Ciphertext = C> + MaybeSync>(
+        &self,
+        other: &Self,
+        public_key: &PK,
+    ) -> Self {
+        self.zip_with(&other.ciphertexts, |a, b| public_key.mul(a, b))
+    }
+
+    /// Exponentiates each ciphertext with the corresponding element of `inputs`.
+    pub fn pow_elementwise<PK: HomomorphicMultiplication<Ciphertext = C> + MaybeSync>(
+        &self,
+        inputs: &[PK::Input],
+        public_key: &PK,
+    ) -> Self
+    where
+        PK::Input: MaybeSync,
+    {
+        self.zip_with(inputs, |a, b| public_key.pow(a, b))
+    }
+
+    /// Exponentiates every ciphertext with the same scalar `input`.
+    pub fn pow_broadcast<PK: HomomorphicMultiplication<Ciphertext = C> + MaybeSync>(
+        &self,
+        input: &PK::Input,
+        public_key: &PK,
+    ) -> Self
+    where
+        PK::Input: MaybeSync,
+    {
+        self.map(|a| public_key.pow(a, input))
+    }
+}
+
+/// The changes between two versions of a ciphertext vector of the same length, which consist of
+/// the indices that changed and fresh ciphertexts for them. Clients that resend similar vectors
+/// every round only need to send the delta, which the server applies to its copy of the previous
+/// vector.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct CiphertextDelta<C> {
+    /// The length of the vector that this delta applies to
+    pub len: usize,
+    /// The changed indices in increasing order, together with their new ciphertexts
+    pub changes: Vec<(usize, C)>,
+}
+
+/// Error returned when a delta does not fit the vector that it is applied to, or when its indices
+/// are out of range or not strictly increasing.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidDelta;
+
+impl<C> CiphertextDelta<C> {
+    /// Encrypts the elements of `plaintexts` that differ from `previous` under the `public_key`.
+    /// Unchanged elements are left out, so they keep their ciphertexts from the previous round.
+    pub fn encrypt<PK: EncryptionKey<Ciphertext = C>, R: SecureRng>(
+        previous: &[PK::Plaintext],
+        plaintexts: &[PK::Plaintext],
+        public_key: &PK,
+        rng: &mut GeneralRng<R>,
+    ) -> Self
+    where
+        PK::Plaintext: PartialEq,
+    {
+        assert_eq!(
+            previous.len(),
+            plaintexts.len(),
+            "the vectors must have the same length"
+        );
+
+        CiphertextDelta {
+            len: plaintexts.len(),
+            changes: previous
+                .iter()
+                .zip(plaintexts)
+                .enumerate()
+                .filter(|(_, (old, new))| old != new)
+                .map(|(i, (_, new))| (i, public_key.encrypt_raw(new, rng)))
+                .collect(),
+        }
+    }
+
+    /// Returns true if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl<C: Clone + PartialEq + MaybeSync> CiphertextVector<C> {
+    /// Computes the delta from `previous` to this vector. The ciphertexts that changed are
+    /// rerandomized, so the delta cannot be linked to this vector.
+    pub fn diff<PK: EncryptionKey<Ciphertext = C>, R: SecureRng>(
+        &self,
+        previous: &Self,
+        public_key: &PK,
+        rng: &mut GeneralRng<R>,
+    ) -> CiphertextDelta<C> {
+        assert_eq!(
+            self.len(),
+            previous.len(),
+            "the vectors must have the same length"
+        );
+
+        CiphertextDelta {
+            len: self.len(),
+            changes: self
+                .ciphertexts
+                .iter()
+                .zip(&previous.ciphertexts)
+                .enumerate()
+                .filter(|(_, (new, old))| new != old)
+                .map(|(i, (new, _))| (i, public_key.randomize(new.clone(), rng)))
+                .collect(),
+        }
+    }
+
+    /// Replaces the ciphertexts that changed according to `delta`. Returns an error and leaves this
+    /// vector unchanged if the delta is for a vector of a different length, or if its indices are
+    /// out of range or not strictly increasing.
+    pub fn apply_delta(&mut self, delta: &CiphertextDelta<C>) -> Result<(), InvalidDelta> {
+        let increasing = delta
+            .changes
+            .windows(2)
+            .all(|window| window[0].0 < window[1].0);
+        let in_range = delta.changes.last().is_none_or(|(i, _)| *i < self.len());
+
+        if delta.len != self.len() || !increasing || !in_range {
+            return Err(InvalidDelta);
+        }
+
+        for (i, ciphertext) in &delta.changes {
+            self.ciphertexts[*i] = ciphertext.clone();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "curve"))]
+mod tests {
+    use crate::cryptosystems::curve_el_gamal::CurveElGamal;
+    use crate::vector::{matvec, CiphertextDelta, CiphertextVector, InvalidDelta};
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use curve25519_dalek::scalar::Scalar;
+    use rand_core::OsRng;
+    use scicrypt_traits::cryptosystems::{Associable, AsymmetricCryptosystem, DecryptionKey};
+    use scicrypt_traits::homomorphic::HomomorphicAddition;
+    use scicrypt_traits::randomness::{GeneralRng, InsecureTestRng};
+
+    #[test]
+    fn test_elementwise_add_and_broadcast() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let plaintexts: Vec<_> = (1..4u64)
+            .map(|i| RISTRETTO_BASEPOINT_POINT * Scalar::from(i))
+            .collect();
+        let vector = CiphertextVector::encrypt(&plaintexts, &pk, &mut rng);
+
+        let doubled = vector.add(&vector, &pk);
+        let tripled = vector.mul_constant_broadcast(&Scalar::from(3u64), &pk);
+
+        for (i, plaintext) in plaintexts.iter().enumerate() {
+            assert_eq!(plaintext * Scalar::from(2u64), doubled.decrypt(&sk, &pk)[i]);
+            assert_eq!(plaintext * Scalar::from(3u64), tripled.decrypt(&sk, &pk)[i]);
+        }
+    }
+
+    #[test]
+    fn test_inner_product() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let plaintexts = vec![RISTRETTO_BASEPOINT_POINT; 3];
+        let vector = CiphertextVector::encrypt(&plaintexts, &pk, &mut rng);
+        let inputs = [Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64)];
+
+        let inner_product = vector.inner_product(&inputs, &pk).unwrap();
+
+        assert_eq!(
+            RISTRETTO_BASEPOINT_POINT * Scalar::from(6u64),
+            sk.decrypt(&inner_product.associate(&pk))
+        );
+    }
+
+    #[test]
+    fn test_serialize_roundtrip() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+
+        let vector = CiphertextVector::encrypt(&[RISTRETTO_BASEPOINT_POINT; 2], &pk, &mut rng);
+        let serialized = bincode::serialize(&vector).unwrap();
+
+        assert_eq!(vector, bincode::deserialize(&serialized).unwrap());
+    }
+
+    #[test]
+    fn test_matvec() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let plaintexts: Vec<_> = (1..3u64)
+            .map(|i| RISTRETTO_BASEPOINT_POINT * Scalar::from(i))
+            .collect();
+        let vector = CiphertextVector::encrypt(&plaintexts, &pk, &mut rng);
+        let matrix = vec![
+            vec![Scalar::from(1u64), Scalar::from(0u64)],
+            vec![Scalar::from(2u64), Scalar::from(3u64)],
+            vec![Scalar::from(0u64), Scalar::from(4u64)],
+        ];
+
+        let result = matvec(&matrix, &vector, &pk).decrypt(&sk, &pk);
+
+        let expected: Vec<_> = [1u64, 8, 8]
+            .iter()
+            .map(|i| RISTRETTO_BASEPOINT_POINT * Scalar::from(*i))
+            .collect();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_batch_encrypt_deterministic_and_randomize() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let plaintexts = vec![RISTRETTO_BASEPOINT_POINT; 8];
+        let vector_a = CiphertextVector::encrypt(
+            &plaintexts,
+            &pk,
+            &mut GeneralRng::new(InsecureTestRng::from_seed(1)),
+        );
+        let vector_b = CiphertextVector::encrypt(
+            &plaintexts,
+            &pk,
+            &mut GeneralRng::new(InsecureTestRng::from_seed(1)),
+        );
+        assert_eq!(vector_a, vector_b);
+
+        let randomized = vector_a.clone().randomize(&pk, &mut rng);
+        assert_ne!(vector_a, randomized);
+        assert_eq!(plaintexts, randomized.decrypt(&sk, &pk));
+    }
+
+    #[test]
+    fn test_delta_from_plaintexts() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let previous: Vec<_> = (1..5u64)
+            .map(|i| RISTRETTO_BASEPOINT_POINT * Scalar::from(i))
+            .collect();
+        let mut plaintexts = previous.clone();
+        plaintexts[1] = RISTRETTO_BASEPOINT_POINT * Scalar::from(10u64);
+        plaintexts[3] = RISTRETTO_BASEPOINT_POINT * Scalar::from(20u64);
+
+        let mut vector = CiphertextVector::encrypt(&previous, &pk, &mut rng);
+        let delta = CiphertextDelta::encrypt(&previous, &plaintexts, &pk, &mut rng);
+        assert_eq!(
+            vec![1, 3],
+            delta.changes.iter().map(|(i, _)| *i).collect::<Vec<_>>()
+        );
+
+        let serialized = bincode::serialize(&delta).unwrap();
+        vector
+            .apply_delta(&bincode::deserialize(&serialized).unwrap())
+            .unwrap();
+
+        assert_eq!(plaintexts, vector.decrypt(&sk, &pk));
+    }
+
+    #[test]
+    fn test_diff_and_apply_delta() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, sk) = el_gamal.generate_keys(&mut rng);
+
+        let previous = CiphertextVector::encrypt(&[RISTRETTO_BASEPOINT_POINT; 3], &pk, &mut rng);
+        let mut current = previous.clone();
+        current.ciphertexts[2] = pk.add(&previous.ciphertexts[2], &previous.ciphertexts[0]);
+
+        let delta = current.diff(&previous, &pk, &mut rng);
+        assert_eq!(1, delta.changes.len());
+        assert_ne!(current.ciphertexts[2], delta.changes[0].1);
+
+        let mut patched = previous.clone();
+        patched.apply_delta(&delta).unwrap();
+        assert_eq!(current.decrypt(&sk, &pk), patched.decrypt(&sk, &pk));
+
+        assert!(previous.diff(&previous, &pk, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_apply_invalid_delta() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        let el_gamal = CurveElGamal::setup(&Default::default());
+        let (pk, _) = el_gamal.generate_keys(&mut rng);
+
+        let mut vector = CiphertextVector::encrypt(&[RISTRETTO_BASEPOINT_POINT; 2], &pk, &mut rng);
+        let ciphertext = vector.ciphertexts[0].clone();
+        let original = vector.clone();
+
+        for delta in [
+            CiphertextDelta {
+                len: 3,
+                changes: vec![],
+            },
+            CiphertextDelta {
+                len: 2,
+                changes: vec![(2, ciphertext.clone())],
+            },
+            CiphertextDelta {
+                len: 2,
+                changes: vec![(1, ciphertext.clone()), (0, ciphertext.clone())],
+            },
+        ] {
+            assert_eq!(Err(InvalidDelta), vector.apply_delta(&delta));
+        }
+        assert_eq!(original, vector);
+    }
+}
@@ -0,0 +1,450 @@
+//! A tagged and versioned envelope for the messages of the multi-party protocols, such as the
+//! shares and commitments of distributed key generation, decryption shares and proofs. The crate
+//! does not send messages itself, so applications can use any transport, and the envelope tells the
+//! receiver which message it holds and which version of the format produced it.
+//!
+//! An envelope is encoded as the magic bytes `SCIW`, the little-endian `u16` version of the format,
+//! the little-endian `u16` tag of the message, and a sequence of fields. Each field consists of a
+//! little-endian `u16` field number, a little-endian `u32` length and that many bytes. Field 0
+//! holds the `bincode` encoding of the message and must be present. Other fields are extensions:
+//! receivers skip the fields they do not understand but keep them, so that newer senders can attach
+//! additional data without breaking older receivers, and relays forward them unchanged. Changes
+//! that a receiver must not ignore require a new version instead.
+//!
+//! Before exchanging messages, parties can send each other their `VersionRange` and agree on the
+//! highest version that both support.
+//! ```
+//! # use scicrypt_traits::randomness::GeneralRng;
+//! # use scicrypt_he::threshold_cryptosystems::curve_el_gamal::{TOfNCurveElGamal, TOfNCurveElGamalShare};
+//! # use scicrypt_he::wire::{Envelope, VersionRange};
+//! # use scicrypt_traits::threshold_cryptosystems::{PartialDecryptionKey, TOfNCryptosystem};
+//! # use scicrypt_traits::cryptosystems::EncryptionKey;
+//! # use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+//! # use rand_core::OsRng;
+//! let mut rng = GeneralRng::new(OsRng);
+//! let el_gamal = TOfNCurveElGamal::setup(&Default::default());
+//! let (public_key, secret_keys) = el_gamal.generate_keys(2, 3, &mut rng);
+//! let ciphertext = public_key.encrypt(&RISTRETTO_BASEPOINT_POINT, &mut rng);
+//!
+//! // Both parties agree on a version before sending decryption shares
+//! let version = VersionRange::supported()
+//!     .negotiate(&VersionRange::supported())
+//!     .unwrap();
+//!
+//! let share = secret_keys[0].partial_decrypt(&ciphertext);
+//! let bytes = Envelope::new(&share).with_version(version).unwrap().to_bytes();
+//!
+//! // The receiver checks the tag before decoding the share
+//! let envelope = Envelope::from_bytes(&bytes).unwrap();
+//! assert_eq!(share, envelope.open::<TOfNCurveElGamalShare>().unwrap());
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+
+const MAGIC: &[u8; 4] = b"SCIW";
+/// The field that holds the encoded message.
+const PAYLOAD_FIELD: u16 = 0;
+
+/// The newest version of the envelope format, which is used for new envelopes.
+pub const VERSION: u16 = 1;
+/// The oldest version of the envelope format that can still be decoded.
+pub const MIN_VERSION: u16 = 1;
+
+/// Error returned when an envelope cannot be decoded or opened.
+#[derive(PartialEq, Eq, Debug)]
+pub enum WireError {
+    /// The envelope or its message is not encoded correctly
+    Malformed,
+    /// The envelope uses a version of the format that is not supported
+    UnsupportedVersion(u16),
+    /// The parties do not support any common version of the format
+    NoCommonVersion,
+    /// The envelope holds a different type of message than was expected
+    UnexpectedMessage {
+        /// The tag of the expected message
+        expected: u16,
+        /// The tag of the message in the envelope
+        found: u16,
+    },
+}
+
+/// A message of one of the protocols that can be sent in an `Envelope`.
+///
+/// The tags of this crate are grouped by purpose: 0x00xx for the envelope format itself, 0x01xx for
+/// secret sharing and key generation, 0x02xx for decryption shares, 0x03xx for proofs and 0x04xx
+/// for the messages of the two-party protocols. Tags from 0x8000 onwards are free for
+/// application-defined messages.
+pub trait WireMessage: Serialize + DeserializeOwned {
+    /// The tag that identifies this type of message in an envelope
+    const TAG: u16;
+}
+
+/// The versions of the envelope format that a party supports.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct VersionRange {
+    /// The oldest supported version
+    pub min: u16,
+    /// The newest supported version
+    pub max: u16,
+}
+
+impl VersionRange {
+    /// Returns the versions that this implementation supports.
+    pub fn supported() -> Self {
+        VersionRange {
+            min: MIN_VERSION,
+            max: VERSION,
+        }
+    }
+
+    /// Returns the newest version that both `self` and `other` support.
+    pub fn negotiate(&self, other: &VersionRange) -> Result<u16, WireError> {
+        let version = self.max.min(other.max);
+
+        if version < self.min.max(other.min) {
+            return Err(WireError::NoCommonVersion);
+        }
+
+        Ok(version)
+    }
+
+    fn contains(&self, version: u16) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+}
+
+impl WireMessage for VersionRange {
+    const TAG: u16 = 0x0001;
+}
+
+/// A tagged and versioned message, together with any extension fields.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Envelope {
+    version: u16,
+    tag: u16,
+    payload: Vec<u8>,
+    extensions: Vec<(u16, Vec<u8>)>,
+}
+
+impl Envelope {
+    /// Wraps `message` in an envelope of the newest version.
+    pub fn new<M: WireMessage>(message: &M) -> Self {
+        Envelope {
+            version: VERSION,
+            tag: M::TAG,
+            payload: bincode::serialize(message).expect("messages can always be serialized"),
+            extensions: vec![],
+        }
+    }
+
+    /// Changes the version of the envelope to `version`, typically the result of
+    /// `VersionRange::negotiate`. Returns an error if the version is not supported.
+    pub fn with_version(mut self, version: u16) -> Result<Self, WireError> {
+        if !VersionRange::supported().contains(version) {
+            return Err(WireError::UnsupportedVersion(version));
+        }
+
+        self.version = version;
+        Ok(self)
+    }
+
+    /// Attaches the extension `value` under `field`, replacing any previous value. Panics if
+    /// `field` is 0, which is reserved for the message.
+    pub fn with_extension(mut self, field: u16, value: Vec<u8>) -> Self {
+        assert_ne!(field, PAYLOAD_FIELD, "field 0 is reserved for the message");
+
+        match self.extensions.iter_mut().find(|(f, _)| *f == field) {
+            Some(extension) => extension.1 = value,
+            None => self.extensions.push((field, value)),
+        }
+
+        self
+    }
+
+    /// The version of the format in which the envelope was encoded.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// The tag of the message in the envelope.
+    pub fn tag(&self) -> u16 {
+        self.tag
+    }
+
+    /// Returns the value of the extension `field`, if present.
+    pub fn extension(&self, field: u16) -> Option<&[u8]> {
+        self.extensions
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// Decodes the message, or returns an error if the envelope holds a different type of message.
+    pub fn open<M: WireMessage>(&self) -> Result<M, WireError> {
+        if self.tag != M::TAG {
+            return Err(WireError::UnexpectedMessage {
+                expected: M::TAG,
+                found: self.tag,
+            });
+        }
+
+        bincode::deserialize(&self.payload).map_err(|_| WireError::Malformed)
+    }
+
+    /// Encodes the envelope, including the extensions in the order in which they were added.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&self.tag.to_le_bytes());
+
+        write_field(&mut bytes, PAYLOAD_FIELD, &self.payload);
+        for (field, value) in &self.extensions {
+            write_field(&mut bytes, *field, value);
+        }
+
+        bytes
+    }
+
+    /// Decodes an envelope. The message itself is only decoded by `open`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(WireError::Malformed);
+        }
+
+        let version = reader.read_u16()?;
+        if !VersionRange::supported().contains(version) {
+            return Err(WireError::UnsupportedVersion(version));
+        }
+        let tag = reader.read_u16()?;
+
+        let mut payload = None;
+        let mut extensions: Vec<(u16, Vec<u8>)> = vec![];
+        while !reader.bytes.is_empty() {
+            let field = reader.read_u16()?;
+            let length = u32::from_le_bytes(reader.take(4)?.try_into().unwrap()) as usize;
+            let value = reader.take(length)?.to_vec();
+
+            if field == PAYLOAD_FIELD {
+                if payload.replace(value).is_some() {
+                    return Err(WireError::Malformed);
+                }
+            } else if extensions.iter().any(|(f, _)| *f == field) {
+                return Err(WireError::Malformed);
+            } else {
+                extensions.push((field, value));
+            }
+        }
+
+        Ok(Envelope {
+            version,
+            tag,
+            payload: payload.ok_or(WireError::Malformed)?,
+            extensions,
+        })
+    }
+}
+
+fn write_field(bytes: &mut Vec<u8>, field: u16, value: &[u8]) {
+    let length = u32::try_from(value.len()).expect("fields cannot exceed 4 GiB");
+
+    bytes.extend_from_slice(&field.to_le_bytes());
+    bytes.extend_from_slice(&length.to_le_bytes());
+    bytes.extend_from_slice(value);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], WireError> {
+        if self.bytes.len() < length {
+            return Err(WireError::Malformed);
+        }
+
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, WireError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+}
+
+macro_rules! wire_messages {
+    ($feature:meta, $($message:path = $tag:expr),* $(,)?) => {
+        $(
+            #[$feature]
+            impl WireMessage for $message {
+                const TAG: u16 = $tag;
+            }
+        )*
+    };
+}
+
+wire_messages!(
+    cfg(feature = "integer"),
+    crate::secret_sharing::Share = 0x0101,
+);
+
+wire_messages!(
+    cfg(feature = "curve"),
+    crate::secret_sharing::ScalarShare = 0x0102,
+    crate::secret_sharing::FeldmanCommitment = 0x0103,
+    crate::secret_sharing::PedersenShare = 0x0104,
+    crate::secret_sharing::PedersenVssCommitment = 0x0105,
+    crate::threshold_cryptosystems::refresh::RefreshCommitment = 0x0106,
+    crate::threshold_cryptosystems::refresh::RefreshSubShare = 0x0107,
+    crate::threshold_cryptosystems::curve_el_gamal::NOfNCurveElGamalShare = 0x0201,
+    crate::threshold_cryptosystems::curve_el_gamal::TOfNCurveElGamalShare = 0x0202,
+    crate::threshold_cryptosystems::curve_el_gamal::DecryptionShareProof = 0x0301,
+    crate::proofs::range::RangeProof = 0x0302,
+    crate::proofs::range::BitProof = 0x0303,
+    crate::proofs::equality::PlaintextEqualityProof = 0x0304,
+);
+
+wire_messages!(
+    cfg(feature = "integer"),
+    crate::threshold_cryptosystems::integer_el_gamal::NOfNIntegerElGamalShare = 0x0203,
+    crate::threshold_cryptosystems::integer_el_gamal::TOfNIntegerElGamalShare = 0x0204,
+    crate::threshold_cryptosystems::paillier::ThresholdPaillierShare = 0x0205,
+);
+
+wire_messages!(
+    cfg(all(feature = "curve", feature = "integer")),
+    crate::protocols::verifiable_encryption::DiscreteLogEncryptionProof = 0x0305,
+);
+
+wire_messages!(
+    cfg(feature = "curve"),
+    crate::protocols::oprf::BlindedElement = 0x0405,
+    crate::protocols::oprf::EvaluatedElement = 0x0406,
+);
+
+wire_messages!(
+    cfg(feature = "integer"),
+    crate::protocols::comparison::BlindedDifference = 0x0407,
+    crate::protocols::comparison::EncryptedBits = 0x0408,
+    crate::protocols::comparison::MaskedBits = 0x0409,
+    crate::protocols::comparison::ZeroTest = 0x040a,
+    crate::protocols::triples::TripleRequest = 0x040b,
+    crate::protocols::triples::TripleResponse = 0x040c,
+);
+
+/// The messages of the generic protocols have the same tag for every cryptosystem, which the
+/// parties agree on beforehand.
+macro_rules! generic_wire_messages {
+    ($feature:meta, $($message:ident = $tag:expr),* $(,)?) => {
+        $(
+            #[$feature]
+            impl<T: Serialize + DeserializeOwned> WireMessage for $message<T> {
+                const TAG: u16 = $tag;
+            }
+        )*
+    };
+}
+
+#[cfg(any(feature = "curve", feature = "integer"))]
+use crate::protocols::blind_decryption::{BlindedCiphertext, BlindedPlaintext};
+#[cfg(any(feature = "curve", feature = "integer"))]
+use crate::protocols::equality::{BlindedDifferences, ZeroTests};
+
+generic_wire_messages!(
+    cfg(any(feature = "curve", feature = "integer")),
+    BlindedCiphertext = 0x0401,
+    BlindedPlaintext = 0x0402,
+    BlindedDifferences = 0x0403,
+    ZeroTests = 0x0404,
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::wire::{Envelope, VersionRange, WireError, WireMessage, VERSION};
+
+    #[test]
+    fn test_roundtrip_with_extensions() {
+        let range = VersionRange { min: 1, max: 3 };
+        let envelope = Envelope::new(&range)
+            .with_extension(7, vec![1, 2, 3])
+            .with_extension(9, vec![]);
+
+        let decoded = Envelope::from_bytes(&envelope.to_bytes()).unwrap();
+
+        assert_eq!(envelope, decoded);
+        assert_eq!(VERSION, decoded.version());
+        assert_eq!(VersionRange::TAG, decoded.tag());
+        assert_eq!(Some(&[1u8, 2, 3][..]), decoded.extension(7));
+        assert_eq!(None, decoded.extension(8));
+        assert_eq!(Ok(range), decoded.open());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_kept() {
+        let mut bytes = Envelope::new(&VersionRange::supported()).to_bytes();
+        // An extension that a newer sender could have added
+        bytes.extend_from_slice(&[0x34, 0x12, 2, 0, 0, 0, 0xab, 0xcd]);
+
+        let envelope = Envelope::from_bytes(&bytes).unwrap();
+
+        assert_eq!(Some(&[0xab, 0xcd][..]), envelope.extension(0x1234));
+        assert_eq!(Ok(VersionRange::supported()), envelope.open());
+        assert_eq!(bytes, envelope.to_bytes());
+    }
+
+    #[test]
+    fn test_rejects_invalid_envelopes() {
+        let bytes = Envelope::new(&VersionRange::supported()).to_bytes();
+
+        assert_eq!(
+            Err(WireError::Malformed),
+            Envelope::from_bytes(&bytes[..bytes.len() - 1])
+        );
+        assert_eq!(Err(WireError::Malformed), Envelope::from_bytes(&bytes[..8]));
+
+        let mut newer = bytes.clone();
+        newer[4..6].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert_eq!(
+            Err(WireError::UnsupportedVersion(VERSION + 1)),
+            Envelope::from_bytes(&newer)
+        );
+
+        let mut duplicate = bytes.clone();
+        duplicate.extend_from_slice(&bytes[8..]);
+        assert_eq!(Err(WireError::Malformed), Envelope::from_bytes(&duplicate));
+    }
+
+    #[cfg(feature = "curve")]
+    #[test]
+    fn test_open_checks_tag() {
+        use crate::secret_sharing::FeldmanCommitment;
+
+        let envelope = Envelope::new(&VersionRange::supported());
+
+        assert_eq!(
+            Err(WireError::UnexpectedMessage {
+                expected: FeldmanCommitment::TAG,
+                found: VersionRange::TAG,
+            }),
+            envelope.open::<FeldmanCommitment>()
+        );
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let ours = VersionRange { min: 1, max: 3 };
+
+        assert_eq!(Ok(3), ours.negotiate(&VersionRange { min: 2, max: 5 }));
+        assert_eq!(Ok(2), ours.negotiate(&VersionRange { min: 2, max: 2 }));
+        assert_eq!(Ok(1), ours.negotiate(&VersionRange { min: 0, max: 1 }));
+        assert_eq!(
+            Err(WireError::NoCommonVersion),
+            ours.negotiate(&VersionRange { min: 4, max: 5 })
+        );
+    }
+}
@@ -6,97 +6,138 @@
 //! Number theoretic functions, particularly suited for cryptography. Functions include extremely
 //! fast (safe) prime generation.
 
+mod pool;
 mod primes;
+mod sieve;
 
 use crate::primes::FIRST_PRIMES;
+use rug::Integer;
 use scicrypt_bigint::UnsignedInteger;
 use scicrypt_traits::randomness::GeneralRng;
 use scicrypt_traits::randomness::SecureRng;
+use scicrypt_traits::security::BitsOfSecurity;
+
+pub use pool::PrimePool;
+pub use sieve::{PrimeCandidateIterator, Sieve, SieveStep};
+
+/// The number of Miller-Rabin rounds for a prime of `bit_length` bits, as one of the two prime
+/// factors of a public key modulus.
+fn miller_rabin_repetitions(bit_length: u32) -> u32 {
+    BitsOfSecurity::Custom {
+        pk_bits: 2 * bit_length,
+    }
+    .to_miller_rabin_repetitions()
+}
 
 /// Generates a uniformly random prime number of a given bit length. So, the number contains
-/// `bit_length` bits, of which the first and the last bit are always 1.
+/// `bit_length` bits, of which the first and the last bit are always 1. The number of
+/// Miller-Rabin rounds is chosen from the security level that this prime corresponds to (see
+/// [`BitsOfSecurity::to_miller_rabin_repetitions`]).
 pub fn gen_prime<R: SecureRng>(bit_length: u32, rng: &mut GeneralRng<R>) -> UnsignedInteger {
+    let sieve = Sieve::new(bit_length, SieveStep::Prime);
+    let repetitions = miller_rabin_repetitions(bit_length);
+
+    loop {
+        let candidate = match sieve.sieve(rng) {
+            Some(candidate) => candidate,
+            None => continue,
+        };
+
+        // Ensure that we have a prime with a stronger primality test
+        if candidate.is_probably_prime_with_leaky(repetitions) {
+            return candidate;
+        }
+    }
+}
+
+/// Generates a uniformly random prime number $p$ of a given bit length such that
+/// $p \equiv \text{residue} \pmod{\text{modulus}}$. This is useful for constructing Schnorr groups
+/// ($p \equiv 1 \pmod q$) or Blum integers ($p \equiv 3 \pmod 4$). The `residue` must be coprime
+/// to the `modulus` and the `modulus` must be smaller than `bit_length` bits. The congruence is
+/// computed in a way that is not constant-time.
+pub fn gen_prime_congruent<R: SecureRng>(
+    bit_length: u32,
+    residue: &UnsignedInteger,
+    modulus: &UnsignedInteger,
+    rng: &mut GeneralRng<R>,
+) -> UnsignedInteger {
+    let modulus_rug = modulus.clone().to_rug();
+    let residue_rug = residue.clone().to_rug() % &modulus_rug;
+    assert!(
+        modulus_rug.significant_bits() < bit_length,
+        "the modulus must be smaller than the prime"
+    );
+    assert_eq!(
+        Integer::from(residue_rug.gcd_ref(&modulus_rug)),
+        1,
+        "the residue must be coprime to the modulus"
+    );
+
+    let prime_count = Sieve::new(bit_length, SieveStep::Prime).prime_count();
+    let repetitions = miller_rabin_repetitions(bit_length);
+    let modulus_mods = modulus.mod_many_u_leaky(&FIRST_PRIMES[..prime_count]);
+
     'outer: loop {
         let mut candidate = UnsignedInteger::random(bit_length, rng);
         candidate.set_bit_leaky(bit_length - 1);
-        candidate.set_bit_leaky(0);
-
-        // A heuristic that closely follows OpenSSL (https://github.com/openssl/openssl/blob/4cedf30e995f9789cf6bb103e248d33285a84067/crypto/bn/bn_prime.c)
-        let prime_count: usize = bit_length as usize / 3;
-        let mods: Vec<u64> = FIRST_PRIMES[..prime_count]
-            .iter()
-            .map(|p| candidate.mod_u_leaky(*p))
-            .collect();
-
-        let mut delta = 0;
-        let max_delta = u64::MAX - FIRST_PRIMES.last().unwrap();
-        candidate += &'sieve: loop {
-            for i in 1..prime_count {
-                if (mods[i] + delta) % FIRST_PRIMES[i] == 0 {
-                    // For candidate x and prime p, if x % p = 0 then x is not prime
-                    // So, we go to the next odd number and try again
-                    delta += 2;
 
-                    if delta > max_delta {
-                        continue 'outer;
-                    }
+        // Move the candidate to a number with the right residue, which differs from it by less
+        // than the modulus
+        let mut candidate = candidate.to_rug();
+        candidate -= Integer::from(&candidate % &modulus_rug);
+        candidate += &residue_rug;
 
+        let candidate = UnsignedInteger::from(candidate);
+        let mods = candidate.mod_many_u_leaky(&FIRST_PRIMES[..prime_count]);
+
+        // We step through candidates of the form candidate + steps * modulus
+        let mut steps = 0;
+        'sieve: loop {
+            for i in 0..prime_count {
+                let step_mod = (steps % FIRST_PRIMES[i]) * modulus_mods[i];
+                if (mods[i] + step_mod).is_multiple_of(FIRST_PRIMES[i]) {
+                    // For candidate x and prime p, if x % p = 0 then x is not prime
+                    // So, we go to the next number with the right residue and try again
+                    steps += 1;
                     continue 'sieve;
                 }
             }
 
-            // If we have passed all prime_count first primes, then we are fairly certain this is a prime!
-            break UnsignedInteger::from(delta);
-        };
+            // The candidate has no small prime factors, so it is worth a full primality test
+            break;
+        }
+
+        let candidate = candidate.to_rug() + Integer::from(&modulus_rug * steps);
+        if candidate.significant_bits() != bit_length {
+            continue 'outer;
+        }
 
         // Ensure that we have a prime with a stronger primality test
-        if candidate.is_probably_prime_leaky() {
+        let candidate = UnsignedInteger::from(candidate);
+        if candidate.is_probably_prime_with_leaky(repetitions) {
             return candidate;
         }
     }
 }
 
 /// Generates a uniformly random *safe* prime number of a given bit length. This is a prime $p$ of
-/// the form $p = 2q + 1$, where $q$ is a smaller prime.
+/// the form $p = 2q + 1$, where $q$ is a smaller prime. The number of Miller-Rabin rounds is
+/// chosen like in [`gen_prime`].
 pub fn gen_safe_prime<R: SecureRng>(bit_length: u32, rng: &mut GeneralRng<R>) -> UnsignedInteger {
-    'outer: loop {
-        let mut candidate = UnsignedInteger::random(bit_length, rng);
-        candidate.set_bit_leaky(bit_length - 1);
-        candidate.set_bit_leaky(0);
-
-        // A heuristic that closely follows OpenSSL (https://github.com/openssl/openssl/blob/4cedf30e995f9789cf6bb103e248d33285a84067/crypto/bn/bn_prime.c)
-        let prime_count: usize = bit_length as usize / 3;
-        let mods: Vec<u64> = FIRST_PRIMES[..prime_count]
-            .iter()
-            .map(|p| candidate.mod_u_leaky(*p))
-            .collect();
-
-        let mut delta = 0;
-        let max_delta = u64::MAX - FIRST_PRIMES[prime_count - 1];
-        candidate += &'sieve: loop {
-            for i in 1..prime_count {
-                if (mods[i] + delta) % FIRST_PRIMES[i] <= 1 {
-                    // For candidate x and prime p, if x % p = 0 then x is not prime
-                    // So, we go to the next odd number and try again
-                    delta += 4;
+    let sieve = Sieve::new(bit_length, SieveStep::SafePrime);
+    let repetitions = miller_rabin_repetitions(bit_length);
 
-                    if delta > max_delta {
-                        continue 'outer;
-                    }
-
-                    continue 'sieve;
-                }
-            }
-
-            // If we have passed all prime_count first primes, then we are fairly certain this is a prime!
-            break UnsignedInteger::from(delta);
+    loop {
+        let candidate = match sieve.sieve(rng) {
+            Some(candidate) => candidate,
+            None => continue,
         };
 
         // Ensure that we have a prime with a stronger primality test
-        if candidate.is_probably_prime_leaky() {
+        if candidate.is_probably_prime_with_leaky(repetitions) {
             // Ensure that p for 2p = 1 is also a prime with the stronger primality test
             let candidate_reduced = &candidate >> 1;
-            if candidate_reduced.is_probably_prime_leaky() {
+            if candidate_reduced.is_probably_prime_with_leaky(repetitions) {
                 return candidate;
             }
         }
@@ -117,10 +158,124 @@ pub fn gen_rsa_modulus<R: SecureRng>(
     (n, p, q)
 }
 
+/// The prime factorization of a modulus $n = p q$ that is the product of two primes.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Factorization {
+    /// The first prime factor
+    pub p: UnsignedInteger,
+    /// The second prime factor
+    pub q: UnsignedInteger,
+}
+
+/// Generates a uniformly random Blum integer, which is the product of two primes $p$ and $q$ such
+/// that $p \equiv q \equiv 3 \pmod 4$. The primes are distinct and the modulus has exactly
+/// `bit_length` bits. This method returns the modulus $n = p q$ and its factorization.
+pub fn gen_blum_modulus<R: SecureRng>(
+    bit_length: u32,
+    rng: &mut GeneralRng<R>,
+) -> (UnsignedInteger, Factorization) {
+    let residue = UnsignedInteger::from(3u64);
+    let modulus = UnsignedInteger::from(4u64);
+
+    let p_bits = bit_length.div_ceil(2);
+    let q_bits = bit_length / 2;
+
+    // The product can be one bit shorter than the sum of the sizes, so we pick both primes again
+    // until the modulus has the requested size
+    let (mut n, p, q) = loop {
+        let p = gen_prime_congruent(p_bits, &residue, &modulus, rng);
+        let q = gen_prime_congruent(q_bits, &residue, &modulus, rng);
+        if q == p {
+            continue;
+        }
+
+        let n = &p * &q;
+        if n.significant_bits_leaky() == bit_length {
+            break (n, p, q);
+        }
+    };
+
+    assert_eq!(n.significant_bits_leaky(), bit_length);
+    n.resize_to(bit_length);
+
+    (n, Factorization { p, q })
+}
+
+/// Generates a uniformly random modulus for the Benaloh cryptosystem with block size $r$, which
+/// must be an odd prime of fewer than `bit_length / 2` bits. This is the product of two primes
+/// $p$ and $q$ such that $r$ divides $p - 1$ but not $(p - 1) / r$, and $r$ does not divide
+/// $q - 1$. This method returns the modulus $n = p q$ and its factorization.
+pub fn gen_benaloh_modulus<R: SecureRng>(
+    bit_length: u32,
+    r: &UnsignedInteger,
+    rng: &mut GeneralRng<R>,
+) -> (UnsignedInteger, Factorization) {
+    let r_rug = r.clone().to_rug();
+    assert!(
+        r_rug.is_odd() && r.is_probably_prime_with_leaky(miller_rabin_repetitions(bit_length / 2)),
+        "the block size must be an odd prime"
+    );
+
+    let one = UnsignedInteger::from(1u64);
+    let p = loop {
+        let p = gen_prime_congruent(bit_length / 2, &one, r, rng);
+
+        let cofactor = (p.clone().to_rug() - 1u32) / &r_rug;
+        if !cofactor.is_divisible(&r_rug) {
+            break p;
+        }
+    };
+    let q = loop {
+        let q = gen_prime(bit_length / 2, rng);
+
+        if !(q.clone().to_rug() - 1u32).is_divisible(&r_rug) {
+            break q;
+        }
+    };
+
+    let n = &p * &q;
+
+    (n, Factorization { p, q })
+}
+
+/// Generates a uniformly random modulus $n = p^2 q$ of exactly `bit_length` bits for the
+/// Okamoto-Uchiyama cryptosystem, where $p$ and $q$ are distinct primes of roughly a third of the
+/// bits each. `bit_length` must be at least 192. This method returns the modulus and its
+/// factorization.
+pub fn gen_p2q_modulus<R: SecureRng>(
+    bit_length: u32,
+    rng: &mut GeneralRng<R>,
+) -> (UnsignedInteger, Factorization) {
+    assert!(bit_length >= 192, "the modulus must have at least 192 bits");
+
+    let p_bits = bit_length / 3;
+    let q_bits = bit_length - 2 * p_bits;
+
+    // The product can be up to two bits shorter than the sum of the sizes, so we pick both primes
+    // again until the modulus has the requested size
+    loop {
+        let p = gen_prime(p_bits, rng);
+        let q = gen_prime(q_bits, rng);
+        if q == p {
+            continue;
+        }
+
+        let mut n = &p.square() * &q;
+        if n.significant_bits_leaky() == bit_length {
+            n.resize_to(bit_length);
+            return (n, Factorization { p, q });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{gen_prime, gen_safe_prime};
+    use crate::{
+        gen_benaloh_modulus, gen_blum_modulus, gen_p2q_modulus, gen_prime, gen_prime_congruent,
+        gen_rsa_modulus, gen_safe_prime,
+    };
     use rand_core::OsRng;
+    use rug::Integer;
     use scicrypt_bigint::UnsignedInteger;
     use scicrypt_traits::randomness::GeneralRng;
 
@@ -155,4 +310,136 @@ mod tests {
 
         assert_primality_100_000_factors(&sophie_germain_prime);
     }
+
+    #[test]
+    fn test_gen_primes_of_partial_limbs() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        for bit_length in [32, 63, 65, 96, 100, 200] {
+            let prime = gen_prime(bit_length, &mut rng);
+            assert_eq!(prime.size_in_bits(), bit_length);
+            assert_eq!(prime.significant_bits_leaky(), bit_length);
+
+            let safe_prime = gen_safe_prime(bit_length, &mut rng);
+            assert_eq!(safe_prime.size_in_bits(), bit_length);
+            assert_eq!(safe_prime.significant_bits_leaky(), bit_length);
+            assert!((&safe_prime >> 1).is_probably_prime_leaky());
+        }
+    }
+
+    #[test]
+    fn test_gen_rsa_modulus_of_partial_limbs() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (n, p, q) = gen_rsa_modulus(200, &mut rng);
+
+        assert_eq!(p.size_in_bits(), 100);
+        assert_eq!(q.size_in_bits(), 100);
+        assert_eq!(n.to_rug(), p.to_rug() * q.to_rug());
+    }
+
+    #[test]
+    fn test_gen_prime_congruent_blum() {
+        let mut rng = GeneralRng::new(OsRng);
+        let generated_prime = gen_prime_congruent(
+            256,
+            &UnsignedInteger::from(3u64),
+            &UnsignedInteger::from(4u64),
+            &mut rng,
+        );
+
+        assert_primality_100_000_factors(&generated_prime);
+        assert_eq!(generated_prime.size_in_bits(), 256);
+        assert_eq!(generated_prime.mod_u_leaky(4), 3);
+    }
+
+    #[test]
+    fn test_gen_prime_congruent_schnorr() {
+        let mut rng = GeneralRng::new(OsRng);
+        let q = gen_prime(128, &mut rng);
+        let generated_prime = gen_prime_congruent(384, &UnsignedInteger::from(1u64), &q, &mut rng);
+
+        assert_primality_100_000_factors(&generated_prime);
+        assert_eq!(generated_prime.size_in_bits(), 384);
+        assert_eq!(generated_prime.to_rug() % q.to_rug(), 1);
+    }
+
+    #[test]
+    fn test_gen_blum_modulus() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (n, factorization) = gen_blum_modulus(512, &mut rng);
+
+        assert_eq!(factorization.p.mod_u_leaky(4), 3);
+        assert_eq!(factorization.q.mod_u_leaky(4), 3);
+        assert_eq!(
+            n.to_rug(),
+            factorization.p.to_rug() * factorization.q.to_rug()
+        );
+    }
+
+    #[test]
+    fn test_gen_blum_modulus_exact_size() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        for bit_length in [255, 256, 511, 513] {
+            let (n, factorization) = gen_blum_modulus(bit_length, &mut rng);
+
+            assert_ne!(factorization.p, factorization.q);
+            assert_eq!(n.significant_bits_leaky(), bit_length);
+            assert_eq!(n.size_in_bits(), bit_length);
+            assert_eq!(factorization.p.size_in_bits(), bit_length.div_ceil(2));
+            assert_eq!(factorization.q.size_in_bits(), bit_length / 2);
+        }
+    }
+
+    #[test]
+    fn test_gen_benaloh_modulus() {
+        let mut rng = GeneralRng::new(OsRng);
+        let r = UnsignedInteger::from(65537u64);
+        let (n, factorization) = gen_benaloh_modulus(512, &r, &mut rng);
+
+        let p_minus_one = factorization.p.clone().to_rug() - 1u32;
+        assert_eq!(Integer::from(&p_minus_one % 65537u32), 0);
+        assert_ne!((p_minus_one / 65537u32) % 65537u32, 0);
+        assert_ne!((factorization.q.clone().to_rug() - 1u32) % 65537u32, 0);
+        assert_eq!(
+            n.to_rug(),
+            factorization.p.to_rug() * factorization.q.to_rug()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the block size must be an odd prime")]
+    fn test_gen_benaloh_modulus_composite_block_size() {
+        let mut rng = GeneralRng::new(OsRng);
+        let r = UnsignedInteger::from(65535u64);
+        gen_benaloh_modulus(512, &r, &mut rng);
+    }
+
+    #[test]
+    fn test_gen_p2q_modulus_exact_size() {
+        let mut rng = GeneralRng::new(OsRng);
+
+        for bit_length in [200, 500, 1000] {
+            let (n, factorization) = gen_p2q_modulus(bit_length, &mut rng);
+
+            assert_eq!(n.significant_bits_leaky(), bit_length);
+            assert_eq!(n.size_in_bits(), bit_length);
+            assert_eq!(factorization.p.size_in_bits(), bit_length / 3);
+        }
+    }
+
+    #[test]
+    fn test_gen_p2q_modulus() {
+        let mut rng = GeneralRng::new(OsRng);
+        let (n, factorization) = gen_p2q_modulus(768, &mut rng);
+
+        assert_primality_100_000_factors(&factorization.p);
+        assert_primality_100_000_factors(&factorization.q);
+        assert_eq!(factorization.p.size_in_bits(), 256);
+        assert_eq!(n.significant_bits_leaky(), 768);
+        assert_eq!(
+            n.to_rug(),
+            factorization.p.to_rug().square() * factorization.q.to_rug()
+        );
+    }
 }
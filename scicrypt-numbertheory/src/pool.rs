@@ -0,0 +1,325 @@
+use crate::{gen_safe_prime, miller_rabin_repetitions};
+use rand_core::OsRng;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::randomness::GeneralRng;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// A pool of safe primes that are generated ahead of time on background threads, so that key
+/// generation does not have to wait for them. Every bit length that the pool serves has a target
+/// number of primes, which the workers refill as primes are taken.
+///
+/// The primes in the pool become secret key material once they are taken, so a file written by
+/// [`PrimePool::save`] must be protected like a secret key. Taken primes are removed from the pool
+/// and are never handed out twice.
+/// ```
+/// # use scicrypt_numbertheory::PrimePool;
+/// let pool = PrimePool::new(&[(128, 2)], 2);
+///
+/// let prime = pool.take(128);
+/// assert_eq!(prime.size_in_bits(), 128);
+/// ```
+pub struct PrimePool {
+    state: Arc<PoolState>,
+    threads: usize,
+}
+
+struct PoolState {
+    targets: HashMap<u32, usize>,
+    primes: Mutex<HashMap<u32, Vec<UnsignedInteger>>>,
+    changed: Condvar,
+    shutdown: AtomicBool,
+}
+
+impl PrimePool {
+    /// Creates a pool that keeps `count` safe primes available for every `(bit_length, count)` in
+    /// `targets`, and starts `threads` background threads to generate them. Without threads, the
+    /// pool never refills, and `take` generates primes on the calling thread once it runs out.
+    pub fn new(targets: &[(u32, usize)], threads: usize) -> Self {
+        PrimePool::with_primes(targets, threads, HashMap::new())
+    }
+
+    fn with_primes(
+        targets: &[(u32, usize)],
+        threads: usize,
+        primes: HashMap<u32, Vec<UnsignedInteger>>,
+    ) -> Self {
+        let state = Arc::new(PoolState {
+            targets: targets.iter().copied().collect(),
+            primes: Mutex::new(primes),
+            changed: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+
+        for _ in 0..threads {
+            let state = Arc::clone(&state);
+            thread::spawn(move || state.work());
+        }
+
+        PrimePool { state, threads }
+    }
+
+    /// Takes a safe prime of `bit_length` bits from the pool. If the pool serves this bit length
+    /// and has background threads, this waits until a prime is available. Otherwise, a prime that
+    /// is left in the pool (e.g. from [`PrimePool::load`]) is taken, or a new one is generated on
+    /// the calling thread.
+    pub fn take(&self, bit_length: u32) -> UnsignedInteger {
+        if self.threads == 0 || !self.state.targets.contains_key(&bit_length) {
+            return self
+                .try_take(bit_length)
+                .unwrap_or_else(|| gen_safe_prime(bit_length, &mut GeneralRng::new(OsRng)));
+        }
+
+        let mut primes = self.state.primes.lock().unwrap();
+        loop {
+            if let Some(prime) = primes.get_mut(&bit_length).and_then(|p| p.pop()) {
+                self.state.changed.notify_all();
+                return prime;
+            }
+
+            primes = self.state.changed.wait(primes).unwrap();
+        }
+    }
+
+    /// Takes a safe prime of `bit_length` bits if one is available right away.
+    pub fn try_take(&self, bit_length: u32) -> Option<UnsignedInteger> {
+        let prime = self
+            .state
+            .primes
+            .lock()
+            .unwrap()
+            .get_mut(&bit_length)
+            .and_then(|p| p.pop());
+
+        if prime.is_some() {
+            self.state.changed.notify_all();
+        }
+
+        prime
+    }
+
+    /// Returns the number of safe primes of `bit_length` bits that are available right away.
+    pub fn available(&self, bit_length: u32) -> usize {
+        self.state
+            .primes
+            .lock()
+            .unwrap()
+            .get(&bit_length)
+            .map_or(0, |p| p.len())
+    }
+
+    /// Writes the primes that are currently in the pool to `path`, one per line as the bit length
+    /// followed by the prime in hexadecimal. On Unix, a new file is only readable and writable by
+    /// its owner.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let primes = self.state.primes.lock().unwrap();
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut writer = BufWriter::new(options.open(path)?);
+
+        for (bit_length, primes) in primes.iter() {
+            for prime in primes {
+                writeln!(writer, "{} {}", bit_length, prime.to_string_radix_leaky(16))?;
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Creates a pool like [`PrimePool::new`] that starts with the primes saved at `path`. Every
+    /// prime is tested again, so a modified file cannot introduce composite numbers. Returns an
+    /// error if the file cannot be read or contains a number that is not a safe prime of the
+    /// stated size.
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        targets: &[(u32, usize)],
+        threads: usize,
+    ) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut primes: HashMap<u32, Vec<UnsignedInteger>> = HashMap::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let (bit_length, hex) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid("expected a bit length and a prime"))?;
+            let bit_length: u32 = bit_length
+                .parse()
+                .map_err(|_| invalid("invalid bit length"))?;
+
+            if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid("invalid hexadecimal prime"));
+            }
+            // Check the stated size before allocating it, so that a modified file cannot request
+            // arbitrarily large numbers
+            if hex_significant_bits(hex) != bit_length as usize {
+                return Err(invalid("the prime does not have the stated bit length"));
+            }
+            let prime = UnsignedInteger::from_string_leaky(hex.to_string(), 16, bit_length);
+
+            if !is_safe_prime(&prime, bit_length) {
+                return Err(invalid("not a safe prime of the stated bit length"));
+            }
+
+            primes.entry(bit_length).or_default().push(prime);
+        }
+
+        Ok(PrimePool::with_primes(targets, threads, primes))
+    }
+}
+
+/// The number of significant bits of a hexadecimal number, ignoring leading zeros.
+fn hex_significant_bits(hex: &str) -> usize {
+    let digits = hex.trim_start_matches('0');
+
+    match digits.chars().next().and_then(|c| c.to_digit(16)) {
+        Some(leading) => 4 * (digits.len() - 1) + (32 - leading.leading_zeros()) as usize,
+        None => 0,
+    }
+}
+
+/// Tests `candidate` as thoroughly as `gen_safe_prime` tests the primes that it generates.
+fn is_safe_prime(candidate: &UnsignedInteger, bit_length: u32) -> bool {
+    let repetitions = miller_rabin_repetitions(bit_length);
+
+    candidate.significant_bits_leaky() == bit_length
+        && candidate.is_probably_prime_with_leaky(repetitions)
+        && (candidate >> 1).is_probably_prime_with_leaky(repetitions)
+}
+
+impl Drop for PrimePool {
+    /// Stops the background threads after they finish the prime they are working on.
+    fn drop(&mut self) {
+        self.state.shutdown.store(true, Ordering::SeqCst);
+        self.state.changed.notify_all();
+    }
+}
+
+impl PoolState {
+    /// Returns the bit length whose supply is furthest below its target, if any.
+    fn most_needed(&self, primes: &HashMap<u32, Vec<UnsignedInteger>>) -> Option<u32> {
+        self.targets
+            .iter()
+            .map(|(bit_length, target)| {
+                let available = primes.get(bit_length).map_or(0, |p| p.len());
+                (*bit_length, target.saturating_sub(available))
+            })
+            .filter(|(_, missing)| *missing > 0)
+            .max_by_key(|(_, missing)| *missing)
+            .map(|(bit_length, _)| bit_length)
+    }
+
+    fn work(&self) {
+        let mut rng = GeneralRng::new(OsRng);
+
+        loop {
+            let bit_length = {
+                let mut primes = self.primes.lock().unwrap();
+                loop {
+                    if self.shutdown.load(Ordering::SeqCst) {
+                        return;
+                    }
+
+                    // Several workers may pick the same bit length, which can briefly overshoot
+                    // its target by at most one prime per worker
+                    match self.most_needed(&primes) {
+                        Some(bit_length) => break bit_length,
+                        None => primes = self.changed.wait(primes).unwrap(),
+                    }
+                }
+            };
+
+            let prime = gen_safe_prime(bit_length, &mut rng);
+
+            let mut primes = self.primes.lock().unwrap();
+            primes.entry(bit_length).or_default().push(prime);
+            self.changed.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PrimePool;
+    use std::env;
+
+    #[test]
+    fn test_take_refills() {
+        let pool = PrimePool::new(&[(128, 3), (192, 1)], 2);
+
+        let primes: Vec<_> = (0..5).map(|_| pool.take(128)).collect();
+        assert!(primes.iter().all(|p| p.size_in_bits() == 128));
+        assert!(primes.iter().all(|p| p.mod_u_leaky(4) == 3));
+
+        assert_eq!(pool.take(192).size_in_bits(), 192);
+        assert_eq!(pool.try_take(256), None);
+        assert_eq!(pool.take(256).size_in_bits(), 256);
+    }
+
+    #[test]
+    fn test_take_partial_limbs() {
+        let pool = PrimePool::new(&[(100, 1)], 1);
+
+        assert_eq!(pool.take(100).size_in_bits(), 100);
+        assert_eq!(pool.take(72).size_in_bits(), 72);
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = env::temp_dir().join(format!("scicrypt-prime-pool-{}", std::process::id()));
+
+        let pool = PrimePool::new(&[(128, 2)], 1);
+        pool.take(128);
+        while pool.available(128) < 2 {
+            std::thread::yield_now();
+        }
+        pool.save(&path).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let loaded = PrimePool::load(&path, &[], 0).unwrap();
+        assert_eq!(loaded.available(128), 2);
+        assert!(loaded.try_take(128).is_some());
+        assert!(loaded.try_take(128).is_some());
+        assert_eq!(loaded.try_take(128), None);
+
+        // Loaded primes are served by `take`, also for bit lengths without a target
+        let loaded = PrimePool::load(&path, &[(192, 0)], 1).unwrap();
+        let taken = [loaded.take(128), loaded.take(128)];
+        assert!(taken
+            .iter()
+            .all(|p| saved.contains(&p.to_string_radix_leaky(16))));
+        assert_eq!(loaded.available(128), 0);
+
+        // Without threads, the pool generates primes on the calling thread once it runs out
+        let loaded = PrimePool::load(&path, &[(128, 2)], 0).unwrap();
+        let taken = [loaded.take(128), loaded.take(128), loaded.take(128)];
+        assert!(taken.iter().all(|p| p.size_in_bits() == 128));
+
+        // A composite number is rejected
+        std::fs::write(&path, "128 ffffffffffffffffffffffffffffffff\n").unwrap();
+        assert!(PrimePool::load(&path, &[], 0).is_err());
+
+        // A stated size that does not match the number is rejected before allocating it
+        std::fs::write(&path, "4000000000 ff\n").unwrap();
+        assert!(PrimePool::load(&path, &[], 0).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
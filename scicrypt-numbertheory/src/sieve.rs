@@ -0,0 +1,369 @@
+use crate::primes::FIRST_PRIMES;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::randomness::SecureRng;
+
+/// The number of small primes that are covered by the wheel, i.e. 2, 3, 5 and 7.
+const WHEEL_PRIME_COUNT: usize = 4;
+
+/// Describes which candidates a [`Sieve`] steps through and which residues it rejects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SieveStep {
+    /// Steps through odd candidates (step 2) and rejects candidates $x$ with $x \equiv 0 \pmod p$.
+    Prime,
+    /// Steps through candidates that are 3 mod 4 (step 4) and rejects candidates $x$ with
+    /// $x \bmod p \leq 1$, because then either $x$ or $(x - 1) / 2$ is divisible by $p$.
+    SafePrime,
+}
+
+impl SieveStep {
+    /// The distance between two consecutive candidates before any sieving takes place.
+    pub fn step(&self) -> u64 {
+        match self {
+            SieveStep::Prime => 2,
+            SieveStep::SafePrime => 4,
+        }
+    }
+
+    /// Returns true if a candidate with this `residue` modulo an odd prime must be rejected.
+    pub fn rejects(&self, residue: u64) -> bool {
+        match self {
+            SieveStep::Prime => residue == 0,
+            SieveStep::SafePrime => residue <= 1,
+        }
+    }
+
+    /// Returns true if `candidate` passes this step's rule for all of the given odd `primes`, and
+    /// has the right residue modulo the step.
+    fn accepts(&self, candidate: u64, primes: &[u64]) -> bool {
+        candidate % self.step() == self.step() - 1
+            && primes.iter().all(|p| !self.rejects(candidate % p))
+    }
+}
+
+/// A sieve that finds candidates for (safe) primes by ruling out small prime factors. It steps
+/// over a wheel modulo the primorial of 2, 3, 5 and 7 (times 2 for safe primes), so candidates
+/// that are divisible by these primes are never considered. For the remaining small primes, it
+/// only updates the residues of the starting point with the distance travelled.
+pub struct Sieve {
+    step: SieveStep,
+    bit_length: u32,
+    prime_count: usize,
+    wheel_modulus: u64,
+    wheel_residues: Vec<u64>,
+}
+
+impl Sieve {
+    /// Creates a sieve for candidates of `bit_length` bits, which must be at least 6. The number of
+    /// small primes to divide by follows OpenSSL's heuristic
+    /// (https://github.com/openssl/openssl/blob/4cedf30e995f9789cf6bb103e248d33285a84067/crypto/bn/bn_prime.c),
+    /// but only primes below $2^{\text{bit\\_length} - 2}$ are used. Larger primes could be a
+    /// candidate itself, or half of a safe prime candidate, which must not be ruled out.
+    pub fn new(bit_length: u32, step: SieveStep) -> Self {
+        assert!(
+            bit_length >= 6,
+            "the sieve only supports candidates of at least 6 bits"
+        );
+
+        let prime_count = match bit_length {
+            0..=512 => 64,
+            513..=1024 => 128,
+            1025..=2048 => 384,
+            _ => FIRST_PRIMES.len(),
+        };
+        let prime_count = if bit_length - 2 < 64 {
+            prime_count.min(FIRST_PRIMES.partition_point(|p| *p < 1 << (bit_length - 2)))
+        } else {
+            prime_count
+        };
+
+        let wheel_primes = &FIRST_PRIMES[1..WHEEL_PRIME_COUNT];
+        let wheel_modulus = step.step() * wheel_primes.iter().product::<u64>();
+        let wheel_residues = (0..wheel_modulus)
+            .filter(|r| step.accepts(*r, wheel_primes))
+            .collect();
+
+        Sieve {
+            step,
+            bit_length,
+            prime_count,
+            wheel_modulus,
+            wheel_residues,
+        }
+    }
+
+    /// The number of small primes that candidates are checked against.
+    pub fn prime_count(&self) -> usize {
+        self.prime_count
+    }
+
+    /// Sieves from a uniformly random starting point of `bit_length` bits. Returns `None` when the
+    /// search runs past `bit_length` bits.
+    pub fn sieve<R: SecureRng>(&self, rng: &mut GeneralRng<R>) -> Option<UnsignedInteger> {
+        let mut start = UnsignedInteger::random(self.bit_length, rng);
+        start.set_bit_leaky(self.bit_length - 1);
+
+        self.sieve_from(start)
+    }
+
+    /// Returns the first candidate from `start` onwards that is not ruled out by any of the small
+    /// primes. Returns `None` when the search runs past `bit_length` bits.
+    pub fn sieve_from(&self, mut start: UnsignedInteger) -> Option<UnsignedInteger> {
+        // Only the small primes that are not on the wheel have to be checked
+        let primes = &FIRST_PRIMES[WHEEL_PRIME_COUNT..self.prime_count];
+        let mods = start.mod_many_u_leaky(primes);
+
+        let offset = start.mod_u_leaky(self.wheel_modulus);
+        let mut wheel_index = self.wheel_residues.partition_point(|r| *r < offset);
+        let mut wheel_start = 0;
+        let max_delta = u64::MAX - self.wheel_modulus;
+
+        let delta = 'sieve: loop {
+            if wheel_index == self.wheel_residues.len() {
+                wheel_index = 0;
+                wheel_start += self.wheel_modulus;
+            }
+
+            let delta = wheel_start + self.wheel_residues[wheel_index] - offset;
+            wheel_index += 1;
+
+            if delta > max_delta {
+                return None;
+            }
+
+            // We only update the residues of the start with the delta instead of dividing again
+            for (p, m) in primes.iter().zip(&mods) {
+                if self.step.rejects((m + delta % p) % p) {
+                    continue 'sieve;
+                }
+            }
+
+            // If we have passed all small primes, then we are fairly certain this is a prime!
+            break delta;
+        };
+
+        start += delta;
+        if start.significant_bits_leaky() != self.bit_length {
+            return None;
+        }
+
+        // The addition rounds the declared size up to make room for a carry, so restore it
+        start.resize_to(self.bit_length);
+        Some(start)
+    }
+}
+
+/// An iterator over consecutive candidates that pass a [`Sieve`], in increasing order. This makes
+/// it possible to apply a custom primality test to the candidates, or to split the search over
+/// several machines by giving each of them a different starting point. The iterator ends once the
+/// candidates run past `bit_length` bits.
+///
+/// Note that consecutive candidates from a single starting point are not uniformly distributed
+/// primes: a prime that follows a large gap is more likely to be found first. Use a fresh random
+/// starting point for every prime when uniformity matters, as [`crate::gen_prime`] does.
+/// ```
+/// # use scicrypt_numbertheory::{PrimeCandidateIterator, SieveStep};
+/// # use scicrypt_traits::randomness::GeneralRng;
+/// # use rand_core::OsRng;
+/// let mut rng = GeneralRng::new(OsRng);
+/// let mut candidates = PrimeCandidateIterator::random(128, SieveStep::Prime, &mut rng);
+///
+/// let prime = candidates.find(|c| c.is_probably_prime_leaky()).unwrap();
+/// assert_eq!(prime.size_in_bits(), 128);
+/// ```
+pub struct PrimeCandidateIterator {
+    sieve: Sieve,
+    next_start: Option<UnsignedInteger>,
+}
+
+impl PrimeCandidateIterator {
+    /// Creates an iterator over the candidates of `bit_length` bits from `start` onwards.
+    pub fn new(bit_length: u32, step: SieveStep, start: UnsignedInteger) -> Self {
+        PrimeCandidateIterator {
+            sieve: Sieve::new(bit_length, step),
+            next_start: Some(start),
+        }
+    }
+
+    /// Creates an iterator over the candidates of `bit_length` bits from a uniformly random
+    /// starting point of `bit_length` bits. Panics if `bit_length` is smaller than the sieve
+    /// supports.
+    pub fn random<R: SecureRng>(bit_length: u32, step: SieveStep, rng: &mut GeneralRng<R>) -> Self {
+        // Create the sieve first, so that it rejects a bit length of 0 before we set bit -1
+        let sieve = Sieve::new(bit_length, step);
+
+        let mut start = UnsignedInteger::random(bit_length, rng);
+        start.set_bit_leaky(bit_length - 1);
+
+        PrimeCandidateIterator {
+            sieve,
+            next_start: Some(start),
+        }
+    }
+
+    /// The sieve that candidates are taken from.
+    pub fn sieve(&self) -> &Sieve {
+        &self.sieve
+    }
+}
+
+impl Iterator for PrimeCandidateIterator {
+    type Item = UnsignedInteger;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let candidate = self.sieve.sieve_from(self.next_start.take()?)?;
+
+        let mut next_start = candidate.clone();
+        next_start += 1;
+        self.next_start = Some(next_start);
+
+        Some(candidate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primes::FIRST_PRIMES;
+    use crate::sieve::{PrimeCandidateIterator, Sieve, SieveStep};
+    use rand::Rng;
+    use rand_core::OsRng;
+    use scicrypt_bigint::UnsignedInteger;
+    use scicrypt_traits::randomness::GeneralRng;
+
+    fn assert_first_candidate(step: SieveStep, start: u64) {
+        let sieve = Sieve::new(64, step);
+        let primes = &FIRST_PRIMES[1..sieve.prime_count()];
+
+        let found = match sieve.sieve_from(UnsignedInteger::from(start)) {
+            Some(found) => found.to_rug().to_u64().unwrap(),
+            None => {
+                // Then no candidate may exist between start and 2^64
+                assert!((start..=u64::MAX).all(|x| !step.accepts(x, primes)));
+                return;
+            }
+        };
+
+        assert!(step.accepts(found, primes), "{} was not sieved", found);
+        assert!(
+            (start..found).all(|x| !step.accepts(x, primes)),
+            "skipped a candidate between {} and {}",
+            start,
+            found
+        );
+    }
+
+    #[test]
+    fn test_wheel_residues() {
+        assert_eq!(Sieve::new(64, SieveStep::Prime).wheel_residues.len(), 48);
+        assert_eq!(
+            Sieve::new(64, SieveStep::SafePrime).wheel_residues.len(),
+            15
+        );
+    }
+
+    #[test]
+    fn test_prime_sieve_finds_first_candidate() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            assert_first_candidate(SieveStep::Prime, rng.gen_range((1 << 63)..u64::MAX - 1));
+        }
+    }
+
+    #[test]
+    fn test_safe_prime_sieve_finds_first_candidate() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            assert_first_candidate(SieveStep::SafePrime, rng.gen_range((1 << 63)..u64::MAX - 1));
+        }
+    }
+
+    #[test]
+    fn test_small_bit_lengths_keep_primes() {
+        // A safe prime candidate x must not be ruled out by a prime that equals (x - 1) / 2
+        for bit_length in 6..16 {
+            let sieve = Sieve::new(bit_length, SieveStep::SafePrime);
+            let smallest = 1 << (bit_length - 1);
+
+            assert!(FIRST_PRIMES[sieve.prime_count() - 1] < smallest / 2);
+        }
+
+        let sieve = Sieve::new(8, SieveStep::Prime);
+        let primes: Vec<u64> =
+            PrimeCandidateIterator::new(8, SieveStep::Prime, UnsignedInteger::new(128, 8))
+                .map(|c| c.to_rug().to_u64().unwrap())
+                .filter(|c| (2..*c).all(|d| c % d != 0))
+                .collect();
+        assert_eq!(
+            sieve.prime_count(),
+            FIRST_PRIMES.partition_point(|p| *p < 64)
+        );
+        assert_eq!(
+            primes,
+            (128u64..256)
+                .filter(|c| (2..*c).all(|d| c % d != 0))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sieve_past_bit_length() {
+        let sieve = Sieve::new(64, SieveStep::Prime);
+
+        assert_eq!(sieve.sieve_from(UnsignedInteger::from(u64::MAX - 1)), None);
+    }
+
+    #[test]
+    fn test_sieve_past_bit_length_within_limb() {
+        let sieve = Sieve::new(40, SieveStep::Prime);
+
+        assert_eq!(
+            sieve.sieve_from(UnsignedInteger::new((1 << 40) - 2, 40)),
+            None
+        );
+        assert_eq!(
+            40,
+            sieve
+                .sieve_from(UnsignedInteger::new(1 << 39, 40))
+                .unwrap()
+                .size_in_bits()
+        );
+    }
+
+    #[test]
+    fn test_candidate_iterator_is_consecutive() {
+        let mut rng = rand::thread_rng();
+        let primes = &FIRST_PRIMES[1..Sieve::new(64, SieveStep::SafePrime).prime_count()];
+
+        let start = rng.gen_range((1 << 63)..u64::MAX - 1_000_000);
+        let candidates: Vec<u64> =
+            PrimeCandidateIterator::new(64, SieveStep::SafePrime, UnsignedInteger::from(start))
+                .take(100)
+                .map(|c| c.to_rug().to_u64().unwrap())
+                .collect();
+
+        let end = *candidates.last().unwrap();
+        let expected: Vec<u64> = (start..=end)
+            .filter(|x| SieveStep::SafePrime.accepts(*x, primes))
+            .collect();
+        assert_eq!(expected, candidates);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 6 bits")]
+    fn test_candidate_iterator_zero_bits() {
+        PrimeCandidateIterator::random(0, SieveStep::Prime, &mut GeneralRng::new(OsRng));
+    }
+
+    #[test]
+    fn test_candidate_iterator_ends_past_bit_length() {
+        let candidates = PrimeCandidateIterator::new(
+            64,
+            SieveStep::Prime,
+            UnsignedInteger::from(u64::MAX - 100),
+        );
+
+        assert!(candidates.count() < 50);
+    }
+}
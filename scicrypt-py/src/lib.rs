@@ -0,0 +1,468 @@
+#![warn(missing_docs, unused_imports)]
+
+//! _This is a part of **scicrypt**. For more information, head to the
+//! [scicrypt](https://crates.io/crates/scicrypt) crate homepage._
+//!
+//! Python bindings for the homomorphic cryptosystems, built as the `scicrypt` Python module with
+//! [maturin](https://www.maturin.rs). Here is an example in Python:
+//! ```python
+//! import numpy
+//! import scicrypt
+//!
+//! public_key, secret_key = scicrypt.CurveElGamal.generate_keys()
+//! ciphertexts = public_key.encrypt_batch(numpy.array([3, 4, 5]))
+//! total = public_key.sum(ciphertexts)
+//! assert secret_key.decrypt(public_key, total, max_value=100) == 12
+//! ```
+//!
+//! Plaintexts are Python integers, and batch methods accept any sequence of integers, including
+//! NumPy arrays. Public keys and ciphertexts can be converted to and from `bytes`.
+
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::scalar::Scalar;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyInt};
+use rand_core::OsRng;
+use scicrypt_bigint::UnsignedInteger;
+use scicrypt_he::cryptosystems::curve_el_gamal::{
+    CurveDiscreteLogTable, CurveElGamal as CurveElGamalScheme,
+    CurveElGamalCiphertext as CurveCiphertext, CurveElGamalPK, CurveElGamalSK,
+};
+use scicrypt_he::cryptosystems::integer_el_gamal::{
+    IntegerElGamal as IntegerElGamalScheme, IntegerElGamalCiphertext as IntegerCiphertext,
+    IntegerElGamalPK, IntegerElGamalSK,
+};
+use scicrypt_traits::cryptosystems::{AsymmetricCryptosystem, DecryptionKey, EncryptionKey};
+use scicrypt_traits::homomorphic::{HomomorphicAddition, HomomorphicMultiplication};
+use scicrypt_traits::randomness::GeneralRng;
+use scicrypt_traits::security::BitsOfSecurity;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+fn to_bytes<'py, T: Serialize>(py: Python<'py>, value: &T) -> Bound<'py, PyBytes> {
+    PyBytes::new(
+        py,
+        &bincode::serialize(value).expect("keys and ciphertexts can always be serialized"),
+    )
+}
+
+fn from_bytes<T: DeserializeOwned>(data: &[u8]) -> PyResult<T> {
+    bincode::deserialize(data)
+        .map_err(|e| PyValueError::new_err(format!("invalid encoding: {}", e)))
+}
+
+fn security_from_bits(security_bits: u32) -> PyResult<BitsOfSecurity> {
+    match security_bits {
+        80 => Ok(BitsOfSecurity::AES80),
+        112 => Ok(BitsOfSecurity::AES112),
+        128 => Ok(BitsOfSecurity::AES128),
+        _ => Err(PyValueError::new_err(
+            "the security level must be 80, 112 or 128 bits",
+        )),
+    }
+}
+
+/// Converts a Python integer (or anything that `int()` accepts, such as NumPy integers) into an
+/// `UnsignedInteger` of `size_in_bits` bits.
+fn to_unsigned(value: &Bound<'_, PyAny>, size_in_bits: u32) -> PyResult<UnsignedInteger> {
+    let py = value.py();
+    let value = py.get_type::<PyInt>().call1((value,))?;
+
+    let bit_length: u32 = value.call_method0("bit_length")?.extract()?;
+    if bit_length > size_in_bits {
+        return Err(PyValueError::new_err(
+            "the plaintext is too large for this key",
+        ));
+    }
+
+    // Negative integers raise an OverflowError here
+    let bytes = value.call_method1("to_bytes", (bit_length.div_ceil(8), "little"))?;
+
+    Ok(UnsignedInteger::from_le_bytes(
+        bytes.cast::<PyBytes>()?.as_bytes(),
+        size_in_bits,
+    ))
+}
+
+fn to_python_int<'py>(py: Python<'py>, value: &UnsignedInteger) -> PyResult<Bound<'py, PyAny>> {
+    py.get_type::<PyInt>().call_method1(
+        "from_bytes",
+        (PyBytes::new(py, &value.to_le_bytes()), "little"),
+    )
+}
+
+/// Multiplicatively homomorphic ElGamal over a safe prime group.
+#[pyclass(module = "scicrypt")]
+struct IntegerElGamal;
+
+#[pymethods]
+impl IntegerElGamal {
+    /// Generates a key pair for the given security level in bits, which must be 80, 112 or 128.
+    #[staticmethod]
+    #[pyo3(signature = (security_bits = 128))]
+    fn generate_keys(
+        security_bits: u32,
+    ) -> PyResult<(IntegerElGamalPublicKey, IntegerElGamalSecretKey)> {
+        let mut rng = GeneralRng::new(OsRng);
+        let (public_key, secret_key) =
+            IntegerElGamalScheme::setup(&security_from_bits(security_bits)?)
+                .generate_keys(&mut rng);
+
+        Ok((
+            IntegerElGamalPublicKey(public_key),
+            IntegerElGamalSecretKey(secret_key),
+        ))
+    }
+}
+
+/// Public key of integer-based ElGamal.
+#[pyclass(module = "scicrypt", frozen)]
+struct IntegerElGamalPublicKey(IntegerElGamalPK);
+
+/// Secret key of integer-based ElGamal.
+#[pyclass(module = "scicrypt", frozen)]
+struct IntegerElGamalSecretKey(IntegerElGamalSK);
+
+/// Ciphertext of integer-based ElGamal.
+#[pyclass(module = "scicrypt", frozen)]
+struct IntegerElGamalCiphertext(IntegerCiphertext);
+
+#[pymethods]
+impl IntegerElGamalPublicKey {
+    /// Encrypts a non-negative integer that is smaller than the modulus.
+    fn encrypt(&self, plaintext: &Bound<'_, PyAny>) -> PyResult<IntegerElGamalCiphertext> {
+        let plaintext = to_unsigned(plaintext, self.0.modulus.size_in_bits())?;
+
+        Ok(IntegerElGamalCiphertext(
+            self.0.encrypt_raw(&plaintext, &mut GeneralRng::new(OsRng)),
+        ))
+    }
+
+    /// Encrypts every integer in a sequence, such as a list or NumPy array.
+    fn encrypt_batch(
+        &self,
+        plaintexts: Vec<Bound<'_, PyAny>>,
+    ) -> PyResult<Vec<IntegerElGamalCiphertext>> {
+        plaintexts
+            .iter()
+            .map(|plaintext| self.encrypt(plaintext))
+            .collect()
+    }
+
+    /// Homomorphically multiplies two ciphertexts, which results in an encryption of the product of
+    /// their plaintexts.
+    fn multiply(
+        &self,
+        ciphertext_a: &IntegerElGamalCiphertext,
+        ciphertext_b: &IntegerElGamalCiphertext,
+    ) -> IntegerElGamalCiphertext {
+        IntegerElGamalCiphertext(HomomorphicMultiplication::mul(
+            &self.0,
+            &ciphertext_a.0,
+            &ciphertext_b.0,
+        ))
+    }
+
+    /// Serializes the public key.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        to_bytes(py, &self.0)
+    }
+
+    /// Deserializes a public key.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        Ok(IntegerElGamalPublicKey(from_bytes(data)?))
+    }
+}
+
+#[pymethods]
+impl IntegerElGamalSecretKey {
+    /// Decrypts a ciphertext into an integer.
+    fn decrypt<'py>(
+        &self,
+        py: Python<'py>,
+        public_key: &IntegerElGamalPublicKey,
+        ciphertext: &IntegerElGamalCiphertext,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        to_python_int(py, &self.0.decrypt_raw(&public_key.0, &ciphertext.0))
+    }
+
+    /// Decrypts a sequence of ciphertexts into a list of integers.
+    fn decrypt_batch<'py>(
+        &self,
+        py: Python<'py>,
+        public_key: &IntegerElGamalPublicKey,
+        ciphertexts: Vec<PyRef<'py, IntegerElGamalCiphertext>>,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        ciphertexts
+            .iter()
+            .map(|ciphertext| self.decrypt(py, public_key, ciphertext))
+            .collect()
+    }
+}
+
+#[pymethods]
+impl IntegerElGamalCiphertext {
+    /// Serializes the ciphertext.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        to_bytes(py, &self.0)
+    }
+
+    /// Deserializes a ciphertext.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        Ok(IntegerElGamalCiphertext(from_bytes(data)?))
+    }
+}
+
+/// Additively homomorphic ElGamal over the Ristretto group, where an integer $m$ is encrypted as the
+/// point $m G$.
+#[pyclass(module = "scicrypt")]
+struct CurveElGamal;
+
+#[pymethods]
+impl CurveElGamal {
+    /// Generates a key pair, which provides 128 bits of security.
+    #[staticmethod]
+    fn generate_keys() -> (CurveElGamalPublicKey, CurveElGamalSecretKey) {
+        let mut rng = GeneralRng::new(OsRng);
+        let (public_key, secret_key) =
+            CurveElGamalScheme::setup(&Default::default()).generate_keys(&mut rng);
+
+        (
+            CurveElGamalPublicKey(public_key.compress()),
+            CurveElGamalSecretKey {
+                secret_key,
+                discrete_log: Mutex::new(None),
+            },
+        )
+    }
+}
+
+/// Public key of curve-based ElGamal.
+#[pyclass(module = "scicrypt", frozen)]
+struct CurveElGamalPublicKey(CurveElGamalPK);
+
+/// Secret key of curve-based ElGamal.
+#[pyclass(module = "scicrypt", frozen)]
+struct CurveElGamalSecretKey {
+    secret_key: CurveElGamalSK,
+    /// The largest discrete logarithm table built so far, which is reused by later decryptions
+    discrete_log: Mutex<Option<Arc<CurveDiscreteLogTable>>>,
+}
+
+impl CurveElGamalSecretKey {
+    /// Returns a table that finds discrete logarithms up to `max_value`, reusing the cached table if
+    /// it is large enough.
+    fn discrete_log_table(&self, max_value: u64) -> PyResult<Arc<CurveDiscreteLogTable>> {
+        let mut cached = self.discrete_log.lock().unwrap();
+
+        if let Some(table) = cached.as_ref().filter(|table| table.bound() > max_value) {
+            return Ok(table.clone());
+        }
+
+        let table = Arc::new(
+            CurveDiscreteLogTable::with_max(max_value)
+                .map_err(|_| PyValueError::new_err("max_value must be below 2 ** 48"))?,
+        );
+        *cached = Some(table.clone());
+
+        Ok(table)
+    }
+}
+
+/// Ciphertext of curve-based ElGamal.
+#[pyclass(module = "scicrypt", frozen)]
+struct CurveElGamalCiphertext(CurveCiphertext);
+
+#[pymethods]
+impl CurveElGamalPublicKey {
+    /// Encrypts a non-negative 64-bit integer.
+    fn encrypt(&self, plaintext: u64) -> CurveElGamalCiphertext {
+        let encoded = &Scalar::from(plaintext) * &RISTRETTO_BASEPOINT_TABLE;
+
+        CurveElGamalCiphertext(self.0.encrypt_raw(&encoded, &mut GeneralRng::new(OsRng)))
+    }
+
+    /// Encrypts every integer in a sequence, such as a list or NumPy array.
+    fn encrypt_batch(&self, plaintexts: Vec<u64>) -> Vec<CurveElGamalCiphertext> {
+        plaintexts
+            .into_iter()
+            .map(|plaintext| self.encrypt(plaintext))
+            .collect()
+    }
+
+    /// Homomorphically adds two ciphertexts, which results in an encryption of the sum of their
+    /// plaintexts.
+    fn add(
+        &self,
+        ciphertext_a: &CurveElGamalCiphertext,
+        ciphertext_b: &CurveElGamalCiphertext,
+    ) -> CurveElGamalCiphertext {
+        CurveElGamalCiphertext(self.0.add(&ciphertext_a.0, &ciphertext_b.0))
+    }
+
+    /// Homomorphically multiplies a ciphertext by a non-negative integer constant.
+    fn mul_constant(
+        &self,
+        ciphertext: &CurveElGamalCiphertext,
+        constant: u64,
+    ) -> CurveElGamalCiphertext {
+        CurveElGamalCiphertext(self.0.mul_constant(&ciphertext.0, &Scalar::from(constant)))
+    }
+
+    /// Homomorphically adds up a sequence of ciphertexts.
+    fn sum(&self, ciphertexts: Vec<PyRef<'_, CurveElGamalCiphertext>>) -> CurveElGamalCiphertext {
+        CurveElGamalCiphertext(ciphertexts.iter().map(|ciphertext| &ciphertext.0).sum())
+    }
+
+    /// Serializes the public key.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        to_bytes(py, &self.0)
+    }
+
+    /// Deserializes a public key.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        Ok(CurveElGamalPublicKey(from_bytes(data)?))
+    }
+}
+
+#[pymethods]
+impl CurveElGamalSecretKey {
+    /// Decrypts a ciphertext into an integer of at most `max_value`, or returns `None` if the
+    /// plaintext is larger. Finding the integer takes time proportional to the square root of
+    /// `max_value`, and this time leaks the plaintext. The table for `max_value` is computed once and
+    /// reused by later decryptions. Raises a `ValueError` if `max_value` is $2^{48}$ or more.
+    #[pyo3(signature = (public_key, ciphertext, max_value = 1 << 16))]
+    fn decrypt(
+        &self,
+        public_key: &CurveElGamalPublicKey,
+        ciphertext: &CurveElGamalCiphertext,
+        max_value: u64,
+    ) -> PyResult<Option<u64>> {
+        let discrete_log = self.discrete_log_table(max_value)?;
+
+        Ok(discrete_log
+            .discrete_log_leaky(&self.secret_key.decrypt_raw(&public_key.0, &ciphertext.0))
+            .filter(|plaintext| *plaintext <= max_value))
+    }
+
+    /// Decrypts a sequence of ciphertexts into a list of integers of at most `max_value`, where
+    /// plaintexts that are larger become `None`.
+    #[pyo3(signature = (public_key, ciphertexts, max_value = 1 << 16))]
+    fn decrypt_batch(
+        &self,
+        public_key: &CurveElGamalPublicKey,
+        ciphertexts: Vec<PyRef<'_, CurveElGamalCiphertext>>,
+        max_value: u64,
+    ) -> PyResult<Vec<Option<u64>>> {
+        let discrete_log = self.discrete_log_table(max_value)?;
+
+        Ok(ciphertexts
+            .iter()
+            .map(|ciphertext| {
+                discrete_log
+                    .discrete_log_leaky(&self.secret_key.decrypt_raw(&public_key.0, &ciphertext.0))
+                    .filter(|plaintext| *plaintext <= max_value)
+            })
+            .collect())
+    }
+}
+
+#[pymethods]
+impl CurveElGamalCiphertext {
+    /// Serializes the ciphertext into 64 bytes.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.to_compressed_bytes())
+    }
+
+    /// Deserializes a ciphertext from 64 bytes.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        <&[u8; 64]>::try_from(data)
+            .ok()
+            .and_then(CurveCiphertext::from_compressed_bytes)
+            .map(CurveElGamalCiphertext)
+            .ok_or_else(|| PyValueError::new_err("invalid encoding"))
+    }
+}
+
+/// Homomorphic cryptosystems from scicrypt.
+#[pymodule]
+fn scicrypt(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<IntegerElGamal>()?;
+    m.add_class::<IntegerElGamalPublicKey>()?;
+    m.add_class::<IntegerElGamalSecretKey>()?;
+    m.add_class::<IntegerElGamalCiphertext>()?;
+    m.add_class::<CurveElGamal>()?;
+    m.add_class::<CurveElGamalPublicKey>()?;
+    m.add_class::<CurveElGamalSecretKey>()?;
+    m.add_class::<CurveElGamalCiphertext>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_conversion() {
+        Python::attach(|py| {
+            let value = py.eval(pyo3::ffi::c_str!("3 ** 100"), None, None).unwrap();
+            let converted = to_unsigned(&value, 1024).unwrap();
+
+            assert!(value.eq(to_python_int(py, &converted).unwrap()).unwrap());
+            assert!(to_unsigned(&value, 64).is_err());
+            assert!(to_unsigned(&(-1i64).into_pyobject(py).unwrap(), 64).is_err());
+        });
+    }
+
+    #[test]
+    fn test_integer_el_gamal() {
+        Python::attach(|py| {
+            let (public_key, secret_key) = IntegerElGamal::generate_keys(80).unwrap();
+            let plaintexts = vec![
+                6u64.into_pyobject(py).unwrap().into_any(),
+                7u64.into_pyobject(py).unwrap().into_any(),
+            ];
+
+            let ciphertexts = public_key.encrypt_batch(plaintexts).unwrap();
+            let product = public_key.multiply(&ciphertexts[0], &ciphertexts[1]);
+            let product =
+                IntegerElGamalCiphertext::from_bytes(product.to_bytes(py).as_bytes()).unwrap();
+
+            let decrypted: u64 = secret_key
+                .decrypt(py, &public_key, &product)
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(42, decrypted);
+            assert!(IntegerElGamal::generate_keys(100).is_err());
+        });
+    }
+
+    #[test]
+    fn test_curve_el_gamal() {
+        Python::attach(|py| {
+            let (public_key, secret_key) = CurveElGamal::generate_keys();
+            let public_key =
+                CurveElGamalPublicKey::from_bytes(public_key.to_bytes(py).as_bytes()).unwrap();
+
+            let ciphertexts = public_key.encrypt_batch(vec![3, 4]);
+            let sum = public_key.add(&ciphertexts[0], &ciphertexts[1]);
+            let scaled = public_key.mul_constant(&sum, 5);
+
+            assert_eq!(
+                Some(35),
+                secret_key.decrypt(&public_key, &scaled, 100).unwrap()
+            );
+            assert_eq!(None, secret_key.decrypt(&public_key, &scaled, 34).unwrap());
+            assert!(secret_key.decrypt(&public_key, &scaled, 1 << 60).is_err());
+            assert!(CurveElGamalCiphertext::from_bytes(&[0u8; 63]).is_err());
+        });
+    }
+}
@@ -1,7 +1,10 @@
+use crate::encoding::{EncodingError, PlaintextEncodable, PlaintextSpace};
 use crate::randomness::GeneralRng;
 use crate::randomness::SecureRng;
 use crate::security::BitsOfSecurity;
+use crate::DecryptionError;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 /// An asymmetric cryptosystem is a system of methods to encrypt plaintexts into ciphertexts, and
 /// decrypt those ciphertexts back into plaintexts. Anyone who has access to the public key can
@@ -50,17 +53,41 @@ pub trait EncryptionKey: Sized + Debug + PartialEq {
         self.encrypt_raw(plaintext, rng).associate(self)
     }
 
+    /// Encodes `value` into the plaintext space of this key and encrypts it, or returns an error if the value does not fit.
+    fn encrypt_encodable<'pk, T: PlaintextEncodable<Self>, R: SecureRng>(
+        &'pk self,
+        value: &T,
+        rng: &mut GeneralRng<R>,
+    ) -> Result<AssociatedCiphertext<'pk, Self::Ciphertext, Self>, EncodingError> {
+        Ok(self.encrypt(&value.encode(self)?, rng))
+    }
+
     /// Encrypt the plaintext using the public key and a cryptographic RNG.
     fn encrypt_raw<R: SecureRng>(
         &self,
         plaintext: &Self::Plaintext,
         rng: &mut GeneralRng<R>,
     ) -> Self::Ciphertext {
+        #[cfg(feature = "trace")]
+        let _span =
+            tracing::debug_span!("encrypt", cryptosystem = std::any::type_name::<Self>()).entered();
+
         let message = self.encrypt_without_randomness(plaintext);
 
         self.randomize(message, rng)
     }
 
+    /// Encrypt the plaintext using the public key and the user supplied randomness. Given the same randomness, the resulting ciphertext is always the same, which allows the encrypting party to later prove that the encryption was done correctly. The randomness must be sampled from the right distribution for the encryption to be secure.
+    fn encrypt_with_randomness(
+        &self,
+        plaintext: &Self::Plaintext,
+        randomness: &Self::Randomness,
+    ) -> Self::Ciphertext {
+        let message = self.encrypt_without_randomness(plaintext);
+
+        self.randomize_with(message, randomness)
+    }
+
     /// **WARNING: This is not a full encryption. The resulting ciphertext is completely insecure.** 'Encrypts' the plaintext using the public key deterministically, essentially creating a trivial ciphertext. The encryption is not secure until you call `randomize` or `randomize_with` with suitable randomness.
     fn encrypt_without_randomness(&self, plaintext: &Self::Plaintext) -> Self::Ciphertext;
 
@@ -77,6 +104,29 @@ pub trait EncryptionKey: Sized + Debug + PartialEq {
         ciphertext: Self::Ciphertext,
         randomness: &Self::Randomness,
     ) -> Self::Ciphertext;
+
+    /// Returns the level of security that this key was generated for. Keys that do not record their
+    /// level report `ToyParameters`, so that `assert_security_at_least` refuses them.
+    fn security_level(&self) -> BitsOfSecurity {
+        BitsOfSecurity::ToyParameters
+    }
+
+    /// Returns a description of the valid plaintexts of this key, including the bound above which
+    /// the results of homomorphic operations wrap around.
+    fn plaintext_space(&self) -> PlaintextSpace;
+
+    /// Panics if this key provides less than `level` bits of security. Call this when loading keys
+    /// from an untrusted or misconfigured source, to avoid silently using test parameters.
+    fn assert_security_at_least(&self, level: &BitsOfSecurity) {
+        let actual = self.security_level();
+
+        assert!(
+            actual.to_symmetric_bit_length() >= level.to_symmetric_bit_length(),
+            "the key provides {:?} security, but at least {:?} is required",
+            actual,
+            level
+        );
+    }
 }
 
 /// The decryption key.
@@ -89,6 +139,22 @@ pub trait DecryptionKey<PK: EncryptionKey> {
         self.decrypt_raw(ciphertext.public_key, &ciphertext.ciphertext)
     }
 
+    /// Decrypt the associated ciphertext and decode the plaintext into a value of type `T`, or return an error if the plaintext does not correspond to such a value.
+    fn decrypt_decodable<T: PlaintextEncodable<PK>>(
+        &self,
+        ciphertext: &AssociatedCiphertext<'_, PK::Ciphertext, PK>,
+    ) -> Result<T, EncodingError> {
+        T::decode(&self.decrypt(ciphertext), ciphertext.public_key)
+    }
+
+    /// Decrypt the associated ciphertext after checking that it is valid for its public key, or return an error if it is not. Use this instead of `decrypt` for ciphertexts from untrusted parties, such as deserialized ciphertexts, because decrypting malformed ciphertexts can leak information about the secret key.
+    fn decrypt_checked<'pk>(
+        &self,
+        ciphertext: &AssociatedCiphertext<'pk, PK::Ciphertext, PK>,
+    ) -> Result<PK::Plaintext, DecryptionError> {
+        self.decrypt_checked_raw(ciphertext.public_key, &ciphertext.ciphertext)
+    }
+
     /// Returns true if the associated ciphertext encrypts the identity. This is typically faster than a full decryption.
     fn decrypt_identity<'pk>(
         &self,
@@ -100,6 +166,19 @@ pub trait DecryptionKey<PK: EncryptionKey> {
     /// Decrypt the ciphertext using the secret key and its related public key.
     fn decrypt_raw(&self, public_key: &PK, ciphertext: &PK::Ciphertext) -> PK::Plaintext;
 
+    /// Decrypt the ciphertext using the secret key and its related public key after checking that the ciphertext is valid, or return an error if it is not.
+    fn decrypt_checked_raw(
+        &self,
+        public_key: &PK,
+        ciphertext: &PK::Ciphertext,
+    ) -> Result<PK::Plaintext, DecryptionError> {
+        if !ciphertext.is_valid_leaky(public_key) {
+            return Err(DecryptionError);
+        }
+
+        Ok(self.decrypt_raw(public_key, ciphertext))
+    }
+
     /// Returns true if the encrypted value equals the identity. This is typically faster than a full decryption.
     fn decrypt_identity_raw(&self, public_key: &PK, ciphertext: &PK::Ciphertext) -> bool;
 }
@@ -113,8 +192,61 @@ pub struct AssociatedCiphertext<'pk, C: Associable<PK>, PK: EncryptionKey<Cipher
     pub public_key: &'pk PK,
 }
 
+impl<'pk, C: Associable<PK> + Clone, PK: EncryptionKey<Ciphertext = C>> Clone
+    for AssociatedCiphertext<'pk, C, PK>
+{
+    fn clone(&self) -> Self {
+        AssociatedCiphertext {
+            ciphertext: self.ciphertext.clone(),
+            public_key: self.public_key,
+        }
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C>> AssociatedCiphertext<'pk, C, PK> {
+    /// Turns this ciphertext into one that shares ownership of its public key, so that it can be stored in collections or moved across threads independently of the public key's lifetime.
+    pub fn into_owned(self, public_key: Arc<PK>) -> OwnedAssociatedCiphertext<C, PK> {
+        debug_assert_eq!(self.public_key, public_key.as_ref());
+        self.ciphertext.associate_owned(public_key)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+/// An OwnedAssociatedCiphertext associates a ciphertext with a shared public key. Unlike an `AssociatedCiphertext`, it does not borrow the public key, so it can be cloned, stored and sent across threads freely.
+pub struct OwnedAssociatedCiphertext<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C>> {
+    /// A potentially homomorphic ciphertext
+    pub ciphertext: C,
+    /// The related public key
+    pub public_key: Arc<PK>,
+}
+
+impl<C: Associable<PK> + Clone, PK: EncryptionKey<Ciphertext = C>> Clone
+    for OwnedAssociatedCiphertext<C, PK>
+{
+    fn clone(&self) -> Self {
+        OwnedAssociatedCiphertext {
+            ciphertext: self.ciphertext.clone(),
+            public_key: Arc::clone(&self.public_key),
+        }
+    }
+}
+
+impl<C: Associable<PK> + Clone, PK: EncryptionKey<Ciphertext = C>>
+    OwnedAssociatedCiphertext<C, PK>
+{
+    /// Returns a copy of this ciphertext that borrows the public key, which supports all operations of an `AssociatedCiphertext` such as decryption.
+    pub fn to_associated(&self) -> AssociatedCiphertext<'_, C, PK> {
+        self.ciphertext.clone().associate(&self.public_key)
+    }
+}
+
 /// Functionality to easily turn a ciphertext into an associated ciphertext
 pub trait Associable<PK: EncryptionKey<Ciphertext = Self>>: Sized {
+    /// Returns true if this ciphertext is well-formed for `public_key`, for example because its components lie in the right subgroup. Honestly generated ciphertexts are always valid, but ciphertexts from untrusted parties should be checked before they are decrypted or combined with other ciphertexts. By default, every ciphertext is valid, which holds for ciphertext types that cannot represent malformed values. This function is not constant-time.
+    fn is_valid_leaky(&self, _public_key: &PK) -> bool {
+        true
+    }
+
     /// 'Enriches' a ciphertext by associating it with a corresponding public key. This allows to overlead operators for homomorphic operations.
     fn associate(self, public_key: &PK) -> AssociatedCiphertext<'_, Self, PK> {
         AssociatedCiphertext {
@@ -122,6 +254,14 @@ pub trait Associable<PK: EncryptionKey<Ciphertext = Self>>: Sized {
             public_key,
         }
     }
+
+    /// 'Enriches' a ciphertext by associating it with a shared public key. This allows to overload operators for homomorphic operations without borrowing the public key.
+    fn associate_owned(self, public_key: Arc<PK>) -> OwnedAssociatedCiphertext<Self, PK> {
+        OwnedAssociatedCiphertext {
+            ciphertext: self,
+            public_key,
+        }
+    }
 }
 
 /// The Verification key.
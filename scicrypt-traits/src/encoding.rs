@@ -0,0 +1,139 @@
+use crate::cryptosystems::EncryptionKey;
+
+/// Error that arises when a value does not fit in the plaintext space of a key, or when a plaintext
+/// does not decode into a value of the requested type.
+#[derive(PartialEq, Eq, Debug)]
+pub struct EncodingError;
+
+/// A type whose values can be encoded into the plaintext space of the encryption key `PK`, and
+/// decoded back after decryption. This allows encrypting "a number" rather than a group element or
+/// a residue modulo some large integer.
+pub trait PlaintextEncodable<PK: EncryptionKey>: Sized {
+    /// Encodes this value as a plaintext of `public_key`, or returns an error if it does not fit.
+    fn encode(&self, public_key: &PK) -> Result<PK::Plaintext, EncodingError>;
+
+    /// Decodes a plaintext of `public_key` back into a value, or returns an error if the plaintext
+    /// does not correspond to a value of this type (for example after a homomorphic operation
+    /// overflowed).
+    fn decode(plaintext: &PK::Plaintext, public_key: &PK) -> Result<Self, EncodingError>;
+}
+
+/// The operation on plaintexts that is reflected by combining ciphertexts homomorphically.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlaintextOperation {
+    /// Plaintexts are added modulo the plaintext modulus or the order of the plaintext group
+    Addition,
+    /// Plaintexts are multiplied modulo the plaintext modulus
+    Multiplication,
+}
+
+/// Describes the plaintexts of a public key, so that generic code can check whether values and the
+/// results of homomorphic operations on them fit, instead of silently wrapping around.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PlaintextSpace {
+    /// The operation that homomorphic combinations of ciphertexts apply to the plaintexts
+    pub operation: PlaintextOperation,
+    /// All integers smaller than $2^\text{capacity\\_bits}$ are valid plaintexts, and results that
+    /// stay below this bound never wrap around. This is one less than the number of bits of the
+    /// plaintext modulus or group order.
+    pub capacity_bits: u32,
+}
+
+impl PlaintextSpace {
+    /// Returns how many plaintexts of at most `value_bits` bits can be combined homomorphically
+    /// before the result may wrap around, saturating at `u64::MAX`. Signed values that are encoded
+    /// as residues need one more bit than their magnitude.
+    pub fn max_safe_operands(&self, value_bits: u32) -> u64 {
+        match self.operation {
+            PlaintextOperation::Addition => match self.capacity_bits.checked_sub(value_bits) {
+                Some(spare_bits) if spare_bits < 64 => 1 << spare_bits,
+                Some(_) => u64::MAX,
+                None => 0,
+            },
+            PlaintextOperation::Multiplication if value_bits == 0 => u64::MAX,
+            PlaintextOperation::Multiplication => (self.capacity_bits / value_bits) as u64,
+        }
+    }
+}
+
+/// A signed decimal number with `DECIMALS` digits after the decimal point. It is stored as the
+/// integer `mantissa` $= x \cdot 10^\text{DECIMALS}$, and encoded like an `i64`. Homomorphic
+/// additions of two fixed-point numbers keep the scale, but multiplying by an encoded constant
+/// multiplies the scale as well.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedPoint<const DECIMALS: u32> {
+    /// The number multiplied by $10^\text{DECIMALS}$
+    pub mantissa: i64,
+}
+
+impl<const DECIMALS: u32> FixedPoint<DECIMALS> {
+    /// Rounds `value` to the nearest fixed-point number, or returns `None` if it is not finite or
+    /// does not fit.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let scaled = (value * 10f64.powi(DECIMALS as i32)).round();
+
+        // `i64::MAX as f64` rounds up to 2^63, which itself does not fit
+        if !scaled.is_finite() || scaled < i64::MIN as f64 || scaled >= i64::MAX as f64 {
+            return None;
+        }
+
+        Some(FixedPoint {
+            mantissa: scaled as i64,
+        })
+    }
+
+    /// Returns the closest `f64` to this number.
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(DECIMALS as i32)
+    }
+}
+
+impl<PK: EncryptionKey, const DECIMALS: u32> PlaintextEncodable<PK> for FixedPoint<DECIMALS>
+where
+    i64: PlaintextEncodable<PK>,
+{
+    fn encode(&self, public_key: &PK) -> Result<PK::Plaintext, EncodingError> {
+        self.mantissa.encode(public_key)
+    }
+
+    fn decode(plaintext: &PK::Plaintext, public_key: &PK) -> Result<Self, EncodingError> {
+        Ok(FixedPoint {
+            mantissa: i64::decode(plaintext, public_key)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding::{FixedPoint, PlaintextOperation, PlaintextSpace};
+
+    #[test]
+    fn test_fixed_point_f64() {
+        assert_eq!(
+            Some(FixedPoint::<2> { mantissa: -1234 }),
+            FixedPoint::from_f64(-12.344)
+        );
+        assert_eq!(0.1, FixedPoint::<3>::from_f64(0.1).unwrap().to_f64());
+        assert_eq!(None, FixedPoint::<2>::from_f64(f64::NAN));
+        assert_eq!(None, FixedPoint::<2>::from_f64(1e18));
+    }
+
+    #[test]
+    fn test_max_safe_operands() {
+        let additive = PlaintextSpace {
+            operation: PlaintextOperation::Addition,
+            capacity_bits: 100,
+        };
+        assert_eq!(1 << 36, additive.max_safe_operands(64));
+        assert_eq!(1, additive.max_safe_operands(100));
+        assert_eq!(0, additive.max_safe_operands(101));
+        assert_eq!(u64::MAX, additive.max_safe_operands(0));
+
+        let multiplicative = PlaintextSpace {
+            operation: PlaintextOperation::Multiplication,
+            capacity_bits: 100,
+        };
+        assert_eq!(3, multiplicative.max_safe_operands(32));
+        assert_eq!(0, multiplicative.max_safe_operands(101));
+    }
+}
@@ -1,10 +1,74 @@
-use std::ops::{Add, Mul, Sub};
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
-use crate::cryptosystems::{Associable, AssociatedCiphertext, EncryptionKey};
+use crate::cryptosystems::{
+    Associable, AssociatedCiphertext, EncryptionKey, OwnedAssociatedCiphertext,
+};
 
 auto trait PotentialInput {}
 
 impl<'pk, C, PK> !PotentialInput for AssociatedCiphertext<'pk, C, PK> {}
+impl<C, PK> !PotentialInput for OwnedAssociatedCiphertext<C, PK> {}
+// References are excluded as well, so that operators can take inputs both by value and by reference
+impl<T: ?Sized> !PotentialInput for &T {}
+
+/// Error returned when ciphertexts under different keys are combined. The result would not decrypt
+/// correctly, and keys of different security levels would silently downgrade ciphertexts under
+/// production keys to the security of test keys.
+#[derive(PartialEq, Eq, Debug)]
+pub struct IncompatibleKeys;
+
+/// Checks that ciphertexts under these keys may be combined, which requires them to be the same key
+/// of the same security level. Other keys are refused with an error. The operators only compare the
+/// keys in debug builds, so use this check (or the `try_` methods on associated ciphertexts) when the
+/// keys come from an untrusted source.
+pub fn check_compatible_keys<PK: EncryptionKey>(
+    key_a: &PK,
+    key_b: &PK,
+) -> Result<(), IncompatibleKeys> {
+    if key_a != key_b || key_a.security_level() != key_b.security_level() {
+        return Err(IncompatibleKeys);
+    }
+
+    Ok(())
+}
+
+/// Implements a binary operator with an owned right-hand side by borrowing it.
+macro_rules! forward_owned_rhs {
+    ([$($generics:tt)*] $imp:ident::$method:ident for $lhs:ty, $rhs:ty => $output:ty) => {
+        impl<$($generics)*> $imp<$rhs> for $lhs {
+            type Output = $output;
+
+            fn $method(self, rhs: $rhs) -> Self::Output {
+                $imp::$method(self, &rhs)
+            }
+        }
+    };
+}
+
+/// Implements a binary operator with an owned left-hand side by borrowing it.
+macro_rules! forward_owned_lhs {
+    ([$($generics:tt)*] $imp:ident::$method:ident for $lhs:ty, $rhs:ty => $output:ty) => {
+        impl<$($generics)*> $imp<$rhs> for $lhs {
+            type Output = $output;
+
+            fn $method(self, rhs: $rhs) -> Self::Output {
+                $imp::$method(&self, rhs)
+            }
+        }
+    };
+}
+
+/// Implements a compound assignment operator with an owned right-hand side by borrowing it.
+macro_rules! forward_owned_assign {
+    ([$($generics:tt)*] $imp:ident::$method:ident for $lhs:ty, $rhs:ty) => {
+        impl<$($generics)*> $imp<$rhs> for $lhs {
+            fn $method(&mut self, rhs: $rhs) {
+                $imp::$method(self, &rhs)
+            }
+        }
+    };
+}
 
 /// Trait implemented by additively homomorphic cryptosystems
 pub trait HomomorphicAddition: EncryptionKey {
@@ -15,6 +79,11 @@ pub trait HomomorphicAddition: EncryptionKey {
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext;
 
+    /// Combines two ciphertexts in-place so that the decrypted value of `ciphertext_a` reflects some addition operation. Cryptosystems can override this to avoid allocating a new ciphertext.
+    fn add_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        *ciphertext_a = self.add(ciphertext_a, ciphertext_b);
+    }
+
     /// Combines two ciphertexts so that their decrypted value reflects some subtract operation
     fn sub(
         &self,
@@ -22,9 +91,36 @@ pub trait HomomorphicAddition: EncryptionKey {
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext;
 
+    /// Combines two ciphertexts in-place so that the decrypted value of `ciphertext_a` reflects some subtract operation. Cryptosystems can override this to avoid allocating a new ciphertext.
+    fn sub_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        *ciphertext_a = self.sub(ciphertext_a, ciphertext_b);
+    }
+
     /// Applies some operation on a ciphertext so that the decrypted value reflects some multiplication with `input`
     fn mul_constant(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext;
 
+    /// Computes the linear combination of `ciphertexts` with the weights in `inputs`, so that the decrypted value reflects the sum of each plaintext multiplied with its weight. Returns `None` if there are no ciphertexts. Cryptosystems can override this with a faster multi-scalar multiplication.
+    fn linear_combination(
+        &self,
+        ciphertexts: &[Self::Ciphertext],
+        inputs: &[Self::Input],
+    ) -> Option<Self::Ciphertext> {
+        assert_eq!(
+            ciphertexts.len(),
+            inputs.len(),
+            "every ciphertext must have exactly one weight"
+        );
+
+        ciphertexts
+            .iter()
+            .zip(inputs)
+            .map(|(ciphertext, input)| self.mul_constant(ciphertext, input))
+            .reduce(|mut sum, product| {
+                self.add_assign(&mut sum, &product);
+                sum
+            })
+    }
+
     /// Combines two ciphertexts so that their decrypted value reflects some addition operation with a constant
     fn add_constant(
         &self,
@@ -114,6 +210,381 @@ impl<
     }
 }
 
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    AddAssign<&AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    fn add_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .add_assign(&mut self.ciphertext, &rhs.ciphertext);
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    Add<&AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    type Output = AssociatedCiphertext<'pk, C, PK>;
+
+    fn add(mut self, rhs: &Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition> Add
+    for AssociatedCiphertext<'pk, C, PK>
+{
+    type Output = AssociatedCiphertext<'pk, C, PK>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    SubAssign<&AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .sub_assign(&mut self.ciphertext, &rhs.ciphertext);
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    Sub<&AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    type Output = AssociatedCiphertext<'pk, C, PK>;
+
+    fn sub(mut self, rhs: &Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition> Sub
+    for AssociatedCiphertext<'pk, C, PK>
+{
+    type Output = AssociatedCiphertext<'pk, C, PK>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self - &rhs
+    }
+}
+
+forward_owned_rhs!(['pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] Add::add for &AssociatedCiphertext<'pk, C, PK>, AssociatedCiphertext<'pk, C, PK> => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_assign!(['pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] AddAssign::add_assign for AssociatedCiphertext<'pk, C, PK>, AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!(['pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] Sub::sub for &AssociatedCiphertext<'pk, C, PK>, AssociatedCiphertext<'pk, C, PK> => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_assign!(['pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] SubAssign::sub_assign for AssociatedCiphertext<'pk, C, PK>, AssociatedCiphertext<'pk, C, PK>);
+
+forward_owned_lhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Add::add for AssociatedCiphertext<'pk, C, PK>, &P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Add::add for &AssociatedCiphertext<'pk, C, PK>, P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Add::add for AssociatedCiphertext<'pk, C, PK>, P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_lhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Sub::sub for AssociatedCiphertext<'pk, C, PK>, &P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Sub::sub for &AssociatedCiphertext<'pk, C, PK>, P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Sub::sub for AssociatedCiphertext<'pk, C, PK>, P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_lhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition
+] Mul::mul for AssociatedCiphertext<'pk, C, PK>, &P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition
+] Mul::mul for &AssociatedCiphertext<'pk, C, PK>, P => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_rhs!([
+    'pk,
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition
+] Mul::mul for AssociatedCiphertext<'pk, C, PK>, P => AssociatedCiphertext<'pk, C, PK>);
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    AssociatedCiphertext<'pk, C, PK>
+{
+    /// Adds `rhs` to this ciphertext like the `+` operator, but returns an error instead of
+    /// combining ciphertexts under different keys.
+    pub fn try_add(
+        &self,
+        rhs: &Self,
+    ) -> Result<AssociatedCiphertext<'pk, C, PK>, IncompatibleKeys> {
+        check_compatible_keys(self.public_key, rhs.public_key)?;
+        Ok(self
+            .public_key
+            .add(&self.ciphertext, &rhs.ciphertext)
+            .associate(self.public_key))
+    }
+
+    /// Subtracts `rhs` from this ciphertext like the `-` operator, but returns an error instead of
+    /// combining ciphertexts under different keys.
+    pub fn try_sub(
+        &self,
+        rhs: &Self,
+    ) -> Result<AssociatedCiphertext<'pk, C, PK>, IncompatibleKeys> {
+        check_compatible_keys(self.public_key, rhs.public_key)?;
+        Ok(self
+            .public_key
+            .sub(&self.ciphertext, &rhs.ciphertext)
+            .associate(self.public_key))
+    }
+}
+
+/// Sums associated ciphertexts so that the decrypted value reflects the addition of all plaintexts. Panics if the iterator is empty, because there is no public key to encrypt the identity with.
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition> Sum
+    for AssociatedCiphertext<'pk, C, PK>
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|a, b| a + &b)
+            .expect("cannot sum an empty iterator of ciphertexts")
+    }
+}
+
+/// Sums associated ciphertexts so that the decrypted value reflects the addition of all plaintexts. Panics if the iterator is empty, because there is no public key to encrypt the identity with.
+impl<
+        'a,
+        'pk,
+        C: Associable<PK> + Clone,
+        PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition,
+    > Sum<&'a AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    fn sum<I: Iterator<Item = &'a Self>>(mut iter: I) -> Self {
+        let first = iter
+            .next()
+            .expect("cannot sum an empty iterator of ciphertexts")
+            .clone();
+        iter.fold(first, |a, b| a + b)
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition> Add
+    for &OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .add(&self.ciphertext, &rhs.ciphertext)
+            .associate_owned(self.public_key.clone())
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition> Sub
+    for &OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .sub(&self.ciphertext, &rhs.ciphertext)
+            .associate_owned(self.public_key.clone())
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    AddAssign<&OwnedAssociatedCiphertext<C, PK>> for OwnedAssociatedCiphertext<C, PK>
+{
+    fn add_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .add_assign(&mut self.ciphertext, &rhs.ciphertext);
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    SubAssign<&OwnedAssociatedCiphertext<C, PK>> for OwnedAssociatedCiphertext<C, PK>
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .sub_assign(&mut self.ciphertext, &rhs.ciphertext);
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    Add<&OwnedAssociatedCiphertext<C, PK>> for OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn add(mut self, rhs: &Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    Sub<&OwnedAssociatedCiphertext<C, PK>> for OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn sub(mut self, rhs: &Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition>
+    OwnedAssociatedCiphertext<C, PK>
+{
+    /// Adds `rhs` to this ciphertext like the `+` operator, but returns an error instead of
+    /// combining ciphertexts under different keys.
+    pub fn try_add(
+        &self,
+        rhs: &Self,
+    ) -> Result<OwnedAssociatedCiphertext<C, PK>, IncompatibleKeys> {
+        check_compatible_keys(&*self.public_key, &*rhs.public_key)?;
+        Ok(self
+            .public_key
+            .add(&self.ciphertext, &rhs.ciphertext)
+            .associate_owned(self.public_key.clone()))
+    }
+
+    /// Subtracts `rhs` from this ciphertext like the `-` operator, but returns an error instead of
+    /// combining ciphertexts under different keys.
+    pub fn try_sub(
+        &self,
+        rhs: &Self,
+    ) -> Result<OwnedAssociatedCiphertext<C, PK>, IncompatibleKeys> {
+        check_compatible_keys(&*self.public_key, &*rhs.public_key)?;
+        Ok(self
+            .public_key
+            .sub(&self.ciphertext, &rhs.ciphertext)
+            .associate_owned(self.public_key.clone()))
+    }
+}
+
+forward_owned_rhs!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] Add::add for &OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK> => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] Add::add for OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK> => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_assign!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] AddAssign::add_assign for OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] Sub::sub for &OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK> => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] Sub::sub for OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK> => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_assign!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicAddition] SubAssign::sub_assign for OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK>);
+
+impl<
+        P: PotentialInput,
+        C: Associable<PK>,
+        PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition,
+    > Add<&P> for &OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn add(self, rhs: &PK::Plaintext) -> Self::Output {
+        self.public_key
+            .add_constant(&self.ciphertext, rhs)
+            .associate_owned(self.public_key.clone())
+    }
+}
+
+impl<
+        P: PotentialInput,
+        C: Associable<PK>,
+        PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition,
+    > Sub<&P> for &OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn sub(self, rhs: &PK::Plaintext) -> Self::Output {
+        self.public_key
+            .sub_constant(&self.ciphertext, rhs)
+            .associate_owned(self.public_key.clone())
+    }
+}
+
+impl<
+        P: PotentialInput,
+        C: Associable<PK>,
+        PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition,
+    > Mul<&P> for &OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn mul(self, rhs: &PK::Input) -> Self::Output {
+        self.public_key
+            .mul_constant(&self.ciphertext, rhs)
+            .associate_owned(self.public_key.clone())
+    }
+}
+
+forward_owned_lhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Add::add for OwnedAssociatedCiphertext<C, PK>, &P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Add::add for &OwnedAssociatedCiphertext<C, PK>, P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Add::add for OwnedAssociatedCiphertext<C, PK>, P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_lhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Sub::sub for OwnedAssociatedCiphertext<C, PK>, &P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Sub::sub for &OwnedAssociatedCiphertext<C, PK>, P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Plaintext = P> + HomomorphicAddition
+] Sub::sub for OwnedAssociatedCiphertext<C, PK>, P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_lhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition
+] Mul::mul for OwnedAssociatedCiphertext<C, PK>, &P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition
+] Mul::mul for &OwnedAssociatedCiphertext<C, PK>, P => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([
+    P: PotentialInput,
+    C: Associable<PK>,
+    PK: EncryptionKey<Ciphertext = C, Input = P> + HomomorphicAddition
+] Mul::mul for OwnedAssociatedCiphertext<C, PK>, P => OwnedAssociatedCiphertext<C, PK>);
+
 /// Trait implemented by multiplicatively homomorphic cryptosystems
 pub trait HomomorphicMultiplication: EncryptionKey {
     /// Combines two ciphertexts so that their decrypted value reflects some multiplication operation
@@ -123,8 +594,37 @@ pub trait HomomorphicMultiplication: EncryptionKey {
         ciphertext_b: &Self::Ciphertext,
     ) -> Self::Ciphertext;
 
-    /// Applies some operation on a ciphertext so that the decrypted value reflects some exponentiation with `input`
+    /// Combines two ciphertexts in-place so that the decrypted value of `ciphertext_a` reflects some multiplication operation. Cryptosystems can override this to avoid allocating a new ciphertext.
+    fn mul_assign(&self, ciphertext_a: &mut Self::Ciphertext, ciphertext_b: &Self::Ciphertext) {
+        *ciphertext_a = self.mul(ciphertext_a, ciphertext_b);
+    }
+
+    /// Applies some operation on a ciphertext so that the decrypted value reflects some exponentiation with `input`. The running time must only depend on the size of `input` and not on its value, so that `input` may be secret.
     fn pow(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext;
+
+    /// Computes the same as `pow`, but may take time that depends on the value of `input`, so it must only be used when `input` is public. Cryptosystems can override this with a faster variable-time exponentiation.
+    fn pow_leaky(&self, ciphertext: &Self::Ciphertext, input: &Self::Input) -> Self::Ciphertext {
+        self.pow(ciphertext, input)
+    }
+
+    /// Applies some operation on a ciphertext so that the decrypted value reflects the multiplicative inverse of the plaintext. Returns `None` if the ciphertext has no inverse, which can only happen for ciphertexts that are not valid for this key.
+    fn invert(&self, ciphertext: &Self::Ciphertext) -> Option<Self::Ciphertext>;
+
+    /// Applies some operation on a ciphertext so that the decrypted value reflects some exponentiation with `input` if `negative` is false, and with `-input` otherwise. The running time does not depend on the value of `input`, but may depend on `negative`. Returns `None` if `negative` is true and the result has no inverse.
+    fn pow_signed(
+        &self,
+        ciphertext: &Self::Ciphertext,
+        input: &Self::Input,
+        negative: bool,
+    ) -> Option<Self::Ciphertext> {
+        let result = self.pow(ciphertext, input);
+
+        if negative {
+            self.invert(&result)
+        } else {
+            Some(result)
+        }
+    }
 }
 
 impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication> Mul
@@ -143,10 +643,194 @@ impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMult
 impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication>
     AssociatedCiphertext<'pk, C, PK>
 {
-    /// Applies some operation on this ciphertext so that the decrypted value reflects some exponentiation with `input`
+    /// Multiplies this ciphertext with `rhs` like the `*` operator, but returns an error instead of
+    /// combining ciphertexts under keys of different security levels.
+    pub fn try_mul(
+        &self,
+        rhs: &Self,
+    ) -> Result<AssociatedCiphertext<'pk, C, PK>, IncompatibleKeys> {
+        check_compatible_keys(self.public_key, rhs.public_key)?;
+        Ok(self
+            .public_key
+            .mul(&self.ciphertext, &rhs.ciphertext)
+            .associate(self.public_key))
+    }
+
+    /// Applies some operation on this ciphertext so that the decrypted value reflects some exponentiation with `input`. This takes constant time in the value of `rhs`, so it may be secret, for example a blinding factor in a two-party protocol.
     pub fn pow(&self, rhs: &PK::Input) -> AssociatedCiphertext<'pk, C, PK> {
         self.public_key
             .pow(&self.ciphertext, rhs)
             .associate(self.public_key)
     }
+
+    /// Computes the same as `pow`, but may take time that depends on the value of `rhs`. This is faster for some cryptosystems, but must only be used when `rhs` is public.
+    pub fn pow_leaky(&self, rhs: &PK::Input) -> AssociatedCiphertext<'pk, C, PK> {
+        self.public_key
+            .pow_leaky(&self.ciphertext, rhs)
+            .associate(self.public_key)
+    }
+
+    /// Applies some operation on this ciphertext so that the decrypted value reflects the multiplicative inverse of the plaintext. Returns `None` if the ciphertext has no inverse, for example because it was deserialized from an untrusted source.
+    pub fn invert(&self) -> Option<AssociatedCiphertext<'pk, C, PK>> {
+        self.public_key
+            .invert(&self.ciphertext)
+            .map(|inverse| inverse.associate(self.public_key))
+    }
+
+    /// Applies some operation on this ciphertext so that the decrypted value reflects some exponentiation with `rhs` if `negative` is false, and with `-rhs` otherwise. This allows protocols to divide by a scaled encrypted value without inverting it separately. Only the sign may influence the running time, so `rhs` may still be secret. Returns `None` if the result must be inverted but has no inverse.
+    pub fn pow_signed(
+        &self,
+        rhs: &PK::Input,
+        negative: bool,
+    ) -> Option<AssociatedCiphertext<'pk, C, PK>> {
+        self.public_key
+            .pow_signed(&self.ciphertext, rhs, negative)
+            .map(|result| result.associate(self.public_key))
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication>
+    MulAssign<&AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    fn mul_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .mul_assign(&mut self.ciphertext, &rhs.ciphertext);
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication>
+    Mul<&AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    type Output = AssociatedCiphertext<'pk, C, PK>;
+
+    fn mul(mut self, rhs: &Self) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication> Mul
+    for AssociatedCiphertext<'pk, C, PK>
+{
+    type Output = AssociatedCiphertext<'pk, C, PK>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self * &rhs
+    }
+}
+
+forward_owned_rhs!(['pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication] Mul::mul for &AssociatedCiphertext<'pk, C, PK>, AssociatedCiphertext<'pk, C, PK> => AssociatedCiphertext<'pk, C, PK>);
+forward_owned_assign!(['pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication] MulAssign::mul_assign for AssociatedCiphertext<'pk, C, PK>, AssociatedCiphertext<'pk, C, PK>);
+
+/// Multiplies associated ciphertexts so that the decrypted value reflects the product of all plaintexts. Panics if the iterator is empty, because there is no public key to encrypt the identity with.
+impl<'pk, C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication> Product
+    for AssociatedCiphertext<'pk, C, PK>
+{
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|a, b| a * &b)
+            .expect("cannot multiply an empty iterator of ciphertexts")
+    }
+}
+
+/// Multiplies associated ciphertexts so that the decrypted value reflects the product of all plaintexts. Panics if the iterator is empty, because there is no public key to encrypt the identity with.
+impl<
+        'a,
+        'pk,
+        C: Associable<PK> + Clone,
+        PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication,
+    > Product<&'a AssociatedCiphertext<'pk, C, PK>> for AssociatedCiphertext<'pk, C, PK>
+{
+    fn product<I: Iterator<Item = &'a Self>>(mut iter: I) -> Self {
+        let first = iter
+            .next()
+            .expect("cannot multiply an empty iterator of ciphertexts")
+            .clone();
+        iter.fold(first, |a, b| a * b)
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication> Mul
+    for &OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .mul(&self.ciphertext, &rhs.ciphertext)
+            .associate_owned(self.public_key.clone())
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication>
+    OwnedAssociatedCiphertext<C, PK>
+{
+    /// Multiplies this ciphertext with `rhs` like the `*` operator, but returns an error instead of
+    /// combining ciphertexts under keys of different security levels.
+    pub fn try_mul(
+        &self,
+        rhs: &Self,
+    ) -> Result<OwnedAssociatedCiphertext<C, PK>, IncompatibleKeys> {
+        check_compatible_keys(&*self.public_key, &*rhs.public_key)?;
+        Ok(self
+            .public_key
+            .mul(&self.ciphertext, &rhs.ciphertext)
+            .associate_owned(self.public_key.clone()))
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication>
+    MulAssign<&OwnedAssociatedCiphertext<C, PK>> for OwnedAssociatedCiphertext<C, PK>
+{
+    fn mul_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.public_key, rhs.public_key);
+        self.public_key
+            .mul_assign(&mut self.ciphertext, &rhs.ciphertext);
+    }
+}
+
+impl<C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication>
+    Mul<&OwnedAssociatedCiphertext<C, PK>> for OwnedAssociatedCiphertext<C, PK>
+{
+    type Output = OwnedAssociatedCiphertext<C, PK>;
+
+    fn mul(mut self, rhs: &Self) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+forward_owned_rhs!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication] Mul::mul for &OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK> => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_rhs!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication] Mul::mul for OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK> => OwnedAssociatedCiphertext<C, PK>);
+forward_owned_assign!([C: Associable<PK>, PK: EncryptionKey<Ciphertext = C> + HomomorphicMultiplication] MulAssign::mul_assign for OwnedAssociatedCiphertext<C, PK>, OwnedAssociatedCiphertext<C, PK>);
+
+/// Adds all `ciphertexts` in parallel so that the decrypted value reflects the addition of all plaintexts. Returns `None` if there are no ciphertexts.
+#[cfg(feature = "rayon")]
+pub fn par_sum<PK>(public_key: &PK, ciphertexts: &[PK::Ciphertext]) -> Option<PK::Ciphertext>
+where
+    PK: HomomorphicAddition + Sync,
+    PK::Ciphertext: Clone + Send + Sync,
+{
+    use rayon::prelude::*;
+
+    ciphertexts.par_iter().cloned().reduce_with(|mut a, b| {
+        public_key.add_assign(&mut a, &b);
+        a
+    })
+}
+
+/// Multiplies all `ciphertexts` in parallel so that the decrypted value reflects the product of all plaintexts. Returns `None` if there are no ciphertexts.
+#[cfg(feature = "rayon")]
+pub fn par_product<PK>(public_key: &PK, ciphertexts: &[PK::Ciphertext]) -> Option<PK::Ciphertext>
+where
+    PK: HomomorphicMultiplication + Sync,
+    PK::Ciphertext: Clone + Send + Sync,
+{
+    use rayon::prelude::*;
+
+    ciphertexts.par_iter().cloned().reduce_with(|mut a, b| {
+        public_key.mul_assign(&mut a, &b);
+        a
+    })
 }
@@ -0,0 +1,41 @@
+use crate::cryptosystems::AsymmetricCryptosystem;
+use crate::randomness::{GeneralRng, SecureRng};
+use crate::security::Redacted;
+use std::fmt::{Debug, Formatter};
+
+/// A symmetric key that is established through a key encapsulation mechanism, e.g. to key a
+/// symmetric cipher or a MAC.
+#[derive(PartialEq, Eq, Clone)]
+pub struct SharedSecret(pub [u8; 32]);
+
+impl Debug for SharedSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedSecret")
+            .field(&Redacted::new(&self.0, 256))
+            .finish()
+    }
+}
+
+/// A key encapsulation mechanism (KEM) lets anyone with a public key generate a fresh shared secret
+/// together with an encapsulation of it. Only the holder of the corresponding secret key can
+/// recover the shared secret from the encapsulation. This way the keys of an asymmetric
+/// cryptosystem can bootstrap a symmetric session.
+pub trait Kem: AsymmetricCryptosystem {
+    /// The encapsulated shared secret, which is sent to the holder of the secret key.
+    type Encapsulation;
+
+    /// Generates a fresh shared secret and its encapsulation under `public_key`.
+    fn encapsulate<R: SecureRng>(
+        public_key: &Self::PublicKey,
+        rng: &mut GeneralRng<R>,
+    ) -> (SharedSecret, Self::Encapsulation);
+
+    /// Recovers the shared secret from `encapsulation` using the `secret_key` that belongs to
+    /// `public_key`. The encapsulation comes from an untrusted party, so this returns `None` if it
+    /// is not a valid encapsulation, for example one that forces a known shared secret.
+    fn decapsulate(
+        secret_key: &Self::SecretKey,
+        public_key: &Self::PublicKey,
+        encapsulation: &Self::Encapsulation,
+    ) -> Option<SharedSecret>;
+}
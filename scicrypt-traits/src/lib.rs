@@ -1,7 +1,7 @@
 #![feature(trait_alias)]
 // These features are necessary to prevent the operator overloading for AssociatedCiphertext to clash between additive and multiplicative,
-// so we restrict the AssociatedCiphertext to never be a plaintext.
-#![feature(auto_traits, negative_impls)]
+// so we restrict the AssociatedCiphertext to never be a plaintext. Negative coherence also lets plaintexts be taken by value and by reference.
+#![feature(auto_traits, negative_impls, with_negative_coherence)]
 #![warn(missing_docs, unused_imports)]
 
 //! _This is a part of **scicrypt**. For more information, head to the
@@ -22,6 +22,15 @@ pub mod cryptosystems;
 /// General notion of threshold cryptosystems
 pub mod threshold_cryptosystems;
 
+/// Key encapsulation mechanisms for establishing shared symmetric keys
+pub mod kem;
+
+/// Encoding of numbers into the plaintext spaces of cryptosystems
+pub mod encoding;
+
+/// Fiat-Shamir transcripts for deriving the challenges of non-interactive proofs
+pub mod transcript;
+
 /// General notion of secret sharing
 pub mod secret_sharing;
 
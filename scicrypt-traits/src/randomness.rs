@@ -1,4 +1,9 @@
+#[cfg(feature = "drbg")]
+use rand_core::{RngCore, SeedableRng};
+#[cfg(feature = "rug")]
 use rug::rand::{ThreadRandGen, ThreadRandState};
+#[cfg(feature = "drbg")]
+use sha2::{Digest, Sha256};
 
 pub trait SecureRng = rand_core::RngCore + rand_core::CryptoRng;
 
@@ -21,7 +26,9 @@ impl<R: SecureRng> GeneralRng<R> {
         &mut self.rng_wrapper.rng
     }
 
-    /// Creates a RNG for the `rug` crate that is only suitable for a single thread.
+    /// Creates a RNG for the `rug` crate that is only suitable for a single thread. It draws from
+    /// the underlying RNG, so entropy that is mixed into a `ReseedingRng` also reaches it.
+    #[cfg(feature = "rug")]
     pub fn rug_rng(&mut self) -> ThreadRandState<'_> {
         ThreadRandState::new_custom(&mut self.rng_wrapper)
     }
@@ -31,8 +38,277 @@ struct RngWrapper<R: SecureRng> {
     rng: R,
 }
 
+#[cfg(feature = "rug")]
 impl<R: SecureRng> ThreadRandGen for RngWrapper<R> {
     fn gen(&mut self) -> u32 {
         self.rng.next_u32()
     }
 }
+
+/// A source of entropy that a `ReseedingRng` draws its seeds from, such as a hardware security
+/// module or `/dev/hwrng`. Every cryptographically secure RNG is an entropy source.
+pub trait EntropySource {
+    /// Fills `dest` with fresh entropy, or returns an error if the source is unavailable.
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error>;
+}
+
+impl<R: SecureRng> EntropySource for R {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "drbg")]
+const DRBG_DOMAIN: &[u8] = b"scicrypt-traits reseeding drbg";
+
+/// A deterministic random bit generator based on ChaCha20 that is seeded from an external
+/// `EntropySource`, for deployments where the operating system's RNG cannot be used directly. It
+/// reseeds itself from the source after every `reseed_interval` bytes of output, and can be
+/// reseeded explicitly or be given additional entropy at any time.
+///
+/// Every new key is hashed from output of the current generator together with the fresh entropy,
+/// so the generator stays secure as long as either of them is unpredictable.
+#[cfg(feature = "drbg")]
+pub struct ReseedingRng<S: EntropySource> {
+    source: S,
+    rng: rand_chacha::ChaCha20Rng,
+    reseed_interval: u64,
+    bytes_since_reseed: u64,
+}
+
+#[cfg(feature = "drbg")]
+impl<S: EntropySource> ReseedingRng<S> {
+    /// Creates a new generator that is seeded from `source`, and that reseeds after every
+    /// `reseed_interval` bytes of output. Returns an error if the source fails to provide a seed.
+    pub fn new(mut source: S, reseed_interval: u64) -> Result<Self, rand_core::Error> {
+        assert!(reseed_interval > 0, "the reseed interval must be positive");
+
+        let mut seed = [0u8; 32];
+        source.fill_entropy(&mut seed)?;
+
+        Ok(ReseedingRng {
+            source,
+            rng: rand_chacha::ChaCha20Rng::from_seed(Self::derive_key(&seed, &[])),
+            reseed_interval,
+            bytes_since_reseed: 0,
+        })
+    }
+
+    /// Reseeds the generator with fresh entropy from its source, or returns an error and leaves the
+    /// generator unchanged if the source fails.
+    pub fn reseed(&mut self) -> Result<(), rand_core::Error> {
+        let mut entropy = [0u8; 32];
+        self.source.fill_entropy(&mut entropy)?;
+        self.mix_entropy(&entropy);
+        self.bytes_since_reseed = 0;
+
+        Ok(())
+    }
+
+    /// Mixes `entropy` from another source into the state of the generator, without resetting the
+    /// reseed interval.
+    pub fn mix_entropy(&mut self, entropy: &[u8]) {
+        let mut state = [0u8; 32];
+        self.rng.fill_bytes(&mut state);
+        self.rng = rand_chacha::ChaCha20Rng::from_seed(Self::derive_key(&state, entropy));
+    }
+
+    /// Exposes the underlying entropy source.
+    pub fn source(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    fn derive_key(state: &[u8], entropy: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(DRBG_DOMAIN);
+        hasher.update(state);
+        hasher.update((entropy.len() as u64).to_le_bytes());
+        hasher.update(entropy);
+        hasher.finalize().into()
+    }
+
+    fn before_output(&mut self, length: usize) -> Result<(), rand_core::Error> {
+        if self.bytes_since_reseed >= self.reseed_interval {
+            self.reseed()?;
+        }
+        self.bytes_since_reseed += length as u64;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "drbg")]
+impl<S: EntropySource> rand_core::RngCore for ReseedingRng<S> {
+    fn next_u32(&mut self) -> u32 {
+        self.before_output(4).expect("the entropy source failed");
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.before_output(8).expect("the entropy source failed");
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("the entropy source failed")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.before_output(dest.len())?;
+        self.rng.fill_bytes(dest);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "drbg")]
+impl<S: EntropySource> rand_core::CryptoRng for ReseedingRng<S> {}
+
+/// A deterministic RNG that is seeded from a `u64`, so that key generation and encryption become
+/// reproducible in tests and published test vectors.
+///
+/// **This RNG is not secure.** It implements `CryptoRng` only so that it is accepted wherever a
+/// `SecureRng` is expected. It is only available with the `testing` feature, which must never be
+/// enabled in production.
+#[cfg(feature = "testing")]
+pub struct InsecureTestRng {
+    rng: rand_chacha::ChaCha20Rng,
+}
+
+#[cfg(feature = "testing")]
+impl InsecureTestRng {
+    /// Creates a new deterministic RNG from a `seed`. The same seed always yields the same stream.
+    pub fn from_seed(seed: u64) -> Self {
+        use rand_core::SeedableRng;
+
+        InsecureTestRng {
+            rng: rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl rand_core::RngCore for InsecureTestRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl rand_core::CryptoRng for InsecureTestRng {}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    #[cfg(feature = "drbg")]
+    use crate::randomness::{EntropySource, ReseedingRng};
+    use crate::randomness::{GeneralRng, InsecureTestRng};
+    use rand_core::RngCore;
+
+    #[test]
+    fn test_insecure_test_rng_reproducible() {
+        let mut rng_a = GeneralRng::new(InsecureTestRng::from_seed(42));
+        let mut rng_b = GeneralRng::new(InsecureTestRng::from_seed(42));
+
+        assert_eq!(rng_a.rng().next_u64(), rng_b.rng().next_u64());
+    }
+
+    #[test]
+    fn test_insecure_test_rng_seeds_differ() {
+        let mut rng_a = GeneralRng::new(InsecureTestRng::from_seed(42));
+        let mut rng_b = GeneralRng::new(InsecureTestRng::from_seed(43));
+
+        assert_ne!(rng_a.rng().next_u64(), rng_b.rng().next_u64());
+    }
+
+    /// An entropy source that counts how often it is used, and can be made to fail.
+    #[cfg(feature = "drbg")]
+    struct CountingSource {
+        calls: u32,
+        failing: bool,
+    }
+
+    #[cfg(feature = "drbg")]
+    impl EntropySource for CountingSource {
+        fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            if self.failing {
+                return Err(std::num::NonZeroU32::new(rand_core::Error::CUSTOM_START)
+                    .unwrap()
+                    .into());
+            }
+
+            self.calls += 1;
+            dest.fill(self.calls as u8);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "drbg")]
+    #[test]
+    fn test_reseeding_rng_reseeds_after_interval() {
+        let source = CountingSource {
+            calls: 0,
+            failing: false,
+        };
+        let mut rng = ReseedingRng::new(source, 64).unwrap();
+
+        rng.fill_bytes(&mut [0u8; 64]);
+        assert_eq!(1, rng.source().calls);
+
+        rng.next_u64();
+        assert_eq!(2, rng.source().calls);
+    }
+
+    #[cfg(feature = "drbg")]
+    #[test]
+    fn test_reseeding_rng_mix_entropy() {
+        let new_rng = || {
+            let source = CountingSource {
+                calls: 0,
+                failing: false,
+            };
+            ReseedingRng::new(source, 1024).unwrap()
+        };
+        let (mut rng_a, mut rng_b, mut rng_c) = (new_rng(), new_rng(), new_rng());
+
+        rng_b.mix_entropy(b"additional entropy");
+        rng_c.mix_entropy(b"additional entropy");
+        let output_b = rng_b.next_u64();
+
+        assert_ne!(rng_a.next_u64(), output_b);
+        assert_eq!(rng_c.next_u64(), output_b);
+    }
+
+    #[cfg(feature = "drbg")]
+    #[test]
+    fn test_reseeding_rng_source_failure() {
+        let failing = CountingSource {
+            calls: 0,
+            failing: true,
+        };
+        assert!(ReseedingRng::new(failing, 16).is_err());
+
+        let source = CountingSource {
+            calls: 0,
+            failing: false,
+        };
+        let mut rng = ReseedingRng::new(source, 16).unwrap();
+        rng.source().failing = true;
+
+        assert!(rng.try_fill_bytes(&mut [0u8; 16]).is_ok());
+        assert!(rng.try_fill_bytes(&mut [0u8; 16]).is_err());
+        assert!(rng.reseed().is_err());
+    }
+}
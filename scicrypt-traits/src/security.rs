@@ -1,5 +1,13 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+
 /// The number of bits of security as compared to the AES cryptosystem. Check
 /// <https://www.keylength.com/en/4/> for recommendations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BitsOfSecurity {
     /// Security that is equivalent to the security of the 2TDEA cryptosystem. This choice of
     /// parameters is not secure and is only used for legacy.
@@ -39,6 +47,56 @@ impl BitsOfSecurity {
             Self::ToyParameters => 256,
         }
     }
+
+    /// Returns the security level that corresponds to a public key modulus of `pk_bits` bits. This
+    /// is the inverse of `to_public_key_bit_length`, falling back to `Custom` for other sizes.
+    pub fn from_public_key_bit_length(pk_bits: u32) -> Self {
+        match pk_bits {
+            1024 => Self::AES80,
+            2048 => Self::AES112,
+            3072 => Self::AES128,
+            7680 => Self::AES192,
+            15360 => Self::AES256,
+            256 => Self::ToyParameters,
+            _ => Self::Custom { pk_bits },
+        }
+    }
+
+    /// Returns the number of bits of symmetric security that this level provides. A `Custom` level
+    /// provides the security of the highest named level whose modulus it reaches, and toy
+    /// parameters provide no security at all.
+    pub fn to_symmetric_bit_length(&self) -> u32 {
+        match self {
+            Self::AES80 => 80,
+            Self::AES112 => 112,
+            Self::AES128 => 128,
+            Self::AES192 => 192,
+            Self::AES256 => 256,
+            Self::Custom { pk_bits } => [
+                Self::AES256,
+                Self::AES192,
+                Self::AES128,
+                Self::AES112,
+                Self::AES80,
+            ]
+            .iter()
+            .find(|level| *pk_bits >= level.to_public_key_bit_length())
+            .map_or(0, |level| level.to_symmetric_bit_length()),
+            Self::ToyParameters => 0,
+        }
+    }
+
+    /// Returns the number of Miller-Rabin rounds to perform on each of the two primes that make up
+    /// a public key modulus of this security level. These follow the minimum number of rounds that
+    /// FIPS 186-5 (Appendix B.3) prescribes for an error probability below $2^{-\lambda}$ for
+    /// security level $\lambda$.
+    pub fn to_miller_rabin_repetitions(&self) -> u32 {
+        match self.to_public_key_bit_length() {
+            0..=1024 => 7,
+            1025..=2048 => 5,
+            _ => 4,
+        }
+    }
 }
 
 impl Default for BitsOfSecurity {
@@ -46,3 +104,85 @@ impl Default for BitsOfSecurity {
         Self::AES128
     }
 }
+
+/// Formats a secret value for `Debug` without revealing it. Only the size of the secret in bits and a
+/// short hash are printed, which is enough to tell whether two keys in a log are the same. The
+/// hash is not a commitment: it must only ever be computed over high-entropy secrets such as keys.
+pub struct Redacted<'a, T: Hash + ?Sized> {
+    value: &'a T,
+    size_in_bits: u32,
+}
+
+impl<'a, T: Hash + ?Sized> Redacted<'a, T> {
+    /// Wraps a secret `value` of `size_in_bits` bits.
+    pub fn new(value: &'a T, size_in_bits: u32) -> Self {
+        Redacted {
+            value,
+            size_in_bits,
+        }
+    }
+}
+
+impl<T: Hash + ?Sized> Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut hasher = DefaultHasher::new();
+        self.value.hash(&mut hasher);
+
+        write!(
+            f,
+            "<redacted: {} bits, hash {:08x}>",
+            self.size_in_bits,
+            hasher.finish() as u32
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::security::{BitsOfSecurity, Redacted};
+
+    #[test]
+    fn test_redacted_hides_value() {
+        let secret = 0xdeadbeefu64;
+        let formatted = format!("{:?}", Redacted::new(&secret, 32));
+
+        assert!(formatted.starts_with("<redacted: 32 bits, hash "));
+        assert!(!formatted.contains(&secret.to_string()));
+        assert!(!formatted.contains("deadbeef"));
+    }
+
+    #[test]
+    fn test_security_level_from_public_key_bit_length() {
+        for level in [
+            BitsOfSecurity::AES80,
+            BitsOfSecurity::AES128,
+            BitsOfSecurity::AES256,
+            BitsOfSecurity::ToyParameters,
+        ] {
+            assert_eq!(
+                level,
+                BitsOfSecurity::from_public_key_bit_length(level.to_public_key_bit_length())
+            );
+        }
+
+        let custom = BitsOfSecurity::from_public_key_bit_length(4096);
+        assert_eq!(BitsOfSecurity::Custom { pk_bits: 4096 }, custom);
+        assert_eq!(128, custom.to_symmetric_bit_length());
+        assert_eq!(
+            0,
+            BitsOfSecurity::Custom { pk_bits: 512 }.to_symmetric_bit_length()
+        );
+    }
+
+    #[test]
+    fn test_redacted_distinguishes_values() {
+        assert_eq!(
+            format!("{:?}", Redacted::new(&1u64, 64)),
+            format!("{:?}", Redacted::new(&1u64, 64))
+        );
+        assert_ne!(
+            format!("{:?}", Redacted::new(&1u64, 64)),
+            format!("{:?}", Redacted::new(&2u64, 64))
+        );
+    }
+}
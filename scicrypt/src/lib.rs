@@ -3,6 +3,17 @@
 
 pub use scicrypt_bigint;
 pub use scicrypt_he::cryptosystems;
+pub use scicrypt_he::dynamic;
+pub use scicrypt_he::exponentiation;
+pub use scicrypt_he::hash_to_group;
+pub use scicrypt_he::proofs;
+pub use scicrypt_he::protocols;
+pub use scicrypt_he::signatures;
+pub use scicrypt_he::simple;
+pub use scicrypt_he::storage;
 pub use scicrypt_he::threshold_cryptosystems;
+pub use scicrypt_he::transport;
+pub use scicrypt_he::vector;
+pub use scicrypt_he::wire;
 pub use scicrypt_numbertheory;
 pub use scicrypt_traits;
@@ -0,0 +1,11 @@
+/* mpn_addmul_2 - from x86_64/fat directory for fat binary.
+   Generated by configure - DO NOT EDIT. */
+
+#define OPERATION_addmul_2 1
+#define __gmpn_addmul_2           __gmpn_addmul_2_fat
+#define __gmpn_addmul_2c          __gmpn_addmul_2c_fat
+#define __gmpn_preinv_addmul_2  __gmpn_preinv_addmul_2_fat
+#define __gmpn_addmul_2_cps     __gmpn_addmul_2_cps_fat
+
+#include "../../gmp-src/mpn/x86_64/fat/addmul_2.c"
+
@@ -0,0 +1,11 @@
+/* mpn_mul_basecase - from x86_64/fat directory for fat binary.
+   Generated by configure - DO NOT EDIT. */
+
+#define OPERATION_mul_basecase 1
+#define __gmpn_mul_basecase           __gmpn_mul_basecase_fat
+#define __gmpn_mul_basecasec          __gmpn_mul_basecasec_fat
+#define __gmpn_preinv_mul_basecase  __gmpn_preinv_mul_basecase_fat
+#define __gmpn_mul_basecase_cps     __gmpn_mul_basecase_cps_fat
+
+#include "../../gmp-src/mpn/x86_64/fat/mul_basecase.c"
+
@@ -0,0 +1,11 @@
+/* mpn_mullo_basecase - from x86_64/fat directory for fat binary.
+   Generated by configure - DO NOT EDIT. */
+
+#define OPERATION_mullo_basecase 1
+#define __gmpn_mullo_basecase           __gmpn_mullo_basecase_fat
+#define __gmpn_mullo_basecasec          __gmpn_mullo_basecasec_fat
+#define __gmpn_preinv_mullo_basecase  __gmpn_preinv_mullo_basecase_fat
+#define __gmpn_mullo_basecase_cps     __gmpn_mullo_basecase_cps_fat
+
+#include "../../gmp-src/mpn/x86_64/fat/mullo_basecase.c"
+
@@ -0,0 +1,11 @@
+/* mpn_sqr_basecase - from x86_64/fat directory for fat binary.
+   Generated by configure - DO NOT EDIT. */
+
+#define OPERATION_sqr_basecase 1
+#define __gmpn_sqr_basecase           __gmpn_sqr_basecase_fat
+#define __gmpn_sqr_basecasec          __gmpn_sqr_basecasec_fat
+#define __gmpn_preinv_sqr_basecase  __gmpn_preinv_sqr_basecase_fat
+#define __gmpn_sqr_basecase_cps     __gmpn_sqr_basecase_cps_fat
+
+#include "../../gmp-src/mpn/x86_64/fat/sqr_basecase.c"
+